@@ -836,6 +836,10 @@ impl Step for Compiletest {
             cmd.arg("--quiet");
         }
 
+        if build.config.cmd.bless() {
+            cmd.arg("--bless");
+        }
+
         if build.config.llvm_enabled {
             let llvm_config = build.llvm_config(target);
             let llvm_version = output(Command::new(&llvm_config).arg("--version"));
@@ -55,6 +55,7 @@ pub enum Subcommand {
         paths: Vec<PathBuf>,
         test_args: Vec<String>,
         fail_fast: bool,
+        bless: bool,
     },
     Bench {
         paths: Vec<PathBuf>,
@@ -149,6 +150,7 @@ To learn more about a subcommand, run `./x.py <subcommand> -h`");
         match subcommand.as_str() {
             "test"  => {
                 opts.optflag("", "no-fail-fast", "Run all tests regardless of failure");
+                opts.optflag("", "bless", "Overwrite expected test output with the actual output");
                 opts.optmulti("", "test-args", "extra arguments", "ARGS");
             },
             "bench" => { opts.optmulti("", "test-args", "extra arguments", "ARGS"); },
@@ -220,6 +222,7 @@ Arguments:
         ./x.py test src/test/run-pass
         ./x.py test src/libstd --test-args hash_map
         ./x.py test src/libstd --stage 0
+        ./x.py test src/test/ui --bless
 
     If no arguments are passed then the complete artifacts for that stage are
     compiled and tested.
@@ -284,6 +287,7 @@ Arguments:
                     paths,
                     test_args: matches.opt_strs("test-args"),
                     fail_fast: !matches.opt_present("no-fail-fast"),
+                    bless: matches.opt_present("bless"),
                 }
             }
             "bench" => {
@@ -368,6 +372,13 @@ impl Subcommand {
             _ => false,
         }
     }
+
+    pub fn bless(&self) -> bool {
+        match *self {
+            Subcommand::Test { bless, .. } => bless,
+            _ => false,
+        }
+    }
 }
 
 fn split(s: Vec<String>) -> Vec<String> {
@@ -354,6 +354,46 @@ impl fmt::Display for AllocErr {
     }
 }
 
+/// The `CollectionAllocErr` error is returned by the `try_reserve` methods
+/// of the collections when they fail to make room for more elements.
+#[unstable(feature = "try_reserve", reason = "new API", issue = "0")]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum CollectionAllocErr {
+    /// Error due to the computed capacity exceeding the collection's maximum
+    /// (usually `isize::MAX` bytes).
+    CapacityOverflow,
+    /// Error returned by the allocator. For memory exhaustion, this carries
+    /// the `Layout` of the request that failed.
+    AllocErr(AllocErr),
+}
+
+#[unstable(feature = "try_reserve", reason = "new API", issue = "0")]
+impl From<AllocErr> for CollectionAllocErr {
+    #[inline]
+    fn from(err: AllocErr) -> Self {
+        CollectionAllocErr::AllocErr(err)
+    }
+}
+
+impl CollectionAllocErr {
+    #[unstable(feature = "try_reserve", reason = "new API", issue = "0")]
+    #[inline]
+    pub fn description(&self) -> &str {
+        match *self {
+            CollectionAllocErr::CapacityOverflow => "capacity overflow",
+            CollectionAllocErr::AllocErr(ref err) => err.description(),
+        }
+    }
+}
+
+// (we need this for downstream impl of trait Error)
+#[unstable(feature = "try_reserve", reason = "new API", issue = "0")]
+impl fmt::Display for CollectionAllocErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.description())
+    }
+}
+
 /// The `CannotReallocInPlace` error is used when `grow_in_place` or
 /// `shrink_in_place` were unable to reuse the given memory block for
 /// a requested layout.
@@ -106,10 +106,13 @@ pub struct ExchangeHeapSingleton {
 /// A pointer type for heap allocation.
 ///
 /// See the [module-level documentation](../../std/boxed/index.html) for more.
+// `box` expressions always allocate from `Heap` through the `exchange_malloc` lang item.
+// The drop glue hands both fields to the `box_free` lang item, which frees the memory with
+// the allocator of the `Box`.
 #[lang = "owned_box"]
 #[fundamental]
 #[stable(feature = "rust1", since = "1.0.0")]
-pub struct Box<T: ?Sized>(Unique<T>);
+pub struct Box<T: ?Sized, A: Alloc = Heap>(Unique<T>, A);
 
 /// `IntermediateBox` represents uninitialized backing storage for `Box`.
 ///
@@ -243,6 +246,53 @@ impl<T> Box<T> {
     }
 }
 
+impl<T, A: Alloc> Box<T, A> {
+    /// Like `new` but parameterized over the choice of allocator for the
+    /// returned `Box`.
+    ///
+    /// This doesn't actually allocate if `T` is zero-sized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    ///
+    /// use std::heap::Heap;
+    ///
+    /// let five = Box::new_in(5, Heap);
+    /// assert_eq!(*five, 5);
+    /// ```
+    #[inline]
+    #[unstable(feature = "allocator_api", issue = "32838")]
+    pub fn new_in(x: T, mut a: A) -> Box<T, A> {
+        let ptr = if mem::size_of::<T>() == 0 {
+            Unique::empty()
+        } else {
+            match a.alloc_one::<T>() {
+                Ok(ptr) => Unique::from(ptr),
+                Err(e) => a.oom(e),
+            }
+        };
+        unsafe {
+            ptr::write(ptr.as_ptr(), x);
+        }
+        Box(ptr, a)
+    }
+}
+
+impl<T: ?Sized, A: Alloc> Box<T, A> {
+    /// Returns a reference to the allocator backing this `Box`.
+    ///
+    /// Note: this is an associated function, which means that you have
+    /// to call it as `Box::allocator(&b)` instead of `b.allocator()`. This
+    /// is so that there is no conflict with a method on the inner type.
+    #[inline]
+    #[unstable(feature = "allocator_api", issue = "32838")]
+    pub fn allocator(b: &Box<T, A>) -> &A {
+        &b.1
+    }
+}
+
 impl<T: ?Sized> Box<T> {
     /// Constructs a box from a raw pointer.
     ///
@@ -269,7 +319,7 @@ impl<T: ?Sized> Box<T> {
     #[stable(feature = "box_raw", since = "1.4.0")]
     #[inline]
     pub unsafe fn from_raw(raw: *mut T) -> Self {
-        Box(Unique::new_unchecked(raw))
+        Box(Unique::new_unchecked(raw), Heap)
     }
 
     /// Consumes the `Box`, returning the wrapped raw pointer.
@@ -393,7 +443,7 @@ impl<T: ?Sized> Box<T> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-unsafe impl<#[may_dangle] T: ?Sized> Drop for Box<T> {
+unsafe impl<#[may_dangle] T: ?Sized, A: Alloc> Drop for Box<T, A> {
     fn drop(&mut self) {
         // FIXME: Do nothing, drop is currently performed by compiler.
     }
@@ -683,7 +733,7 @@ impl<T: ?Sized> fmt::Pointer for Box<T> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<T: ?Sized> Deref for Box<T> {
+impl<T: ?Sized, A: Alloc> Deref for Box<T, A> {
     type Target = T;
 
     fn deref(&self) -> &T {
@@ -692,7 +742,7 @@ impl<T: ?Sized> Deref for Box<T> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<T: ?Sized> DerefMut for Box<T> {
+impl<T: ?Sized, A: Alloc> DerefMut for Box<T, A> {
     fn deref_mut(&mut self) -> &mut T {
         &mut **self
     }
@@ -15,9 +15,11 @@ use core::iter::{FromIterator, Peekable, FusedIterator};
 use core::marker::PhantomData;
 use core::ops::Index;
 use core::{fmt, intrinsics, mem, ptr};
+use core::ptr::NonNull;
 
 use borrow::Borrow;
-use Bound::{Excluded, Included, Unbounded};
+use heap::{Alloc, AllocErr, Heap, Layout};
+use Bound::{self, Excluded, Included, Unbounded};
 use range::RangeArgument;
 
 use super::node::{self, Handle, NodeRef, marker};
@@ -132,13 +134,14 @@ use self::Entry::*;
 /// *stat += random_stat_buff();
 /// ```
 #[stable(feature = "rust1", since = "1.0.0")]
-pub struct BTreeMap<K, V> {
+pub struct BTreeMap<K, V, A: Alloc = Heap> {
     root: node::Root<K, V>,
     length: usize,
+    alloc: A,
 }
 
 #[stable(feature = "btree_drop", since = "1.7.0")]
-unsafe impl<#[may_dangle] K, #[may_dangle] V> Drop for BTreeMap<K, V> {
+unsafe impl<#[may_dangle] K, #[may_dangle] V, A: Alloc> Drop for BTreeMap<K, V, A> {
     fn drop(&mut self) {
         unsafe {
             drop(ptr::read(self).into_iter());
@@ -147,19 +150,20 @@ unsafe impl<#[may_dangle] K, #[may_dangle] V> Drop for BTreeMap<K, V> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<K: Clone, V: Clone> Clone for BTreeMap<K, V> {
-    fn clone(&self) -> BTreeMap<K, V> {
-        fn clone_subtree<K: Clone, V: Clone>(node: node::NodeRef<marker::Immut,
-                                                                 K,
-                                                                 V,
-                                                                 marker::LeafOrInternal>)
-                                             -> BTreeMap<K, V> {
+impl<K: Clone, V: Clone, A: Alloc + Clone> Clone for BTreeMap<K, V, A> {
+    fn clone(&self) -> BTreeMap<K, V, A> {
+        fn clone_subtree<K: Clone, V: Clone, A: Alloc + Clone>(
+            node: node::NodeRef<marker::Immut, K, V, marker::LeafOrInternal>,
+            alloc: &A)
+            -> BTreeMap<K, V, A> {
 
             match node.force() {
                 Leaf(leaf) => {
+                    let mut alloc = alloc.clone();
                     let mut out_tree = BTreeMap {
-                        root: node::Root::new_leaf(),
+                        root: node::Root::new_leaf(&mut alloc),
                         length: 0,
+                        alloc,
                     };
 
                     {
@@ -181,10 +185,10 @@ impl<K: Clone, V: Clone> Clone for BTreeMap<K, V> {
                     out_tree
                 }
                 Internal(internal) => {
-                    let mut out_tree = clone_subtree(internal.first_edge().descend());
+                    let mut out_tree = clone_subtree(internal.first_edge().descend(), alloc);
 
                     {
-                        let mut out_node = out_tree.root.push_level();
+                        let mut out_node = out_tree.root.push_level(&mut out_tree.alloc);
                         let mut in_edge = internal.first_edge();
                         while let Ok(kv) = in_edge.right_kv() {
                             let (k, v) = kv.into_kv();
@@ -192,13 +196,14 @@ impl<K: Clone, V: Clone> Clone for BTreeMap<K, V> {
 
                             let k = (*k).clone();
                             let v = (*v).clone();
-                            let subtree = clone_subtree(in_edge.descend());
+                            let subtree = clone_subtree(in_edge.descend(), alloc);
 
                             // We can't destructure subtree directly
                             // because BTreeMap implements Drop
                             let (subroot, sublength) = unsafe {
                                 let root = ptr::read(&subtree.root);
                                 let length = subtree.length;
+                                drop(ptr::read(&subtree.alloc));
                                 mem::forget(subtree);
                                 (root, length)
                             };
@@ -213,7 +218,7 @@ impl<K: Clone, V: Clone> Clone for BTreeMap<K, V> {
             }
         }
 
-        clone_subtree(self.root.as_ref())
+        clone_subtree(self.root.as_ref(), &self.alloc)
     }
 }
 
@@ -236,6 +241,7 @@ impl<K, Q: ?Sized> super::Recover<Q> for BTreeMap<K, ()>
                 Some(OccupiedEntry {
                          handle,
                          length: &mut self.length,
+                         alloc: &mut self.alloc,
                          _marker: PhantomData,
                      }
                      .remove_kv()
@@ -253,6 +259,7 @@ impl<K, Q: ?Sized> super::Recover<Q> for BTreeMap<K, ()>
                     key,
                     handle,
                     length: &mut self.length,
+                    alloc: &mut self.alloc,
                     _marker: PhantomData,
                 }
                 .insert(());
@@ -304,14 +311,15 @@ pub struct IterMut<'a, K: 'a, V: 'a> {
 /// [`into_iter`]: struct.BTreeMap.html#method.into_iter
 /// [`BTreeMap`]: struct.BTreeMap.html
 #[stable(feature = "rust1", since = "1.0.0")]
-pub struct IntoIter<K, V> {
+pub struct IntoIter<K, V, A: Alloc = Heap> {
     front: Handle<NodeRef<marker::Owned, K, V, marker::Leaf>, marker::Edge>,
     back: Handle<NodeRef<marker::Owned, K, V, marker::Leaf>, marker::Edge>,
     length: usize,
+    alloc: A,
 }
 
 #[stable(feature = "collection_debug", since = "1.17.0")]
-impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for IntoIter<K, V> {
+impl<K: fmt::Debug, V: fmt::Debug, A: Alloc> fmt::Debug for IntoIter<K, V, A> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let range = Range {
             front: self.front.reborrow(),
@@ -426,20 +434,20 @@ impl<'a, K: 'a + fmt::Debug, V: 'a + fmt::Debug> fmt::Debug for RangeMut<'a, K,
 /// [`BTreeMap`]: struct.BTreeMap.html
 /// [`entry`]: struct.BTreeMap.html#method.entry
 #[stable(feature = "rust1", since = "1.0.0")]
-pub enum Entry<'a, K: 'a, V: 'a> {
+pub enum Entry<'a, K: 'a, V: 'a, A: Alloc + 'a = Heap> {
     /// A vacant entry.
     #[stable(feature = "rust1", since = "1.0.0")]
     Vacant(#[stable(feature = "rust1", since = "1.0.0")]
-           VacantEntry<'a, K, V>),
+           VacantEntry<'a, K, V, A>),
 
     /// An occupied entry.
     #[stable(feature = "rust1", since = "1.0.0")]
     Occupied(#[stable(feature = "rust1", since = "1.0.0")]
-             OccupiedEntry<'a, K, V>),
+             OccupiedEntry<'a, K, V, A>),
 }
 
 #[stable(feature= "debug_btree_map", since = "1.12.0")]
-impl<'a, K: 'a + Debug + Ord, V: 'a + Debug> Debug for Entry<'a, K, V> {
+impl<'a, K: 'a + Debug + Ord, V: 'a + Debug, A: Alloc> Debug for Entry<'a, K, V, A> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             Vacant(ref v) => f.debug_tuple("Entry")
@@ -457,17 +465,18 @@ impl<'a, K: 'a + Debug + Ord, V: 'a + Debug> Debug for Entry<'a, K, V> {
 ///
 /// [`Entry`]: enum.Entry.html
 #[stable(feature = "rust1", since = "1.0.0")]
-pub struct VacantEntry<'a, K: 'a, V: 'a> {
+pub struct VacantEntry<'a, K: 'a, V: 'a, A: Alloc + 'a = Heap> {
     key: K,
     handle: Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::Edge>,
     length: &'a mut usize,
+    alloc: &'a mut A,
 
     // Be invariant in `K` and `V`
     _marker: PhantomData<&'a mut (K, V)>,
 }
 
 #[stable(feature= "debug_btree_map", since = "1.12.0")]
-impl<'a, K: 'a + Debug + Ord, V: 'a> Debug for VacantEntry<'a, K, V> {
+impl<'a, K: 'a + Debug + Ord, V: 'a, A: Alloc> Debug for VacantEntry<'a, K, V, A> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_tuple("VacantEntry")
          .field(self.key())
@@ -480,17 +489,18 @@ impl<'a, K: 'a + Debug + Ord, V: 'a> Debug for VacantEntry<'a, K, V> {
 ///
 /// [`Entry`]: enum.Entry.html
 #[stable(feature = "rust1", since = "1.0.0")]
-pub struct OccupiedEntry<'a, K: 'a, V: 'a> {
+pub struct OccupiedEntry<'a, K: 'a, V: 'a, A: Alloc + 'a = Heap> {
     handle: Handle<NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal>, marker::KV>,
 
     length: &'a mut usize,
+    alloc: &'a mut A,
 
     // Be invariant in `K` and `V`
     _marker: PhantomData<&'a mut (K, V)>,
 }
 
 #[stable(feature= "debug_btree_map", since = "1.12.0")]
-impl<'a, K: 'a + Debug + Ord, V: 'a + Debug> Debug for OccupiedEntry<'a, K, V> {
+impl<'a, K: 'a + Debug + Ord, V: 'a + Debug, A: Alloc> Debug for OccupiedEntry<'a, K, V, A> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("OccupiedEntry")
          .field("key", self.key())
@@ -499,6 +509,88 @@ impl<'a, K: 'a + Debug + Ord, V: 'a + Debug> Debug for OccupiedEntry<'a, K, V> {
     }
 }
 
+/// A cursor over a `BTreeMap`.
+///
+/// A `Cursor` is like an iterator, except that it can freely seek back-and-forth.
+///
+/// Cursors always point to an element in the tree, and index in a logically circular way.
+/// To accommodate this, there is a "ghost" non-element that yields `None` between the last and
+/// first elements of the tree.
+///
+/// A `Cursor` is created with the [`lower_bound`] and [`upper_bound`] methods on [`BTreeMap`].
+///
+/// [`BTreeMap`]: struct.BTreeMap.html
+/// [`lower_bound`]: struct.BTreeMap.html#method.lower_bound
+/// [`upper_bound`]: struct.BTreeMap.html#method.upper_bound
+#[unstable(feature = "btree_cursors", issue = "0")]
+pub struct Cursor<'a, K: 'a, V: 'a> {
+    current: Option<Handle<NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal>, marker::KV>>,
+    root: NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal>,
+}
+
+#[unstable(feature = "btree_cursors", issue = "0")]
+impl<'a, K, V> Clone for Cursor<'a, K, V> {
+    fn clone(&self) -> Cursor<'a, K, V> {
+        Cursor {
+            current: self.current,
+            root: self.root,
+        }
+    }
+}
+
+#[unstable(feature = "btree_cursors", issue = "0")]
+impl<'a, K: 'a + Debug, V: 'a + Debug> Debug for Cursor<'a, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Cursor")
+         .field(&self.key_value())
+         .finish()
+    }
+}
+
+/// A cursor over a `BTreeMap` with editing operations.
+///
+/// A `Cursor` is like an iterator, except that it can freely seek back-and-forth, and can
+/// safely mutate the tree during iteration. This is because the lifetime of its yielded
+/// references is tied to its own lifetime, instead of just the underlying tree. This means
+/// cursors cannot yield multiple elements at once.
+///
+/// Cursors always point to an element in the tree, and index in a logically circular way.
+/// To accommodate this, there is a "ghost" non-element that yields `None` between the last and
+/// first elements of the tree.
+///
+/// A `CursorMut` is created with the [`lower_bound_mut`] and [`upper_bound_mut`] methods on
+/// [`BTreeMap`].
+///
+/// [`BTreeMap`]: struct.BTreeMap.html
+/// [`lower_bound_mut`]: struct.BTreeMap.html#method.lower_bound_mut
+/// [`upper_bound_mut`]: struct.BTreeMap.html#method.upper_bound_mut
+#[unstable(feature = "btree_cursors", issue = "0")]
+pub struct CursorMut<'a, K: 'a, V: 'a, A: Alloc + 'a = Heap> {
+    current: Option<Handle<NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal>, marker::KV>>,
+
+    // The root may change underneath `current` when an element is removed,
+    // so it is looked up through the map every time it is needed.
+    map: NonNull<BTreeMap<K, V, A>>,
+
+    // Be invariant in `K` and `V`
+    _marker: PhantomData<&'a mut BTreeMap<K, V, A>>,
+}
+
+#[unstable(feature = "btree_cursors", issue = "0")]
+unsafe impl<'a, K: Send, V: Send, A: Alloc + Send> Send for CursorMut<'a, K, V, A> {}
+
+#[unstable(feature = "btree_cursors", issue = "0")]
+unsafe impl<'a, K: Sync, V: Sync, A: Alloc + Sync> Sync for CursorMut<'a, K, V, A> {}
+
+#[unstable(feature = "btree_cursors", issue = "0")]
+impl<'a, K: 'a + Debug, V: 'a + Debug, A: Alloc> Debug for CursorMut<'a, K, V, A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("CursorMut")
+         .field(&self.key_value())
+         .finish()
+    }
+}
+
 // An iterator for merging two sorted sequences into one
 struct MergeIter<K, V, I: Iterator<Item = (K, V)>> {
     left: Peekable<I>,
@@ -522,9 +614,32 @@ impl<K: Ord, V> BTreeMap<K, V> {
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn new() -> BTreeMap<K, V> {
+        BTreeMap::new_in(Heap)
+    }
+}
+
+impl<K: Ord, V, A: Alloc> BTreeMap<K, V, A> {
+    /// Like `new` but parameterized over the choice of allocator for the
+    /// returned `BTreeMap`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    ///
+    /// use std::collections::BTreeMap;
+    /// use std::heap::Heap;
+    ///
+    /// let mut map: BTreeMap<u32, &str, Heap> = BTreeMap::new_in(Heap);
+    /// map.insert(1, "a");
+    /// assert_eq!(map[&1], "a");
+    /// ```
+    #[unstable(feature = "allocator_api", issue = "32838")]
+    pub fn new_in(mut a: A) -> BTreeMap<K, V, A> {
         BTreeMap {
-            root: node::Root::new_leaf(),
+            root: node::Root::new_leaf(&mut a),
             length: 0,
+            alloc: a,
         }
     }
 
@@ -545,7 +660,9 @@ impl<K: Ord, V> BTreeMap<K, V> {
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn clear(&mut self) {
         // FIXME(gereeter) .clear() allocates
-        *self = BTreeMap::new();
+        let root = mem::replace(&mut self.root, node::Root::new_leaf(&mut self.alloc));
+        let length = mem::replace(&mut self.length, 0);
+        drop(BTreeMap { root, length, alloc: AllocRef(&mut self.alloc) });
     }
 
     /// Returns a reference to the value corresponding to the key.
@@ -697,6 +814,7 @@ impl<K: Ord, V> BTreeMap<K, V> {
                 Some(OccupiedEntry {
                          handle,
                          length: &mut self.length,
+                         alloc: &mut self.alloc,
                          _marker: PhantomData,
                      }
                      .remove())
@@ -734,21 +852,26 @@ impl<K: Ord, V> BTreeMap<K, V> {
     /// assert_eq!(a[&5], "f");
     /// ```
     #[stable(feature = "btree_append", since = "1.11.0")]
-    pub fn append(&mut self, other: &mut Self) {
+    pub fn append(&mut self, other: &mut Self)
+        where A: Clone
+    {
         // Do we have to append anything at all?
         if other.len() == 0 {
             return;
         }
 
-        // We can just swap `self` and `other` if `self` is empty.
+        // We can just swap `self` and `other` if `self` is empty. The allocators are swapped
+        // along with the trees, so every node is still freed by the allocator it came from.
         if self.len() == 0 {
             mem::swap(self, other);
             return;
         }
 
         // First, we merge `self` and `other` into a sorted sequence in linear time.
-        let self_iter = mem::replace(self, BTreeMap::new()).into_iter();
-        let other_iter = mem::replace(other, BTreeMap::new()).into_iter();
+        let self_alloc = self.alloc.clone();
+        let other_alloc = other.alloc.clone();
+        let self_iter = mem::replace(self, BTreeMap::new_in(self_alloc)).into_iter();
+        let other_iter = mem::replace(other, BTreeMap::new_in(other_alloc)).into_iter();
         let iter = MergeIter {
             left: self_iter.peekable(),
             right: other_iter.peekable(),
@@ -843,6 +966,176 @@ impl<K: Ord, V> BTreeMap<K, V> {
         }
     }
 
+    /// Returns a [`Cursor`] pointing at the first element that is above the
+    /// given bound.
+    ///
+    /// If no such element exists then a cursor pointing at the "ghost"
+    /// non-element is returned.
+    ///
+    /// Passing `Unbounded` will return a cursor pointing at the first element
+    /// of the map.
+    ///
+    /// [`Cursor`]: struct.Cursor.html
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// #![feature(btree_cursors)]
+    ///
+    /// use std::collections::BTreeMap;
+    /// use std::collections::Bound;
+    ///
+    /// let mut a = BTreeMap::new();
+    /// a.insert(1, "a");
+    /// a.insert(2, "b");
+    /// a.insert(3, "c");
+    /// a.insert(4, "d");
+    /// let cursor = a.lower_bound(Bound::Excluded(&2));
+    /// assert_eq!(cursor.key(), Some(&3));
+    /// ```
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn lower_bound<Q: ?Sized>(&self, bound: Bound<&Q>) -> Cursor<K, V>
+        where K: Borrow<Q>, Q: Ord
+    {
+        let root = self.root.as_ref();
+        Cursor {
+            current: lower_bound_kv(root, bound),
+            root,
+        }
+    }
+
+    /// Returns a [`CursorMut`] pointing at the first element that is above the
+    /// given bound.
+    ///
+    /// If no such element exists then a cursor pointing at the "ghost"
+    /// non-element is returned.
+    ///
+    /// Passing `Unbounded` will return a cursor pointing at the first element
+    /// of the map.
+    ///
+    /// [`CursorMut`]: struct.CursorMut.html
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// #![feature(btree_cursors)]
+    ///
+    /// use std::collections::BTreeMap;
+    /// use std::collections::Bound;
+    ///
+    /// let mut a = BTreeMap::new();
+    /// a.insert(1, "a");
+    /// a.insert(2, "b");
+    /// a.insert(3, "c");
+    /// a.insert(4, "d");
+    /// {
+    ///     let mut cursor = a.lower_bound_mut(Bound::Included(&2));
+    ///     assert_eq!(cursor.remove_current(), Some((2, "b")));
+    ///     assert_eq!(cursor.key(), Some(&3));
+    /// }
+    /// assert_eq!(a.len(), 3);
+    /// ```
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn lower_bound_mut<Q: ?Sized>(&mut self, bound: Bound<&Q>) -> CursorMut<K, V, A>
+        where K: Borrow<Q>, Q: Ord
+    {
+        let map = NonNull::from(self);
+        let root = unsafe { (*map.as_ptr()).root.as_mut() };
+        CursorMut {
+            current: lower_bound_kv(root, bound),
+            map,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns a [`Cursor`] pointing at the last element that is below the
+    /// given bound.
+    ///
+    /// If no such element exists then a cursor pointing at the "ghost"
+    /// non-element is returned.
+    ///
+    /// Passing `Unbounded` will return a cursor pointing at the last element
+    /// of the map.
+    ///
+    /// [`Cursor`]: struct.Cursor.html
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// #![feature(btree_cursors)]
+    ///
+    /// use std::collections::BTreeMap;
+    /// use std::collections::Bound;
+    ///
+    /// let mut a = BTreeMap::new();
+    /// a.insert(1, "a");
+    /// a.insert(2, "b");
+    /// a.insert(3, "c");
+    /// a.insert(4, "d");
+    /// let cursor = a.upper_bound(Bound::Excluded(&3));
+    /// assert_eq!(cursor.key(), Some(&2));
+    /// ```
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn upper_bound<Q: ?Sized>(&self, bound: Bound<&Q>) -> Cursor<K, V>
+        where K: Borrow<Q>, Q: Ord
+    {
+        let root = self.root.as_ref();
+        Cursor {
+            current: upper_bound_kv(root, bound),
+            root,
+        }
+    }
+
+    /// Returns a [`CursorMut`] pointing at the last element that is below the
+    /// given bound.
+    ///
+    /// If no such element exists then a cursor pointing at the "ghost"
+    /// non-element is returned.
+    ///
+    /// Passing `Unbounded` will return a cursor pointing at the last element
+    /// of the map.
+    ///
+    /// [`CursorMut`]: struct.CursorMut.html
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// #![feature(btree_cursors)]
+    ///
+    /// use std::collections::BTreeMap;
+    /// use std::collections::Bound;
+    ///
+    /// let mut a = BTreeMap::new();
+    /// a.insert(1, "a");
+    /// a.insert(2, "b");
+    /// a.insert(3, "c");
+    /// a.insert(4, "d");
+    /// let mut cursor = a.upper_bound_mut(Bound::Included(&3));
+    /// assert_eq!(cursor.key(), Some(&3));
+    /// *cursor.value_mut().unwrap() = "e";
+    /// assert_eq!(cursor.peek_prev(), Some((&2, &"b")));
+    /// ```
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn upper_bound_mut<Q: ?Sized>(&mut self, bound: Bound<&Q>) -> CursorMut<K, V, A>
+        where K: Borrow<Q>, Q: Ord
+    {
+        let map = NonNull::from(self);
+        let root = unsafe { (*map.as_ptr()).root.as_mut() };
+        CursorMut {
+            current: upper_bound_kv(root, bound),
+            map,
+            _marker: PhantomData,
+        }
+    }
+
     /// Gets the given key's corresponding entry in the map for in-place manipulation.
     ///
     /// # Examples
@@ -862,12 +1155,13 @@ impl<K: Ord, V> BTreeMap<K, V> {
     /// assert_eq!(count["a"], 3);
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
-    pub fn entry(&mut self, key: K) -> Entry<K, V> {
+    pub fn entry(&mut self, key: K) -> Entry<K, V, A> {
         match search::search_tree(self.root.as_mut(), &key) {
             Found(handle) => {
                 Occupied(OccupiedEntry {
                     handle,
                     length: &mut self.length,
+                    alloc: &mut self.alloc,
                     _marker: PhantomData,
                 })
             }
@@ -876,6 +1170,7 @@ impl<K: Ord, V> BTreeMap<K, V> {
                     key,
                     handle,
                     length: &mut self.length,
+                    alloc: &mut self.alloc,
                     _marker: PhantomData,
                 })
             }
@@ -908,7 +1203,7 @@ impl<K: Ord, V> BTreeMap<K, V> {
                         }
                         Err(node) => {
                             // We are at the top, create a new root node and push there.
-                            open_node = node.into_root_mut().push_level();
+                            open_node = node.into_root_mut().push_level(&mut self.alloc);
                             break;
                         }
                     }
@@ -916,9 +1211,9 @@ impl<K: Ord, V> BTreeMap<K, V> {
 
                 // Push key-value pair and new right subtree.
                 let tree_height = open_node.height() - 1;
-                let mut right_tree = node::Root::new_leaf();
+                let mut right_tree = node::Root::new_leaf(&mut self.alloc);
                 for _ in 0..tree_height {
-                    right_tree.push_level();
+                    right_tree.push_level(&mut self.alloc);
                 }
                 open_node.push(key, value, right_tree);
 
@@ -983,17 +1278,17 @@ impl<K: Ord, V> BTreeMap<K, V> {
     /// ```
     #[stable(feature = "btree_split_off", since = "1.11.0")]
     pub fn split_off<Q: ?Sized + Ord>(&mut self, key: &Q) -> Self
-        where K: Borrow<Q>
+        where K: Borrow<Q>, A: Clone
     {
         if self.is_empty() {
-            return Self::new();
+            return Self::new_in(self.alloc.clone());
         }
 
         let total_num = self.len();
 
-        let mut right = Self::new();
+        let mut right = Self::new_in(self.alloc.clone());
         for _ in 0..(self.root.as_ref().height()) {
-            right.root.push_level();
+            right.root.push_level(&mut right.alloc);
         }
 
         {
@@ -1073,7 +1368,7 @@ impl<K: Ord, V> BTreeMap<K, V> {
                     break;
                 }
             }
-            self.root.pop_level();
+            self.root.pop_level(&mut self.alloc);
         }
     }
 
@@ -1087,7 +1382,7 @@ impl<K: Ord, V> BTreeMap<K, V> {
                 let mut last_kv = node.last_kv();
 
                 if last_kv.can_merge() {
-                    cur_node = last_kv.merge().descend();
+                    cur_node = last_kv.merge(&mut self.alloc).descend();
                 } else {
                     let right_len = last_kv.reborrow().right_edge().descend().len();
                     // `MINLEN + 1` to avoid readjust if merge happens on the next level.
@@ -1113,7 +1408,7 @@ impl<K: Ord, V> BTreeMap<K, V> {
                 let mut first_kv = node.first_kv();
 
                 if first_kv.can_merge() {
-                    cur_node = first_kv.merge().descend();
+                    cur_node = first_kv.merge(&mut self.alloc).descend();
                 } else {
                     let left_len = first_kv.reborrow().left_edge().descend().len();
                     if left_len < node::MIN_LEN + 1 {
@@ -1129,7 +1424,7 @@ impl<K: Ord, V> BTreeMap<K, V> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<'a, K: 'a, V: 'a> IntoIterator for &'a BTreeMap<K, V> {
+impl<'a, K: 'a, V: 'a, A: Alloc> IntoIterator for &'a BTreeMap<K, V, A> {
     type Item = (&'a K, &'a V);
     type IntoIter = Iter<'a, K, V>;
 
@@ -1189,7 +1484,7 @@ impl<'a, K, V> Clone for Iter<'a, K, V> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<'a, K: 'a, V: 'a> IntoIterator for &'a mut BTreeMap<K, V> {
+impl<'a, K: 'a, V: 'a, A: Alloc> IntoIterator for &'a mut BTreeMap<K, V, A> {
     type Item = (&'a K, &'a mut V);
     type IntoIter = IterMut<'a, K, V>;
 
@@ -1239,34 +1534,36 @@ impl<'a, K: 'a, V: 'a> ExactSizeIterator for IterMut<'a, K, V> {
 impl<'a, K, V> FusedIterator for IterMut<'a, K, V> {}
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<K, V> IntoIterator for BTreeMap<K, V> {
+impl<K, V, A: Alloc> IntoIterator for BTreeMap<K, V, A> {
     type Item = (K, V);
-    type IntoIter = IntoIter<K, V>;
+    type IntoIter = IntoIter<K, V, A>;
 
-    fn into_iter(self) -> IntoIter<K, V> {
+    fn into_iter(self) -> IntoIter<K, V, A> {
         let root1 = unsafe { ptr::read(&self.root).into_ref() };
         let root2 = unsafe { ptr::read(&self.root).into_ref() };
         let len = self.length;
+        let alloc = unsafe { ptr::read(&self.alloc) };
         mem::forget(self);
 
         IntoIter {
             front: first_leaf_edge(root1),
             back: last_leaf_edge(root2),
             length: len,
+            alloc,
         }
     }
 }
 
 #[stable(feature = "btree_drop", since = "1.7.0")]
-impl<K, V> Drop for IntoIter<K, V> {
+impl<K, V, A: Alloc> Drop for IntoIter<K, V, A> {
     fn drop(&mut self) {
         for _ in &mut *self {
         }
         unsafe {
             let leaf_node = ptr::read(&self.front).into_node();
-            if let Some(first_parent) = leaf_node.deallocate_and_ascend() {
+            if let Some(first_parent) = leaf_node.deallocate_and_ascend(&mut self.alloc) {
                 let mut cur_node = first_parent.into_node();
-                while let Some(parent) = cur_node.deallocate_and_ascend() {
+                while let Some(parent) = cur_node.deallocate_and_ascend(&mut self.alloc) {
                     cur_node = parent.into_node()
                 }
             }
@@ -1275,7 +1572,7 @@ impl<K, V> Drop for IntoIter<K, V> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<K, V> Iterator for IntoIter<K, V> {
+impl<K, V, A: Alloc> Iterator for IntoIter<K, V, A> {
     type Item = (K, V);
 
     fn next(&mut self) -> Option<(K, V)> {
@@ -1295,7 +1592,7 @@ impl<K, V> Iterator for IntoIter<K, V> {
                 return Some((k, v));
             }
             Err(last_edge) => unsafe {
-                unwrap_unchecked(last_edge.into_node().deallocate_and_ascend())
+                unwrap_unchecked(last_edge.into_node().deallocate_and_ascend(&mut self.alloc))
             },
         };
 
@@ -1308,7 +1605,8 @@ impl<K, V> Iterator for IntoIter<K, V> {
                     return Some((k, v));
                 }
                 Err(last_edge) => unsafe {
-                    cur_handle = unwrap_unchecked(last_edge.into_node().deallocate_and_ascend());
+                    let parent = last_edge.into_node().deallocate_and_ascend(&mut self.alloc);
+                    cur_handle = unwrap_unchecked(parent);
                 },
             }
         }
@@ -1320,7 +1618,7 @@ impl<K, V> Iterator for IntoIter<K, V> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<K, V> DoubleEndedIterator for IntoIter<K, V> {
+impl<K, V, A: Alloc> DoubleEndedIterator for IntoIter<K, V, A> {
     fn next_back(&mut self) -> Option<(K, V)> {
         if self.length == 0 {
             return None;
@@ -1338,7 +1636,7 @@ impl<K, V> DoubleEndedIterator for IntoIter<K, V> {
                 return Some((k, v));
             }
             Err(last_edge) => unsafe {
-                unwrap_unchecked(last_edge.into_node().deallocate_and_ascend())
+                unwrap_unchecked(last_edge.into_node().deallocate_and_ascend(&mut self.alloc))
             },
         };
 
@@ -1351,7 +1649,8 @@ impl<K, V> DoubleEndedIterator for IntoIter<K, V> {
                     return Some((k, v));
                 }
                 Err(last_edge) => unsafe {
-                    cur_handle = unwrap_unchecked(last_edge.into_node().deallocate_and_ascend());
+                    let parent = last_edge.into_node().deallocate_and_ascend(&mut self.alloc);
+                    cur_handle = unwrap_unchecked(parent);
                 },
             }
         }
@@ -1359,14 +1658,14 @@ impl<K, V> DoubleEndedIterator for IntoIter<K, V> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<K, V> ExactSizeIterator for IntoIter<K, V> {
+impl<K, V, A: Alloc> ExactSizeIterator for IntoIter<K, V, A> {
     fn len(&self) -> usize {
         self.length
     }
 }
 
 #[unstable(feature = "fused", issue = "35602")]
-impl<K, V> FusedIterator for IntoIter<K, V> {}
+impl<K, V, A: Alloc> FusedIterator for IntoIter<K, V, A> {}
 
 #[stable(feature = "rust1", since = "1.0.0")]
 impl<'a, K, V> Iterator for Keys<'a, K, V> {
@@ -1675,7 +1974,7 @@ impl<K: Ord, V> FromIterator<(K, V)> for BTreeMap<K, V> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<K: Ord, V> Extend<(K, V)> for BTreeMap<K, V> {
+impl<K: Ord, V, A: Alloc> Extend<(K, V)> for BTreeMap<K, V, A> {
     #[inline]
     fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
         for (k, v) in iter {
@@ -1685,14 +1984,14 @@ impl<K: Ord, V> Extend<(K, V)> for BTreeMap<K, V> {
 }
 
 #[stable(feature = "extend_ref", since = "1.2.0")]
-impl<'a, K: Ord + Copy, V: Copy> Extend<(&'a K, &'a V)> for BTreeMap<K, V> {
+impl<'a, K: Ord + Copy, V: Copy, A: Alloc> Extend<(&'a K, &'a V)> for BTreeMap<K, V, A> {
     fn extend<I: IntoIterator<Item = (&'a K, &'a V)>>(&mut self, iter: I) {
         self.extend(iter.into_iter().map(|(&key, &value)| (key, value)));
     }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<K: Hash, V: Hash> Hash for BTreeMap<K, V> {
+impl<K: Hash, V: Hash, A: Alloc> Hash for BTreeMap<K, V, A> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         for elt in self {
             elt.hash(state);
@@ -1709,40 +2008,40 @@ impl<K: Ord, V> Default for BTreeMap<K, V> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<K: PartialEq, V: PartialEq> PartialEq for BTreeMap<K, V> {
-    fn eq(&self, other: &BTreeMap<K, V>) -> bool {
+impl<K: PartialEq, V: PartialEq, A: Alloc> PartialEq for BTreeMap<K, V, A> {
+    fn eq(&self, other: &BTreeMap<K, V, A>) -> bool {
         self.len() == other.len() && self.iter().zip(other).all(|(a, b)| a == b)
     }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<K: Eq, V: Eq> Eq for BTreeMap<K, V> {}
+impl<K: Eq, V: Eq, A: Alloc> Eq for BTreeMap<K, V, A> {}
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<K: PartialOrd, V: PartialOrd> PartialOrd for BTreeMap<K, V> {
+impl<K: PartialOrd, V: PartialOrd, A: Alloc> PartialOrd for BTreeMap<K, V, A> {
     #[inline]
-    fn partial_cmp(&self, other: &BTreeMap<K, V>) -> Option<Ordering> {
+    fn partial_cmp(&self, other: &BTreeMap<K, V, A>) -> Option<Ordering> {
         self.iter().partial_cmp(other.iter())
     }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<K: Ord, V: Ord> Ord for BTreeMap<K, V> {
+impl<K: Ord, V: Ord, A: Alloc> Ord for BTreeMap<K, V, A> {
     #[inline]
-    fn cmp(&self, other: &BTreeMap<K, V>) -> Ordering {
+    fn cmp(&self, other: &BTreeMap<K, V, A>) -> Ordering {
         self.iter().cmp(other.iter())
     }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<K: Debug, V: Debug> Debug for BTreeMap<K, V> {
+impl<K: Debug, V: Debug, A: Alloc> Debug for BTreeMap<K, V, A> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_map().entries(self.iter()).finish()
     }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<'a, K: Ord, Q: ?Sized, V> Index<&'a Q> for BTreeMap<K, V>
+impl<'a, K: Ord, Q: ?Sized, V, A: Alloc> Index<&'a Q> for BTreeMap<K, V, A>
     where K: Borrow<Q>,
           Q: Ord
 {
@@ -1780,6 +2079,98 @@ fn last_leaf_edge<BorrowType, K, V>
     }
 }
 
+/// Returns the first key-value pair to the right of the leaf edge `edge`,
+/// if any.
+fn next_kv_from_edge<BorrowType, K, V>
+    (mut edge: Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::Edge>)
+     -> Option<Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV>> {
+    loop {
+        edge = match edge.right_kv() {
+            Ok(kv) => return Some(kv),
+            Err(last_edge) => match last_edge.into_node().ascend() {
+                Ok(parent_edge) => parent_edge.forget_node_type(),
+                Err(_) => return None,
+            },
+        }
+    }
+}
+
+/// Returns the first key-value pair to the left of the leaf edge `edge`,
+/// if any.
+fn prev_kv_from_edge<BorrowType, K, V>
+    (mut edge: Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::Edge>)
+     -> Option<Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV>> {
+    loop {
+        edge = match edge.left_kv() {
+            Ok(kv) => return Some(kv),
+            Err(first_edge) => match first_edge.into_node().ascend() {
+                Ok(parent_edge) => parent_edge.forget_node_type(),
+                Err(_) => return None,
+            },
+        }
+    }
+}
+
+/// Returns the key-value pair following `kv` in the tree, if any.
+fn next_kv<BorrowType, K, V>
+    (kv: Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV>)
+     -> Option<Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV>> {
+    let leaf_edge = match kv.right_edge().force() {
+        Leaf(leaf_edge) => leaf_edge,
+        Internal(internal_edge) => first_leaf_edge(internal_edge.descend()),
+    };
+    next_kv_from_edge(leaf_edge.forget_node_type())
+}
+
+/// Returns the key-value pair preceding `kv` in the tree, if any.
+fn prev_kv<BorrowType, K, V>
+    (kv: Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV>)
+     -> Option<Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV>> {
+    let leaf_edge = match kv.left_edge().force() {
+        Leaf(leaf_edge) => leaf_edge,
+        Internal(internal_edge) => last_leaf_edge(internal_edge.descend()),
+    };
+    prev_kv_from_edge(leaf_edge.forget_node_type())
+}
+
+/// Finds the first key-value pair in the tree that is above `bound`.
+fn lower_bound_kv<BorrowType, K, V, Q: ?Sized>
+    (root: NodeRef<BorrowType, K, V, marker::LeafOrInternal>, bound: Bound<&Q>)
+     -> Option<Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV>>
+        where Q: Ord, K: Borrow<Q>
+{
+    let key = match bound {
+        Unbounded => return next_kv_from_edge(first_leaf_edge(root).forget_node_type()),
+        Included(key) | Excluded(key) => key,
+    };
+    match search::search_tree(root, key) {
+        Found(kv) => match bound {
+            Excluded(_) => next_kv(kv),
+            _ => Some(kv),
+        },
+        GoDown(edge) => next_kv_from_edge(edge.forget_node_type()),
+    }
+}
+
+/// Finds the last key-value pair in the tree that is below `bound`.
+fn upper_bound_kv<BorrowType, K, V, Q: ?Sized>
+    (root: NodeRef<BorrowType, K, V, marker::LeafOrInternal>, bound: Bound<&Q>)
+     -> Option<Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV>>
+        where Q: Ord, K: Borrow<Q>
+{
+    let key = match bound {
+        Unbounded => return prev_kv_from_edge(last_leaf_edge(root).forget_node_type()),
+        Included(key) | Excluded(key) => key,
+    };
+    match search::search_tree(root, key) {
+        Found(kv) => match bound {
+            Excluded(_) => prev_kv(kv),
+            _ => Some(kv),
+        },
+        GoDown(edge) => prev_kv_from_edge(edge.forget_node_type()),
+    }
+}
+
 fn range_search<BorrowType, K, V, Q: ?Sized, R: RangeArgument<Q>>(
     root1: NodeRef<BorrowType, K, V, marker::LeafOrInternal>,
     root2: NodeRef<BorrowType, K, V, marker::LeafOrInternal>,
@@ -1865,7 +2256,250 @@ unsafe fn unwrap_unchecked<T>(val: Option<T>) -> T {
     })
 }
 
-impl<K, V> BTreeMap<K, V> {
+impl<'a, K, V> Cursor<'a, K, V> {
+    /// Moves the cursor to the next element of the `BTreeMap`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the first element of the `BTreeMap`. If it is pointing to the last
+    /// element of the `BTreeMap` then this will move it to the "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn move_next(&mut self) {
+        self.current = match self.current.take() {
+            None => next_kv_from_edge(first_leaf_edge(self.root).forget_node_type()),
+            Some(kv) => next_kv(kv),
+        };
+    }
+
+    /// Moves the cursor to the previous element of the `BTreeMap`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the last element of the `BTreeMap`. If it is pointing to the first
+    /// element of the `BTreeMap` then this will move it to the "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn move_prev(&mut self) {
+        self.current = match self.current.take() {
+            None => prev_kv_from_edge(last_leaf_edge(self.root).forget_node_type()),
+            Some(kv) => prev_kv(kv),
+        };
+    }
+
+    /// Returns a reference to the key of the element that the cursor is
+    /// currently pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn key(&self) -> Option<&'a K> {
+        self.current.map(|kv| kv.into_kv().0)
+    }
+
+    /// Returns a reference to the value of the element that the cursor is
+    /// currently pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn value(&self) -> Option<&'a V> {
+        self.current.map(|kv| kv.into_kv().1)
+    }
+
+    /// Returns a reference to the key and value of the element that the cursor
+    /// is currently pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn key_value(&self) -> Option<(&'a K, &'a V)> {
+        self.current.map(|kv| kv.into_kv())
+    }
+
+    /// Returns a reference to the next element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns
+    /// the first element of the `BTreeMap`. If it is pointing to the last
+    /// element of the `BTreeMap` then this returns `None`.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn peek_next(&self) -> Option<(&'a K, &'a V)> {
+        let mut next = self.clone();
+        next.move_next();
+        next.key_value()
+    }
+
+    /// Returns a reference to the previous element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns
+    /// the last element of the `BTreeMap`. If it is pointing to the first
+    /// element of the `BTreeMap` then this returns `None`.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn peek_prev(&self) -> Option<(&'a K, &'a V)> {
+        let mut prev = self.clone();
+        prev.move_prev();
+        prev.key_value()
+    }
+}
+
+impl<'a, K, V, A: Alloc> CursorMut<'a, K, V, A> {
+    fn root(&mut self) -> NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal> {
+        unsafe { (*self.map.as_ptr()).root.as_mut() }
+    }
+
+    /// Moves the cursor to the next element of the `BTreeMap`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the first element of the `BTreeMap`. If it is pointing to the last
+    /// element of the `BTreeMap` then this will move it to the "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn move_next(&mut self) {
+        self.current = match self.current.take() {
+            None => next_kv_from_edge(first_leaf_edge(self.root()).forget_node_type()),
+            Some(kv) => next_kv(kv),
+        };
+    }
+
+    /// Moves the cursor to the previous element of the `BTreeMap`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the last element of the `BTreeMap`. If it is pointing to the first
+    /// element of the `BTreeMap` then this will move it to the "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn move_prev(&mut self) {
+        self.current = match self.current.take() {
+            None => prev_kv_from_edge(last_leaf_edge(self.root()).forget_node_type()),
+            Some(kv) => prev_kv(kv),
+        };
+    }
+
+    /// Returns a reference to the key of the element that the cursor is
+    /// currently pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn key(&self) -> Option<&K> {
+        self.current.as_ref().map(|kv| kv.reborrow().into_kv().0)
+    }
+
+    /// Returns a reference to the value of the element that the cursor is
+    /// currently pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn value(&self) -> Option<&V> {
+        self.current.as_ref().map(|kv| kv.reborrow().into_kv().1)
+    }
+
+    /// Returns a reference to the key and value of the element that the cursor
+    /// is currently pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn key_value(&self) -> Option<(&K, &V)> {
+        self.current.as_ref().map(|kv| kv.reborrow().into_kv())
+    }
+
+    /// Returns a mutable reference to the value of the element that the cursor
+    /// is currently pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn value_mut(&mut self) -> Option<&mut V> {
+        self.current.as_mut().map(|kv| kv.kv_mut().1)
+    }
+
+    /// Returns a reference to the key and a mutable reference to the value of
+    /// the element that the cursor is currently pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn key_value_mut(&mut self) -> Option<(&K, &mut V)> {
+        self.current.as_mut().map(|kv| {
+            let (k, v) = kv.kv_mut();
+            (&*k, v)
+        })
+    }
+
+    /// Returns a reference to the next element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns
+    /// the first element of the `BTreeMap`. If it is pointing to the last
+    /// element of the `BTreeMap` then this returns `None`.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn peek_next(&self) -> Option<(&K, &V)> {
+        self.as_cursor().peek_next()
+    }
+
+    /// Returns a reference to the previous element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns
+    /// the last element of the `BTreeMap`. If it is pointing to the first
+    /// element of the `BTreeMap` then this returns `None`.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn peek_prev(&self) -> Option<(&K, &V)> {
+        self.as_cursor().peek_prev()
+    }
+
+    /// Returns a read-only cursor pointing to the current element.
+    ///
+    /// The lifetime of the returned `Cursor` is bound to that of the
+    /// `CursorMut`, which means it cannot outlive the `CursorMut` and that the
+    /// `CursorMut` is frozen for the lifetime of the `Cursor`.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn as_cursor<'cm>(&'cm self) -> Cursor<'cm, K, V> {
+        Cursor {
+            current: self.current.as_ref().map(|kv| kv.reborrow()),
+            root: unsafe { (*self.map.as_ptr()).root.as_ref() },
+        }
+    }
+}
+
+impl<'a, K: Ord, V, A: Alloc> CursorMut<'a, K, V, A> {
+    /// Removes the current element from the `BTreeMap`.
+    ///
+    /// The element that was removed is returned, and the cursor is
+    /// moved to point to the next element in the `BTreeMap`.
+    ///
+    /// If the cursor is currently pointing to the "ghost" non-element then no element
+    /// is removed and `None` is returned. The cursor is not moved in this case.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn remove_current(&mut self) -> Option<(K, V)> {
+        let (key, value) = self.remove_kv()?;
+        // Rebalancing may have moved elements between nodes, so the position
+        // is found again from the root.
+        self.current = lower_bound_kv(self.root(), Excluded(&key));
+        Some((key, value))
+    }
+
+    /// Removes the current element from the `BTreeMap`.
+    ///
+    /// The element that was removed is returned, and the cursor is
+    /// moved to point to the previous element in the `BTreeMap`.
+    ///
+    /// If the cursor is currently pointing to the "ghost" non-element then no element
+    /// is removed and `None` is returned. The cursor is not moved in this case.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn remove_current_and_move_back(&mut self) -> Option<(K, V)> {
+        let (key, value) = self.remove_kv()?;
+        self.current = upper_bound_kv(self.root(), Excluded(&key));
+        Some((key, value))
+    }
+
+    fn remove_kv(&mut self) -> Option<(K, V)> {
+        let handle = self.current.take()?;
+        let entry = OccupiedEntry {
+            handle,
+            length: unsafe { &mut (*self.map.as_ptr()).length },
+            alloc: unsafe { &mut (*self.map.as_ptr()).alloc },
+            _marker: PhantomData,
+        };
+        Some(entry.remove_kv())
+    }
+}
+
+impl<K, V, A: Alloc> BTreeMap<K, V, A> {
     /// Gets an iterator over the entries of the map, sorted by key.
     ///
     /// # Examples
@@ -2038,9 +2672,16 @@ impl<K, V> BTreeMap<K, V> {
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    /// Returns a reference to the allocator backing this `BTreeMap`.
+    #[inline]
+    #[unstable(feature = "allocator_api", issue = "32838")]
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
 }
 
-impl<'a, K: Ord, V> Entry<'a, K, V> {
+impl<'a, K: Ord, V, A: Alloc> Entry<'a, K, V, A> {
     /// Ensures a value is in the entry by inserting the default if empty, and returns
     /// a mutable reference to the value in the entry.
     ///
@@ -2138,7 +2779,7 @@ impl<'a, K: Ord, V> Entry<'a, K, V> {
     }
 }
 
-impl<'a, K: Ord, V: Default> Entry<'a, K, V> {
+impl<'a, K: Ord, V: Default, A: Alloc> Entry<'a, K, V, A> {
     #[unstable(feature = "entry_or_default", issue = "44324")]
     /// Ensures a value is in the entry by inserting the default value if empty,
     /// and returns a mutable reference to the value in the entry.
@@ -2165,7 +2806,7 @@ impl<'a, K: Ord, V: Default> Entry<'a, K, V> {
 
 }
 
-impl<'a, K: Ord, V> VacantEntry<'a, K, V> {
+impl<'a, K: Ord, V, A: Alloc> VacantEntry<'a, K, V, A> {
     /// Gets a reference to the key that would be used when inserting a value
     /// through the VacantEntry.
     ///
@@ -2228,7 +2869,7 @@ impl<'a, K: Ord, V> VacantEntry<'a, K, V> {
         let mut ins_v;
         let mut ins_edge;
 
-        let mut cur_parent = match self.handle.insert(self.key, value) {
+        let mut cur_parent = match self.handle.insert(self.key, value, self.alloc) {
             (Fit(handle), _) => return handle.into_kv_mut().1,
             (Split(left, k, v, right), ptr) => {
                 ins_k = k;
@@ -2242,7 +2883,7 @@ impl<'a, K: Ord, V> VacantEntry<'a, K, V> {
         loop {
             match cur_parent {
                 Ok(parent) => {
-                    match parent.insert(ins_k, ins_v, ins_edge) {
+                    match parent.insert(ins_k, ins_v, ins_edge, self.alloc) {
                         Fit(_) => return unsafe { &mut *out_ptr },
                         Split(left, k, v, right) => {
                             ins_k = k;
@@ -2253,7 +2894,7 @@ impl<'a, K: Ord, V> VacantEntry<'a, K, V> {
                     }
                 }
                 Err(root) => {
-                    root.push_level().push(ins_k, ins_v, ins_edge);
+                    root.push_level(self.alloc).push(ins_k, ins_v, ins_edge);
                     return unsafe { &mut *out_ptr };
                 }
             }
@@ -2261,7 +2902,7 @@ impl<'a, K: Ord, V> VacantEntry<'a, K, V> {
     }
 }
 
-impl<'a, K: Ord, V> OccupiedEntry<'a, K, V> {
+impl<'a, K: Ord, V, A: Alloc> OccupiedEntry<'a, K, V, A> {
     /// Gets a reference to the key in the entry.
     ///
     /// # Examples
@@ -2437,13 +3078,13 @@ impl<'a, K: Ord, V> OccupiedEntry<'a, K, V> {
         // Handle underflow
         let mut cur_node = small_leaf.forget_type();
         while cur_node.len() < node::CAPACITY / 2 {
-            match handle_underfull_node(cur_node) {
+            match handle_underfull_node(cur_node, self.alloc) {
                 AtRoot => break,
                 EmptyParent(_) => unreachable!(),
                 Merged(parent) => {
                     if parent.len() == 0 {
                         // We must be at the root
-                        parent.into_root_mut().pop_level();
+                        parent.into_root_mut().pop_level(self.alloc);
                         break;
                     } else {
                         cur_node = parent.forget_type();
@@ -2457,6 +3098,24 @@ impl<'a, K: Ord, V> OccupiedEntry<'a, K, V> {
     }
 }
 
+/// Forwards to a borrowed allocator, so that a tree detached from its map can still be torn
+/// down through `IntoIter`.
+struct AllocRef<'a, A: 'a>(&'a mut A);
+
+unsafe impl<'a, A: Alloc> Alloc for AllocRef<'a, A> {
+    unsafe fn alloc(&mut self, layout: Layout) -> Result<*mut u8, AllocErr> {
+        self.0.alloc(layout)
+    }
+
+    unsafe fn dealloc(&mut self, ptr: *mut u8, layout: Layout) {
+        self.0.dealloc(ptr, layout)
+    }
+
+    fn oom(&mut self, err: AllocErr) -> ! {
+        self.0.oom(err)
+    }
+}
+
 enum UnderflowResult<'a, K, V> {
     AtRoot,
     EmptyParent(NodeRef<marker::Mut<'a>, K, V, marker::Internal>),
@@ -2464,8 +3123,10 @@ enum UnderflowResult<'a, K, V> {
     Stole(NodeRef<marker::Mut<'a>, K, V, marker::Internal>),
 }
 
-fn handle_underfull_node<'a, K, V>(node: NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal>)
-                                   -> UnderflowResult<'a, K, V> {
+fn handle_underfull_node<'a, K, V, A: Alloc>(
+    node: NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal>,
+    a: &mut A
+) -> UnderflowResult<'a, K, V> {
     let parent = if let Ok(parent) = node.ascend() {
         parent
     } else {
@@ -2485,7 +3146,7 @@ fn handle_underfull_node<'a, K, V>(node: NodeRef<marker::Mut<'a>, K, V, marker::
     };
 
     if handle.can_merge() {
-        Merged(handle.merge().into_node())
+        Merged(handle.merge(a).into_node())
     } else {
         if is_left {
             handle.steal_left();
@@ -256,7 +256,7 @@ use core::marker::{Unsize, PhantomData};
 use core::mem::{self, align_of_val, forget, size_of_val, uninitialized};
 use core::ops::Deref;
 use core::ops::CoerceUnsized;
-use core::ptr::{self, NonNull};
+use core::ptr::{self, NonNull, Unique};
 use core::convert::From;
 
 use heap::{Heap, Alloc, Layout, box_free};
@@ -693,7 +693,7 @@ impl<T: ?Sized> Rc<T> {
                 value_size);
 
             // Free the allocation without dropping its contents
-            box_free(bptr);
+            box_free(Unique::new_unchecked(bptr), Heap);
 
             Rc { ptr: NonNull::new_unchecked(ptr), phantom: PhantomData }
         }
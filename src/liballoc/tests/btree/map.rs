@@ -8,6 +8,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use std::cell::Cell;
 use std::collections::BTreeMap;
 use std::collections::Bound::{self, Excluded, Included, Unbounded};
 use std::collections::btree_map::Entry::{Occupied, Vacant};
@@ -15,6 +16,7 @@ use std::rc::Rc;
 
 use std::iter::FromIterator;
 use super::DeterministicRng;
+use super::super::CountingAlloc;
 
 #[test]
 fn test_basic_large() {
@@ -360,6 +362,118 @@ fn test_borrow() {
     }
 }
 
+#[test]
+fn test_cursor() {
+    let map: BTreeMap<_, _> = (0..100).map(|i| (i * 2, i)).collect();
+
+    let mut cur = map.lower_bound(Included(&10));
+    assert_eq!(cur.key_value(), Some((&10, &5)));
+    assert_eq!(cur.peek_prev(), Some((&8, &4)));
+    assert_eq!(cur.peek_next(), Some((&12, &6)));
+    cur.move_next();
+    assert_eq!(cur.key(), Some(&12));
+    cur.move_prev();
+    cur.move_prev();
+    assert_eq!(cur.key(), Some(&8));
+
+    assert_eq!(map.lower_bound(Included(&11)).key(), Some(&12));
+    assert_eq!(map.lower_bound(Excluded(&10)).key(), Some(&12));
+    assert_eq!(map.lower_bound(Excluded(&198)).key(), None);
+    assert_eq!(map.upper_bound(Included(&11)).key(), Some(&10));
+    assert_eq!(map.upper_bound(Excluded(&10)).key(), Some(&8));
+    assert_eq!(map.upper_bound(Excluded(&0)).key(), None);
+
+    // The cursor wraps around through the "ghost" non-element.
+    let mut cur = map.upper_bound(Unbounded);
+    assert_eq!(cur.key(), Some(&198));
+    cur.move_next();
+    assert_eq!(cur.key(), None);
+    assert_eq!(cur.peek_next(), Some((&0, &0)));
+    assert_eq!(cur.peek_prev(), Some((&198, &99)));
+    cur.move_next();
+    assert_eq!(cur.key(), Some(&0));
+    cur.move_prev();
+    cur.move_prev();
+    assert_eq!(cur.key(), Some(&198));
+
+    // Walking the whole map visits every element once, in order.
+    let mut cur = map.lower_bound(Unbounded);
+    let mut keys = vec![];
+    while let Some(&k) = cur.key() {
+        keys.push(k);
+        cur.move_next();
+    }
+    assert_eq!(keys, map.keys().cloned().collect::<Vec<_>>());
+
+    let empty: BTreeMap<i32, i32> = BTreeMap::new();
+    let mut cur = empty.lower_bound(Unbounded);
+    assert_eq!(cur.key(), None);
+    cur.move_next();
+    assert_eq!(cur.key(), None);
+    cur.move_prev();
+    assert_eq!(cur.key(), None);
+}
+
+#[test]
+fn test_cursor_mut() {
+    let mut map: BTreeMap<_, _> = (0..1000).map(|i| (i, i)).collect();
+
+    {
+        let mut cur = map.lower_bound_mut(Included(&500));
+        assert_eq!(cur.key_value(), Some((&500, &500)));
+        *cur.value_mut().unwrap() = 5000;
+        cur.move_next();
+        assert_eq!(cur.key(), Some(&501));
+        cur.move_prev();
+        assert_eq!(cur.value(), Some(&5000));
+    }
+    assert_eq!(map[&500], 5000);
+
+    // Remove every odd key, walking forwards.
+    {
+        let mut cur = map.lower_bound_mut(Unbounded);
+        loop {
+            let k = match cur.key() {
+                Some(&k) => k,
+                None => break,
+            };
+            if k % 2 == 1 {
+                assert_eq!(cur.remove_current().map(|(k, _)| k), Some(k));
+            } else {
+                cur.move_next();
+            }
+        }
+        assert_eq!(cur.remove_current(), None);
+    }
+    assert_eq!(map.len(), 500);
+    assert!(map.keys().all(|k| k % 2 == 0));
+
+    // Remove every key that is a multiple of 4, walking backwards.
+    {
+        let mut cur = map.upper_bound_mut(Unbounded);
+        loop {
+            let k = match cur.key() {
+                Some(&k) => k,
+                None => break,
+            };
+            if k % 4 == 0 {
+                assert_eq!(cur.remove_current_and_move_back().map(|(k, _)| k), Some(k));
+            } else {
+                cur.move_prev();
+            }
+        }
+    }
+    assert_eq!(map.len(), 250);
+    assert_eq!(map.keys().cloned().collect::<Vec<_>>(),
+               (0..250).map(|i| i * 4 + 2).collect::<Vec<_>>());
+
+    {
+        let mut cur = map.lower_bound_mut(Unbounded);
+        while cur.remove_current().is_some() {}
+    }
+    assert!(map.is_empty());
+}
+
 #[test]
 fn test_entry() {
     let xs = [(1, 10), (2, 20), (3, 30), (4, 40), (5, 50), (6, 60)];
@@ -685,3 +799,42 @@ fn test_split_off_large_random_sorted() {
     assert!(map.into_iter().eq(data.clone().into_iter().filter(|x| x.0 < key)));
     assert!(right.into_iter().eq(data.into_iter().filter(|x| x.0 >= key)));
 }
+
+#[test]
+fn test_alloc_param() {
+    let live = Rc::new(Cell::new(0));
+    let a = CountingAlloc { live: live.clone() };
+    {
+        let mut map = BTreeMap::new_in(a);
+        assert_eq!(live.get(), 1);
+        map.extend((0..100).map(|i| (i, i * 10)));
+        *map.entry(42).or_insert(0) += 1;
+        assert_eq!(map[&42], 421);
+
+        let nodes = live.get();
+        let copy = map.clone();
+        assert_eq!(live.get(), 2 * nodes);
+        assert_eq!(copy, map);
+        drop(copy);
+        assert_eq!(live.get(), nodes);
+
+        let mut right = map.split_off(&50);
+        assert_eq!(map.len(), 50);
+        for i in 50..75 {
+            assert_eq!(right.remove(&i), Some(i * 10));
+        }
+        assert_eq!(right.lower_bound_mut(Included(&75)).remove_current(), Some((75, 750)));
+
+        map.append(&mut right);
+        assert!(right.is_empty());
+        assert_eq!(map.len(), 74);
+
+        map.clear();
+        assert_eq!(live.get(), 2);
+
+        map.insert(1, 10);
+        assert_eq!(map.into_iter().collect::<Vec<_>>(), [(1, 10)]);
+        assert_eq!(live.get(), 1);
+    }
+    assert_eq!(live.get(), 0);
+}
@@ -14,6 +14,7 @@
 #![feature(alloc_system)]
 #![feature(attr_literals)]
 #![feature(box_syntax)]
+#![feature(btree_cursors)]
 #![feature(inclusive_range_syntax)]
 #![feature(collection_placement)]
 #![feature(const_fn)]
@@ -28,6 +29,7 @@
 #![feature(splice)]
 #![feature(str_escape)]
 #![feature(string_retain)]
+#![feature(try_reserve)]
 #![feature(unboxed_closures)]
 #![feature(unicode)]
 #![feature(exact_chunks)]
@@ -36,8 +38,11 @@ extern crate alloc_system;
 extern crate std_unicode;
 extern crate rand;
 
+use std::cell::Cell;
 use std::hash::{Hash, Hasher};
+use std::heap::{Alloc, AllocErr, Heap, Layout};
 use std::collections::hash_map::DefaultHasher;
+use std::rc::Rc;
 
 mod binary_heap;
 mod btree;
@@ -57,6 +62,25 @@ fn hash<T: Hash>(t: &T) -> u64 {
     s.finish()
 }
 
+// Forwards to `Heap`, keeping track of the number of live allocations.
+#[derive(Clone)]
+struct CountingAlloc {
+    live: Rc<Cell<usize>>,
+}
+
+unsafe impl Alloc for CountingAlloc {
+    unsafe fn alloc(&mut self, layout: Layout) -> Result<*mut u8, AllocErr> {
+        let ptr = Heap.alloc(layout)?;
+        self.live.set(self.live.get() + 1);
+        Ok(ptr)
+    }
+
+    unsafe fn dealloc(&mut self, ptr: *mut u8, layout: Layout) {
+        self.live.set(self.live.get() - 1);
+        Heap.dealloc(ptr, layout)
+    }
+}
+
 // FIXME: Instantiated functions with i128 in the signature is not supported in Emscripten.
 // See https://github.com/kripken/emscripten-fastcomp/issues/169
 #[cfg(not(target_os = "emscripten"))]
@@ -72,3 +96,28 @@ fn test_boxed_hasher() {
     5u32.hash(&mut hasher_2);
     assert_eq!(ordinary_hash, hasher_2.finish());
 }
+
+#[test]
+fn test_box_alloc_param() {
+    let live = Rc::new(Cell::new(0));
+
+    let mut b = Box::new_in(vec![1, 2], CountingAlloc { live: live.clone() });
+    assert_eq!(live.get(), 1);
+    b.push(3);
+    assert_eq!(*b, [1, 2, 3]);
+    assert_eq!(Box::allocator(&b).live.get(), 1);
+    drop(b);
+    assert_eq!(live.get(), 0);
+
+    // Zero-sized values aren't allocated.
+    let unit = Box::new_in((), CountingAlloc { live: live.clone() });
+    assert_eq!(live.get(), 0);
+    drop(unit);
+
+    // Moving the value out frees the box.
+    let b = Box::new_in(String::from("moved"), CountingAlloc { live: live.clone() });
+    assert_eq!(live.get(), 1);
+    let s = *b;
+    assert_eq!(live.get(), 0);
+    assert_eq!(s, "moved");
+}
@@ -9,6 +9,8 @@
 // except according to those terms.
 
 use std::borrow::Cow;
+use std::collections::CollectionAllocErr;
+use std::usize;
 
 pub trait IntoCow<'a, B: ?Sized> where B: ToOwned {
     fn into_cow(self) -> Cow<'a, B>;
@@ -498,6 +500,21 @@ fn test_extend_ref() {
     assert_eq!(&a, "foobar");
 }
 
+#[test]
+fn test_try_reserve() {
+    let mut s = String::new();
+    assert_eq!(s.try_reserve(16), Ok(()));
+    assert!(s.capacity() >= 16);
+
+    s.push('a');
+    assert_eq!(s.try_reserve(usize::MAX), Err(CollectionAllocErr::CapacityOverflow));
+    assert_eq!(s.try_reserve_exact(usize::MAX), Err(CollectionAllocErr::CapacityOverflow));
+    assert_eq!(s, "a");
+
+    assert_eq!(s.try_reserve_exact(32), Ok(()));
+    assert!(s.capacity() >= 33);
+}
+
 #[test]
 fn test_into_boxed_str() {
     let xs = String::from("hello my name is bob");
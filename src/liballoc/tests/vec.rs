@@ -9,10 +9,16 @@
 // except according to those terms.
 
 use std::borrow::Cow;
+use std::cell::Cell;
+use std::collections::CollectionAllocErr;
 use std::mem::size_of;
 use std::panic;
+use std::usize;
+use std::rc::Rc;
 use std::vec::{Drain, IntoIter};
 
+use super::CountingAlloc;
+
 struct DropCounter<'a> {
     count: &'a mut u32,
 }
@@ -78,6 +84,22 @@ fn test_reserve() {
     assert!(v.capacity() >= 33)
 }
 
+#[test]
+fn test_try_reserve() {
+    let mut v: Vec<u8> = Vec::new();
+    assert_eq!(v.try_reserve(16), Ok(()));
+    assert!(v.capacity() >= 16);
+
+    v.push(1);
+    assert_eq!(v.try_reserve(usize::MAX), Err(CollectionAllocErr::CapacityOverflow));
+    assert_eq!(v.try_reserve_exact(usize::MAX), Err(CollectionAllocErr::CapacityOverflow));
+    assert_eq!(v, [1]);
+
+    // The element count fits in `usize`, but the size in bytes does not.
+    let mut v: Vec<u32> = Vec::new();
+    assert_eq!(v.try_reserve(usize::MAX / 2), Err(CollectionAllocErr::CapacityOverflow));
+}
+
 #[test]
 fn test_extend() {
     let mut v = Vec::new();
@@ -965,3 +987,34 @@ fn drain_filter_complex() {
         assert_eq!(vec, vec![1, 3, 5, 7, 9, 11, 13, 15, 17, 19]);
     }
 }
+
+#[test]
+fn test_alloc_param() {
+    let live = Rc::new(Cell::new(0));
+    let a = CountingAlloc { live: live.clone() };
+    {
+        let mut vec = Vec::new_in(a);
+        assert_eq!(live.get(), 0);
+        vec.extend(0..10);
+        vec.push(10);
+        assert_eq!(live.get(), 1);
+        assert_eq!(vec, (0..11).collect::<Vec<_>>());
+
+        let tail = vec.split_off(5);
+        assert_eq!(live.get(), 2);
+        assert_eq!(tail, [5, 6, 7, 8, 9, 10]);
+
+        let copy = vec.clone();
+        assert_eq!(live.get(), 3);
+        assert_eq!(copy, vec);
+
+        let mut iter = tail.into_iter();
+        assert_eq!(iter.next(), Some(5));
+        drop(iter);
+        assert_eq!(live.get(), 2);
+
+        vec.drain(1..3);
+        assert_eq!(vec, [0, 3, 4]);
+    }
+    assert_eq!(live.get(), 0);
+}
@@ -8,9 +8,14 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use std::collections::VecDeque;
+use std::cell::Cell;
+use std::collections::{CollectionAllocErr, VecDeque};
 use std::fmt::Debug;
 use std::collections::vec_deque::{Drain};
+use std::rc::Rc;
+use std::usize;
+
+use super::CountingAlloc;
 
 use self::Taggy::*;
 use self::Taggypar::*;
@@ -253,6 +258,25 @@ fn test_reserve() {
     assert!(d.capacity() >= 51);
 }
 
+#[test]
+fn test_try_reserve() {
+    let mut d: VecDeque<u8> = VecDeque::new();
+    assert_eq!(d.try_reserve(16), Ok(()));
+    assert!(d.capacity() >= 16);
+
+    d.push_back(1);
+    assert_eq!(d.try_reserve(usize::MAX), Err(CollectionAllocErr::CapacityOverflow));
+    assert_eq!(d.try_reserve_exact(usize::MAX), Err(CollectionAllocErr::CapacityOverflow));
+    assert_eq!(d.iter().cloned().collect::<Vec<_>>(), [1]);
+
+    assert_eq!(d.try_reserve_exact(50), Ok(()));
+    assert!(d.capacity() >= 51);
+
+    // The element count fits in `usize`, but the size in bytes does not.
+    let mut d: VecDeque<u32> = VecDeque::new();
+    assert_eq!(d.try_reserve(usize::MAX / 2), Err(CollectionAllocErr::CapacityOverflow));
+}
+
 #[test]
 fn test_swap() {
     let mut d: VecDeque<_> = (0..5).collect();
@@ -1022,3 +1046,35 @@ fn test_placement_in() {
     }
     assert_eq!(buf, [5,4,3,1,2,6]);
 }
+
+#[test]
+fn test_alloc_param() {
+    let live = Rc::new(Cell::new(0));
+    let a = CountingAlloc { live: live.clone() };
+    {
+        let mut deq = VecDeque::new_in(a);
+        assert_eq!(live.get(), 1);
+        deq.extend(1..10);
+        deq.push_front(0);
+        assert_eq!(deq, (0..10).collect::<Vec<_>>());
+
+        let tail = deq.split_off(5);
+        assert_eq!(live.get(), 2);
+        assert_eq!(tail, [5, 6, 7, 8, 9]);
+
+        let copy = deq.clone();
+        assert_eq!(live.get(), 3);
+        assert_eq!(copy, deq);
+
+        let vec = Vec::from(tail);
+        assert_eq!(live.get(), 3);
+        assert_eq!(vec, [5, 6, 7, 8, 9]);
+        let tail = VecDeque::from(vec);
+        drop(tail);
+        assert_eq!(live.get(), 2);
+
+        deq.drain(1..3);
+        assert_eq!(deq, [0, 3, 4]);
+    }
+    assert_eq!(live.get(), 0);
+}
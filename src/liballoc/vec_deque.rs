@@ -29,6 +29,8 @@ use core::slice;
 use core::hash::{Hash, Hasher};
 use core::cmp;
 
+use allocator::CollectionAllocErr;
+use heap::{Alloc, Heap};
 use raw_vec::RawVec;
 
 use super::range::RangeArgument;
@@ -54,7 +56,7 @@ const MAXIMUM_ZST_CAPACITY: usize = 1 << (64 - 1); // Largest possible power of
 /// [`extend`]: #method.extend
 /// [`append`]: #method.append
 #[stable(feature = "rust1", since = "1.0.0")]
-pub struct VecDeque<T> {
+pub struct VecDeque<T, A: Alloc = Heap> {
     // tail and head are pointers into the buffer. Tail always points
     // to the first element that could be read, Head always points
     // to where data should be written.
@@ -62,18 +64,20 @@ pub struct VecDeque<T> {
     // is defined as the distance between the two.
     tail: usize,
     head: usize,
-    buf: RawVec<T>,
+    buf: RawVec<T, A>,
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<T: Clone> Clone for VecDeque<T> {
-    fn clone(&self) -> VecDeque<T> {
-        self.iter().cloned().collect()
+impl<T: Clone, A: Alloc + Clone> Clone for VecDeque<T, A> {
+    fn clone(&self) -> VecDeque<T, A> {
+        let mut deq = VecDeque::with_capacity_in(self.len(), self.buf.alloc().clone());
+        deq.extend(self.iter().cloned());
+        deq
     }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-unsafe impl<#[may_dangle] T> Drop for VecDeque<T> {
+unsafe impl<#[may_dangle] T, A: Alloc> Drop for VecDeque<T, A> {
     fn drop(&mut self) {
         let (front, back) = self.as_mut_slices();
         unsafe {
@@ -94,7 +98,7 @@ impl<T> Default for VecDeque<T> {
     }
 }
 
-impl<T> VecDeque<T> {
+impl<T, A: Alloc> VecDeque<T, A> {
     /// Marginally more convenient
     #[inline]
     fn ptr(&self) -> *mut T {
@@ -390,6 +394,47 @@ impl<T> VecDeque<T> {
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn with_capacity(n: usize) -> VecDeque<T> {
+        VecDeque::with_capacity_in(n, Heap)
+    }
+}
+
+impl<T, A: Alloc> VecDeque<T, A> {
+    /// Like `new` but parameterized over the choice of allocator for the
+    /// returned `VecDeque`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    ///
+    /// use std::collections::VecDeque;
+    /// use std::heap::Heap;
+    ///
+    /// let mut buf: VecDeque<u32, Heap> = VecDeque::new_in(Heap);
+    /// buf.push_back(1);
+    /// assert_eq!(buf, [1]);
+    /// ```
+    #[unstable(feature = "allocator_api", issue = "32838")]
+    pub fn new_in(a: A) -> VecDeque<T, A> {
+        VecDeque::with_capacity_in(INITIAL_CAPACITY, a)
+    }
+
+    /// Like `with_capacity` but parameterized over the choice of allocator
+    /// for the returned `VecDeque`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    ///
+    /// use std::collections::VecDeque;
+    /// use std::heap::Heap;
+    ///
+    /// let buf: VecDeque<u32, Heap> = VecDeque::with_capacity_in(10, Heap);
+    /// assert!(buf.capacity() >= 10);
+    /// ```
+    #[unstable(feature = "allocator_api", issue = "32838")]
+    pub fn with_capacity_in(n: usize, a: A) -> VecDeque<T, A> {
         // +1 since the ringbuffer always leaves one space empty
         let cap = cmp::max(n + 1, MINIMUM_CAPACITY + 1).next_power_of_two();
         assert!(cap > n, "capacity overflow");
@@ -397,10 +442,17 @@ impl<T> VecDeque<T> {
         VecDeque {
             tail: 0,
             head: 0,
-            buf: RawVec::with_capacity(cap),
+            buf: RawVec::with_capacity_in(cap, a),
         }
     }
 
+    /// Returns a reference to the allocator backing this `VecDeque`.
+    #[inline]
+    #[unstable(feature = "allocator_api", issue = "32838")]
+    pub fn allocator(&self) -> &A {
+        self.buf.alloc()
+    }
+
     /// Retrieves an element in the `VecDeque` by index.
     ///
     /// Element at index 0 is the front of the queue.
@@ -566,6 +618,97 @@ impl<T> VecDeque<T> {
         }
     }
 
+    /// Tries to reserve the minimum capacity for exactly `additional` more elements to
+    /// be inserted in the given `VecDeque<T>`. After calling `try_reserve_exact`,
+    /// capacity will be greater than or equal to `self.len() + additional`.
+    /// Does nothing if the capacity is already sufficient.
+    ///
+    /// Note that the allocator may give the collection more space than it
+    /// requests. Therefore capacity can not be relied upon to be precisely
+    /// minimal. Prefer `try_reserve` if future insertions are expected.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    /// use std::collections::CollectionAllocErr;
+    /// use std::collections::VecDeque;
+    ///
+    /// fn process_data(data: &[u32]) -> Result<VecDeque<u32>, CollectionAllocErr> {
+    ///     let mut output = VecDeque::new();
+    ///
+    ///     // Pre-reserve the memory, exiting if we can't
+    ///     output.try_reserve_exact(data.len())?;
+    ///
+    ///     // Now we know this can't OOM in the middle of our complex work
+    ///     output.extend(data.iter().map(|&val| {
+    ///         val * 2 + 5 // very complicated
+    ///     }));
+    ///
+    ///     Ok(output)
+    /// }
+    /// # process_data(&[1, 2, 3]).expect("why is the test harness OOMing on 12 bytes?");
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue = "0")]
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), CollectionAllocErr> {
+        self.try_reserve(additional)
+    }
+
+    /// Tries to reserve capacity for at least `additional` more elements to be inserted
+    /// in the given `VecDeque<T>`. The collection may reserve more space to avoid
+    /// frequent reallocations. After calling `try_reserve`, capacity will be
+    /// greater than or equal to `self.len() + additional`. Does nothing if
+    /// capacity is already sufficient.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    /// use std::collections::CollectionAllocErr;
+    /// use std::collections::VecDeque;
+    ///
+    /// fn process_data(data: &[u32]) -> Result<VecDeque<u32>, CollectionAllocErr> {
+    ///     let mut output = VecDeque::new();
+    ///
+    ///     // Pre-reserve the memory, exiting if we can't
+    ///     output.try_reserve(data.len())?;
+    ///
+    ///     // Now we know this can't OOM in the middle of our complex work
+    ///     output.extend(data.iter().map(|&val| {
+    ///         val * 2 + 5 // very complicated
+    ///     }));
+    ///
+    ///     Ok(output)
+    /// }
+    /// # process_data(&[1, 2, 3]).expect("why is the test harness OOMing on 12 bytes?");
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue = "0")]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), CollectionAllocErr> {
+        let old_cap = self.cap();
+        let used_cap = self.len() + 1;
+        let new_cap = used_cap.checked_add(additional)
+            .and_then(|needed_cap| needed_cap.checked_next_power_of_two())
+            .ok_or(CollectionAllocErr::CapacityOverflow)?;
+
+        if new_cap > old_cap {
+            self.buf.try_reserve_exact(used_cap, new_cap - used_cap)?;
+            unsafe {
+                self.handle_cap_increase(old_cap);
+            }
+        }
+        Ok(())
+    }
+
     /// Shrinks the capacity of the `VecDeque` as much as possible.
     ///
     /// It will drop down as close as possible to the length but the allocator may still inform the
@@ -843,7 +986,7 @@ impl<T> VecDeque<T> {
     /// ```
     #[inline]
     #[stable(feature = "drain", since = "1.6.0")]
-    pub fn drain<R>(&mut self, range: R) -> Drain<T>
+    pub fn drain<R>(&mut self, range: R) -> Drain<T, A>
         where R: RangeArgument<usize>
     {
         // Memory safety
@@ -1649,12 +1792,12 @@ impl<T> VecDeque<T> {
     /// ```
     #[inline]
     #[stable(feature = "split_off", since = "1.4.0")]
-    pub fn split_off(&mut self, at: usize) -> Self {
+    pub fn split_off(&mut self, at: usize) -> Self where A: Clone {
         let len = self.len();
         assert!(at <= len, "`at` out of bounds");
 
         let other_len = len - at;
-        let mut other = VecDeque::with_capacity(other_len);
+        let mut other = VecDeque::with_capacity_in(other_len, self.buf.alloc().clone());
 
         unsafe {
             let (first_half, second_half) = self.as_slices();
@@ -1783,7 +1926,7 @@ impl<T> VecDeque<T> {
     #[unstable(feature = "collection_placement",
                reason = "placement protocol is subject to change",
                issue = "30172")]
-    pub fn place_back(&mut self) -> PlaceBack<T> {
+    pub fn place_back(&mut self) -> PlaceBack<T, A> {
         PlaceBack { vec_deque: self }
     }
 
@@ -1808,12 +1951,12 @@ impl<T> VecDeque<T> {
     #[unstable(feature = "collection_placement",
                reason = "placement protocol is subject to change",
                issue = "30172")]
-    pub fn place_front(&mut self) -> PlaceFront<T> {
+    pub fn place_front(&mut self) -> PlaceFront<T, A> {
         PlaceFront { vec_deque: self }
     }
 }
 
-impl<T: Clone> VecDeque<T> {
+impl<T: Clone, A: Alloc> VecDeque<T, A> {
     /// Modifies the `VecDeque` in-place so that `len()` is equal to new_len,
     /// either by removing excess elements or by appending clones of `value` to the back.
     ///
@@ -2095,12 +2238,12 @@ impl<'a, T> FusedIterator for IterMut<'a, T> {}
 /// [`VecDeque`]: struct.VecDeque.html
 #[derive(Clone)]
 #[stable(feature = "rust1", since = "1.0.0")]
-pub struct IntoIter<T> {
-    inner: VecDeque<T>,
+pub struct IntoIter<T, A: Alloc = Heap> {
+    inner: VecDeque<T, A>,
 }
 
 #[stable(feature = "collection_debug", since = "1.17.0")]
-impl<T: fmt::Debug> fmt::Debug for IntoIter<T> {
+impl<T: fmt::Debug, A: Alloc> fmt::Debug for IntoIter<T, A> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_tuple("IntoIter")
          .field(&self.inner)
@@ -2109,7 +2252,7 @@ impl<T: fmt::Debug> fmt::Debug for IntoIter<T> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<T> Iterator for IntoIter<T> {
+impl<T, A: Alloc> Iterator for IntoIter<T, A> {
     type Item = T;
 
     #[inline]
@@ -2125,7 +2268,7 @@ impl<T> Iterator for IntoIter<T> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<T> DoubleEndedIterator for IntoIter<T> {
+impl<T, A: Alloc> DoubleEndedIterator for IntoIter<T, A> {
     #[inline]
     fn next_back(&mut self) -> Option<T> {
         self.inner.pop_back()
@@ -2133,14 +2276,14 @@ impl<T> DoubleEndedIterator for IntoIter<T> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<T> ExactSizeIterator for IntoIter<T> {
+impl<T, A: Alloc> ExactSizeIterator for IntoIter<T, A> {
     fn is_empty(&self) -> bool {
         self.inner.is_empty()
     }
 }
 
 #[unstable(feature = "fused", issue = "35602")]
-impl<T> FusedIterator for IntoIter<T> {}
+impl<T, A: Alloc> FusedIterator for IntoIter<T, A> {}
 
 /// A draining iterator over the elements of a `VecDeque`.
 ///
@@ -2150,15 +2293,15 @@ impl<T> FusedIterator for IntoIter<T> {}
 /// [`drain`]: struct.VecDeque.html#method.drain
 /// [`VecDeque`]: struct.VecDeque.html
 #[stable(feature = "drain", since = "1.6.0")]
-pub struct Drain<'a, T: 'a> {
+pub struct Drain<'a, T: 'a, A: Alloc + 'a = Heap> {
     after_tail: usize,
     after_head: usize,
     iter: Iter<'a, T>,
-    deque: NonNull<VecDeque<T>>,
+    deque: NonNull<VecDeque<T, A>>,
 }
 
 #[stable(feature = "collection_debug", since = "1.17.0")]
-impl<'a, T: 'a + fmt::Debug> fmt::Debug for Drain<'a, T> {
+impl<'a, T: 'a + fmt::Debug, A: Alloc> fmt::Debug for Drain<'a, T, A> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_tuple("Drain")
          .field(&self.after_tail)
@@ -2169,12 +2312,12 @@ impl<'a, T: 'a + fmt::Debug> fmt::Debug for Drain<'a, T> {
 }
 
 #[stable(feature = "drain", since = "1.6.0")]
-unsafe impl<'a, T: Sync> Sync for Drain<'a, T> {}
+unsafe impl<'a, T: Sync, A: Alloc + Sync> Sync for Drain<'a, T, A> {}
 #[stable(feature = "drain", since = "1.6.0")]
-unsafe impl<'a, T: Send> Send for Drain<'a, T> {}
+unsafe impl<'a, T: Send, A: Alloc + Send> Send for Drain<'a, T, A> {}
 
 #[stable(feature = "drain", since = "1.6.0")]
-impl<'a, T: 'a> Drop for Drain<'a, T> {
+impl<'a, T: 'a, A: Alloc> Drop for Drain<'a, T, A> {
     fn drop(&mut self) {
         for _ in self.by_ref() {}
 
@@ -2221,7 +2364,7 @@ impl<'a, T: 'a> Drop for Drain<'a, T> {
 }
 
 #[stable(feature = "drain", since = "1.6.0")]
-impl<'a, T: 'a> Iterator for Drain<'a, T> {
+impl<'a, T: 'a, A: Alloc> Iterator for Drain<'a, T, A> {
     type Item = T;
 
     #[inline]
@@ -2236,7 +2379,7 @@ impl<'a, T: 'a> Iterator for Drain<'a, T> {
 }
 
 #[stable(feature = "drain", since = "1.6.0")]
-impl<'a, T: 'a> DoubleEndedIterator for Drain<'a, T> {
+impl<'a, T: 'a, A: Alloc> DoubleEndedIterator for Drain<'a, T, A> {
     #[inline]
     fn next_back(&mut self) -> Option<T> {
         self.iter.next_back().map(|elt| unsafe { ptr::read(elt) })
@@ -2244,14 +2387,14 @@ impl<'a, T: 'a> DoubleEndedIterator for Drain<'a, T> {
 }
 
 #[stable(feature = "drain", since = "1.6.0")]
-impl<'a, T: 'a> ExactSizeIterator for Drain<'a, T> {}
+impl<'a, T: 'a, A: Alloc> ExactSizeIterator for Drain<'a, T, A> {}
 
 #[unstable(feature = "fused", issue = "35602")]
-impl<'a, T: 'a> FusedIterator for Drain<'a, T> {}
+impl<'a, T: 'a, A: Alloc> FusedIterator for Drain<'a, T, A> {}
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<A: PartialEq> PartialEq for VecDeque<A> {
-    fn eq(&self, other: &VecDeque<A>) -> bool {
+impl<T: PartialEq, A: Alloc> PartialEq for VecDeque<T, A> {
+    fn eq(&self, other: &VecDeque<T, A>) -> bool {
         if self.len() != other.len() {
             return false;
         }
@@ -2289,7 +2432,7 @@ impl<A: PartialEq> PartialEq for VecDeque<A> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<A: Eq> Eq for VecDeque<A> {}
+impl<T: Eq, A: Alloc> Eq for VecDeque<T, A> {}
 
 macro_rules! __impl_slice_eq1 {
     ($Lhs: ty, $Rhs: ty) => {
@@ -2297,7 +2440,9 @@ macro_rules! __impl_slice_eq1 {
     };
     ($Lhs: ty, $Rhs: ty, $Bound: ident) => {
         #[stable(feature = "vec-deque-partial-eq-slice", since = "1.17.0")]
-        impl<'a, 'b, A: $Bound, B> PartialEq<$Rhs> for $Lhs where A: PartialEq<B> {
+        impl<'a, 'b, A: $Bound, B, AllocA: Alloc> PartialEq<$Rhs> for $Lhs
+            where A: PartialEq<B>
+        {
             fn eq(&self, other: &$Rhs) -> bool {
                 if self.len() != other.len() {
                     return false;
@@ -2310,16 +2455,16 @@ macro_rules! __impl_slice_eq1 {
     }
 }
 
-__impl_slice_eq1! { VecDeque<A>, Vec<B> }
-__impl_slice_eq1! { VecDeque<A>, &'b [B] }
-__impl_slice_eq1! { VecDeque<A>, &'b mut [B] }
+__impl_slice_eq1! { VecDeque<A, AllocA>, Vec<B> }
+__impl_slice_eq1! { VecDeque<A, AllocA>, &'b [B] }
+__impl_slice_eq1! { VecDeque<A, AllocA>, &'b mut [B] }
 
 macro_rules! array_impls {
     ($($N: expr)+) => {
         $(
-            __impl_slice_eq1! { VecDeque<A>, [B; $N] }
-            __impl_slice_eq1! { VecDeque<A>, &'b [B; $N] }
-            __impl_slice_eq1! { VecDeque<A>, &'b mut [B; $N] }
+            __impl_slice_eq1! { VecDeque<A, AllocA>, [B; $N] }
+            __impl_slice_eq1! { VecDeque<A, AllocA>, &'b [B; $N] }
+            __impl_slice_eq1! { VecDeque<A, AllocA>, &'b mut [B; $N] }
         )+
     }
 }
@@ -2332,22 +2477,22 @@ array_impls! {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<A: PartialOrd> PartialOrd for VecDeque<A> {
-    fn partial_cmp(&self, other: &VecDeque<A>) -> Option<Ordering> {
+impl<T: PartialOrd, A: Alloc> PartialOrd for VecDeque<T, A> {
+    fn partial_cmp(&self, other: &VecDeque<T, A>) -> Option<Ordering> {
         self.iter().partial_cmp(other.iter())
     }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<A: Ord> Ord for VecDeque<A> {
+impl<T: Ord, A: Alloc> Ord for VecDeque<T, A> {
     #[inline]
-    fn cmp(&self, other: &VecDeque<A>) -> Ordering {
+    fn cmp(&self, other: &VecDeque<T, A>) -> Ordering {
         self.iter().cmp(other.iter())
     }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<A: Hash> Hash for VecDeque<A> {
+impl<T: Hash, A: Alloc> Hash for VecDeque<T, A> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.len().hash(state);
         let (a, b) = self.as_slices();
@@ -2357,19 +2502,19 @@ impl<A: Hash> Hash for VecDeque<A> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<A> Index<usize> for VecDeque<A> {
-    type Output = A;
+impl<T, A: Alloc> Index<usize> for VecDeque<T, A> {
+    type Output = T;
 
     #[inline]
-    fn index(&self, index: usize) -> &A {
+    fn index(&self, index: usize) -> &T {
         self.get(index).expect("Out of bounds access")
     }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<A> IndexMut<usize> for VecDeque<A> {
+impl<T, A: Alloc> IndexMut<usize> for VecDeque<T, A> {
     #[inline]
-    fn index_mut(&mut self, index: usize) -> &mut A {
+    fn index_mut(&mut self, index: usize) -> &mut T {
         self.get_mut(index).expect("Out of bounds access")
     }
 }
@@ -2386,19 +2531,19 @@ impl<A> FromIterator<A> for VecDeque<A> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<T> IntoIterator for VecDeque<T> {
+impl<T, A: Alloc> IntoIterator for VecDeque<T, A> {
     type Item = T;
-    type IntoIter = IntoIter<T>;
+    type IntoIter = IntoIter<T, A>;
 
     /// Consumes the list into a front-to-back iterator yielding elements by
     /// value.
-    fn into_iter(self) -> IntoIter<T> {
+    fn into_iter(self) -> IntoIter<T, A> {
         IntoIter { inner: self }
     }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<'a, T> IntoIterator for &'a VecDeque<T> {
+impl<'a, T, A: Alloc> IntoIterator for &'a VecDeque<T, A> {
     type Item = &'a T;
     type IntoIter = Iter<'a, T>;
 
@@ -2408,7 +2553,7 @@ impl<'a, T> IntoIterator for &'a VecDeque<T> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<'a, T> IntoIterator for &'a mut VecDeque<T> {
+impl<'a, T, A: Alloc> IntoIterator for &'a mut VecDeque<T, A> {
     type Item = &'a mut T;
     type IntoIter = IterMut<'a, T>;
 
@@ -2418,8 +2563,8 @@ impl<'a, T> IntoIterator for &'a mut VecDeque<T> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<A> Extend<A> for VecDeque<A> {
-    fn extend<T: IntoIterator<Item = A>>(&mut self, iter: T) {
+impl<T, A: Alloc> Extend<T> for VecDeque<T, A> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
         for elt in iter {
             self.push_back(elt);
         }
@@ -2427,25 +2572,26 @@ impl<A> Extend<A> for VecDeque<A> {
 }
 
 #[stable(feature = "extend_ref", since = "1.2.0")]
-impl<'a, T: 'a + Copy> Extend<&'a T> for VecDeque<T> {
+impl<'a, T: 'a + Copy, A: Alloc> Extend<&'a T> for VecDeque<T, A> {
     fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
         self.extend(iter.into_iter().cloned());
     }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<T: fmt::Debug> fmt::Debug for VecDeque<T> {
+impl<T: fmt::Debug, A: Alloc> fmt::Debug for VecDeque<T, A> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_list().entries(self).finish()
     }
 }
 
 #[stable(feature = "vecdeque_vec_conversions", since = "1.10.0")]
-impl<T> From<Vec<T>> for VecDeque<T> {
-    fn from(mut other: Vec<T>) -> Self {
+impl<T, A: Alloc> From<Vec<T, A>> for VecDeque<T, A> {
+    fn from(mut other: Vec<T, A>) -> Self {
         unsafe {
             let other_buf = other.as_mut_ptr();
-            let mut buf = RawVec::from_raw_parts(other_buf, other.capacity());
+            let a = ptr::read(other.allocator());
+            let mut buf = RawVec::from_raw_parts_in(other_buf, other.capacity(), a);
             let len = other.len();
             mem::forget(other);
 
@@ -2467,8 +2613,8 @@ impl<T> From<Vec<T>> for VecDeque<T> {
 }
 
 #[stable(feature = "vecdeque_vec_conversions", since = "1.10.0")]
-impl<T> From<VecDeque<T>> for Vec<T> {
-    fn from(other: VecDeque<T>) -> Self {
+impl<T, A: Alloc> From<VecDeque<T, A>> for Vec<T, A> {
+    fn from(other: VecDeque<T, A>) -> Self {
         unsafe {
             let buf = other.buf.ptr();
             let len = other.len();
@@ -2530,7 +2676,8 @@ impl<T> From<VecDeque<T>> for Vec<T> {
                 }
 
             }
-            let out = Vec::from_raw_parts(buf, len, cap);
+            let a = ptr::read(other.buf.alloc());
+            let out = Vec::from_raw_parts_in(buf, len, cap, a);
             mem::forget(other);
             out
         }
@@ -2545,15 +2692,15 @@ impl<T> From<VecDeque<T>> for Vec<T> {
            reason = "struct name and placement protocol are subject to change",
            issue = "30172")]
 #[derive(Debug)]
-pub struct PlaceBack<'a, T: 'a> {
-    vec_deque: &'a mut VecDeque<T>,
+pub struct PlaceBack<'a, T: 'a, A: Alloc + 'a = Heap> {
+    vec_deque: &'a mut VecDeque<T, A>,
 }
 
 #[unstable(feature = "collection_placement",
            reason = "placement protocol is subject to change",
            issue = "30172")]
-impl<'a, T> Placer<T> for PlaceBack<'a, T> {
-    type Place = PlaceBack<'a, T>;
+impl<'a, T, A: Alloc> Placer<T> for PlaceBack<'a, T, A> {
+    type Place = PlaceBack<'a, T, A>;
 
     fn make_place(self) -> Self {
         self.vec_deque.grow_if_necessary();
@@ -2564,7 +2711,7 @@ impl<'a, T> Placer<T> for PlaceBack<'a, T> {
 #[unstable(feature = "collection_placement",
            reason = "placement protocol is subject to change",
            issue = "30172")]
-impl<'a, T> Place<T> for PlaceBack<'a, T> {
+impl<'a, T, A: Alloc> Place<T> for PlaceBack<'a, T, A> {
     fn pointer(&mut self) -> *mut T {
         unsafe { self.vec_deque.ptr().offset(self.vec_deque.head as isize) }
     }
@@ -2573,7 +2720,7 @@ impl<'a, T> Place<T> for PlaceBack<'a, T> {
 #[unstable(feature = "collection_placement",
            reason = "placement protocol is subject to change",
            issue = "30172")]
-impl<'a, T> InPlace<T> for PlaceBack<'a, T> {
+impl<'a, T, A: Alloc> InPlace<T> for PlaceBack<'a, T, A> {
     type Owner = &'a mut T;
 
     unsafe fn finalize(self) -> &'a mut T {
@@ -2591,15 +2738,15 @@ impl<'a, T> InPlace<T> for PlaceBack<'a, T> {
            reason = "struct name and placement protocol are subject to change",
            issue = "30172")]
 #[derive(Debug)]
-pub struct PlaceFront<'a, T: 'a> {
-    vec_deque: &'a mut VecDeque<T>,
+pub struct PlaceFront<'a, T: 'a, A: Alloc + 'a = Heap> {
+    vec_deque: &'a mut VecDeque<T, A>,
 }
 
 #[unstable(feature = "collection_placement",
            reason = "placement protocol is subject to change",
            issue = "30172")]
-impl<'a, T> Placer<T> for PlaceFront<'a, T> {
-    type Place = PlaceFront<'a, T>;
+impl<'a, T, A: Alloc> Placer<T> for PlaceFront<'a, T, A> {
+    type Place = PlaceFront<'a, T, A>;
 
     fn make_place(self) -> Self {
         self.vec_deque.grow_if_necessary();
@@ -2610,7 +2757,7 @@ impl<'a, T> Placer<T> for PlaceFront<'a, T> {
 #[unstable(feature = "collection_placement",
            reason = "placement protocol is subject to change",
            issue = "30172")]
-impl<'a, T> Place<T> for PlaceFront<'a, T> {
+impl<'a, T, A: Alloc> Place<T> for PlaceFront<'a, T, A> {
     fn pointer(&mut self) -> *mut T {
         let tail = self.vec_deque.wrap_sub(self.vec_deque.tail, 1);
         unsafe { self.vec_deque.ptr().offset(tail as isize) }
@@ -2620,7 +2767,7 @@ impl<'a, T> Place<T> for PlaceFront<'a, T> {
 #[unstable(feature = "collection_placement",
            reason = "placement protocol is subject to change",
            issue = "30172")]
-impl<'a, T> InPlace<T> for PlaceFront<'a, T> {
+impl<'a, T, A: Alloc> InPlace<T> for PlaceFront<'a, T, A> {
     type Owner = &'a mut T;
 
     unsafe fn finalize(self) -> &'a mut T {
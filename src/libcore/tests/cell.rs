@@ -304,3 +304,54 @@ fn refcell_replace_borrows() {
     let _b = x.borrow();
     x.replace(1);
 }
+
+#[test]
+fn once_cell() {
+    let c = OnceCell::new();
+    assert!(c.get().is_none());
+    c.get_or_init(|| 92);
+    assert_eq!(c.get(), Some(&92));
+
+    c.get_or_init(|| panic!("Kabom!"));
+    assert_eq!(c.get(), Some(&92));
+    assert_eq!(c.set(62), Err(62));
+}
+
+#[test]
+fn once_cell_get_mut_take() {
+    let mut c = OnceCell::new();
+    assert!(c.get_mut().is_none());
+    c.set(90).unwrap();
+    *c.get_mut().unwrap() += 2;
+    assert_eq!(c.get(), Some(&92));
+
+    assert_eq!(c.take(), Some(92));
+    assert!(c.get().is_none());
+    c.set(1).unwrap();
+    assert_eq!(c.into_inner(), Some(1));
+}
+
+#[test]
+#[should_panic]
+fn once_cell_reentrant_init() {
+    let c: OnceCell<Box<i32>> = OnceCell::new();
+    c.get_or_init(|| {
+        c.get_or_init(|| Box::new(1));
+        Box::new(2)
+    });
+}
+
+#[test]
+fn lazy_new() {
+    let called = Cell::new(0);
+    let x = Lazy::new(|| {
+        called.set(called.get() + 1);
+        92
+    });
+
+    assert_eq!(called.get(), 0);
+    assert_eq!(*x, 92);
+    assert_eq!(called.get(), 1);
+    assert_eq!(*Lazy::force(&x), 92);
+    assert_eq!(called.get(), 1);
+}
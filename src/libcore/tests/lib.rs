@@ -28,6 +28,7 @@
 #![feature(iter_rfind)]
 #![feature(iter_rfold)]
 #![feature(nonzero)]
+#![feature(once_cell)]
 #![feature(pattern)]
 #![feature(raw)]
 #![feature(refcell_replace_swap)]
@@ -17,7 +17,11 @@ use rustc_data_structures::stable_hasher::{HashStable, StableHasher,
                                            StableHasherResult};
 use std::mem;
 
-impl_stable_hash_for!(struct mir::GeneratorLayout<'tcx> { fields });
+impl_stable_hash_for!(struct mir::GeneratorLayout<'tcx> {
+    fields,
+    slots,
+    variant_fields
+});
 impl_stable_hash_for!(struct mir::SourceInfo { span, scope });
 impl_stable_hash_for!(enum mir::Mutability { Mut, Not });
 impl_stable_hash_for!(enum mir::BorrowKind { Shared, Unique, Mut });
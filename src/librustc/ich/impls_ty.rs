@@ -346,6 +346,12 @@ for ::middle::const_val::ConstVal<'gcx> {
                 def_id.hash_stable(hcx, hasher);
                 substs.hash_stable(hcx, hasher);
             }
+            Pointer(ptr) => {
+                // FIXME `AllocId`s aren't stable across sessions, so this
+                // causes more recompilation than needed.
+                ptr.alloc_id.0.hash_stable(hcx, hasher);
+                ptr.offset.hash_stable(hcx, hasher);
+            }
         }
     }
 }
@@ -384,6 +390,8 @@ for ::middle::const_val::ErrKind<'gcx> {
             MiscBinaryOp |
             MiscCatchAll |
             IndexOpFeatureGated |
+            ExecutionTimeLimitReached |
+            AlreadyReported |
             TypeckError |
             CheckMatchError => {
                 // nothing to do
@@ -42,7 +42,7 @@ use std::default::Default as StdDefault;
 use std::cell::{Ref, RefCell};
 use syntax::ast;
 use syntax_pos::{MultiSpan, Span};
-use errors::DiagnosticBuilder;
+use errors::{Applicability, DiagnosticBuilder};
 use hir;
 use hir::def_id::LOCAL_CRATE;
 use hir::intravisit as hir_visit;
@@ -92,6 +92,16 @@ pub struct BufferedEarlyLint {
     pub ast_id: ast::NodeId,
     pub span: MultiSpan,
     pub msg: String,
+    pub suggestion: Option<BufferedSuggestion>,
+}
+
+/// A suggestion to be attached to a `BufferedEarlyLint` when it is emitted.
+#[derive(PartialEq, RustcEncodable, RustcDecodable, Debug)]
+pub struct BufferedSuggestion {
+    pub span: Span,
+    pub msg: String,
+    pub replacement: String,
+    pub applicability: Applicability,
 }
 
 /// Extra information for a future incompatibility lint. See the call
@@ -499,9 +509,16 @@ impl<'a> EarlyContext<'a> {
 
     fn check_id(&mut self, id: ast::NodeId) {
         for early_lint in self.buffered.take(id) {
-            self.lookup_and_emit(early_lint.lint_id.lint,
-                                 Some(early_lint.span.clone()),
-                                 &early_lint.msg);
+            let mut err = self.lookup(early_lint.lint_id.lint,
+                                      Some(early_lint.span.clone()),
+                                      &early_lint.msg);
+            if let Some(suggestion) = early_lint.suggestion {
+                err.span_suggestion_with_applicability(suggestion.span,
+                                                       &suggestion.msg,
+                                                       suggestion.replacement,
+                                                       suggestion.applicability);
+            }
+            err.emit();
         }
     }
 }
@@ -50,7 +50,8 @@ use util::nodemap::NodeMap;
 
 pub use lint::context::{LateContext, EarlyContext, LintContext, LintStore,
                         check_crate, check_ast_crate,
-                        FutureIncompatibleInfo, BufferedEarlyLint};
+                        FutureIncompatibleInfo, BufferedEarlyLint,
+                        BufferedSuggestion};
 
 /// Specification of a single lint.
 #[derive(Copy, Clone, Debug)]
@@ -378,12 +379,14 @@ impl LintBuffer {
                     lint: &'static Lint,
                     id: ast::NodeId,
                     sp: MultiSpan,
-                    msg: &str) {
+                    msg: &str,
+                    suggestion: Option<BufferedSuggestion>) {
         let early_lint = BufferedEarlyLint {
             lint_id: LintId::of(lint),
             ast_id: id,
             span: sp,
             msg: msg.to_string(),
+            suggestion,
         };
         let arr = self.map.entry(id).or_insert(Vec::new());
         if !arr.contains(&early_lint) {
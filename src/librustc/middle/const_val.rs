@@ -11,6 +11,7 @@
 pub use rustc_const_math::ConstInt;
 
 use hir::def_id::DefId;
+use mir::interpret::MemoryPointer;
 use ty::{self, TyCtxt, layout};
 use ty::subst::Substs;
 use rustc_const_math::*;
@@ -38,6 +39,9 @@ pub enum ConstVal<'tcx> {
     Function(DefId, &'tcx Substs<'tcx>),
     Aggregate(ConstAggregate<'tcx>),
     Unevaluated(DefId, &'tcx Substs<'tcx>),
+    /// A pointer into an allocation interned by miri, e.g. one
+    /// made with `box` while evaluating a constant.
+    Pointer(MemoryPointer),
 }
 
 #[derive(Copy, Clone, Debug, Hash, RustcEncodable, Eq, PartialEq)]
@@ -67,6 +71,18 @@ impl<'tcx> Decodable for ConstAggregate<'tcx> {
     }
 }
 
+impl Encodable for MemoryPointer {
+    fn encode<S: Encoder>(&self, _: &mut S) -> Result<(), S::Error> {
+        bug!("should never encode MemoryPointer {:?}", self)
+    }
+}
+
+impl Decodable for MemoryPointer {
+    fn decode<D: Decoder>(_: &mut D) -> Result<Self, D::Error> {
+        bug!("should never decode MemoryPointer")
+    }
+}
+
 impl<'tcx> ConstVal<'tcx> {
     pub fn to_const_int(&self) -> Option<ConstInt> {
         match *self {
@@ -106,6 +122,13 @@ pub enum ErrKind<'tcx> {
 
     ErroneousReferencedConstant(Box<ConstEvalErr<'tcx>>),
 
+    /// Evaluation took more than `#![const_eval_limit]` steps.
+    ExecutionTimeLimitReached,
+
+    /// The error has already been reported, e.g. by miri
+    /// along with the backtrace of the const fn calls.
+    AlreadyReported,
+
     TypeckError,
     CheckMatchError,
 }
@@ -167,6 +190,10 @@ impl<'a, 'gcx, 'tcx> ConstEvalErr<'tcx> {
             LayoutError(ref err) => Simple(err.to_string().into_cow()),
 
             ErroneousReferencedConstant(_) => simple!("could not evaluate referenced constant"),
+            ExecutionTimeLimitReached => {
+                simple!("reached the configured maximum execution time")
+            }
+            AlreadyReported => simple!("constant evaluation failed"),
 
             TypeckError => simple!("type-checking failed"),
             CheckMatchError => simple!("match-checking failed"),
@@ -192,7 +219,7 @@ impl<'a, 'gcx, 'tcx> ConstEvalErr<'tcx> {
     }
 
     pub fn note(&self,
-        _tcx: TyCtxt<'a, 'gcx, 'tcx>,
+        tcx: TyCtxt<'a, 'gcx, 'tcx>,
         primary_span: Span,
         primary_kind: &str,
         diag: &mut DiagnosticBuilder)
@@ -203,6 +230,10 @@ impl<'a, 'gcx, 'tcx> ConstEvalErr<'tcx> {
             }
         }
 
+        if let ErrKind::ExecutionTimeLimitReached = self.kind {
+            diag.help(&tcx.sess.const_eval_limit_help());
+        }
+
         if !primary_span.contains(self.span) {
             diag.span_note(primary_span,
                         &format!("for {} here", primary_kind));
@@ -215,7 +246,7 @@ impl<'a, 'gcx, 'tcx> ConstEvalErr<'tcx> {
         primary_kind: &str)
     {
         match self.kind {
-            ErrKind::TypeckError | ErrKind::CheckMatchError => return,
+            ErrKind::TypeckError | ErrKind::CheckMatchError | ErrKind::AlreadyReported => return,
             _ => {}
         }
         self.struct_error(tcx, primary_span, primary_kind).emit();
@@ -25,6 +25,8 @@ pub fn update_limits(sess: &Session, krate: &ast::Crate) {
                  "recursion limit");
     update_limit(sess, krate, &sess.type_length_limit, "type_length_limit",
                  "type length limit");
+    update_limit(sess, krate, &sess.const_eval_limit, "const_eval_limit",
+                 "const eval limit");
 }
 
 fn update_limit(sess: &Session, krate: &ast::Crate, limit: &Cell<usize>,
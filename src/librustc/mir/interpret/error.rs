@@ -102,6 +102,14 @@ pub enum EvalErrorKind<'tcx> {
         ptr: MemoryPointer,
         lock: Lock,
     },
+    DataRace {
+        ptr: MemoryPointer,
+        len: u64,
+        access: AccessKind,
+        thread: usize,
+        other_access: AccessKind,
+        other_thread: usize,
+    },
     ValidationFailure(String),
     CalledClosureAsFunction,
     VtableForArgumentlessMethod,
@@ -161,6 +169,8 @@ impl<'tcx> Error for EvalError<'tcx> {
                 "invalid attempt to release write lock",
             DeallocatedLockedMemory { .. } =>
                 "tried to deallocate memory in conflict with a lock",
+            DataRace { .. } =>
+                "data race between two threads",
             ReadPointerAsBytes =>
                 "a raw memory access tried to access part of a pointer value as raw bytes",
             ReadBytesAsPointer =>
@@ -282,6 +292,11 @@ impl<'tcx> fmt::Display for EvalError<'tcx> {
                 write!(f, "tried to deallocate memory at {:?} in conflict with lock {:?}",
                        ptr, lock)
             }
+            DataRace { ptr, len, access, thread, other_access, other_thread } => {
+                write!(f, "{:?} access by thread {} at {:?}, size {}, races with a {:?} access \
+                           by thread {}",
+                       access, thread, ptr, len, other_access, other_thread)
+            }
             ValidationFailure(ref err) => {
                 write!(f, "type validation failed: {}", err)
             }
@@ -1782,7 +1782,8 @@ fn fmt_const_val<W: Write>(fmt: &mut W, const_val: &ConstVal) -> fmt::Result {
         Variant(def_id) |
         Function(def_id, _) => write!(fmt, "{}", item_path_str(def_id)),
         Aggregate(_) => bug!("`ConstVal::{:?}` should not be in MIR", const_val),
-        Unevaluated(..) => write!(fmt, "{:?}", const_val)
+        Unevaluated(..) |
+        Pointer(_) => write!(fmt, "{:?}", const_val)
     }
 }
 
@@ -1880,7 +1881,28 @@ pub struct UnsafetyCheckResult {
 /// The layout of generator state
 #[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
 pub struct GeneratorLayout<'tcx> {
+    /// The locals which are saved in the generator, one field each.
     pub fields: Vec<LocalDecl<'tcx>>,
+
+    /// The storage slot of each field in `fields`. Fields in the same slot are
+    /// never storage-live at the same time, so they are placed at the same offset.
+    pub slots: Vec<usize>,
+
+    /// For each suspension point, in order of their generator states, the
+    /// indices in `fields` of the locals which are live across it.
+    pub variant_fields: Vec<Vec<usize>>,
+}
+
+impl<'tcx> GeneratorLayout<'tcx> {
+    /// The number of distinct storage slots used by the saved locals.
+    pub fn slot_count(&self) -> usize {
+        self.slots.iter().map(|&slot| slot + 1).max().unwrap_or(0)
+    }
+
+    /// Returns true if some of the saved locals share storage.
+    pub fn has_overlap(&self) -> bool {
+        self.slot_count() < self.fields.len()
+    }
 }
 
 /// After we borrow check a closure, we are left with various
@@ -2015,6 +2037,8 @@ impl<'tcx> TypeFoldable<'tcx> for GeneratorLayout<'tcx> {
     fn super_fold_with<'gcx: 'tcx, F: TypeFolder<'gcx, 'tcx>>(&self, folder: &mut F) -> Self {
         GeneratorLayout {
             fields: self.fields.fold_with(folder),
+            slots: self.slots.clone(),
+            variant_fields: self.variant_fields.clone(),
         }
     }
 
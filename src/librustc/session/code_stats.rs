@@ -54,6 +54,7 @@ pub enum DataTypeKind {
     Union,
     Enum,
     Closure,
+    Generator,
 }
 
 #[derive(PartialEq, Eq, Hash, Debug)]
@@ -126,7 +127,9 @@ impl CodeStats {
 
             let struct_like = match info.kind {
                 DataTypeKind::Struct | DataTypeKind::Closure => true,
-                DataTypeKind::Enum | DataTypeKind::Union => false,
+                DataTypeKind::Enum |
+                DataTypeKind::Union |
+                DataTypeKind::Generator => false,
             };
             for (i, variant_info) in info.variants.iter().enumerate() {
                 let VariantInfo { ref name, kind: _, align: _, size, ref fields } = *variant_info;
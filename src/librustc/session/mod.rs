@@ -98,6 +98,10 @@ pub struct Session {
     /// The maximum length of types during monomorphization.
     pub type_length_limit: Cell<usize>,
 
+    /// The maximum number of steps the compile-time evaluator may take
+    /// while evaluating a single constant.
+    pub const_eval_limit: Cell<usize>,
+
     /// The metadata::creader module may inject an allocator/panic_runtime
     /// dependency if it didn't already find one, and this tracks what was
     /// injected.
@@ -187,6 +191,11 @@ impl Session {
             None => bug!("accessing disambiguator before initialization"),
         }
     }
+    /// The help shown when constant evaluation hits `#![const_eval_limit]`.
+    pub fn const_eval_limit_help(&self) -> String {
+        format!("consider raising the limit with `#![const_eval_limit=\"{}\"]`",
+                self.const_eval_limit.get().saturating_mul(2))
+    }
     pub fn struct_span_warn<'a, S: Into<MultiSpan>>(&'a self,
                                                     sp: S,
                                                     msg: &str)
@@ -336,7 +345,20 @@ impl Session {
                                            sp: S,
                                            msg: &str) {
         match *self.buffered_lints.borrow_mut() {
-            Some(ref mut buffer) => buffer.add_lint(lint, id, sp.into(), msg),
+            Some(ref mut buffer) => buffer.add_lint(lint, id, sp.into(), msg, None),
+            None => bug!("can't buffer lints after HIR lowering"),
+        }
+    }
+
+    /// Like `buffer_lint`, but the lint carries `suggestion` when it is emitted.
+    pub fn buffer_lint_with_suggestion<S: Into<MultiSpan>>(&self,
+                                                           lint: &'static lint::Lint,
+                                                           id: ast::NodeId,
+                                                           sp: S,
+                                                           msg: &str,
+                                                           suggestion: lint::BufferedSuggestion) {
+        match *self.buffered_lints.borrow_mut() {
+            Some(ref mut buffer) => buffer.add_lint(lint, id, sp.into(), msg, Some(suggestion)),
             None => bug!("can't buffer lints after HIR lowering"),
         }
     }
@@ -869,6 +891,8 @@ pub fn build_session_with_codemap(sopts: config::Options,
 
     let external_macro_backtrace = sopts.debugging_opts.external_macro_backtrace;
 
+    let collect_suggestions = sopts.debugging_opts.apply_suggestions;
+
     let emitter: Box<Emitter> = match (sopts.error_format, emitter_dest) {
         (config::ErrorOutputType::HumanReadable(color_config), None) => {
             Box::new(EmitterWriter::stderr(color_config, Some(codemap.clone()), false))
@@ -897,6 +921,7 @@ pub fn build_session_with_codemap(sopts: config::Options,
                 can_emit_warnings,
                 treat_err_as_bug,
                 external_macro_backtrace,
+                collect_suggestions,
                 .. Default::default()
             });
 
@@ -969,6 +994,7 @@ pub fn build_session_(sopts: config::Options,
         features: RefCell::new(feature_gate::Features::new()),
         recursion_limit: Cell::new(64),
         type_length_limit: Cell::new(1048576),
+        const_eval_limit: Cell::new(1_000_000),
         next_node_id: Cell::new(NodeId::new(1)),
         injected_allocator: Cell::new(None),
         allocator_kind: Cell::new(None),
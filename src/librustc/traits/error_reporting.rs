@@ -814,8 +814,10 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
             }
 
             ConstEvalFailure(ref err) => {
-                if let const_val::ErrKind::TypeckError = err.kind {
-                    return;
+                match err.kind {
+                    const_val::ErrKind::TypeckError |
+                    const_val::ErrKind::AlreadyReported => return,
+                    _ => {}
                 }
                 err.struct_error(self.tcx, span, "constant expression")
             }
@@ -218,7 +218,8 @@ impl FlagComputation {
             ConstVal::ByteStr(_) |
             ConstVal::Bool(_) |
             ConstVal::Char(_) |
-            ConstVal::Variant(_) => {}
+            ConstVal::Variant(_) |
+            ConstVal::Pointer(_) => {}
             ConstVal::Function(_, substs) => {
                 self.add_substs(substs);
             }
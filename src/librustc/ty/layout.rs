@@ -1288,10 +1288,66 @@ impl<'a, 'tcx> LayoutDetails {
 
             // Tuples, generators and closures.
             ty::TyGenerator(def_id, ref substs, _) => {
-                let tys = substs.field_tys(def_id, tcx);
-                univariant(&tys.map(|ty| cx.layout_of(ty)).collect::<Result<Vec<_>, _>>()?,
-                    &ReprOptions::default(),
-                    StructKind::AlwaysSized)?
+                let gen_layout = tcx.generator_layout(def_id);
+                if !gen_layout.has_overlap() {
+                    let tys = substs.field_tys(def_id, tcx);
+                    univariant(&tys.map(|ty| cx.layout_of(ty)).collect::<Result<Vec<_>, _>>()?,
+                        &ReprOptions::default(),
+                        StructKind::AlwaysSized)?
+                } else {
+                    // The upvars and the state come first, followed by one storage slot
+                    // for each group of saved locals which are never live at the same
+                    // time. All the locals in a slot are placed at its offset.
+                    let prefix_tys = substs.upvar_tys(def_id, tcx)
+                        .chain(iter::once(tcx.types.u32));
+                    let prefix = univariant_uninterned(
+                        &prefix_tys.map(|ty| cx.layout_of(ty)).collect::<Result<Vec<_>, _>>()?,
+                        &ReprOptions::default(),
+                        StructKind::AlwaysSized)?;
+                    let saved = substs.state_tys(def_id, tcx).map(|ty| cx.layout_of(ty))
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    let mut offsets: Vec<_> = (0..prefix.fields.count())
+                        .map(|i| prefix.fields.offset(i))
+                        .collect();
+                    let mut align = prefix.align;
+                    let mut offset = prefix.size;
+
+                    let mut slot_offsets = Vec::with_capacity(gen_layout.slot_count());
+                    for slot in 0..gen_layout.slot_count() {
+                        let mut slot_size = Size::from_bytes(0);
+                        let mut slot_align = dl.i8_align;
+                        for (field, _) in saved.iter().zip(&gen_layout.slots)
+                                               .filter(|&(_, &s)| s == slot) {
+                            slot_size = cmp::max(slot_size, field.size);
+                            slot_align = slot_align.max(field.align);
+                        }
+                        offset = offset.abi_align(slot_align);
+                        align = align.max(slot_align);
+                        slot_offsets.push(offset);
+                        offset = offset.checked_add(slot_size, dl)
+                            .ok_or(LayoutError::SizeOverflow(ty))?;
+                    }
+                    offsets.extend(gen_layout.slots.iter().map(|&slot| slot_offsets[slot]));
+
+                    let mut inverse_memory_index: Vec<u32> = (0..offsets.len() as u32).collect();
+                    inverse_memory_index.sort_by_key(|&i| offsets[i as usize]);
+                    let mut memory_index = vec![0; inverse_memory_index.len()];
+                    for (i, &field) in inverse_memory_index.iter().enumerate() {
+                        memory_index[field as usize] = i as u32;
+                    }
+
+                    tcx.intern_layout(LayoutDetails {
+                        variants: Variants::Single { index: 0 },
+                        fields: FieldPlacement::Arbitrary {
+                            offsets,
+                            memory_index
+                        },
+                        abi: Abi::Aggregate { sized: true },
+                        align,
+                        size: offset.abi_align(align)
+                    })
+                }
             }
 
             ty::TyClosure(def_id, ref substs) => {
@@ -1735,6 +1791,56 @@ impl<'a, 'tcx> LayoutDetails {
                 return;
             }
 
+            ty::TyGenerator(def_id, ref substs, _) => {
+                debug!("print-type-size t: `{:?}` record generator", ty);
+                // Report one variant per suspension point, made of the upvars, the
+                // state and the locals which are live across that suspension point.
+                let gen_layout = tcx.generator_layout(def_id);
+                let upvar_count = substs.upvar_tys(def_id, tcx).count();
+                let field_info = |i: usize, name: String| {
+                    match layout.field(cx, i) {
+                        Err(err) => {
+                            bug!("no layout found for field {}: `{:?}`", name, err);
+                        }
+                        Ok(field_layout) => {
+                            session::FieldInfo {
+                                name,
+                                offset: layout.fields.offset(i).bytes(),
+                                size: field_layout.size.bytes(),
+                                align: field_layout.align.abi(),
+                            }
+                        }
+                    }
+                };
+                let prefix: Vec<_> = (0..upvar_count + 1).map(|i| {
+                    if i < upvar_count {
+                        field_info(i, format!("upvar{}", i))
+                    } else {
+                        field_info(i, "state".to_string())
+                    }
+                }).collect();
+                let variant_infos = gen_layout.variant_fields.iter().enumerate().map(|(n, fields)| {
+                    let mut field_infos = prefix.clone();
+                    field_infos.extend(fields.iter().map(|&field| {
+                        let name = match gen_layout.fields[field].name {
+                            Some(name) => name.to_string(),
+                            None => format!("local{}", field),
+                        };
+                        field_info(upvar_count + 1 + field, name)
+                    }));
+                    let size = field_infos.iter().map(|f| f.offset + f.size).max().unwrap_or(0);
+                    session::VariantInfo {
+                        name: Some(format!("Suspend{}", n)),
+                        kind: session::SizeKind::Exact,
+                        align: layout.align.abi(),
+                        size,
+                        fields: field_infos,
+                    }
+                }).collect();
+                record(DataTypeKind::Generator, None, variant_infos);
+                return;
+            }
+
             _ => {
                 debug!("print-type-size t: `{:?}` skip non-nominal", ty);
                 return;
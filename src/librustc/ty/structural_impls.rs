@@ -632,6 +632,8 @@ impl<'a, 'tcx> Lift<'tcx> for const_val::ErrKind<'a> {
             ErroneousReferencedConstant(ref e) => {
                 return tcx.lift(e).map(ErroneousReferencedConstant)
             }
+            ExecutionTimeLimitReached => ExecutionTimeLimitReached,
+            AlreadyReported => AlreadyReported,
 
             TypeckError => TypeckError,
             CheckMatchError => CheckMatchError,
@@ -1317,6 +1319,7 @@ impl<'tcx> TypeFoldable<'tcx> for ConstVal<'tcx> {
             ConstVal::Unevaluated(def_id, substs) => {
                 ConstVal::Unevaluated(def_id, substs.fold_with(folder))
             }
+            ConstVal::Pointer(ptr) => ConstVal::Pointer(ptr),
         }
     }
 
@@ -1328,7 +1331,8 @@ impl<'tcx> TypeFoldable<'tcx> for ConstVal<'tcx> {
             ConstVal::ByteStr(_) |
             ConstVal::Bool(_) |
             ConstVal::Char(_) |
-            ConstVal::Variant(_) => false,
+            ConstVal::Variant(_) |
+            ConstVal::Pointer(_) => false,
             ConstVal::Function(_, substs) => substs.visit_with(visitor),
             ConstVal::Aggregate(ConstAggregate::Struct(fields)) => {
                 fields.iter().any(|&(_, v)| v.visit_with(visitor))
@@ -143,7 +143,8 @@ fn push_const<'tcx>(stack: &mut TypeWalkerStack<'tcx>, constant: &'tcx ty::Const
         ConstVal::ByteStr(_) |
         ConstVal::Bool(_) |
         ConstVal::Char(_) |
-        ConstVal::Variant(_) => {}
+        ConstVal::Variant(_) |
+        ConstVal::Pointer(_) => {}
         ConstVal::Function(_, substs) => {
             stack.extend(substs.types().rev());
         }
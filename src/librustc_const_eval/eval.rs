@@ -273,6 +273,9 @@ fn eval_const_expr_partial<'a, 'tcx>(cx: &ConstContext<'a, 'tcx>,
                         Err(ConstEvalErr { kind: TypeckError, .. }) => {
                             signal!(e, TypeckError);
                         }
+                        Err(ConstEvalErr { kind: AlreadyReported, .. }) => {
+                            signal!(e, AlreadyReported);
+                        }
                         Err(err) => {
                             debug!("bad reference: {:?}, {:?}", err.description(), err.span);
                             signal!(e, ErroneousReferencedConstant(box err))
@@ -118,7 +118,8 @@ fn print_const_val(value: &ConstVal, f: &mut fmt::Formatter) -> fmt::Result {
         ConstVal::Variant(_) |
         ConstVal::Function(..) |
         ConstVal::Aggregate(_) |
-        ConstVal::Unevaluated(..) => bug!("{:?} not printable in a pattern", value)
+        ConstVal::Unevaluated(..) |
+        ConstVal::Pointer(_) => bug!("{:?} not printable in a pattern", value)
     }
 }
 
@@ -26,6 +26,7 @@ use rustc::ty::{self, TyCtxt, Resolutions, AllArenas};
 use rustc::traits;
 use rustc::util::common::{ErrorReported, time};
 use rustc_allocator as allocator;
+use rustc_back::PanicStrategy;
 use rustc_borrowck as borrowck;
 use rustc_incremental;
 use rustc_resolve::{MakeGlobMap, Resolver};
@@ -249,6 +250,13 @@ pub fn compile_input(sess: &Session,
 
             result?;
 
+            if tcx.sess.opts.debugging_opts.interpret_main {
+                time(sess.time_passes(),
+                     "MIR interpretation",
+                     || mir::interpret::interpret_main(tcx));
+                return tcx.sess.compile_status().and_then(|_| Err(CompileIncomplete::Stopped));
+            }
+
             if log_enabled!(::log::Level::Info) {
                 println!("Pre-trans");
                 tcx.print_debug_stats();
@@ -802,6 +810,7 @@ pub fn phase_2_configure_and_expand<F>(sess: &Session,
         syntax::test::modify_for_testing(&sess.parse_sess,
                                          &mut resolver,
                                          sess.opts.test,
+                                         sess.panic_strategy() == PanicStrategy::Abort,
                                          krate,
                                          sess.diagnostic())
     });
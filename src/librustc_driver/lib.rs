@@ -245,15 +245,43 @@ pub fn run_compiler<'a>(args: &[String],
 
     let plugins = sess.opts.debugging_opts.extra_plugins.clone();
     let control = callbacks.build_controller(&sess, &matches);
-    (driver::compile_input(&sess,
-                           &cstore,
-                           &input_file_path,
-                           &input,
-                           &odir,
-                           &ofile,
-                           Some(plugins),
-                           &control),
-     Some(sess))
+    let mut result = driver::compile_input(&sess,
+                                           &cstore,
+                                           &input_file_path,
+                                           &input,
+                                           &odir,
+                                           &ofile,
+                                           Some(plugins),
+                                           &control);
+    if sess.opts.debugging_opts.apply_suggestions {
+        apply_suggestions(&sess);
+        result = result.and_then(|()| sess.compile_status());
+    }
+    (result, Some(sess))
+}
+
+/// Rewrites the source files with the machine-applicable suggestions emitted
+/// while compiling them, and reports the suggestions that had to be left out.
+fn apply_suggestions(sess: &Session) {
+    let suggestions = sess.diagnostic().take_applicable_suggestions();
+    let report = errors::fix::apply_suggestions(sess.codemap(), suggestions);
+
+    for &(ref path, count) in &report.fixed {
+        sess.note_without_error(&format!("applied {} suggestion{} to `{}`",
+                                         count,
+                                         if count == 1 { "" } else { "s" },
+                                         path.display()));
+    }
+    for &(ref suggestion, reason) in &report.skipped {
+        let span = suggestion.substitutions[0].parts[0].span;
+        sess.struct_span_warn(span, &format!("suggestion not applied because {}",
+                                             reason.description()))
+            .note(&format!("the suggestion was: {}", suggestion.msg))
+            .emit();
+    }
+    for &(ref path, ref err) in &report.errors {
+        sess.err(&format!("failed to apply suggestions to `{}`: {}", path.display(), err));
+    }
 }
 
 // Extract output directory and file from matches.
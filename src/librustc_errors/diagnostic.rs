@@ -9,6 +9,7 @@
 // except according to those terms.
 
 use CodeSuggestion;
+use Applicability;
 use SubstitutionPart;
 use Substitution;
 use Level;
@@ -213,17 +214,8 @@ impl Diagnostic {
     ///
     /// See `CodeSuggestion` for more information.
     pub fn span_suggestion_short(&mut self, sp: Span, msg: &str, suggestion: String) -> &mut Self {
-        self.suggestions.push(CodeSuggestion {
-            substitutions: vec![Substitution {
-                parts: vec![SubstitutionPart {
-                    snippet: suggestion,
-                    span: sp,
-                }],
-            }],
-            msg: msg.to_owned(),
-            show_code_when_inline: false,
-        });
-        self
+        self.span_suggestion_short_with_applicability(sp, msg, suggestion,
+                                                      Applicability::Unspecified)
     }
 
     /// Prints out a message with a suggested edit of the code.
@@ -243,6 +235,19 @@ impl Diagnostic {
     ///
     /// See `CodeSuggestion` for more information.
     pub fn span_suggestion(&mut self, sp: Span, msg: &str, suggestion: String) -> &mut Self {
+        self.span_suggestion_with_applicability(sp, msg, suggestion, Applicability::Unspecified)
+    }
+
+    /// Prints out a message with multiple suggested edits of the code.
+    pub fn span_suggestions(&mut self, sp: Span, msg: &str, suggestions: Vec<String>) -> &mut Self {
+        self.span_suggestions_with_applicability(sp, msg, suggestions, Applicability::Unspecified)
+    }
+
+    /// This is a suggestion that may contain mistakes or fillers and should
+    /// be read and understood by a human.
+    pub fn span_suggestion_with_applicability(&mut self, sp: Span, msg: &str,
+                                              suggestion: String,
+                                              applicability: Applicability) -> &mut Self {
         self.suggestions.push(CodeSuggestion {
             substitutions: vec![Substitution {
                 parts: vec![SubstitutionPart {
@@ -252,12 +257,31 @@ impl Diagnostic {
             }],
             msg: msg.to_owned(),
             show_code_when_inline: true,
+            applicability,
         });
         self
     }
 
-    /// Prints out a message with multiple suggested edits of the code.
-    pub fn span_suggestions(&mut self, sp: Span, msg: &str, suggestions: Vec<String>) -> &mut Self {
+    pub fn span_suggestion_short_with_applicability(&mut self, sp: Span, msg: &str,
+                                                    suggestion: String,
+                                                    applicability: Applicability) -> &mut Self {
+        self.suggestions.push(CodeSuggestion {
+            substitutions: vec![Substitution {
+                parts: vec![SubstitutionPart {
+                    snippet: suggestion,
+                    span: sp,
+                }],
+            }],
+            msg: msg.to_owned(),
+            show_code_when_inline: false,
+            applicability,
+        });
+        self
+    }
+
+    pub fn span_suggestions_with_applicability(&mut self, sp: Span, msg: &str,
+                                               suggestions: Vec<String>,
+                                               applicability: Applicability) -> &mut Self {
         self.suggestions.push(CodeSuggestion {
             substitutions: suggestions.into_iter().map(|snippet| Substitution {
                 parts: vec![SubstitutionPart {
@@ -267,6 +291,7 @@ impl Diagnostic {
             }).collect(),
             msg: msg.to_owned(),
             show_code_when_inline: true,
+            applicability,
         });
         self
     }
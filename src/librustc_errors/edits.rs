@@ -0,0 +1,83 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Choosing which suggestions to apply to a single file.
+//!
+//! This module only uses `std`, as compiletest includes it with `#[path]` so
+//! that run-rustfix tests apply suggestions exactly like `-Z apply-suggestions`.
+
+/// A single replacement, as a byte range relative to the start of a file.
+pub type Edit = (usize, usize, String);
+
+/// What happened to a suggestion passed to `FileEdits::add`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Added {
+    /// The edits of the suggestion were taken.
+    Applied,
+    /// An identical suggestion was already taken.
+    Duplicate,
+    /// The suggestion overlaps with one that was taken, so it was left out.
+    Overlap,
+}
+
+/// The edits taken so far for one file.
+///
+/// Suggestions have to be added in source order, so that the same set of
+/// suggestions always produces the same file.
+#[derive(Default)]
+pub struct FileEdits {
+    edits: Vec<Edit>,
+    applied: usize,
+}
+
+impl FileEdits {
+    pub fn new() -> FileEdits {
+        FileEdits::default()
+    }
+
+    /// Takes all the edits of one suggestion, unless any of them overlaps with
+    /// an edit that was already taken.
+    pub fn add(&mut self, edits: Vec<Edit>) -> Added {
+        if edits.iter().all(|edit| self.edits.contains(edit)) {
+            return Added::Duplicate;
+        }
+        if edits.iter().any(|edit| self.edits.iter().any(|taken| overlaps(edit, taken))) {
+            return Added::Overlap;
+        }
+        self.edits.extend(edits);
+        self.applied += 1;
+        Added::Applied
+    }
+
+    /// The number of suggestions taken.
+    pub fn applied(&self) -> usize {
+        self.applied
+    }
+
+    /// Returns `src` with all the edits taken applied to it.
+    pub fn apply(mut self, src: &str) -> String {
+        self.edits.sort();
+        let mut fixed = String::with_capacity(src.len());
+        let mut pos = 0;
+        for (start, end, snippet) in self.edits {
+            fixed.push_str(&src[pos..start]);
+            fixed.push_str(&snippet);
+            pos = end;
+        }
+        fixed.push_str(&src[pos..]);
+        fixed
+    }
+}
+
+/// Two edits conflict if they touch a common byte, or if both start at the
+/// same position (e.g. two insertions), where their order would be ambiguous.
+fn overlaps(a: &Edit, b: &Edit) -> bool {
+    (a.0 < b.1 && b.0 < a.1) || a.0 == b.0
+}
@@ -0,0 +1,163 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Rewriting source files with the machine-applicable suggestions emitted
+//! during a compilation (`-Z apply-suggestions`).
+
+use {Applicability, CodeMapper, CodeSuggestion};
+use edits::{Added, Edit, FileEdits};
+
+use syntax_pos::{FileName, FileMap, NO_EXPANSION};
+
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use std::rc::Rc;
+
+/// Why a suggestion was left out when rewriting a file.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SkipReason {
+    /// The suggestion edits code that overlaps with another suggestion which
+    /// was applied instead.
+    Overlap,
+    /// The suggestion points into a macro expansion, or into a file that is
+    /// not on disk.
+    NotInSource,
+}
+
+impl SkipReason {
+    pub fn description(&self) -> &'static str {
+        match *self {
+            SkipReason::Overlap => "it overlaps with another suggestion",
+            SkipReason::NotInSource => "it does not point into a source file",
+        }
+    }
+}
+
+/// The outcome of `apply_suggestions`.
+#[derive(Default)]
+pub struct FixReport {
+    /// The files that were rewritten, with the number of suggestions applied
+    /// to each of them.
+    pub fixed: Vec<(PathBuf, usize)>,
+    /// The suggestions that were not applied.
+    pub skipped: Vec<(CodeSuggestion, SkipReason)>,
+    /// The files that could not be rewritten.
+    pub errors: Vec<(PathBuf, io::Error)>,
+}
+
+/// Whether `suggestion` can be applied without a human looking at it first.
+/// Suggestions offering several alternatives never are.
+pub fn is_machine_applicable(suggestion: &CodeSuggestion) -> bool {
+    suggestion.applicability == Applicability::MachineApplicable &&
+        suggestion.substitutions.len() == 1
+}
+
+/// Applies `suggestions` to the files they point into.
+///
+/// Suggestions are considered in source order rather than in the order they
+/// were emitted, so the same set of suggestions always produces the same
+/// files. A suggestion whose edits overlap with an earlier one is skipped as a
+/// whole, and suggestions identical to one already applied are dropped.
+pub fn apply_suggestions(cm: &CodeMapper, suggestions: Vec<CodeSuggestion>) -> FixReport {
+    let mut report = FixReport::default();
+
+    let mut resolved = vec![];
+    for suggestion in suggestions {
+        match resolve(cm, &suggestion) {
+            Some((path, filemap, edits)) => resolved.push((path, filemap, edits, suggestion)),
+            None => report.skipped.push((suggestion, SkipReason::NotInSource)),
+        }
+    }
+    resolved.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.2.cmp(&b.2)));
+
+    let mut files: BTreeMap<PathBuf, (Rc<FileMap>, FileEdits)> = BTreeMap::new();
+    for (path, filemap, edits, suggestion) in resolved {
+        let file = files.entry(path).or_insert_with(|| (filemap, FileEdits::new()));
+        if file.1.add(edits) == Added::Overlap {
+            report.skipped.push((suggestion, SkipReason::Overlap));
+        }
+    }
+
+    for (path, (filemap, edits)) in files {
+        let count = edits.applied();
+        match rewrite(&path, &filemap, edits) {
+            Ok(()) => report.fixed.push((path, count)),
+            Err(e) => report.errors.push((path, e)),
+        }
+    }
+
+    report
+}
+
+/// Finds the file a suggestion edits, and turns its parts into byte ranges
+/// within that file.
+fn resolve(cm: &CodeMapper, suggestion: &CodeSuggestion)
+           -> Option<(PathBuf, Rc<FileMap>, Vec<Edit>)> {
+    let parts = &suggestion.substitutions.get(0)?.parts;
+    let mut filemap: Option<Rc<FileMap>> = None;
+    let mut edits = vec![];
+    for part in parts {
+        if part.span.ctxt() != NO_EXPANSION || part.span.lo() > part.span.hi() {
+            return None;
+        }
+        let fm = cm.lookup_char_pos(part.span.lo()).file;
+        if part.span.hi() > fm.end_pos || fm.src.is_none() {
+            return None;
+        }
+        if let Some(ref prev) = filemap {
+            if prev.start_pos != fm.start_pos {
+                return None;
+            }
+        }
+        let start = (part.span.lo() - fm.start_pos).0 as usize;
+        let end = (part.span.hi() - fm.start_pos).0 as usize;
+        edits.push((start, end, part.snippet.clone()));
+        filemap = Some(fm);
+    }
+    let filemap = filemap?;
+    let path = match *filemap.unmapped_path.as_ref().unwrap_or(&filemap.name) {
+        FileName::Real(ref path) => path.clone(),
+        _ => return None,
+    };
+    edits.sort();
+    Some((path, filemap, edits))
+}
+
+fn rewrite(path: &PathBuf, filemap: &FileMap, edits: FileEdits) -> io::Result<()> {
+    let src = filemap.src.as_ref().expect("suggestion into a file without source");
+
+    // Refuse to touch files that changed since they were read, as the spans
+    // would no longer line up.
+    let mut on_disk = String::new();
+    File::open(path)?.read_to_string(&mut on_disk)?;
+    if on_disk != **src {
+        return Err(io::Error::new(io::ErrorKind::Other,
+                                  "the file changed since it was compiled"));
+    }
+
+    // Write the new contents next to the file and move them over it, so an
+    // error half way through never leaves a truncated source file behind.
+    let file_name = path.file_name().expect("rewriting a path without a file name");
+    let mut tmp_name = OsString::from(".");
+    tmp_name.push(file_name);
+    tmp_name.push(".fix.tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+    let result = File::create(&tmp_path)
+        .and_then(|mut tmp| tmp.write_all(edits.apply(src).as_bytes()))
+        .and_then(|()| fs::set_permissions(&tmp_path, fs::metadata(path)?.permissions()))
+        .and_then(|()| fs::rename(&tmp_path, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result
+}
@@ -47,7 +47,9 @@ use std::sync::atomic::Ordering::SeqCst;
 
 mod diagnostic;
 mod diagnostic_builder;
+mod edits;
 pub mod emitter;
+pub mod fix;
 mod snippet;
 pub mod registry;
 mod styled_buffer;
@@ -55,6 +57,30 @@ mod lock;
 
 use syntax_pos::{BytePos, Loc, FileLinesResult, FileMap, FileName, MultiSpan, Span, NO_EXPANSION};
 
+/// Indicates the confidence in the correctness of a suggestion.
+///
+/// All suggestions are marked with an `Applicability`. Tools use the applicability of a
+/// suggestion to determine whether it should be automatically applied or if the user should
+/// be consulted before applying the suggestion.
+#[derive(Copy, Clone, Debug, PartialEq, Hash, RustcEncodable, RustcDecodable)]
+pub enum Applicability {
+    /// The suggestion is definitely what the user intended. This suggestion should be
+    /// automatically applied.
+    MachineApplicable,
+
+    /// The suggestion contains placeholders like `(...)` or `{ /* fields */ }`. The suggestion
+    /// cannot be applied automatically because it will not result in valid Rust code. The user
+    /// will need to fill in the placeholders.
+    HasPlaceholders,
+
+    /// The suggestion may be what the user intended, but it is uncertain. The suggestion should
+    /// result in valid Rust code if it is applied.
+    MaybeIncorrect,
+
+    /// The suggestion's applicability is unknown.
+    Unspecified,
+}
+
 #[derive(Clone, Debug, PartialEq, Hash, RustcEncodable, RustcDecodable)]
 pub struct CodeSuggestion {
     /// Each substitute can have multiple variants due to multiple
@@ -81,6 +107,12 @@ pub struct CodeSuggestion {
     pub substitutions: Vec<Substitution>,
     pub msg: String,
     pub show_code_when_inline: bool,
+    /// Whether or not the suggestion is approximate
+    ///
+    /// Sometimes we may show suggestions with placeholders,
+    /// which are useful for users but not useful for
+    /// tools like rustfix
+    pub applicability: Applicability,
 }
 
 #[derive(Clone, Debug, PartialEq, Hash, RustcEncodable, RustcDecodable)]
@@ -249,6 +281,10 @@ pub struct Handler {
     // this handler. These hashes is used to avoid emitting the same error
     // twice.
     emitted_diagnostics: RefCell<FxHashSet<u128>>,
+
+    // Machine-applicable suggestions of the diagnostics emitted so far, kept
+    // only if the `collect_suggestions` flag is set.
+    applicable_suggestions: RefCell<Vec<CodeSuggestion>>,
 }
 
 #[derive(Default)]
@@ -256,6 +292,7 @@ pub struct HandlerFlags {
     pub can_emit_warnings: bool,
     pub treat_err_as_bug: bool,
     pub external_macro_backtrace: bool,
+    pub collect_suggestions: bool,
 }
 
 impl Handler {
@@ -304,6 +341,7 @@ impl Handler {
             delayed_span_bug: RefCell::new(None),
             tracked_diagnostics: RefCell::new(None),
             emitted_diagnostics: RefCell::new(FxHashSet()),
+            applicable_suggestions: RefCell::new(Vec::new()),
         }
     }
 
@@ -564,6 +602,12 @@ impl Handler {
         }
     }
 
+    /// Takes the machine-applicable suggestions emitted so far, see `fix::apply_suggestions`.
+    /// This is always empty unless the `collect_suggestions` flag is set.
+    pub fn take_applicable_suggestions(&self) -> Vec<CodeSuggestion> {
+        mem::replace(&mut *self.applicable_suggestions.borrow_mut(), Vec::new())
+    }
+
     pub fn track_diagnostics<F, R>(&self, f: F) -> (R, Vec<Diagnostic>)
         where F: FnOnce() -> R
     {
@@ -593,6 +637,12 @@ impl Handler {
         // one:
         if self.emitted_diagnostics.borrow_mut().insert(diagnostic_hash) {
             self.emitter.borrow_mut().emit(db);
+            if self.flags.collect_suggestions {
+                let suggestions = diagnostic.suggestions.iter()
+                    .filter(|s| fix::is_machine_applicable(s))
+                    .cloned();
+                self.applicable_suggestions.borrow_mut().extend(suggestions);
+            }
             if db.is_error() {
                 self.bump_err_count();
             }
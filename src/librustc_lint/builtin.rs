@@ -43,6 +43,7 @@ use std::collections::HashSet;
 
 use syntax::ast;
 use syntax::attr;
+use syntax::errors::Applicability;
 use syntax::feature_gate::{AttributeGate, AttributeType, Stability, deprecated_attributes};
 use syntax_pos::{BytePos, Span, SyntaxContext};
 use syntax::symbol::keywords;
@@ -79,7 +80,11 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for WhileTrue {
                         let msg = "denote infinite loops with `loop { ... }`";
                         let condition_span = cx.tcx.sess.codemap().def_span(e.span);
                         let mut err = cx.struct_span_lint(WHILE_TRUE, condition_span, msg);
-                        err.span_suggestion_short(condition_span, "use `loop`", "loop".to_owned());
+                        err.span_suggestion_short_with_applicability(
+                            condition_span,
+                            "use `loop`",
+                            "loop".to_owned(),
+                            Applicability::MachineApplicable);
                         err.emit();
                     }
                 }
@@ -177,9 +182,11 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for NonShorthandFieldPatterns {
                                      &format!("the `{}:` in this pattern is redundant",
                                               ident.node));
                         let subspan = cx.tcx.sess.codemap().span_through_char(fieldpat.span, ':');
-                        err.span_suggestion_short(subspan,
-                                                  "remove this",
-                                                  format!("{}", ident.node));
+                        err.span_suggestion_short_with_applicability(
+                            subspan,
+                            "remove this",
+                            format!("{}", ident.node),
+                            Applicability::MachineApplicable);
                         err.emit();
                     }
                 }
@@ -625,9 +632,21 @@ impl EarlyLintPass for AnonymousParameters {
                     match arg.pat.node {
                         ast::PatKind::Ident(_, ident, None) => {
                             if ident.node.name == keywords::Invalid.name() {
-                                cx.span_lint(ANONYMOUS_PARAMETERS,
-                                             arg.pat.span,
-                                             "use of deprecated anonymous parameter");
+                                let ty_snip = cx.sess.codemap().span_to_snippet(arg.ty.span);
+                                let (ty_snip, appl) = match ty_snip {
+                                    Ok(snip) => (snip, Applicability::MachineApplicable),
+                                    Err(_) => ("<type>".to_owned(), Applicability::HasPlaceholders),
+                                };
+                                let mut err = cx.struct_span_lint(
+                                    ANONYMOUS_PARAMETERS,
+                                    arg.pat.span,
+                                    "use of deprecated anonymous parameter");
+                                err.span_suggestion_with_applicability(
+                                    arg.ty.span,
+                                    "try naming the parameter or explicitly ignoring it",
+                                    format!("_: {}", ty_snip),
+                                    appl);
+                                err.emit();
                             }
                         }
                         _ => (),
@@ -673,7 +692,11 @@ impl EarlyLintPass for DeprecatedAttr {
                     let msg = format!("use of deprecated attribute `{}`: {}. See {}",
                                       name, reason, link);
                     let mut err = cx.struct_span_lint(DEPRECATED, attr.span, &msg);
-                    err.span_suggestion_short(attr.span, "remove this attribute", "".to_owned());
+                    err.span_suggestion_short_with_applicability(
+                        attr.span,
+                        "remove this attribute",
+                        "".to_owned(),
+                        Applicability::MachineApplicable);
                     err.emit();
                 }
                 return;
@@ -1155,9 +1178,11 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for InvalidNoMangleItems {
                         let mut err = cx.struct_span_lint(PRIVATE_NO_MANGLE_FNS, it.span, msg);
                         let insertion_span = it.span.with_hi(it.span.lo());
                         if it.vis == hir::Visibility::Inherited {
-                            err.span_suggestion(insertion_span,
-                                                "try making it public",
-                                                "pub ".to_owned());
+                            err.span_suggestion_with_applicability(
+                                insertion_span,
+                                "try making it public",
+                                "pub ".to_owned(),
+                                Applicability::MachineApplicable);
                         }
                         err.emit();
                     }
@@ -1166,9 +1191,11 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for InvalidNoMangleItems {
                                                           it.span,
                                                           "functions generic over \
                                                            types must be mangled");
-                        err.span_suggestion_short(no_mangle_attr.span,
-                                                  "remove this attribute",
-                                                  "".to_owned());
+                        err.span_suggestion_short_with_applicability(
+                            no_mangle_attr.span,
+                            "remove this attribute",
+                            "".to_owned(),
+                            Applicability::MachineApplicable);
                         err.emit();
                     }
                 }
@@ -1180,9 +1207,11 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for InvalidNoMangleItems {
                        let mut err = cx.struct_span_lint(PRIVATE_NO_MANGLE_STATICS, it.span, msg);
                        let insertion_span = it.span.with_hi(it.span.lo());
                        if it.vis == hir::Visibility::Inherited {
-                           err.span_suggestion(insertion_span,
-                                               "try making it public",
-                                               "pub ".to_owned());
+                           err.span_suggestion_with_applicability(
+                               insertion_span,
+                               "try making it public",
+                               "pub ".to_owned(),
+                               Applicability::MachineApplicable);
                        }
                        err.emit();
                 }
@@ -1200,9 +1229,11 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for InvalidNoMangleItems {
                         .unwrap_or(0) as u32;
                     // `const` is 5 chars
                     let const_span = it.span.with_hi(BytePos(it.span.lo().0 + start + 5));
-                    err.span_suggestion(const_span,
-                                        "try a static value",
-                                        "pub static".to_owned());
+                    err.span_suggestion_with_applicability(
+                        const_span,
+                        "try a static value",
+                        "pub static".to_owned(),
+                        Applicability::MachineApplicable);
                     err.emit();
                 }
             }
@@ -1360,7 +1391,10 @@ impl UnreachablePub {
             } else {
                 "pub(crate)"
             }.to_owned();
-            err.span_suggestion(pub_span, "consider restricting its visibility", replacement);
+            err.span_suggestion_with_applicability(pub_span,
+                                                   "consider restricting its visibility",
+                                                   replacement,
+                                                   Applicability::MachineApplicable);
             if exportable {
                 err.help("or consider exporting it for use by other crates");
             }
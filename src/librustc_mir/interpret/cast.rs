@@ -8,7 +8,7 @@ use rustc_apfloat::ieee::{Single, Double};
 use rustc_apfloat::Float;
 
 impl<'a, 'tcx, M: Machine<'tcx>> EvalContext<'a, 'tcx, M> {
-    pub(super) fn cast_primval(
+    pub fn cast_primval(
         &self,
         val: PrimVal,
         src_ty: Ty<'tcx>,
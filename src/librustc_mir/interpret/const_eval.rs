@@ -3,7 +3,7 @@ use rustc::ty::layout::{self, LayoutOf};
 use rustc::ty::subst::Substs;
 use rustc::hir::def_id::DefId;
 use rustc::mir;
-use rustc::middle::const_val::ErrKind::{CheckMatchError, TypeckError};
+use rustc::middle::const_val::ErrKind::{AlreadyReported, CheckMatchError, TypeckError};
 use rustc::middle::const_val::{ConstEvalErr, ConstVal};
 use rustc_const_eval::{lookup_const_by_id, ConstContext};
 use rustc::mir::Field;
@@ -13,7 +13,7 @@ use syntax::ast::Mutability;
 use syntax::codemap::Span;
 
 use rustc::mir::interpret::{EvalResult, EvalError, EvalErrorKind, GlobalId, Value, MemoryPointer, Pointer, PrimVal};
-use super::{Place, EvalContext, StackPopCleanup, ValTy};
+use super::{Place, EvalContext, StackPopCleanup, ValTy, HasMemory, MemoryKind, ResourceLimits};
 
 use rustc_const_math::ConstInt;
 
@@ -21,13 +21,21 @@ use std::fmt;
 use std::error::Error;
 
 
+/// The limits of compile-time evaluation, `#![const_eval_limit]` sets the number of steps.
+fn const_eval_limits(tcx: TyCtxt) -> ResourceLimits {
+    ResourceLimits {
+        step_limit: tcx.sess.const_eval_limit.get() as u64,
+        ..ResourceLimits::default()
+    }
+}
+
 pub fn mk_eval_cx<'a, 'tcx>(
     tcx: TyCtxt<'a, 'tcx, 'tcx>,
     instance: Instance<'tcx>,
     param_env: ty::ParamEnv<'tcx>,
 ) -> EvalResult<'tcx, EvalContext<'a, 'tcx, CompileTimeEvaluator>> {
     debug!("mk_eval_cx: {:?}, {:?}", instance, param_env);
-    let limits = super::ResourceLimits::default();
+    let limits = const_eval_limits(tcx);
     let mut ecx = EvalContext::new(tcx, param_env, limits, CompileTimeEvaluator, ());
     let mir = ecx.load_mir(instance.def)?;
     // insert a stack frame so any queries have the correct substs
@@ -46,9 +54,28 @@ pub fn eval_body<'a, 'tcx>(
     instance: Instance<'tcx>,
     param_env: ty::ParamEnv<'tcx>,
 ) -> EvalResult<'tcx, (Pointer, Ty<'tcx>)> {
+    eval_body_and_ecx(tcx, instance, param_env).0
+}
+
+/// Like `eval_body`, but also returns the evaluation context,
+/// whose stack is needed to report errors.
+fn eval_body_and_ecx<'a, 'tcx>(
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    instance: Instance<'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+) -> (EvalResult<'tcx, (Pointer, Ty<'tcx>)>, EvalContext<'a, 'tcx, CompileTimeEvaluator>) {
     debug!("eval_body: {:?}, {:?}", instance, param_env);
-    let limits = super::ResourceLimits::default();
+    let limits = const_eval_limits(tcx);
     let mut ecx = EvalContext::new(tcx, param_env, limits, CompileTimeEvaluator, ());
+    let res = eval_body_using_ecx(&mut ecx, instance);
+    (res, ecx)
+}
+
+fn eval_body_using_ecx<'a, 'tcx>(
+    ecx: &mut EvalContext<'a, 'tcx, CompileTimeEvaluator>,
+    instance: Instance<'tcx>,
+) -> EvalResult<'tcx, (Pointer, Ty<'tcx>)> {
+    let tcx = ecx.tcx;
     let cid = GlobalId {
         instance,
         promoted: None,
@@ -80,6 +107,12 @@ pub fn eval_body<'a, 'tcx>(
         )?;
 
         while ecx.step()? {}
+
+        // Heap allocations reachable from the final value have been interned
+        // along with it when its frame was popped, the others were leaked.
+        if ecx.memory.has_live_allocations(MemoryKind::Machine(ConstHeap)) {
+            return Err(ConstEvalError::HeapLeak.into());
+        }
     }
     let alloc = tcx.interpret_interner.borrow().get_cached(cid).expect("global not cached");
     Ok((MemoryPointer::new(alloc, 0).into(), instance_ty))
@@ -131,6 +164,16 @@ pub fn eval_body_as_integer<'a, 'tcx>(
 
 pub struct CompileTimeEvaluator;
 
+/// The memory kinds of the compile-time evaluator in addition to the stack.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CompileTimeMemoryKind {
+    /// Allocations made by `box`. They must either be freed or
+    /// be reachable from the final value, which interns them.
+    ConstHeap,
+}
+
+use self::CompileTimeMemoryKind::ConstHeap;
+
 impl<'tcx> Into<EvalError<'tcx>> for ConstEvalError {
     fn into(self) -> EvalError<'tcx> {
         EvalErrorKind::MachineError(Box::new(self)).into()
@@ -141,6 +184,8 @@ impl<'tcx> Into<EvalError<'tcx>> for ConstEvalError {
 enum ConstEvalError {
     NeedsRfc(String),
     NotConst(String),
+    HeapLeak,
+    UnrepresentableValue(String),
 }
 
 impl fmt::Display for ConstEvalError {
@@ -155,6 +200,20 @@ impl fmt::Display for ConstEvalError {
                 )
             }
             NotConst(ref msg) => write!(f, "Cannot evaluate within constants: \"{}\"", msg),
+            HeapLeak => {
+                write!(
+                    f,
+                    "heap allocations must be freed before the end of constant evaluation"
+                )
+            }
+            UnrepresentableValue(ref ty) => {
+                write!(
+                    f,
+                    "constants of type `{}` can't be evaluated with control flow or heap \
+                     allocations yet, only primitives, thin pointers and aggregates of them can",
+                    ty
+                )
+            }
         }
     }
 }
@@ -165,6 +224,8 @@ impl Error for ConstEvalError {
         match *self {
             NeedsRfc(_) => "this feature needs an rfc before being allowed inside constants",
             NotConst(_) => "this feature is not compatible with constant evaluation",
+            HeapLeak => "a heap allocation was leaked during constant evaluation",
+            UnrepresentableValue(_) => "the value of the constant can't be represented",
         }
     }
 
@@ -175,17 +236,33 @@ impl Error for ConstEvalError {
 
 impl<'tcx> super::Machine<'tcx> for CompileTimeEvaluator {
     type MemoryData = ();
-    type MemoryKinds = !;
+    type MemoryKinds = CompileTimeMemoryKind;
     fn eval_fn_call<'a>(
         ecx: &mut EvalContext<'a, 'tcx, Self>,
         instance: ty::Instance<'tcx>,
         destination: Option<(Place, mir::BasicBlock)>,
-        _args: &[ValTy<'tcx>],
+        args: &[ValTy<'tcx>],
         span: Span,
         _sig: ty::FnSig<'tcx>,
     ) -> EvalResult<'tcx, bool> {
         debug!("eval_fn_call: {:?}", instance);
-        if !ecx.tcx.is_const_fn(instance.def_id()) {
+        if Some(instance.def_id()) == ecx.tcx.lang_items().box_free_fn() {
+            // Freeing the allocation of a `Box` dropped during evaluation.
+            let ptr = ecx.into_ptr(args[0].value)?.to_ptr()?;
+            ecx.memory.deallocate(ptr, None, MemoryKind::Machine(ConstHeap))?;
+            match destination {
+                Some((_, target)) => ecx.goto_block(target),
+                None => return err!(Unreachable),
+            }
+            return Ok(true);
+        }
+        // The drop glue of a `Box` frees it through `box_free`,
+        // user-defined destructors are rejected when they're called.
+        let is_drop_glue = match instance.def {
+            ty::InstanceDef::DropGlue(..) => true,
+            _ => false,
+        };
+        if !is_drop_glue && !ecx.tcx.is_const_fn(instance.def_id()) {
             return Err(
                 ConstEvalError::NotConst(format!("calling non-const fn `{}`", instance)).into(),
             );
@@ -243,6 +320,12 @@ impl<'tcx> super::Machine<'tcx> for CompileTimeEvaluator {
                 ecx.write_primval(dest, PrimVal::from_u128(size), dest_layout.ty)?;
             }
 
+            "needs_drop" => {
+                let ty = substs.type_at(0);
+                let needs_drop = ty.needs_drop(ecx.tcx, ecx.param_env);
+                ecx.write_primval(dest, PrimVal::from_bool(needs_drop), dest_layout.ty)?;
+            }
+
             name => return Err(ConstEvalError::NeedsRfc(format!("calling intrinsic `{}`", name)).into()),
         }
 
@@ -255,34 +338,75 @@ impl<'tcx> super::Machine<'tcx> for CompileTimeEvaluator {
     }
 
     fn try_ptr_op<'a>(
-        _ecx: &EvalContext<'a, 'tcx, Self>,
-        _bin_op: mir::BinOp,
+        ecx: &EvalContext<'a, 'tcx, Self>,
+        bin_op: mir::BinOp,
         left: PrimVal,
         _left_ty: Ty<'tcx>,
         right: PrimVal,
         _right_ty: Ty<'tcx>,
     ) -> EvalResult<'tcx, Option<(PrimVal, bool)>> {
+        use rustc::mir::BinOp::*;
+
         if left.is_bytes() && right.is_bytes() {
-            Ok(None)
-        } else {
-            Err(
+            return Ok(None);
+        }
+        let res = match (left, right) {
+            // Pointers into the same allocation compare by offset.
+            (PrimVal::Ptr(left), PrimVal::Ptr(right)) if left.alloc_id == right.alloc_id => {
+                match bin_op {
+                    Eq => Some(left.offset == right.offset),
+                    Ne => Some(left.offset != right.offset),
+                    Lt => Some(left.offset < right.offset),
+                    Le => Some(left.offset <= right.offset),
+                    Gt => Some(left.offset > right.offset),
+                    Ge => Some(left.offset >= right.offset),
+                    _ => None,
+                }
+            }
+            // Pointers into distinct allocations are never equal, unless one
+            // of them is out of bounds and the other allocation happens to be
+            // placed right after it, which is only known after linking.
+            (PrimVal::Ptr(left), PrimVal::Ptr(right)) if bin_op == Eq || bin_op == Ne => {
+                let in_bounds = |ptr: MemoryPointer| -> EvalResult<'tcx, bool> {
+                    Ok(ptr.offset < ecx.memory.get(ptr.alloc_id)?.bytes.len() as u64)
+                };
+                if in_bounds(left)? && in_bounds(right)? {
+                    Some(bin_op == Ne)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        };
+        match res {
+            Some(res) => Ok(Some((PrimVal::from_bool(res), false))),
+            None => Err(
                 ConstEvalError::NeedsRfc("Pointer arithmetic or comparison".to_string()).into(),
-            )
+            ),
         }
     }
 
-    fn mark_static_initialized(m: !) -> EvalResult<'tcx> {
-        m
+    fn mark_static_initialized(m: CompileTimeMemoryKind) -> EvalResult<'tcx> {
+        match m {
+            // Interned into the final value.
+            ConstHeap => Ok(()),
+        }
     }
 
     fn box_alloc<'a>(
-        _ecx: &mut EvalContext<'a, 'tcx, Self>,
-        _ty: Ty<'tcx>,
-        _dest: Place,
+        ecx: &mut EvalContext<'a, 'tcx, Self>,
+        ty: Ty<'tcx>,
+        dest: Place,
     ) -> EvalResult<'tcx> {
-        Err(
-            ConstEvalError::NeedsRfc("Heap allocations via `box` keyword".to_string()).into(),
-        )
+        let layout = ecx.layout_of(ty)?;
+        assert!(!layout.is_unsized(), "cannot alloc memory for unsized type");
+        let ptr = ecx.memory.allocate(
+            layout.size.bytes(),
+            layout.align,
+            Some(MemoryKind::Machine(ConstHeap)),
+        )?;
+        let box_ty = ecx.tcx.mk_box(ty);
+        ecx.write_primval(dest, PrimVal::Ptr(ptr), box_ty)
     }
 
     fn global_item_with_linkage<'a>(
@@ -337,10 +461,14 @@ pub fn const_eval_provider<'a, 'tcx>(
     trace!("running old const eval");
     let old_result = ConstContext::new(tcx, key.param_env.and(substs), tables).eval(&body.value);
     trace!("old const eval produced {:?}", old_result);
+    let miri_fallback = {
+        let features = tcx.sess.features.borrow();
+        features.const_control_flow || features.const_heap
+    };
     if tcx.sess.opts.debugging_opts.miri {
         let instance = ty::Instance::new(def_id, substs);
         trace!("const eval instance: {:?}, {:?}", instance, key.param_env);
-        let miri_result = ::interpret::eval_body(tcx, instance, key.param_env);
+        let (miri_result, ecx) = eval_body_and_ecx(tcx, instance, key.param_env);
         match (miri_result, old_result) {
             (Err(err), Ok(ok)) => {
                 trace!("miri failed, ctfe returned {:?}", ok);
@@ -348,7 +476,6 @@ pub fn const_eval_provider<'a, 'tcx>(
                     tcx.def_span(key.value.0),
                     "miri failed to eval, while ctfe succeeded",
                 );
-                let ecx = mk_eval_cx(tcx, instance, key.param_env).unwrap();
                 let () = unwrap_miri(&ecx, Err(err));
                 Ok(ok)
             },
@@ -361,11 +488,125 @@ pub fn const_eval_provider<'a, 'tcx>(
                 Ok(ctfe)
             }
         }
+    } else if old_result.is_err() && miri_fallback {
+        // The old evaluator supports neither control flow nor heap
+        // allocations, so miri gets to evaluate what it rejected.
+        let instance = ty::Instance::new(def_id, substs);
+        trace!("falling back to miri for {:?}, {:?}", instance, key.param_env);
+        let span = tcx.def_span(key.value.0);
+        let (miri_result, ecx) = eval_body_and_ecx(tcx, instance, key.param_env);
+        let val = miri_result.and_then(|(ptr, ty)| {
+            let mut ecx = mk_eval_cx(tcx, instance, key.param_env)?;
+            let place = Place::from_primval_ptr(ptr, ecx.layout_of(ty)?.align);
+            Ok(tcx.mk_const(ty::Const {
+                val: miri_value_to_const_val(&mut ecx, place, ty)?,
+                ty,
+            }))
+        });
+        val.map_err(|mut err| {
+            if ecx.stack().is_empty() {
+                tcx.sess.span_err(span, &err.to_string());
+            } else {
+                report(&ecx, &mut err);
+            }
+            ConstEvalErr { span, kind: AlreadyReported }
+        })
     } else {
         old_result
     }
 }
 
+/// Converts the value miri computed for a constant into a `ConstVal`.
+///
+/// Primitives, thin pointers and structs, tuples and arrays of them are
+/// supported. The allocations the pointers point into, including the heap
+/// allocations still reachable from the value, have been interned along
+/// with the constant, and are lowered to LLVM globals by trans.
+fn miri_value_to_const_val<'a, 'tcx>(
+    ecx: &mut EvalContext<'a, 'tcx, CompileTimeEvaluator>,
+    place: Place,
+    ty: Ty<'tcx>,
+) -> EvalResult<'tcx, ConstVal<'tcx>> {
+    use rustc::middle::const_val::ConstAggregate::*;
+    use rustc_const_math::ConstFloat;
+    use rustc::ty::TypeVariants::*;
+    let tcx = ecx.tcx;
+    let layout = ecx.layout_of(ty)?;
+    let mk_const = |val, ty| tcx.mk_const(ty::Const { val, ty });
+    match ty.sty {
+        TyTuple(tys, _) => {
+            let mut fields = Vec::with_capacity(tys.len());
+            for (i, &field_ty) in tys.iter().enumerate() {
+                let (field_place, _) = ecx.place_field(place, Field::new(i), layout)?;
+                let val = miri_value_to_const_val(ecx, field_place, field_ty)?;
+                fields.push(mk_const(val, field_ty));
+            }
+            return Ok(ConstVal::Aggregate(Tuple(tcx.alloc_const_slice(&fields))));
+        }
+        TyAdt(adt_def, _) if adt_def.is_struct() && !ecx.is_ptr_box(ty) => {
+            let variant = adt_def.non_enum_variant();
+            let mut fields = Vec::with_capacity(variant.fields.len());
+            for (i, field) in variant.fields.iter().enumerate() {
+                let (field_place, field_layout) =
+                    ecx.place_field(place, Field::new(i), layout)?;
+                let val = miri_value_to_const_val(ecx, field_place, field_layout.ty)?;
+                fields.push((field.name, mk_const(val, field_layout.ty)));
+            }
+            return Ok(ConstVal::Aggregate(Struct(tcx.alloc_name_const_slice(&fields))));
+        }
+        TyArray(elem_ty, n) => {
+            let n = n.val.to_const_int().unwrap().to_u64().unwrap();
+            let mut elems = Vec::with_capacity(n as usize);
+            for i in 0..n {
+                let (elem_place, _) = ecx.place_field(place, Field::new(i as usize), layout)?;
+                let val = miri_value_to_const_val(ecx, elem_place, elem_ty)?;
+                elems.push(mk_const(val, elem_ty));
+            }
+            return Ok(ConstVal::Aggregate(Array(tcx.alloc_const_slice(&elems))));
+        }
+        _ => {}
+    }
+    let value = ecx.read_place(place)?;
+    let is_thin_ptr = match ty.sty {
+        TyRawPtr(_) | TyRef(..) => !ecx.type_is_fat_ptr(ty),
+        TyAdt(..) => ecx.is_ptr_box(ty) && !ecx.type_is_fat_ptr(ty),
+        TyFnPtr(_) => true,
+        _ => false,
+    };
+    if is_thin_ptr {
+        // Integers cast to pointers have nowhere to point to.
+        return match ecx.value_to_primval(ValTy { value, ty })? {
+            PrimVal::Ptr(ptr) => Ok(ConstVal::Pointer(ptr)),
+            _ => Err(ConstEvalError::UnrepresentableValue(ty.to_string()).into()),
+        };
+    }
+    let bits = match ty.sty {
+        TyInt(_) | TyUint(_) | TyFloat(_) | TyBool | TyChar => {
+            ecx.value_to_primval(ValTy { value, ty })?.to_bytes()?
+        }
+        _ => return Err(ConstEvalError::UnrepresentableValue(ty.to_string()).into()),
+    };
+    Ok(match ty.sty {
+        TyInt(int_ty) => ConstVal::Integral(ConstInt::new_signed_truncating(
+            bits as i128,
+            int_ty,
+            tcx.sess.target.isize_ty,
+        )),
+        TyUint(uint_ty) => ConstVal::Integral(ConstInt::new_unsigned_truncating(
+            bits,
+            uint_ty,
+            tcx.sess.target.usize_ty,
+        )),
+        TyFloat(float_ty) => ConstVal::Float(ConstFloat { bits, ty: float_ty }),
+        TyBool => ConstVal::Bool(bits == 1),
+        TyChar => match ::std::char::from_u32(bits as u32) {
+            Some(c) => ConstVal::Char(c),
+            None => return err!(InvalidChar(bits)),
+        },
+        _ => bug!("non-primitive type {:?}", ty),
+    })
+}
+
 fn check_ctfe_against_miri<'a, 'tcx>(
     ecx: &mut EvalContext<'a, 'tcx, CompileTimeEvaluator>,
     miri_place: Place,
@@ -554,6 +795,21 @@ fn get_prim<'a, 'tcx>(
     unwrap_miri(ecx, res)
 }
 
+/// Reports an error of constant evaluation, suggesting a higher
+/// `#![const_eval_limit]` if it was reached.
+fn report<'a, 'tcx>(
+    ecx: &EvalContext<'a, 'tcx, CompileTimeEvaluator>,
+    err: &mut EvalError<'tcx>,
+) {
+    let limit_reached = match err.kind {
+        EvalErrorKind::ExecutionTimeLimitReached => true,
+        _ => false,
+    };
+    ecx.report_with(err, |diag| if limit_reached {
+        diag.help(&ecx.tcx.sess.const_eval_limit_help());
+    });
+}
+
 fn unwrap_miri<'a, 'tcx, T>(
     ecx: &EvalContext<'a, 'tcx, CompileTimeEvaluator>,
     res: Result<T, EvalError<'tcx>>,
@@ -561,7 +817,7 @@ fn unwrap_miri<'a, 'tcx, T>(
     match res {
         Ok(val) => val,
         Err(mut err) => {
-            ecx.report(&mut err);
+            report(ecx, &mut err);
             ecx.tcx.sess.abort_if_errors();
             bug!("{:#?}", err);
         }
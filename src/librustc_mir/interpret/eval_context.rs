@@ -9,6 +9,7 @@ use rustc::traits::Reveal;
 use rustc::ty::layout::{self, Size, Align, HasDataLayout, LayoutOf, TyLayout};
 use rustc::ty::subst::{Subst, Substs, Kind};
 use rustc::ty::{self, Ty, TyCtxt};
+use rustc_errors::DiagnosticBuilder;
 use rustc_data_structures::indexed_vec::Idx;
 use syntax::codemap::{self, DUMMY_SP};
 use syntax::ast::Mutability;
@@ -258,6 +259,8 @@ impl<'a, 'tcx, M: Machine<'tcx>> EvalContext<'a, 'tcx, M> {
                 PrimVal::Ptr(ptr)
             }
 
+            ConstVal::Pointer(ptr) => PrimVal::Ptr(ptr),
+
             Unevaluated(def_id, substs) => {
                 let instance = self.resolve(def_id, substs)?;
                 return Ok(self.read_global_as_value(GlobalId {
@@ -792,7 +795,18 @@ impl<'a, 'tcx, M: Machine<'tcx>> EvalContext<'a, 'tcx, M> {
         match ty.sty {
             ty::TyRawPtr(ref tam) |
             ty::TyRef(_, ref tam) => !self.type_is_sized(tam.ty),
-            ty::TyAdt(def, _) if def.is_box() => !self.type_is_sized(ty.boxed_ty()),
+            ty::TyAdt(..) if self.is_ptr_box(ty) => !self.type_is_sized(ty.boxed_ty()),
+            _ => false,
+        }
+    }
+
+    /// Whether `ty` is a `Box` with a zero-sized allocator, which makes the `Box`
+    /// just a pointer. Other boxes are handled like the structs they are.
+    pub(super) fn is_ptr_box(&self, ty: Ty<'tcx>) -> bool {
+        match ty.sty {
+            ty::TyAdt(def, substs) if def.is_box() => {
+                self.layout_of(substs.type_at(1)).map(|layout| layout.is_zst()).unwrap_or(false)
+            }
             _ => false,
         }
     }
@@ -1191,7 +1205,7 @@ impl<'a, 'tcx, M: Machine<'tcx>> EvalContext<'a, 'tcx, M> {
             ty::TyRef(_, ref tam) |
             ty::TyRawPtr(ref tam) if self.type_is_sized(tam.ty) => PrimValKind::Ptr,
 
-            ty::TyAdt(def, _) if def.is_box() => PrimValKind::Ptr,
+            ty::TyAdt(..) if self.is_ptr_box(ty) => PrimValKind::Ptr,
 
             ty::TyAdt(..) => {
                 match self.layout_of(ty)?.abi {
@@ -1322,7 +1336,7 @@ impl<'a, 'tcx, M: Machine<'tcx>> EvalContext<'a, 'tcx, M> {
             ty::TyRawPtr(ref tam) => return self.read_ptr(ptr, ptr_align, tam.ty).map(Some),
 
             ty::TyAdt(def, _) => {
-                if def.is_box() {
+                if def.is_box() && self.is_ptr_box(ty) {
                     return self.read_ptr(ptr, ptr_align, ty.boxed_ty()).map(Some);
                 }
 
@@ -1563,6 +1577,13 @@ impl<'a, 'tcx, M: Machine<'tcx>> EvalContext<'a, 'tcx, M> {
     }
 
     pub fn report(&self, e: &mut EvalError) {
+        self.report_with(e, |_| {})
+    }
+
+    /// Like `report`, but `add_notes` can add to the error pointing into the stack.
+    pub fn report_with<F>(&self, e: &mut EvalError, add_notes: F)
+        where F: FnOnce(&mut DiagnosticBuilder)
+    {
         if let Some(ref mut backtrace) = e.backtrace {
             let mut trace_text = "\n\nAn error occurred in miri:\n".to_string();
             backtrace.resolve();
@@ -1610,6 +1631,7 @@ impl<'a, 'tcx, M: Machine<'tcx>> EvalContext<'a, 'tcx, M> {
                 }
                 err.span_note(span, &format!("inside call to {}", instance));
             }
+            add_notes(&mut err);
             err.emit();
         } else {
             self.tcx.sess.err(&e.to_string());
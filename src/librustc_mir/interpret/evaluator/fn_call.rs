@@ -0,0 +1,420 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use rustc::hir::def_id::DefId;
+use rustc::mir;
+use rustc::mir::interpret::{EvalError, EvalErrorKind, EvalResult, PrimVal};
+use rustc::ty::{self, TyCtxt};
+use rustc::ty::layout::{Align, LayoutOf, TyLayout};
+use syntax::attr;
+use syntax::codemap::Span;
+
+use super::{Evaluator, EvaluatorMemoryKind};
+use super::races::ThreadId;
+use super::super::{EvalContext, HasMemory, MemoryKind, Place, StackPopCleanup, ValTy};
+
+pub(super) fn eval_fn_call<'a, 'tcx>(
+    ecx: &mut EvalContext<'a, 'tcx, Evaluator<'tcx>>,
+    instance: ty::Instance<'tcx>,
+    destination: Option<(Place, mir::BasicBlock)>,
+    args: &[ValTy<'tcx>],
+    span: Span,
+    sig: ty::FnSig<'tcx>,
+) -> EvalResult<'tcx, bool> {
+    trace!("eval_fn_call: {:#?}, {:?}", instance, destination);
+
+    // There is no unwinding, a panic ends the evaluation.
+    if is_panic_fn(ecx.tcx, instance.def_id()) {
+        return err!(Panic);
+    }
+
+    let mir = match ecx.load_mir(instance.def) {
+        Ok(mir) => mir,
+        Err(EvalError { kind: EvalErrorKind::NoMirFor(path), .. }) => {
+            // Other functions of other crates only have MIR if those
+            // were built with `-Z always-encode-mir`.
+            if !ecx.tcx.is_foreign_item(instance.def_id()) {
+                return err!(NoMirFor(path));
+            }
+            call_foreign_fn(ecx, instance.def_id(), destination, args, sig)?;
+            return Ok(true);
+        }
+        Err(other) => return Err(other),
+    };
+    let (return_place, return_to_block) = match destination {
+        Some((place, block)) => (place, StackPopCleanup::Goto(block)),
+        None => (Place::undef(), StackPopCleanup::None),
+    };
+
+    ecx.push_stack_frame(
+        instance,
+        span,
+        mir,
+        return_place,
+        return_to_block,
+    )?;
+
+    Ok(false)
+}
+
+/// Whether calling `def_id` starts a panic.
+fn is_panic_fn(tcx: TyCtxt, def_id: DefId) -> bool {
+    let lang_items = tcx.lang_items();
+    if Some(def_id) == lang_items.panic_fn() ||
+        Some(def_id) == lang_items.panic_bounds_check_fn() ||
+        Some(def_id) == lang_items.panic_fmt()
+    {
+        return true;
+    }
+    let path = tcx.item_path_str(def_id);
+    path == "std::panicking::begin_panic" || path == "std::panicking::begin_panic_fmt"
+}
+
+/// Checks the size and alignment passed to the Rust allocator functions.
+fn heap_align<'tcx>(size: u64, align: u64) -> EvalResult<'tcx, Align> {
+    if size == 0 {
+        return err!(HeapAllocZeroBytes);
+    }
+    if !align.is_power_of_two() {
+        return err!(HeapAllocNonPowerOfTwoAlignment(align));
+    }
+    Ok(Align::from_bytes(align, align).unwrap())
+}
+
+/// The layout of the type `arg`, the first argument of the foreign function `name`,
+/// points to.
+fn pointee_layout<'a, 'tcx>(
+    ecx: &EvalContext<'a, 'tcx, Evaluator<'tcx>>,
+    arg: ValTy<'tcx>,
+    name: &str,
+) -> EvalResult<'tcx, TyLayout<'tcx>> {
+    match arg.ty.builtin_deref(true, ty::LvaluePreference::NoPreference) {
+        Some(tam) => ecx.layout_of(tam.ty),
+        None => {
+            let msg = format!("the first argument of `{}` must be a pointer", name);
+            err!(AbiViolation(msg))
+        }
+    }
+}
+
+/// Emulates a call to the foreign function `def_id`.
+fn call_foreign_fn<'a, 'tcx>(
+    ecx: &mut EvalContext<'a, 'tcx, Evaluator<'tcx>>,
+    def_id: DefId,
+    destination: Option<(Place, mir::BasicBlock)>,
+    args: &[ValTy<'tcx>],
+    sig: ty::FnSig<'tcx>,
+) -> EvalResult<'tcx> {
+    let attrs = ecx.tcx.get_attrs(def_id);
+    let link_name = match attr::first_attr_value_str_by_name(&attrs, "link_name") {
+        Some(name) => name.as_str(),
+        None => ecx.tcx.item_name(def_id),
+    };
+    let (dest, target) = match destination {
+        Some(destination) => destination,
+        None => {
+            let msg = format!("can't call diverging foreign function: {}", link_name);
+            return err!(Unimplemented(msg));
+        }
+    };
+    let dest_ty = sig.output();
+    let rust_heap = MemoryKind::Machine(EvaluatorMemoryKind::Rust);
+    let c_heap = MemoryKind::Machine(EvaluatorMemoryKind::C);
+    let env = MemoryKind::Machine(EvaluatorMemoryKind::Env);
+
+    match &link_name[..] {
+        "__rust_alloc" | "__rust_alloc_zeroed" => {
+            let size = ecx.value_to_primval(args[0])?.to_u64()?;
+            let align = ecx.value_to_primval(args[1])?.to_u64()?;
+            let align = heap_align(size, align)?;
+            let ptr = ecx.memory.allocate(size, align, Some(rust_heap))?;
+            if &link_name[..] == "__rust_alloc_zeroed" {
+                ecx.memory.write_repeat(ptr.into(), 0, size)?;
+            }
+            ecx.write_primval(dest, PrimVal::Ptr(ptr), dest_ty)?;
+        }
+
+        "__rust_dealloc" => {
+            let ptr = ecx.into_ptr(args[0].value)?.to_ptr()?;
+            let size = ecx.value_to_primval(args[1])?.to_u64()?;
+            let align = ecx.value_to_primval(args[2])?.to_u64()?;
+            let align = heap_align(size, align)?;
+            ecx.memory.deallocate(ptr, Some((size, align)), rust_heap)?;
+        }
+
+        "__rust_realloc" => {
+            let ptr = ecx.into_ptr(args[0].value)?.to_ptr()?;
+            let old_size = ecx.value_to_primval(args[1])?.to_u64()?;
+            let old_align = ecx.value_to_primval(args[2])?.to_u64()?;
+            let new_size = ecx.value_to_primval(args[3])?.to_u64()?;
+            let new_align = ecx.value_to_primval(args[4])?.to_u64()?;
+            let old_align = heap_align(old_size, old_align)?;
+            let new_align = heap_align(new_size, new_align)?;
+            let new_ptr = ecx.memory.reallocate(
+                ptr,
+                old_size,
+                old_align,
+                new_size,
+                new_align,
+                rust_heap,
+            )?;
+            ecx.write_primval(dest, PrimVal::Ptr(new_ptr), dest_ty)?;
+        }
+
+        "malloc" => {
+            let size = ecx.value_to_primval(args[0])?.to_u64()?;
+            if size == 0 {
+                ecx.write_primval(dest, PrimVal::Bytes(0), dest_ty)?;
+            } else {
+                // Suitably aligned for any fundamental type.
+                let align = ecx.memory.pointer_size() * 2;
+                let align = Align::from_bytes(align, align).unwrap();
+                let ptr = ecx.memory.allocate(size, align, Some(c_heap))?;
+                ecx.write_primval(dest, PrimVal::Ptr(ptr), dest_ty)?;
+            }
+        }
+
+        "free" => {
+            let ptr = ecx.into_ptr(args[0].value)?;
+            if !ptr.is_null()? {
+                ecx.memory.deallocate(ptr.to_ptr()?, None, c_heap)?;
+            }
+        }
+
+        "write" => {
+            let fd = ecx.value_to_primval(args[0])?.to_bytes()? as i32;
+            let buf = ecx.into_ptr(args[1].value)?;
+            let n = ecx.value_to_primval(args[2])?.to_u64()?;
+            let result = if fd == 1 || fd == 2 {
+                let bytes = ecx.memory.read_bytes(buf, n)?;
+                let written = if fd == 1 {
+                    io::stdout().write(bytes)
+                } else {
+                    io::stderr().write(bytes)
+                };
+                match written {
+                    Ok(written) => written as i128,
+                    Err(_) => -1,
+                }
+            } else {
+                // Only the standard streams are emulated.
+                -1
+            };
+            ecx.write_primval(dest, PrimVal::from_i128(result), dest_ty)?;
+        }
+
+        "getenv" => {
+            let name_ptr = ecx.into_ptr(args[0].value)?.to_ptr()?;
+            let result = {
+                let name = ecx.memory.read_c_str(name_ptr)?;
+                match ecx.machine.env_vars.get(name) {
+                    Some(&var) => PrimVal::Ptr(var),
+                    None => PrimVal::Bytes(0),
+                }
+            };
+            ecx.write_primval(dest, result, dest_ty)?;
+        }
+
+        "setenv" => {
+            let name_ptr = ecx.into_ptr(args[0].value)?.to_ptr()?;
+            let value_ptr = ecx.into_ptr(args[1].value)?.to_ptr()?;
+            let overwrite = ecx.value_to_primval(args[2])?.to_bytes()? != 0;
+            let name = ecx.memory.read_c_str(name_ptr)?.to_owned();
+            let mut value = ecx.memory.read_c_str(value_ptr)?.to_owned();
+            let result = if name.is_empty() || name.contains(&b'=') {
+                -1
+            } else if !overwrite && ecx.machine.env_vars.contains_key(&name) {
+                0
+            } else {
+                // `getenv` hands out pointers to the values, so they live in memory.
+                value.push(0);
+                let align = Align::from_bytes(1, 1).unwrap();
+                let var = ecx.memory.allocate(value.len() as u64, align, Some(env))?;
+                ecx.memory.write_bytes(var.into(), &value)?;
+                if let Some(old) = ecx.machine.env_vars.insert(name, var) {
+                    ecx.memory.deallocate(old, None, env)?;
+                }
+                0
+            };
+            ecx.write_primval(dest, PrimVal::from_i128(result), dest_ty)?;
+        }
+
+        "unsetenv" => {
+            let name_ptr = ecx.into_ptr(args[0].value)?.to_ptr()?;
+            let name = ecx.memory.read_c_str(name_ptr)?.to_owned();
+            let result = if name.is_empty() || name.contains(&b'=') {
+                -1
+            } else {
+                if let Some(old) = ecx.machine.env_vars.remove(&name) {
+                    ecx.memory.deallocate(old, None, env)?;
+                }
+                0
+            };
+            ecx.write_primval(dest, PrimVal::from_i128(result), dest_ty)?;
+        }
+
+        "pthread_key_create" => {
+            let key_ptr = ecx.into_ptr(args[0].value)?.to_ptr()?;
+            let dtor = match ecx.into_ptr(args[1].value)?.into_inner_primval() {
+                PrimVal::Ptr(dtor_ptr) => Some(ecx.memory.get_fn(dtor_ptr)?),
+                PrimVal::Bytes(0) => None,
+                PrimVal::Bytes(_) => return err!(ReadBytesAsPointer),
+                PrimVal::Undef => return err!(ReadUndefBytes),
+            };
+            // The size of `pthread_key_t` depends on the platform.
+            let key_layout = pointee_layout(ecx, args[0], &link_name[..])?;
+            let key = ecx.machine.create_tls_key(dtor);
+            let key_bits = key_layout.size.bits();
+            if key_bits < 128 && key >= 1 << key_bits {
+                return err!(OutOfTls);
+            }
+            ecx.memory.write_primval(
+                key_ptr,
+                key_layout.align,
+                PrimVal::Bytes(key),
+                key_layout.size.bytes(),
+                false,
+            )?;
+            ecx.write_primval(dest, PrimVal::Bytes(0), dest_ty)?;
+        }
+
+        "pthread_key_delete" => {
+            let key = ecx.value_to_primval(args[0])?.to_bytes()?;
+            ecx.machine.delete_tls_key(key)?;
+            ecx.write_primval(dest, PrimVal::Bytes(0), dest_ty)?;
+        }
+
+        "pthread_getspecific" => {
+            let key = ecx.value_to_primval(args[0])?.to_bytes()?;
+            let ptr = ecx.machine.load_tls(key)?;
+            ecx.write_ptr(dest, ptr, dest_ty)?;
+        }
+
+        "pthread_setspecific" => {
+            let key = ecx.value_to_primval(args[0])?.to_bytes()?;
+            let new_ptr = ecx.into_ptr(args[1].value)?;
+            ecx.machine.store_tls(key, new_ptr)?;
+            ecx.write_primval(dest, PrimVal::Bytes(0), dest_ty)?;
+        }
+
+        "pthread_create" => {
+            let thread_ptr = ecx.into_ptr(args[0].value)?.to_ptr()?;
+            let start_ptr = ecx.into_ptr(args[2].value)?.to_ptr()?;
+            let start = ecx.memory.get_fn(start_ptr)?;
+            let arg = ecx.into_ptr(args[3].value)?;
+            // The new thread runs to completion right away, with its own thread locals.
+            let thread = ecx.memory.data.races.start_thread();
+            let parent_tls = ecx.machine.swap_tls_values(BTreeMap::new());
+            let ret = super::call_with_ptr_arg(ecx, start, arg)?;
+            let ret = ecx.read_place(ret)?;
+            let ret = ecx.into_ptr(ret)?;
+            super::run_tls_dtors(ecx)?;
+            ecx.machine.swap_tls_values(parent_tls);
+            ecx.memory.data.races.finish_thread();
+            ecx.machine.finished_threads.insert(thread, ret);
+
+            // The size of `pthread_t` depends on the platform.
+            let thread_layout = pointee_layout(ecx, args[0], &link_name[..])?;
+            ecx.memory.write_primval(
+                thread_ptr,
+                thread_layout.align,
+                PrimVal::Bytes(thread as u128),
+                thread_layout.size.bytes(),
+                false,
+            )?;
+            ecx.write_primval(dest, PrimVal::Bytes(0), dest_ty)?;
+        }
+
+        "pthread_join" => {
+            let thread = ecx.value_to_primval(args[0])?.to_bytes()? as ThreadId;
+            let retval_ptr = ecx.into_ptr(args[1].value)?;
+            let ret = match ecx.machine.finished_threads.remove(&thread) {
+                Some(ret) => ret,
+                None => {
+                    let msg = format!("thread {} doesn't exist or was already joined", thread);
+                    return err!(AbiViolation(msg));
+                }
+            };
+            ecx.memory.data.races.join_thread(thread);
+            if !retval_ptr.is_null()? {
+                let ptr_align = ecx.tcx.data_layout.pointer_align;
+                ecx.memory.write_ptr_sized_unsigned(
+                    retval_ptr.to_ptr()?,
+                    ptr_align,
+                    ret.into_inner_primval(),
+                )?;
+            }
+            ecx.write_primval(dest, PrimVal::Bytes(0), dest_ty)?;
+        }
+
+        "pthread_detach" => {
+            let thread = ecx.value_to_primval(args[0])?.to_bytes()? as ThreadId;
+            ecx.machine.finished_threads.remove(&thread);
+            ecx.write_primval(dest, PrimVal::Bytes(0), dest_ty)?;
+        }
+
+        // The threads never run at the same time, so taking a lock always succeeds,
+        // but it orders the accesses of the threads for the detection of data races.
+        "pthread_mutex_lock" | "pthread_rwlock_rdlock" | "pthread_rwlock_wrlock" => {
+            let lock = ecx.into_ptr(args[0].value)?.to_ptr()?;
+            ecx.memory.data.races.lock(lock);
+            ecx.write_primval(dest, PrimVal::Bytes(0), dest_ty)?;
+        }
+
+        "pthread_mutex_unlock" | "pthread_rwlock_unlock" => {
+            let lock = ecx.into_ptr(args[0].value)?.to_ptr()?;
+            ecx.memory.data.races.unlock(lock);
+            ecx.write_primval(dest, PrimVal::Bytes(0), dest_ty)?;
+        }
+
+        "pthread_mutex_init" | "pthread_mutex_destroy" | "pthread_mutexattr_init" |
+        "pthread_mutexattr_settype" | "pthread_mutexattr_destroy" => {
+            ecx.write_primval(dest, PrimVal::Bytes(0), dest_ty)?;
+        }
+
+        "memcmp" => {
+            let left = ecx.into_ptr(args[0].value)?;
+            let right = ecx.into_ptr(args[1].value)?;
+            let n = ecx.value_to_primval(args[2])?.to_u64()?;
+            let result = {
+                let left_bytes = ecx.memory.read_bytes(left, n)?;
+                let right_bytes = ecx.memory.read_bytes(right, n)?;
+                match left_bytes.cmp(right_bytes) {
+                    Ordering::Less => -1,
+                    Ordering::Equal => 0,
+                    Ordering::Greater => 1,
+                }
+            };
+            ecx.write_primval(dest, PrimVal::from_i128(result), dest_ty)?;
+        }
+
+        "memchr" => {
+            let ptr = ecx.into_ptr(args[0].value)?;
+            let val = ecx.value_to_primval(args[1])?.to_bytes()? as u8;
+            let n = ecx.value_to_primval(args[2])?.to_u64()?;
+            let idx = ecx.memory.read_bytes(ptr, n)?.iter().position(|&c| c == val);
+            match idx {
+                Some(idx) => {
+                    let new_ptr = ptr.offset(idx as u64, &ecx.tcx.data_layout)?;
+                    ecx.write_ptr(dest, new_ptr, dest_ty)?;
+                }
+                None => ecx.write_primval(dest, PrimVal::Bytes(0), dest_ty)?,
+            }
+        }
+
+        "strlen" => {
+            let ptr = ecx.into_ptr(args[0].value)?.to_ptr()?;
+            let n = ecx.memory.read_c_str(ptr)?.len();
+            ecx.write_primval(dest, PrimVal::from_u128(n as u128), dest_ty)?;
+        }
+
+        _ => return err!(Unimplemented(format!("can't call foreign function: {}", link_name))),
+    }
+
+    // Since we pushed no stack frame, the main loop will act
+    // as if the call just completed and it's returning to the
+    // current frame.
+    ecx.goto_block(target);
+    Ok(())
+}
@@ -0,0 +1,373 @@
+use rustc::mir;
+use rustc::mir::interpret::{EvalErrorKind, EvalResult, Pointer, PrimVal, PrimValKind, Value};
+use rustc::ty::{self, Ty};
+use rustc::ty::layout::{LayoutOf, TyLayout};
+
+use super::Evaluator;
+use super::super::{EvalContext, HasMemory, Place, StackPopCleanup, ValTy};
+
+pub(super) fn call_intrinsic<'a, 'tcx>(
+    ecx: &mut EvalContext<'a, 'tcx, Evaluator<'tcx>>,
+    instance: ty::Instance<'tcx>,
+    args: &[ValTy<'tcx>],
+    dest: Place,
+    dest_layout: TyLayout<'tcx>,
+    target: mir::BasicBlock,
+) -> EvalResult<'tcx> {
+    use rustc::mir::BinOp::*;
+
+    let substs = instance.substs;
+    let intrinsic_name = &ecx.tcx.item_name(instance.def_id())[..];
+    match intrinsic_name {
+        "add_with_overflow" => {
+            ecx.intrinsic_with_overflow(Add, args[0], args[1], dest, dest_layout.ty)?
+        }
+        "sub_with_overflow" => {
+            ecx.intrinsic_with_overflow(Sub, args[0], args[1], dest, dest_layout.ty)?
+        }
+        "mul_with_overflow" => {
+            ecx.intrinsic_with_overflow(Mul, args[0], args[1], dest, dest_layout.ty)?
+        }
+
+        "overflowing_add" => {
+            ecx.intrinsic_overflowing(Add, args[0], args[1], dest, dest_layout.ty)?;
+        }
+        "overflowing_sub" => {
+            ecx.intrinsic_overflowing(Sub, args[0], args[1], dest, dest_layout.ty)?;
+        }
+        "overflowing_mul" => {
+            ecx.intrinsic_overflowing(Mul, args[0], args[1], dest, dest_layout.ty)?;
+        }
+
+        "unchecked_div" | "unchecked_rem" | "exact_div" => {
+            let ty = substs.type_at(0);
+            let l = ecx.value_to_primval(args[0])?;
+            let r = ecx.value_to_primval(args[1])?;
+            if r.to_bytes()? == 0 {
+                return err!(Intrinsic(format!("Division by 0 in {}", intrinsic_name)));
+            }
+            if intrinsic_name == "exact_div" {
+                let (rem, _) = ecx.binary_op(Rem, l, ty, r, ty)?;
+                if rem.to_bytes()? != 0 {
+                    return err!(Intrinsic(format!("Inexact division in {}", intrinsic_name)));
+                }
+            }
+            let op = if intrinsic_name == "unchecked_rem" { Rem } else { Div };
+            if ecx.intrinsic_overflowing(op, args[0], args[1], dest, dest_layout.ty)? {
+                return err!(Intrinsic(format!("Overflowing arithmetic in {}", intrinsic_name)));
+            }
+        }
+
+        "unchecked_shl" | "unchecked_shr" => {
+            let bits = dest_layout.size.bits() as u128;
+            let rhs = ecx.value_to_primval(args[1])?.to_bytes()?;
+            if rhs >= bits {
+                return err!(Intrinsic(
+                    format!("Overflowing shift by {} in {}", rhs, intrinsic_name),
+                ));
+            }
+            let op = if intrinsic_name == "unchecked_shl" { Shl } else { Shr };
+            ecx.intrinsic_overflowing(op, args[0], args[1], dest, dest_layout.ty)?;
+        }
+
+        "offset" | "arith_offset" => {
+            let offset = ecx.value_to_primval(args[1])?.to_i128()? as i64;
+            let ptr = ecx.into_ptr(args[0].value)?;
+            let pointee_ty = substs.type_at(0);
+            let result_ptr = if intrinsic_name == "offset" {
+                super::pointer_offset(ecx, ptr, pointee_ty, offset)?
+            } else {
+                let pointee_size = ecx.layout_of(pointee_ty)?.size.bytes() as i64;
+                let offset = offset.wrapping_mul(pointee_size);
+                ptr.wrapping_signed_offset(offset, &ecx.tcx.data_layout)?
+            };
+            ecx.write_ptr(dest, result_ptr, dest_layout.ty)?;
+        }
+
+        "assume" => {
+            if !ecx.value_to_primval(args[0])?.to_bool()? {
+                return err!(AssumptionNotHeld);
+            }
+        }
+
+        "likely" | "unlikely" => {
+            let cond = ecx.value_to_primval(args[0])?;
+            ecx.write_primval(dest, cond, dest_layout.ty)?;
+        }
+
+        "forget" => {}
+
+        "abort" => {
+            return err!(Intrinsic("the evaluated program aborted".to_string()));
+        }
+
+        "copy" | "copy_nonoverlapping" => {
+            let elem_layout = ecx.layout_of(substs.type_at(0))?;
+            let count = ecx.value_to_primval(args[2])?.to_u64()?;
+            let src = ecx.into_ptr(args[0].value)?;
+            let dest_ptr = ecx.into_ptr(args[1].value)?;
+            ecx.memory.copy(
+                src,
+                elem_layout.align,
+                dest_ptr,
+                elem_layout.align,
+                count * elem_layout.size.bytes(),
+                intrinsic_name.ends_with("_nonoverlapping"),
+            )?;
+        }
+
+        "write_bytes" => {
+            let layout = ecx.layout_of(substs.type_at(0))?;
+            let val_byte = ecx.value_to_primval(args[1])?.to_u128()? as u8;
+            let count = ecx.value_to_primval(args[2])?.to_u64()?;
+            let ptr = ecx.into_ptr(args[0].value)?;
+            ecx.memory.check_align(ptr, layout.align)?;
+            ecx.memory.write_repeat(ptr, val_byte, layout.size.bytes() * count)?;
+        }
+
+        "ctpop" | "cttz" | "cttz_nonzero" | "ctlz" | "ctlz_nonzero" | "bswap" => {
+            let ty = substs.type_at(0);
+            let num = ecx.value_to_primval(args[0])?.to_bytes()?;
+            let kind = ecx.ty_to_primval_kind(ty)?;
+            let name = if intrinsic_name.ends_with("_nonzero") {
+                if num == 0 {
+                    return err!(Intrinsic(format!("{} called on 0", intrinsic_name)));
+                }
+                intrinsic_name.trim_right_matches("_nonzero")
+            } else {
+                intrinsic_name
+            };
+            let num = numeric_intrinsic(name, num, kind);
+            ecx.write_primval(dest, num, ty)?;
+        }
+
+        "discriminant_value" => {
+            let ty = substs.type_at(0);
+            let ptr = ecx.into_ptr(args[0].value)?;
+            let place = Place::from_primval_ptr(ptr, ecx.layout_of(ty)?.align);
+            let discr_val = ecx.read_discriminant_value(place, ty)?;
+            if let ty::TyAdt(adt_def, _) = ty.sty {
+                let tcx = ecx.tcx;
+                if adt_def.is_enum() &&
+                    adt_def.discriminants(tcx).all(|v| discr_val != v.to_u128_unchecked())
+                {
+                    return err!(InvalidDiscriminant);
+                }
+            }
+            ecx.write_primval(dest, PrimVal::Bytes(discr_val), dest_layout.ty)?;
+        }
+
+        "size_of" => {
+            let size = ecx.layout_of(substs.type_at(0))?.size.bytes();
+            ecx.write_primval(dest, PrimVal::from_u128(size as u128), dest_layout.ty)?;
+        }
+
+        "min_align_of" => {
+            let align = ecx.layout_of(substs.type_at(0))?.align.abi();
+            ecx.write_primval(dest, PrimVal::from_u128(align as u128), dest_layout.ty)?;
+        }
+
+        "pref_align_of" => {
+            let align = ecx.layout_of(substs.type_at(0))?.align.pref();
+            ecx.write_primval(dest, PrimVal::from_u128(align as u128), dest_layout.ty)?;
+        }
+
+        "size_of_val" | "min_align_of_val" => {
+            let ty = substs.type_at(0);
+            let (size, align) = ecx.size_and_align_of_dst(ty, args[0].value)?;
+            let val = if intrinsic_name == "size_of_val" {
+                size.bytes()
+            } else {
+                align.abi()
+            };
+            ecx.write_primval(dest, PrimVal::from_u128(val as u128), dest_layout.ty)?;
+        }
+
+        "needs_drop" => {
+            let needs_drop = substs.type_at(0).needs_drop(ecx.tcx, ecx.param_env);
+            ecx.write_primval(dest, PrimVal::from_bool(needs_drop), dest_layout.ty)?;
+        }
+
+        "type_id" => {
+            let type_id = ecx.tcx.type_id_hash(substs.type_at(0));
+            ecx.write_primval(dest, PrimVal::from_u128(type_id as u128), dest_layout.ty)?;
+        }
+
+        "type_name" => {
+            let name = substs.type_at(0).to_string();
+            let value = ecx.str_to_value(&name)?;
+            ecx.write_value(ValTy { value, ty: dest_layout.ty }, dest)?;
+        }
+
+        "transmute" => {
+            let src_ty = substs.type_at(0);
+            let dest = ecx.force_allocation(dest)?;
+            let (dest_ptr, dest_align) = dest.to_ptr_align();
+            ecx.write_value_to_ptr(args[0].value, dest_ptr, dest_align, src_ty)?;
+        }
+
+        "init" | "uninit" => {
+            let size = dest_layout.size.bytes();
+            if size != 0 {
+                let (ptr, _) = ecx.force_allocation(dest)?.to_ptr_align();
+                if intrinsic_name == "init" {
+                    ecx.memory.write_repeat(ptr, 0, size)?;
+                } else {
+                    ecx.memory.mark_definedness(ptr, size, false)?;
+                }
+            }
+        }
+
+        "move_val_init" => {
+            let ty = substs.type_at(0);
+            let ptr = ecx.into_ptr(args[0].value)?;
+            let align = ecx.layout_of(ty)?.align;
+            ecx.write_value_to_ptr(args[1].value, ptr, align, ty)?;
+        }
+
+        // There is a single thread, so atomic operations are plain reads and writes.
+        name if name.starts_with("volatile_load") || name.starts_with("atomic_load") => {
+            let ty = substs.type_at(0);
+            let ptr = ecx.into_ptr(args[0].value)?;
+            let align = ecx.layout_of(ty)?.align;
+            ecx.write_value(ValTy { value: Value::ByRef(ptr, align), ty }, dest)?;
+        }
+
+        name if name.starts_with("volatile_store") || name.starts_with("atomic_store") => {
+            let ty = substs.type_at(0);
+            let ptr = ecx.into_ptr(args[0].value)?;
+            let align = ecx.layout_of(ty)?.align;
+            ecx.write_value_to_ptr(args[1].value, ptr, align, ty)?;
+        }
+
+        name if name.starts_with("atomic_fence") ||
+            name.starts_with("atomic_singlethreadfence") => {}
+
+        name if name.starts_with("atomic_xchg") => {
+            let ty = substs.type_at(0);
+            let (place, old) = read_atomic(ecx, args[0], ty)?;
+            let new = ecx.value_to_primval(args[1])?;
+            ecx.write_primval(dest, old, ty)?;
+            ecx.write_primval(place, new, ty)?;
+        }
+
+        name if name.starts_with("atomic_cxchg") => {
+            let ty = substs.type_at(0);
+            let (place, old) = read_atomic(ecx, args[0], ty)?;
+            let expected = ecx.value_to_primval(args[1])?;
+            let new = ecx.value_to_primval(args[2])?;
+            let (success, _) = ecx.binary_op(Eq, old, ty, expected, ty)?;
+            let value = Value::ByValPair(old, success);
+            ecx.write_value(ValTy { value, ty: dest_layout.ty }, dest)?;
+            if success.to_bool()? {
+                ecx.write_primval(place, new, ty)?;
+            }
+        }
+
+        "try" => {
+            // There is no unwinding, so this just calls `f(data)` and a panic
+            // inside of it is reported as an error.
+            let f = ecx.into_ptr(args[0].value)?.to_ptr()?;
+            let data = ecx.into_ptr(args[1].value)?;
+            let f_instance = ecx.memory.get_fn(f)?;
+            ecx.write_primval(dest, PrimVal::Bytes(0), dest_layout.ty)?;
+
+            let mir = ecx.load_mir(f_instance.def)?;
+            ecx.push_stack_frame(
+                f_instance,
+                mir.span,
+                mir,
+                Place::undef(),
+                StackPopCleanup::Goto(target),
+            )?;
+            let arg_local = ecx.frame().mir.args_iter().next().ok_or_else(|| {
+                EvalErrorKind::AbiViolation("the `try` callback takes no argument".to_string())
+            })?;
+            let arg_dest = ecx.eval_place(&mir::Place::Local(arg_local))?;
+            let u8_ptr_ty = ecx.tcx.mk_mut_ptr(ecx.tcx.types.u8);
+            ecx.write_ptr(arg_dest, data, u8_ptr_ty)?;
+            // The frame we pushed continues at `target` once it returns.
+            return Ok(());
+        }
+
+        name => match atomic_rmw_op(name) {
+            Some(op) => {
+                let ty = substs.type_at(0);
+                let (place, old) = read_atomic(ecx, args[0], ty)?;
+                let change = ecx.value_to_primval(args[1])?;
+                ecx.write_primval(dest, old, ty)?;
+                // Atomic arithmetic wraps around.
+                let (new, _) = ecx.binary_op(op, old, ty, change, ty)?;
+                ecx.write_primval(place, new, ty)?;
+            }
+            None => return err!(Unimplemented(format!("unimplemented intrinsic: {}", name))),
+        },
+    }
+
+    ecx.goto_block(target);
+
+    // Since we pushed no stack frame, the main loop will act
+    // as if the call just completed and it's returning to the
+    // current frame.
+    Ok(())
+}
+
+/// Reads the primitive value pointed to by the first argument of an atomic intrinsic.
+fn read_atomic<'a, 'tcx>(
+    ecx: &mut EvalContext<'a, 'tcx, Evaluator<'tcx>>,
+    ptr: ValTy<'tcx>,
+    ty: Ty<'tcx>,
+) -> EvalResult<'tcx, (Place, PrimVal)> {
+    let ptr: Pointer = ecx.into_ptr(ptr.value)?;
+    let align = ecx.layout_of(ty)?.align;
+    let place = Place::from_primval_ptr(ptr, align);
+    match ecx.read_value(ptr, align, ty)? {
+        Value::ByVal(val) => Ok((place, val)),
+        _ => err!(Unimplemented(format!("atomic operation on {}", ty))),
+    }
+}
+
+/// The operation performed by an atomic read-modify-write intrinsic like `atomic_xadd_rel`.
+fn atomic_rmw_op(name: &str) -> Option<mir::BinOp> {
+    if !name.starts_with("atomic_") {
+        return None;
+    }
+    match name.split('_').nth(1) {
+        Some("xadd") => Some(mir::BinOp::Add),
+        Some("xsub") => Some(mir::BinOp::Sub),
+        Some("and") => Some(mir::BinOp::BitAnd),
+        Some("or") => Some(mir::BinOp::BitOr),
+        Some("xor") => Some(mir::BinOp::BitXor),
+        _ => None,
+    }
+}
+
+fn numeric_intrinsic(name: &str, bytes: u128, kind: PrimValKind) -> PrimVal {
+    macro_rules! integer_intrinsic {
+        ($method:ident) => ({
+            use rustc::mir::interpret::PrimValKind::*;
+            let result_bytes = match kind {
+                I8 => (bytes as i8).$method() as u128,
+                U8 => (bytes as u8).$method() as u128,
+                I16 => (bytes as i16).$method() as u128,
+                U16 => (bytes as u16).$method() as u128,
+                I32 => (bytes as i32).$method() as u128,
+                U32 => (bytes as u32).$method() as u128,
+                I64 => (bytes as i64).$method() as u128,
+                U64 => (bytes as u64).$method() as u128,
+                I128 => (bytes as i128).$method() as u128,
+                U128 => bytes.$method() as u128,
+                _ => bug!("invalid `{}` argument: {:?}", name, bytes),
+            };
+            PrimVal::Bytes(result_bytes)
+        });
+    }
+
+    match name {
+        "bswap" => integer_intrinsic!(swap_bytes),
+        "ctlz" => integer_intrinsic!(leading_zeros),
+        "ctpop" => integer_intrinsic!(count_ones),
+        "cttz" => integer_intrinsic!(trailing_zeros),
+        _ => bug!("not a numeric intrinsic: {}", name),
+    }
+}
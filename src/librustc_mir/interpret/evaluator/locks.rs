@@ -0,0 +1,314 @@
+//! The locks acquired and released by `Validate` statements.
+//!
+//! `AddValidation` makes functions acquire their arguments when they are called and
+//! release (or suspend, for reborrows) what they pass on to other functions. Acquiring
+//! a `&mut T` write-locks the memory it points to for the current stack frame, acquiring
+//! a `&T` to memory without interior mutability read-locks it. Any access which conflicts
+//! with a lock held by another frame is undefined behaviour, as it breaks the aliasing
+//! guarantees of the references.
+
+use std::collections::HashMap;
+
+use rustc::middle::region;
+use rustc::mir;
+use rustc::mir::interpret::{AccessKind, AllocId, DynamicLifetime, EvalResult, Lock,
+                            MemoryPointer, PrimVal};
+use rustc::ty::{self, Ty};
+use rustc::ty::layout::LayoutOf;
+use rustc::hir;
+use rustc_data_structures::indexed_vec::Idx;
+
+use super::Evaluator;
+use super::super::{EvalContext, HasMemory, Place};
+
+/// A range of an allocation locked by a stack frame.
+#[derive(Clone, Debug)]
+struct LockedRange {
+    offset: u64,
+    len: u64,
+    kind: AccessKind,
+    lifetime: DynamicLifetime,
+}
+
+impl LockedRange {
+    fn overlaps(&self, offset: u64, len: u64) -> bool {
+        self.offset < offset + len && offset < self.offset + self.len
+    }
+
+    /// Whether `frame` may perform the given access on the locked memory.
+    fn permits(&self, frame: usize, access: AccessKind) -> bool {
+        match self.kind {
+            AccessKind::Write => self.lifetime.frame == frame,
+            AccessKind::Read => access == AccessKind::Read,
+        }
+    }
+
+    fn to_lock(&self) -> Lock {
+        match self.kind {
+            AccessKind::Write => Lock::WriteLock(self.lifetime),
+            AccessKind::Read => Lock::ReadLock(vec![self.lifetime]),
+        }
+    }
+}
+
+/// A write lock given up by a frame until the end of one of its regions.
+#[derive(Clone, Debug)]
+struct Suspension {
+    alloc_id: AllocId,
+    range: LockedRange,
+    until: region::Scope,
+}
+
+/// The locks held on the memory of the `Evaluator`.
+#[derive(Default)]
+pub struct MemoryLocks {
+    locks: HashMap<AllocId, Vec<LockedRange>>,
+    suspended: Vec<Suspension>,
+}
+
+impl MemoryLocks {
+    /// Checks that `frame` may access `len` bytes at `ptr`.
+    pub fn check<'tcx>(
+        &self,
+        frame: usize,
+        ptr: MemoryPointer,
+        len: u64,
+        access: AccessKind,
+    ) -> EvalResult<'tcx> {
+        let ranges = match self.locks.get(&ptr.alloc_id) {
+            Some(ranges) => ranges,
+            None => return Ok(()),
+        };
+        for range in ranges {
+            if range.overlaps(ptr.offset, len) && !range.permits(frame, access) {
+                return err!(MemoryLockViolation {
+                    ptr,
+                    len,
+                    frame,
+                    access,
+                    lock: range.to_lock(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Locks `len` bytes at `ptr` for the given lifetime. Write locks are exclusive,
+    /// read locks may only be shared with other read locks.
+    pub fn acquire<'tcx>(
+        &mut self,
+        ptr: MemoryPointer,
+        len: u64,
+        lifetime: DynamicLifetime,
+        kind: AccessKind,
+    ) -> EvalResult<'tcx> {
+        let ranges = self.locks.entry(ptr.alloc_id).or_insert_with(Vec::new);
+        for range in ranges.iter() {
+            let conflict = range.kind == AccessKind::Write || kind == AccessKind::Write;
+            if conflict && range.overlaps(ptr.offset, len) {
+                return err!(MemoryAcquireConflict {
+                    ptr,
+                    len,
+                    kind,
+                    lock: range.to_lock(),
+                });
+            }
+        }
+        ranges.push(LockedRange {
+            offset: ptr.offset,
+            len,
+            kind,
+            lifetime,
+        });
+        Ok(())
+    }
+
+    /// Gives up the write locks `frame` holds on `len` bytes at `ptr`. If `until` is given,
+    /// they are reacquired when that region of `frame` ends.
+    pub fn release_write<'tcx>(
+        &mut self,
+        ptr: MemoryPointer,
+        len: u64,
+        frame: usize,
+        until: Option<region::Scope>,
+    ) -> EvalResult<'tcx> {
+        let ranges = match self.locks.get_mut(&ptr.alloc_id) {
+            Some(ranges) => ranges,
+            None => return Ok(()),
+        };
+        let mut released = vec![];
+        let mut i = 0;
+        while i < ranges.len() {
+            let write_overlap = ranges[i].kind == AccessKind::Write &&
+                ranges[i].overlaps(ptr.offset, len);
+            if !write_overlap {
+                i += 1;
+                continue;
+            }
+            if ranges[i].lifetime.frame != frame {
+                return err!(InvalidMemoryLockRelease {
+                    ptr,
+                    len,
+                    frame,
+                    lock: ranges[i].to_lock(),
+                });
+            }
+            released.push(ranges.swap_remove(i));
+        }
+        if let Some(until) = until {
+            let alloc_id = ptr.alloc_id;
+            self.suspended.extend(released.into_iter().map(|range| {
+                Suspension { alloc_id, range, until }
+            }));
+        }
+        Ok(())
+    }
+
+    /// Releases the locks whose lifetime ends with `region` of `frame`, or with `frame`
+    /// itself if `region` is `None`, and reacquires the write locks suspended until then.
+    pub fn end_region<'tcx>(
+        &mut self,
+        frame: usize,
+        region: Option<region::Scope>,
+    ) -> EvalResult<'tcx> {
+        for ranges in self.locks.values_mut() {
+            ranges.retain(|range| {
+                range.lifetime.frame != frame ||
+                    (region.is_some() && range.lifetime.region != region)
+            });
+        }
+        let (recovered, suspended) = self.suspended.drain(..).partition::<Vec<_>, _>(|s| {
+            s.range.lifetime.frame == frame && region.map_or(true, |ce| s.until == ce)
+        });
+        self.suspended = suspended;
+        if region.is_some() {
+            for Suspension { alloc_id, range, .. } in recovered {
+                let ptr = MemoryPointer::new(alloc_id, range.offset);
+                self.acquire(ptr, range.len, range.lifetime, range.kind)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Forgets the locks on an allocation which is being deallocated by `frame`.
+    /// The frame may still hold locks on it, e.g. for a local which dies before the
+    /// function returns, but no other frame may hold a write lock.
+    pub fn free<'tcx>(&mut self, frame: usize, alloc_id: AllocId) -> EvalResult<'tcx> {
+        if let Some(ranges) = self.locks.remove(&alloc_id) {
+            for range in ranges {
+                if range.kind == AccessKind::Write && range.lifetime.frame != frame {
+                    return err!(DeallocatedLockedMemory {
+                        ptr: MemoryPointer::new(alloc_id, range.offset),
+                        lock: range.to_lock(),
+                    });
+                }
+            }
+        }
+        self.suspended.retain(|s| s.alloc_id != alloc_id);
+        Ok(())
+    }
+}
+
+/// Performs a validation operation on the place of `operand`, acquiring, releasing or
+/// suspending the locks on the memory its references point to. Acquiring and releasing
+/// also count as accesses to that memory for the detection of data races.
+pub(super) fn validation_op<'a, 'tcx>(
+    ecx: &mut EvalContext<'a, 'tcx, Evaluator<'tcx>>,
+    op: mir::ValidationOp,
+    operand: &mir::ValidationOperand<'tcx, mir::Place<'tcx>>,
+) -> EvalResult<'tcx> {
+    let place = ecx.eval_place(&operand.place)?;
+    let substs = ecx.substs();
+    let ty = ecx.monomorphize(operand.ty, substs);
+    validate(ecx, op, place, ty, operand.re, operand.mutbl)
+}
+
+fn validate<'a, 'tcx>(
+    ecx: &mut EvalContext<'a, 'tcx, Evaluator<'tcx>>,
+    op: mir::ValidationOp,
+    place: Place,
+    ty: Ty<'tcx>,
+    re: Option<region::Scope>,
+    mutbl: hir::Mutability,
+) -> EvalResult<'tcx> {
+    let pointee = match ty.sty {
+        ty::TyRef(_, ty::TypeAndMut { ty: pointee, mutbl: ref_mutbl }) => {
+            // A `&mut T` reached through a shared reference can only be read from.
+            let mutbl = if mutbl == hir::MutMutable { ref_mutbl } else { mutbl };
+            Some((pointee, mutbl))
+        }
+        ty::TyAdt(..) if ty.is_box() => Some((ty.boxed_ty(), mutbl)),
+        _ => None,
+    };
+    if let Some((pointee, mutbl)) = pointee {
+        let (place, _) = ecx.box_ptr_place(place, ty)?;
+        return validate_pointee(ecx, op, place, pointee, re, mutbl);
+    }
+
+    // Look for references in the fields of structs and tuples.
+    let field_count = match ty.sty {
+        ty::TyAdt(adt_def, _) if adt_def.is_struct() => adt_def.non_enum_variant().fields.len(),
+        ty::TyTuple(tys, _) => tys.len(),
+        _ => return Ok(()),
+    };
+    let layout = ecx.layout_of(ty)?;
+    for i in 0..field_count {
+        let (field_place, field_layout) = ecx.place_field(place, mir::Field::new(i), layout)?;
+        validate(ecx, op, field_place, field_layout.ty, re, mutbl)?;
+    }
+    Ok(())
+}
+
+fn validate_pointee<'a, 'tcx>(
+    ecx: &mut EvalContext<'a, 'tcx, Evaluator<'tcx>>,
+    op: mir::ValidationOp,
+    place: Place,
+    pointee: Ty<'tcx>,
+    re: Option<region::Scope>,
+    mutbl: hir::Mutability,
+) -> EvalResult<'tcx> {
+    let value = ecx.read_place(place)?;
+    let ptr = ecx.into_ptr(value)?;
+    if ptr.into_inner_primval().is_undef() {
+        // Not initialized yet.
+        return Ok(());
+    }
+    let (size, align) = ecx.size_and_align_of_dst(pointee, value)?;
+    // References are always non-NULL and aligned.
+    ecx.memory.check_align(ptr, align)?;
+    if size.bytes() == 0 {
+        return Ok(());
+    }
+    let ptr = match ptr.into_inner_primval() {
+        PrimVal::Ptr(ptr) => ptr,
+        _ => return err!(ValidationFailure(format!("reference to {} is dangling", pointee))),
+    };
+    let kind = if mutbl == hir::MutMutable {
+        AccessKind::Write
+    } else if pointee.is_freeze(ecx.tcx, ecx.param_env, ecx.frame().span) {
+        AccessKind::Read
+    } else {
+        // Shared references to interior mutability don't prevent writes.
+        return Ok(());
+    };
+    let frame = ecx.cur_frame();
+    let data = &mut ecx.memory.data;
+    let locks = &mut data.locks;
+    match op {
+        mir::ValidationOp::Acquire => {
+            let lifetime = DynamicLifetime { frame, region: re };
+            locks.acquire(ptr, size.bytes(), lifetime, kind)?;
+            data.races.access(ptr, size.bytes(), kind)
+        }
+        // Only the mutable parts are released.
+        mir::ValidationOp::Release if kind == AccessKind::Write => {
+            locks.release_write(ptr, size.bytes(), frame, None)?;
+            data.races.access(ptr, size.bytes(), kind)
+        }
+        mir::ValidationOp::Suspend(ce) if kind == AccessKind::Write => {
+            locks.release_write(ptr, size.bytes(), frame, Some(ce))
+        }
+        mir::ValidationOp::Release |
+        mir::ValidationOp::Suspend(_) => Ok(()),
+    }
+}
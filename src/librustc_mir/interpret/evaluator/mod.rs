@@ -0,0 +1,540 @@
+//! The `-Z interpret-main` mode, which runs `main`, or the `#[test]` functions of a crate
+//! built with `--test`, on the MIR interpreter instead of translating the crate.
+//!
+//! Besides the checks which are part of the interpreter itself, like out-of-bounds,
+//! dangling and misaligned accesses or invalid enum discriminants, the `Evaluator` machine
+//! enforces the locks taken by the `Validate` statements emitted with
+//! `-Z mir-emit-validate`, see the `locks` module, and detects data races between the
+//! accesses of those statements, see the `races` module. Calls to foreign functions are
+//! emulated for a few allocator, libc and pthread functions, including `pthread_create`,
+//! which runs the new thread to completion before returning.
+//!
+//! Only functions whose MIR is available can be run, so the standard library has to be
+//! built with `-Z always-encode-mir`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::mem;
+
+use rustc::hir;
+use rustc::hir::def_id::DefId;
+use rustc::middle::region;
+use rustc::mir;
+use rustc::mir::interpret::{AccessKind, AllocId, EvalErrorKind, EvalResult, GlobalId,
+                            MemoryPointer, Pointer, PrimVal};
+use rustc::traits::Reveal;
+use rustc::ty::{self, Ty, TyCtxt};
+use rustc::ty::layout::{self, LayoutOf};
+use syntax::ast::Mutability;
+use syntax::attr;
+use syntax::codemap::Span;
+
+use super::{EvalContext, Machine, Memory, MemoryKind, Place, ResourceLimits,
+            StackPopCleanup, ValTy};
+
+mod fn_call;
+mod intrinsics;
+mod locks;
+mod races;
+
+pub use self::locks::MemoryLocks;
+pub use self::races::DataRaceDetector;
+use self::races::ThreadId;
+
+/// Runs `main`, or the tests when building with `--test`, on the interpreter.
+/// Undefined behaviour and other errors are reported through the session.
+pub fn interpret_main<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
+    if tcx.sess.opts.test {
+        return run_tests(tcx);
+    }
+    let entry_fn = *tcx.sess.entry_fn.borrow();
+    match entry_fn {
+        Some((node_id, _)) => {
+            let (res, ecx) = run_fn(tcx, tcx.hir.local_def_id(node_id));
+            if let Err(mut err) = res {
+                ecx.report(&mut err);
+            }
+        }
+        None => tcx.sess.err("`-Z interpret-main` requires a `main` function"),
+    }
+}
+
+/// Runs the functions marked `#[test]` and prints their results like the test harness.
+fn run_tests<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
+    let tests: Vec<_> = tcx.hir.krate().items.values().filter(|item| {
+        match item.node {
+            hir::ItemFn(..) => attr::contains_name(&item.attrs, "test"),
+            _ => false,
+        }
+    }).collect();
+
+    println!("\nrunning {} tests", tests.len());
+    let (mut passed, mut failed, mut ignored) = (0, 0, 0);
+    for item in tests {
+        let def_id = tcx.hir.local_def_id(item.id);
+        let name = tcx.item_path_str(def_id);
+        if attr::contains_name(&item.attrs, "ignore") {
+            println!("test {} ... ignored", name);
+            ignored += 1;
+            continue;
+        }
+        // The expected message of `#[should_panic(expected = "...")]` isn't checked,
+        // panics don't keep their payload in the interpreter.
+        let should_panic = attr::contains_name(&item.attrs, "should_panic");
+        let (res, ecx) = run_fn(tcx, def_id);
+        let ok = match res {
+            Ok(()) if should_panic => {
+                tcx.sess.span_err(item.span, "test did not panic as expected");
+                false
+            }
+            Err(ref err) if should_panic && is_panic(&err.kind) => true,
+            Ok(()) => true,
+            Err(mut err) => {
+                ecx.report(&mut err);
+                false
+            }
+        };
+        if ok {
+            println!("test {} ... ok", name);
+            passed += 1;
+        } else {
+            println!("test {} ... FAILED", name);
+            failed += 1;
+        }
+    }
+    println!("\ntest result: {}. {} passed; {} failed; {} ignored\n",
+             if failed == 0 { "ok" } else { "FAILED" }, passed, failed, ignored);
+}
+
+/// Whether the error is the interpreter's version of a panic.
+fn is_panic(kind: &EvalErrorKind) -> bool {
+    match *kind {
+        EvalErrorKind::Panic |
+        EvalErrorKind::ArrayIndexOutOfBounds(..) |
+        EvalErrorKind::Math(..) => true,
+        _ => false,
+    }
+}
+
+/// Runs the function `def_id`, which takes no arguments, and the destructors of the
+/// thread locals it set. The evaluation context is returned along with the result,
+/// because its stack is needed to report errors.
+fn run_fn<'a, 'tcx>(
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    def_id: DefId,
+) -> (EvalResult<'tcx>, EvalContext<'a, 'tcx, Evaluator<'tcx>>) {
+    let limits = ResourceLimits {
+        // Programs may run for as long as they like.
+        step_limit: u64::max_value(),
+        ..ResourceLimits::default()
+    };
+    let param_env = ty::ParamEnv::empty(Reveal::All);
+    let mut ecx = EvalContext::new(
+        tcx,
+        param_env,
+        limits,
+        Evaluator::default(),
+        EvaluatorMemoryData::default(),
+    );
+    let res = run_fn_using_ecx(&mut ecx, def_id);
+    (res, ecx)
+}
+
+fn run_fn_using_ecx<'a, 'tcx>(
+    ecx: &mut EvalContext<'a, 'tcx, Evaluator<'tcx>>,
+    def_id: DefId,
+) -> EvalResult<'tcx> {
+    let instance = ty::Instance::mono(ecx.tcx, def_id);
+    let mir = ecx.load_mir(instance.def)?;
+    if mir.arg_count != 0 || !mir.return_ty().is_nil() {
+        let msg = format!("`{}` must take no arguments and return `()`", instance);
+        return err!(Unimplemented(msg));
+    }
+    let ret_align = ecx.layout_of(mir.return_ty())?.align;
+    let ret = ecx.alloc_ptr(mir.return_ty())?;
+    ecx.push_stack_frame(
+        instance,
+        mir.span,
+        mir,
+        Place::from_ptr(ret, ret_align),
+        StackPopCleanup::None,
+    )?;
+    while ecx.step()? {}
+    run_tls_dtors(ecx)
+}
+
+/// Runs the destructors of the thread locals of the current thread, which may set
+/// thread locals again.
+fn run_tls_dtors<'a, 'tcx>(ecx: &mut EvalContext<'a, 'tcx, Evaluator<'tcx>>) -> EvalResult<'tcx> {
+    while let Some((dtor, data)) = ecx.machine.fetch_tls_dtor() {
+        call_with_ptr_arg(ecx, dtor, data)?;
+    }
+    Ok(())
+}
+
+/// Calls `instance`, which takes a single pointer argument, until it returns, and
+/// returns the place holding its return value.
+fn call_with_ptr_arg<'a, 'tcx>(
+    ecx: &mut EvalContext<'a, 'tcx, Evaluator<'tcx>>,
+    instance: ty::Instance<'tcx>,
+    arg: Pointer,
+) -> EvalResult<'tcx, Place> {
+    let mir = ecx.load_mir(instance.def)?;
+    if mir.arg_count != 1 {
+        let msg = format!("`{}` must take a single pointer argument", instance);
+        return err!(AbiViolation(msg));
+    }
+    let ret_align = ecx.layout_of(mir.return_ty())?.align;
+    let ret = Place::from_ptr(ecx.alloc_ptr(mir.return_ty())?, ret_align);
+    let depth = ecx.stack().len();
+    ecx.push_stack_frame(
+        instance,
+        mir.span,
+        mir,
+        ret,
+        StackPopCleanup::None,
+    )?;
+    let arg_local = mir.args_iter().next().unwrap();
+    let arg_dest = ecx.eval_place(&mir::Place::Local(arg_local))?;
+    let u8_ptr_ty = ecx.tcx.mk_mut_ptr(ecx.tcx.types.u8);
+    ecx.write_ptr(arg_dest, arg, u8_ptr_ty)?;
+    // The call may happen while another function is running, e.g. in `pthread_create`.
+    while ecx.stack().len() > depth {
+        ecx.step()?;
+    }
+    Ok(ret)
+}
+
+/// Offsets `ptr` by `offset` elements of `pointee_ty`. The result has to stay within
+/// the allocation `ptr` points into, or point one byte past its end.
+fn pointer_offset<'a, 'tcx>(
+    ecx: &EvalContext<'a, 'tcx, Evaluator<'tcx>>,
+    ptr: Pointer,
+    pointee_ty: Ty<'tcx>,
+    offset: i64,
+) -> EvalResult<'tcx, Pointer> {
+    let pointee_size = ecx.layout_of(pointee_ty)?.size.bytes() as i64;
+    if offset == 0 || pointee_size == 0 {
+        // Offsetting by 0 is fine even for dangling pointers.
+        return Ok(ptr);
+    }
+    let offset = match offset.checked_mul(pointee_size) {
+        Some(offset) => offset,
+        None => return err!(OverflowingMath),
+    };
+    let ptr = ptr.signed_offset(offset, &ecx.tcx.data_layout)?;
+    // Integers cast to pointers can't be checked.
+    if let PrimVal::Ptr(ptr) = ptr.into_inner_primval() {
+        ecx.memory.check_bounds(ptr, false)?;
+    }
+    Ok(ptr)
+}
+
+pub type TlsKey = u128;
+
+#[derive(Copy, Clone, Debug)]
+struct TlsEntry<'tcx> {
+    /// The value of the key, NULL until it is set.
+    data: Pointer,
+    dtor: Option<ty::Instance<'tcx>>,
+}
+
+/// The machine used by `-Z interpret-main`.
+#[derive(Default)]
+pub struct Evaluator<'tcx> {
+    /// The environment variables set with `setenv`. `getenv` returns pointers to the
+    /// `EvaluatorMemoryKind::Env` allocations holding their values.
+    env_vars: HashMap<Vec<u8>, MemoryPointer>,
+
+    /// The keys created with `pthread_key_create`.
+    thread_local: BTreeMap<TlsKey, TlsEntry<'tcx>>,
+
+    /// The key `pthread_key_create` returns next.
+    next_thread_local: TlsKey,
+
+    /// The return values of the threads which finished but haven't been joined yet.
+    finished_threads: HashMap<ThreadId, Pointer>,
+}
+
+impl<'tcx> Evaluator<'tcx> {
+    fn create_tls_key(&mut self, dtor: Option<ty::Instance<'tcx>>) -> TlsKey {
+        // `pthread_key_t` 0 is a valid key, but many programs use it as a sentinel.
+        self.next_thread_local += 1;
+        let key = self.next_thread_local;
+        self.thread_local.insert(key, TlsEntry { data: Pointer::null(), dtor });
+        trace!("new TLS key {} with dtor {:?}", key, dtor);
+        key
+    }
+
+    fn delete_tls_key(&mut self, key: TlsKey) -> EvalResult<'tcx> {
+        match self.thread_local.remove(&key) {
+            Some(_) => Ok(()),
+            None => err!(TlsOutOfBounds),
+        }
+    }
+
+    fn load_tls(&self, key: TlsKey) -> EvalResult<'tcx, Pointer> {
+        match self.thread_local.get(&key) {
+            Some(entry) => Ok(entry.data),
+            None => err!(TlsOutOfBounds),
+        }
+    }
+
+    fn store_tls(&mut self, key: TlsKey, new_data: Pointer) -> EvalResult<'tcx> {
+        match self.thread_local.get_mut(&key) {
+            Some(entry) => {
+                entry.data = new_data;
+                Ok(())
+            }
+            None => err!(TlsOutOfBounds),
+        }
+    }
+
+    /// Replaces the values of the thread local keys by `values`, NULL for the keys
+    /// missing from it, and returns the old values. Used to give each thread its own.
+    fn swap_tls_values(
+        &mut self,
+        mut values: BTreeMap<TlsKey, Pointer>,
+    ) -> BTreeMap<TlsKey, Pointer> {
+        self.thread_local.iter_mut().map(|(&key, entry)| {
+            let new_data = values.remove(&key).unwrap_or_else(Pointer::null);
+            (key, mem::replace(&mut entry.data, new_data))
+        }).collect()
+    }
+
+    /// Returns a destructor to run along with its argument, like the C library does
+    /// when a thread exits: the key is reset to NULL and the destructor is only
+    /// called for keys whose value isn't NULL.
+    fn fetch_tls_dtor(&mut self) -> Option<(ty::Instance<'tcx>, Pointer)> {
+        for entry in self.thread_local.values_mut() {
+            if let Some(dtor) = entry.dtor {
+                if !entry.data.is_null().unwrap_or(true) {
+                    let data = entry.data;
+                    entry.data = Pointer::null();
+                    return Some((dtor, data));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// The state the `Evaluator` keeps about its memory.
+#[derive(Default)]
+pub struct EvaluatorMemoryData {
+    locks: MemoryLocks,
+    races: DataRaceDetector,
+}
+
+/// The memory kinds of the `Evaluator` in addition to the stack.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum EvaluatorMemoryKind {
+    /// Allocations of the Rust allocator functions, like `__rust_alloc`.
+    Rust,
+    /// Allocations of `malloc`.
+    C,
+    /// The values of the environment variables.
+    Env,
+}
+
+impl<'tcx> Machine<'tcx> for Evaluator<'tcx> {
+    type MemoryData = EvaluatorMemoryData;
+    type MemoryKinds = EvaluatorMemoryKind;
+
+    fn eval_fn_call<'a>(
+        ecx: &mut EvalContext<'a, 'tcx, Self>,
+        instance: ty::Instance<'tcx>,
+        destination: Option<(Place, mir::BasicBlock)>,
+        args: &[ValTy<'tcx>],
+        span: Span,
+        sig: ty::FnSig<'tcx>,
+    ) -> EvalResult<'tcx, bool> {
+        fn_call::eval_fn_call(ecx, instance, destination, args, span, sig)
+    }
+
+    fn call_intrinsic<'a>(
+        ecx: &mut EvalContext<'a, 'tcx, Self>,
+        instance: ty::Instance<'tcx>,
+        args: &[ValTy<'tcx>],
+        dest: Place,
+        dest_layout: layout::TyLayout<'tcx>,
+        target: mir::BasicBlock,
+    ) -> EvalResult<'tcx> {
+        intrinsics::call_intrinsic(ecx, instance, args, dest, dest_layout, target)
+    }
+
+    fn try_ptr_op<'a>(
+        ecx: &EvalContext<'a, 'tcx, Self>,
+        bin_op: mir::BinOp,
+        left: PrimVal,
+        left_ty: Ty<'tcx>,
+        right: PrimVal,
+        right_ty: Ty<'tcx>,
+    ) -> EvalResult<'tcx, Option<(PrimVal, bool)>> {
+        use rustc::mir::BinOp::*;
+
+        if bin_op == Offset {
+            let pointee_ty = left_ty
+                .builtin_deref(true, ty::LvaluePreference::NoPreference)
+                .expect("Offset called on non-ptr type")
+                .ty;
+            let offset = right.to_i128()? as i64;
+            let ptr = pointer_offset(ecx, left.into(), pointee_ty, offset)?;
+            return Ok(Some((ptr.into_inner_primval(), false)));
+        }
+        if left.is_bytes() && right.is_bytes() {
+            return Ok(None);
+        }
+
+        let usize_ty = ecx.tcx.types.usize;
+        let res = match (left, right) {
+            (PrimVal::Ptr(left), PrimVal::Ptr(right)) => {
+                if left.alloc_id == right.alloc_id {
+                    match bin_op {
+                        Eq => PrimVal::from_bool(left.offset == right.offset),
+                        Ne => PrimVal::from_bool(left.offset != right.offset),
+                        Lt => PrimVal::from_bool(left.offset < right.offset),
+                        Le => PrimVal::from_bool(left.offset <= right.offset),
+                        Gt => PrimVal::from_bool(left.offset > right.offset),
+                        Ge => PrimVal::from_bool(left.offset >= right.offset),
+                        Sub => {
+                            let left = PrimVal::Bytes(left.offset as u128);
+                            let right = PrimVal::Bytes(right.offset as u128);
+                            return ecx.binary_op(Sub, left, usize_ty, right, usize_ty)
+                                .map(Some);
+                        }
+                        _ => return err!(InvalidPointerMath),
+                    }
+                } else {
+                    // Distinct allocations never overlap.
+                    match bin_op {
+                        Eq => PrimVal::from_bool(false),
+                        Ne => PrimVal::from_bool(true),
+                        _ => return err!(InvalidPointerMath),
+                    }
+                }
+            }
+            (PrimVal::Ptr(ptr), PrimVal::Bytes(bytes)) |
+            (PrimVal::Bytes(bytes), PrimVal::Ptr(ptr)) => {
+                let ptr_on_left = left.is_ptr();
+                match bin_op {
+                    // Pointers into allocations are never NULL.
+                    Eq if bytes == 0 => PrimVal::from_bool(false),
+                    Ne if bytes == 0 => PrimVal::from_bool(true),
+                    Add => {
+                        let (res, over) = ptr.overflowing_offset(bytes as u64, ecx);
+                        return Ok(Some((PrimVal::Ptr(res), over)));
+                    }
+                    Sub if ptr_on_left => {
+                        let (res, over) = ptr.overflowing_signed_offset(-(bytes as i128), ecx);
+                        return Ok(Some((PrimVal::Ptr(res), over)));
+                    }
+                    // Masking or taking the remainder of the low bits is fine as long as
+                    // those are known from the alignment of the allocation, which is
+                    // what checking the alignment of a pointer does.
+                    BitAnd | Rem if ptr_on_left => {
+                        let alloc_align = ecx.memory.get(ptr.alloc_id)?.align.abi() as u128;
+                        match bin_op {
+                            BitAnd if bytes < alloc_align => {
+                                PrimVal::Bytes(ptr.offset as u128 & bytes)
+                            }
+                            BitAnd if (!bytes & u64::max_value() as u128) < alloc_align => {
+                                // Clearing the low bits, like rounding down to the alignment.
+                                let offset = ptr.offset & bytes as u64;
+                                PrimVal::Ptr(MemoryPointer::new(ptr.alloc_id, offset))
+                            }
+                            Rem if bytes != 0 && alloc_align % bytes == 0 => {
+                                PrimVal::Bytes(ptr.offset as u128 % bytes)
+                            }
+                            _ => return err!(ReadPointerAsBytes),
+                        }
+                    }
+                    _ => return err!(ReadPointerAsBytes),
+                }
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some((res, false)))
+    }
+
+    fn mark_static_initialized(m: EvaluatorMemoryKind) -> EvalResult<'tcx> {
+        match m {
+            EvaluatorMemoryKind::Env => Ok(()),
+            EvaluatorMemoryKind::Rust |
+            EvaluatorMemoryKind::C => {
+                err!(Unimplemented("statics can't refer to heap allocations".to_string()))
+            }
+        }
+    }
+
+    fn box_alloc<'a>(
+        ecx: &mut EvalContext<'a, 'tcx, Self>,
+        ty: Ty<'tcx>,
+        dest: Place,
+    ) -> EvalResult<'tcx> {
+        let layout = ecx.layout_of(ty)?;
+        assert!(!layout.is_unsized(), "cannot alloc memory for unsized type");
+        // `box_free` hands the allocation to `__rust_dealloc`.
+        let ptr = ecx.memory.allocate(
+            layout.size.bytes(),
+            layout.align,
+            Some(MemoryKind::Machine(EvaluatorMemoryKind::Rust)),
+        )?;
+        let box_ty = ecx.tcx.mk_box(ty);
+        ecx.write_primval(dest, PrimVal::Ptr(ptr), box_ty)
+    }
+
+    fn global_item_with_linkage<'a>(
+        ecx: &mut EvalContext<'a, 'tcx, Self>,
+        instance: ty::Instance<'tcx>,
+        mutability: Mutability,
+    ) -> EvalResult<'tcx> {
+        // Weak symbols like `__cxa_thread_atexit_impl` are treated as missing, so the
+        // statics holding their addresses are NULL.
+        let ptr_size = ecx.memory.pointer_size();
+        let ptr_align = ecx.tcx.data_layout.pointer_align;
+        let ptr = ecx.memory.allocate(ptr_size, ptr_align, None)?;
+        ecx.memory.write_ptr_sized_unsigned(ptr, ptr_align, PrimVal::Bytes(0))?;
+        ecx.memory.mark_static_initalized(ptr.alloc_id, mutability)?;
+        let cid = GlobalId {
+            instance,
+            promoted: None,
+        };
+        ecx.tcx.interpret_interner.borrow_mut().cache(cid, ptr.alloc_id);
+        Ok(())
+    }
+
+    fn check_locks<'a>(
+        mem: &Memory<'a, 'tcx, Self>,
+        ptr: MemoryPointer,
+        size: u64,
+        access: AccessKind,
+    ) -> EvalResult<'tcx> {
+        mem.data.locks.check(mem.cur_frame, ptr, size, access)
+    }
+
+    fn free_lock<'a>(
+        mem: &mut Memory<'a, 'tcx, Self>,
+        id: AllocId,
+        _len: u64,
+    ) -> EvalResult<'tcx> {
+        let frame = mem.cur_frame;
+        mem.data.races.free(id);
+        mem.data.locks.free(frame, id)
+    }
+
+    fn end_region<'a>(
+        ecx: &mut EvalContext<'a, 'tcx, Self>,
+        reg: Option<region::Scope>,
+    ) -> EvalResult<'tcx> {
+        let frame = ecx.cur_frame();
+        ecx.memory.data.locks.end_region(frame, reg)
+    }
+
+    fn validation_op<'a>(
+        ecx: &mut EvalContext<'a, 'tcx, Self>,
+        op: mir::ValidationOp,
+        operand: &mir::ValidationOperand<'tcx, mir::Place<'tcx>>,
+    ) -> EvalResult<'tcx> {
+        locks::validation_op(ecx, op, operand)
+    }
+}
@@ -0,0 +1,185 @@
+//! Detection of data races between the threads started with `pthread_create`.
+//!
+//! A new thread runs to completion before `pthread_create` returns, so the accesses of
+//! different threads never interleave. Whether two accesses race doesn't depend on the
+//! order they happened to run in though, only on whether one of them happens before the
+//! other, which is tracked with vector clocks: every thread counts its synchronizing
+//! operations and knows up to which operation of each other thread it has synchronized
+//! with. Starting and joining a thread, and locking a mutex or rwlock after another
+//! thread unlocked it, synchronize the threads.
+//!
+//! The accesses checked are those of the `Validate` statements: acquiring or releasing a
+//! `&mut T` writes the memory it points to, acquiring a `&T` to memory without interior
+//! mutability reads it. Two accesses to the same memory by different threads, at least one
+//! of which is a write, race unless one of them happens before the other. Accesses through
+//! raw pointers aren't checked, and atomic operations don't synchronize threads.
+
+use std::cmp;
+use std::collections::HashMap;
+
+use rustc::mir::interpret::{AccessKind, AllocId, EvalResult, MemoryPointer};
+
+pub type ThreadId = usize;
+
+/// The main thread, which runs `main` or the tests.
+pub const MAIN_THREAD: ThreadId = 0;
+
+/// For each thread, the number of synchronizing operations of that thread which happen
+/// before the current operation of the thread owning the clock.
+#[derive(Clone, Default, Debug)]
+struct VClock(Vec<u64>);
+
+impl VClock {
+    fn get(&self, thread: ThreadId) -> u64 {
+        self.0.get(thread).cloned().unwrap_or(0)
+    }
+
+    fn increment(&mut self, thread: ThreadId) {
+        if self.0.len() <= thread {
+            self.0.resize(thread + 1, 0);
+        }
+        self.0[thread] += 1;
+    }
+
+    fn join(&mut self, other: &VClock) {
+        if self.0.len() < other.0.len() {
+            self.0.resize(other.0.len(), 0);
+        }
+        for (time, &other_time) in self.0.iter_mut().zip(&other.0) {
+            *time = cmp::max(*time, other_time);
+        }
+    }
+}
+
+/// An access by `thread` when its own entry of its clock was `time`.
+#[derive(Clone, Debug)]
+struct Access {
+    offset: u64,
+    len: u64,
+    kind: AccessKind,
+    thread: ThreadId,
+    time: u64,
+}
+
+impl Access {
+    fn overlaps(&self, offset: u64, len: u64) -> bool {
+        self.offset < offset + len && offset < self.offset + self.len
+    }
+}
+
+/// The clocks of the threads and the accesses to the memory of the `Evaluator`.
+#[derive(Default)]
+pub struct DataRaceDetector {
+    /// The clocks of the threads, indexed by their `ThreadId`.
+    clocks: Vec<VClock>,
+    /// The threads which have started but not finished yet. A thread started while
+    /// another one runs finishes first, so the last one is the current thread.
+    running: Vec<ThreadId>,
+    /// The clocks of the threads which last unlocked each mutex or rwlock.
+    locks: HashMap<MemoryPointer, VClock>,
+    accesses: HashMap<AllocId, Vec<Access>>,
+}
+
+impl DataRaceDetector {
+    pub fn cur_thread(&self) -> ThreadId {
+        self.running.last().cloned().unwrap_or(MAIN_THREAD)
+    }
+
+    fn clock_mut(&mut self, thread: ThreadId) -> &mut VClock {
+        if self.clocks.len() <= thread {
+            self.clocks.resize(thread + 1, VClock::default());
+        }
+        &mut self.clocks[thread]
+    }
+
+    /// Starts a new thread, which becomes the current thread until `finish_thread`.
+    /// Everything the creating thread did so far happens before the new thread.
+    pub fn start_thread(&mut self) -> ThreadId {
+        let parent = self.cur_thread();
+        let thread = cmp::max(self.clocks.len(), parent + 1);
+        let mut clock = self.clock_mut(parent).clone();
+        clock.increment(thread);
+        self.clock_mut(parent).increment(parent);
+        *self.clock_mut(thread) = clock;
+        self.running.push(thread);
+        thread
+    }
+
+    /// Ends the current thread, which was started with `start_thread`.
+    pub fn finish_thread(&mut self) {
+        let thread = self.running.pop().expect("no thread was started");
+        self.clock_mut(thread).increment(thread);
+    }
+
+    /// Everything `thread` did happens before what the current thread does from now on.
+    pub fn join_thread(&mut self, thread: ThreadId) {
+        let clock = self.clock_mut(thread).clone();
+        let cur = self.cur_thread();
+        self.clock_mut(cur).join(&clock);
+    }
+
+    /// The current thread locks the mutex or rwlock at `lock`: what the threads which
+    /// unlocked it did before happens before what the current thread does from now on.
+    pub fn lock(&mut self, lock: MemoryPointer) {
+        if let Some(clock) = self.locks.get(&lock).cloned() {
+            let cur = self.cur_thread();
+            self.clock_mut(cur).join(&clock);
+        }
+    }
+
+    /// The current thread unlocks the mutex or rwlock at `lock`.
+    pub fn unlock(&mut self, lock: MemoryPointer) {
+        let cur = self.cur_thread();
+        let clock = self.clock_mut(cur).clone();
+        self.locks.entry(lock).or_insert_with(VClock::default).join(&clock);
+        self.clock_mut(cur).increment(cur);
+    }
+
+    /// Records an access of the current thread to `len` bytes at `ptr`, and checks that it
+    /// doesn't race with an earlier access.
+    pub fn access<'tcx>(
+        &mut self,
+        ptr: MemoryPointer,
+        len: u64,
+        kind: AccessKind,
+    ) -> EvalResult<'tcx> {
+        let thread = self.cur_thread();
+        let clock = self.clock_mut(thread).clone();
+        let accesses = self.accesses.entry(ptr.alloc_id).or_insert_with(Vec::new);
+        for access in accesses.iter() {
+            let conflict = access.kind == AccessKind::Write || kind == AccessKind::Write;
+            let happens_before = access.thread == thread ||
+                access.time <= clock.get(access.thread);
+            if conflict && !happens_before && access.overlaps(ptr.offset, len) {
+                return err!(DataRace {
+                    ptr,
+                    len,
+                    access: kind,
+                    thread,
+                    other_access: access.kind,
+                    other_thread: access.thread,
+                });
+            }
+        }
+        // Only the last access of a thread to the same memory has to be remembered, the
+        // earlier ones happen before it.
+        let time = clock.get(thread);
+        accesses.retain(|access| {
+            access.thread != thread || access.offset != ptr.offset || access.len != len ||
+                (access.kind == AccessKind::Write && kind == AccessKind::Read)
+        });
+        accesses.push(Access {
+            offset: ptr.offset,
+            len,
+            kind,
+            thread,
+            time,
+        });
+        Ok(())
+    }
+
+    /// Forgets the accesses to an allocation which is being deallocated.
+    pub fn free(&mut self, alloc_id: AllocId) {
+        self.accesses.remove(&alloc_id);
+    }
+}
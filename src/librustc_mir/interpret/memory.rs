@@ -7,7 +7,7 @@ use rustc::ty::layout::{self, Align, TargetDataLayout};
 use syntax::ast::Mutability;
 
 use rustc::mir::interpret::{MemoryPointer, AllocId, Allocation, AccessKind, UndefMask, Value, Pointer,
-                            EvalResult, PrimVal, EvalErrorKind};
+                            EvalResult, PrimVal, EvalErrorKind, read_target_uint};
 
 use super::{EvalContext, Machine};
 
@@ -439,6 +439,12 @@ impl<'a, 'tcx, M: Machine<'tcx>> Memory<'a, 'tcx, M> {
         }
     }
 
+    /// Returns whether there are allocations of the given kind
+    /// which haven't been deallocated.
+    pub fn has_live_allocations(&self, kind: MemoryKind<M::MemoryKinds>) -> bool {
+        self.alloc_kind.values().any(|&k| k == kind)
+    }
+
     pub fn leak_report(&self) -> usize {
         trace!("### LEAK REPORT ###");
         let kinds = &self.alloc_kind;
@@ -967,13 +973,6 @@ fn write_target_int(
     }
 }
 
-fn read_target_uint(endianess: layout::Endian, mut source: &[u8]) -> Result<u128, io::Error> {
-    match endianess {
-        layout::Endian::Little => source.read_uint128::<LittleEndian>(source.len()),
-        layout::Endian::Big => source.read_uint128::<BigEndian>(source.len()),
-    }
-}
-
 fn read_target_int(endianess: layout::Endian, mut source: &[u8]) -> Result<i128, io::Error> {
     match endianess {
         layout::Endian::Little => source.read_int128::<LittleEndian>(source.len()),
@@ -1,8 +1,9 @@
-//! An interpreter for MIR used in CTFE and by miri
+//! An interpreter for MIR used in CTFE, by miri and by `-Z interpret-main`
 
 mod cast;
 mod const_eval;
 mod eval_context;
+mod evaluator;
 mod place;
 mod machine;
 mod memory;
@@ -21,3 +22,7 @@ pub use self::memory::{Memory, MemoryKind, HasMemory};
 pub use self::const_eval::{eval_body_as_integer, eval_body, CompileTimeEvaluator, const_eval_provider};
 
 pub use self::machine::Machine;
+
+pub use self::evaluator::{Evaluator, EvaluatorMemoryKind, interpret_main};
+
+pub use self::operator::unary_op;
@@ -216,6 +216,27 @@ impl<'a, 'tcx, M: Machine<'tcx>> EvalContext<'a, 'tcx, M> {
         Ok(place)
     }
 
+    /// Returns the place of the pointer of a `Box` whose allocator isn't
+    /// zero-sized, along with the type of the pointer. Such a `Box` is a
+    /// struct of a `Unique<T>` and the allocator, unlike boxes using `Heap`,
+    /// which are just a pointer. Other places are returned as they are.
+    pub(super) fn box_ptr_place(
+        &mut self,
+        mut place: Place,
+        mut ty: Ty<'tcx>,
+    ) -> EvalResult<'tcx, (Place, Ty<'tcx>)> {
+        if ty.is_box() && !self.is_ptr_box(ty) {
+            while !ty.is_unsafe_ptr() {
+                let layout = self.layout_of(ty)?;
+                let (field_place, field_layout) =
+                    self.place_field(place, mir::Field::new(0), layout)?;
+                place = field_place;
+                ty = field_layout.ty;
+            }
+        }
+        Ok((place, ty))
+    }
+
     pub fn place_field(
         &mut self,
         base: Place,
@@ -358,6 +379,7 @@ impl<'a, 'tcx, M: Machine<'tcx>> EvalContext<'a, 'tcx, M> {
             }
 
             Deref => {
+                let (base, base_ty) = self.box_ptr_place(base, base_ty)?;
                 let val = self.read_place(base)?;
 
                 let pointee_type = match base_ty.sty {
@@ -0,0 +1,562 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Propagates constants through locals which are assigned exactly once, using
+//! the MIR interpreter to evaluate the operations performed on them.
+//!
+//! Operands whose value is known are replaced with constants, `SwitchInt`s and
+//! `Assert`s on known values are turned into `Goto`s, and assertions which are
+//! guaranteed to fail at runtime are reported through the `const_err` lint.
+//!
+//! Only scalars are tracked, so aggregates and their fields, and with them the
+//! `Rvalue::Discriminant`s `match`es on enums switch on, are never known.
+
+use rustc::hir;
+use rustc::lint::builtin::CONST_ERR;
+use rustc::middle::const_val::ConstVal;
+use rustc::mir::{AssertMessage, BasicBlock, BinOp, CastKind, ClearCrossCrate, Constant};
+use rustc::mir::{Literal, Local, LocalKind, Location, Mir, Operand, Place, ProjectionElem};
+use rustc::mir::{Rvalue, SourceInfo, StatementKind, Terminator, TerminatorKind, UnOp};
+use rustc::mir::START_BLOCK;
+use rustc::mir::interpret::{PrimVal, PrimValKind, Value};
+use rustc::mir::traversal;
+use rustc::mir::visit::{MutVisitor, PlaceContext, Visitor};
+use rustc::ty::{self, Ty, TyCtxt};
+use rustc::util::nodemap::{FxHashMap, FxHashSet};
+use rustc_const_math::{ConstFloat, ConstInt};
+use rustc_data_structures::indexed_vec::{Idx, IndexVec};
+use syntax::ast::Mutability;
+use syntax_pos::Span;
+use interpret::{unary_op, CompileTimeEvaluator, EvalContext, ResourceLimits};
+use transform::{MirPass, MirSource};
+
+pub struct ConstProp;
+
+impl MirPass for ConstProp {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        // Promoteds and the bodies of constants and statics are evaluated as a
+        // whole by const eval, which reports its own errors.
+        if source.promoted.is_some() {
+            return
+        }
+        let node_id = tcx.hir.as_local_node_id(source.def_id)
+            .expect("ConstProp running on non-local MIR");
+        match tcx.hir.body_owner_kind(node_id) {
+            hir::BodyOwnerKind::Fn => {}
+            hir::BodyOwnerKind::Const | hir::BodyOwnerKind::Static(_) => return,
+        }
+
+        // First, evaluate what we can and report the guaranteed panics. This
+        // is done in a pre-pass to keep the MIR read-only while we look at the
+        // types of places in it.
+        let optimizations = {
+            let mut propagator = ConstPropagator::new(mir, tcx, source);
+            propagator.propagate();
+            propagator.optimizations
+        };
+
+        // The lints above are emitted at any optimization level, but we only
+        // touch the MIR when optimizing it.
+        if tcx.sess.opts.debugging_opts.mir_opt_level == 0 {
+            return
+        }
+
+        // Then carry out the replacements.
+        ConstPropVisitor { optimizations }.visit_mir(mir);
+    }
+}
+
+/// A value known at compile time.
+#[derive(Copy, Clone, Debug)]
+struct Const<'tcx> {
+    /// Either a single scalar, or a scalar and its overflow flag as produced
+    /// by a `CheckedBinaryOp`.
+    value: Value,
+    ty: Ty<'tcx>,
+    /// Whether the value was derived from a user variable. Overflows in
+    /// expressions made of literals only are already reported by the HIR
+    /// constant checker, so we only report those which were not.
+    propagated: bool,
+}
+
+impl<'tcx> Const<'tcx> {
+    fn to_bits(&self) -> Option<u128> {
+        match self.value {
+            Value::ByVal(PrimVal::Bytes(bits)) => Some(bits),
+            _ => None,
+        }
+    }
+}
+
+/// Computes which locals we can track a value for: those which are assigned
+/// exactly once, and are never borrowed or written to through a projection.
+///
+/// Borrowck guarantees that such a local is assigned before it is read, so the
+/// single assignment dominates all its uses.
+struct CanConstProp {
+    can_const_prop: IndexVec<Local, bool>,
+    found_assignment: IndexVec<Local, bool>,
+}
+
+impl CanConstProp {
+    fn check(mir: &Mir) -> IndexVec<Local, bool> {
+        let mut cpv = CanConstProp {
+            can_const_prop: IndexVec::from_elem(true, &mir.local_decls),
+            found_assignment: IndexVec::from_elem(false, &mir.local_decls),
+        };
+        for (local, val) in cpv.can_const_prop.iter_enumerated_mut() {
+            // Arguments and the return place are set from outside the body.
+            match mir.local_kind(local) {
+                LocalKind::Arg | LocalKind::ReturnPointer => *val = false,
+                LocalKind::Var | LocalKind::Temp => {}
+            }
+        }
+        cpv.visit_mir(mir);
+        cpv.can_const_prop
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for CanConstProp {
+    fn visit_local(&mut self,
+                   &local: &Local,
+                   context: PlaceContext<'tcx>,
+                   _: Location) {
+        use rustc::mir::visit::PlaceContext::*;
+        match context {
+            Store => {
+                if self.found_assignment[local] {
+                    self.can_const_prop[local] = false;
+                } else {
+                    self.found_assignment[local] = true;
+                }
+            }
+            Copy | Move | Inspect | Projection(Mutability::Not) |
+            StorageLive | StorageDead | Validate => {}
+            _ => self.can_const_prop[local] = false,
+        }
+    }
+}
+
+struct ConstPropagator<'b, 'a, 'tcx: 'a + 'b> {
+    mir: &'b Mir<'tcx>,
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    ecx: EvalContext<'a, 'tcx, CompileTimeEvaluator>,
+    can_const_prop: IndexVec<Local, bool>,
+    places: IndexVec<Local, Option<Const<'tcx>>>,
+    /// Blocks which can still be reached once known branches are folded.
+    live_blocks: FxHashSet<BasicBlock>,
+    optimizations: OptimizationList<'tcx>,
+}
+
+#[derive(Default)]
+struct OptimizationList<'tcx> {
+    operands: FxHashMap<(Location, Local), Constant<'tcx>>,
+    rvalues: FxHashMap<Location, Constant<'tcx>>,
+    gotos: FxHashMap<BasicBlock, BasicBlock>,
+}
+
+impl<'b, 'a, 'tcx: 'b> ConstPropagator<'b, 'a, 'tcx> {
+    fn new(mir: &'b Mir<'tcx>,
+           tcx: TyCtxt<'a, 'tcx, 'tcx>,
+           source: MirSource)
+           -> ConstPropagator<'b, 'a, 'tcx> {
+        let param_env = tcx.param_env(source.def_id);
+        ConstPropagator {
+            mir,
+            tcx,
+            ecx: EvalContext::new(tcx, param_env, ResourceLimits::default(),
+                                  CompileTimeEvaluator, ()),
+            can_const_prop: CanConstProp::check(mir),
+            places: IndexVec::from_elem(None, &mir.local_decls),
+            live_blocks: FxHashSet(),
+            optimizations: OptimizationList::default(),
+        }
+    }
+
+    /// Visits the blocks in reverse postorder, so that the assignment of a
+    /// local is always seen before its uses.
+    fn propagate(&mut self) {
+        let mir = self.mir;
+        self.live_blocks.insert(START_BLOCK);
+        for (block, data) in traversal::reverse_postorder(mir) {
+            if !self.live_blocks.contains(&block) {
+                continue
+            }
+
+            for (statement_index, statement) in data.statements.iter().enumerate() {
+                let location = Location { block, statement_index };
+                if let StatementKind::Assign(ref place, ref rvalue) = statement.kind {
+                    self.visit_assign(place, rvalue, location, statement.source_info.span);
+                }
+            }
+
+            let location = Location { block, statement_index: data.statements.len() };
+            self.visit_terminator(block, data.terminator(), location);
+        }
+    }
+
+    fn visit_assign(&mut self,
+                    place: &Place<'tcx>,
+                    rvalue: &Rvalue<'tcx>,
+                    location: Location,
+                    span: Span) {
+        let value = self.eval_rvalue(rvalue);
+        if let Place::Local(local) = *place {
+            if self.can_const_prop[local] {
+                trace!("storing {:?} to {:?}", value, local);
+                self.places[local] = value;
+            }
+        }
+
+        let constant = value.and_then(|value| self.to_constant(value, span));
+        match (constant, rvalue) {
+            (_, &Rvalue::Use(Operand::Constant(_))) => {}
+            (Some(constant), _) => {
+                self.optimizations.rvalues.insert(location, constant);
+            }
+            (None, &Rvalue::Use(ref operand)) |
+            (None, &Rvalue::Repeat(ref operand, _)) |
+            (None, &Rvalue::Cast(_, ref operand, _)) |
+            (None, &Rvalue::UnaryOp(_, ref operand)) => {
+                self.replace_operand(operand, location, span);
+            }
+            (None, &Rvalue::BinaryOp(_, ref left, ref right)) |
+            (None, &Rvalue::CheckedBinaryOp(_, ref left, ref right)) => {
+                self.replace_operand(left, location, span);
+                self.replace_operand(right, location, span);
+            }
+            (None, &Rvalue::Aggregate(_, ref operands)) => {
+                for operand in operands {
+                    self.replace_operand(operand, location, span);
+                }
+            }
+            (None, _) => {}
+        }
+    }
+
+    /// Folds the terminator if the value it depends on is known, and marks
+    /// the blocks it can still jump to as live.
+    fn visit_terminator(&mut self,
+                        block: BasicBlock,
+                        terminator: &Terminator<'tcx>,
+                        location: Location) {
+        let source_info = terminator.source_info;
+        match terminator.kind {
+            TerminatorKind::SwitchInt { ref discr, ref values, ref targets, .. } => {
+                if let Some(bits) = self.eval_operand(discr).and_then(|c| c.to_bits()) {
+                    let (otherwise, targets) = targets.split_last().unwrap();
+                    let target = values.iter()
+                        .zip(targets)
+                        .find(|&(value, _)| value.to_u128_unchecked() == bits)
+                        .map_or(*otherwise, |(_, &target)| target);
+                    self.optimizations.gotos.insert(block, target);
+                    self.live_blocks.insert(target);
+                    return
+                }
+                self.replace_operand(discr, location, source_info.span);
+            }
+            TerminatorKind::Assert { ref cond, expected, ref msg, target, cleanup } => {
+                if let Some(cond) = self.eval_operand(cond) {
+                    match cond.to_bits() {
+                        Some(bits) if (bits != 0) == expected => {
+                            self.optimizations.gotos.insert(block, target);
+                            self.live_blocks.insert(target);
+                            return
+                        }
+                        Some(_) => {
+                            self.report_assert(msg, cond.propagated, source_info);
+                            // Whatever follows the assertion is never
+                            // executed, so don't go looking for more errors
+                            // there.
+                            self.live_blocks.extend(cleanup);
+                            return
+                        }
+                        None => {}
+                    }
+                }
+            }
+            TerminatorKind::Call { ref args, .. } => {
+                for arg in args {
+                    self.replace_operand(arg, location, source_info.span);
+                }
+            }
+            _ => {}
+        }
+        self.live_blocks.extend(terminator.successors().iter().cloned());
+    }
+
+    fn report_assert(&self,
+                     msg: &AssertMessage<'tcx>,
+                     propagated: bool,
+                     source_info: SourceInfo) {
+        let msg = match *msg {
+            AssertMessage::Math(ref err) => {
+                if !propagated {
+                    return
+                }
+                err.description().to_string()
+            }
+            AssertMessage::BoundsCheck { ref len, ref index } => {
+                let len = self.eval_operand(len).and_then(|c| c.to_bits());
+                let index = self.eval_operand(index).and_then(|c| c.to_bits());
+                match (len, index) {
+                    (Some(len), Some(index)) => {
+                        format!("index out of bounds: the len is {} but the index is {}",
+                                len, index)
+                    }
+                    _ => "index out of bounds".to_string(),
+                }
+            }
+            // The state of a generator is never known statically.
+            AssertMessage::GeneratorResumedAfterReturn |
+            AssertMessage::GeneratorResumedAfterPanic => return,
+        };
+        let lint_root = match self.mir.visibility_scope_info {
+            ClearCrossCrate::Set(ref info) => info[source_info.scope].lint_root,
+            ClearCrossCrate::Clear => return,
+        };
+        self.tcx.lint_node(CONST_ERR,
+                           lint_root,
+                           source_info.span,
+                           &format!("this expression will panic at runtime: {}", msg));
+    }
+
+    fn eval_rvalue(&self, rvalue: &Rvalue<'tcx>) -> Option<Const<'tcx>> {
+        match *rvalue {
+            Rvalue::Use(ref operand) => self.eval_operand(operand),
+            Rvalue::Len(ref place) => {
+                let place_ty = place.ty(self.mir, self.tcx).to_ty(self.tcx);
+                let len = match place_ty.sty {
+                    ty::TyArray(_, len) => len.val.to_const_int()?.to_u64()?,
+                    _ => return None,
+                };
+                Some(Const {
+                    value: Value::ByVal(PrimVal::Bytes(len as u128)),
+                    ty: self.tcx.types.usize,
+                    propagated: false,
+                })
+            }
+            Rvalue::Cast(CastKind::Misc, ref operand, ty) => {
+                let operand = self.eval_operand(operand)?;
+                if !is_scalar(operand.ty) || !is_scalar(ty) {
+                    return None
+                }
+                let bits = operand.to_bits()?;
+                let value = self.ecx.cast_primval(PrimVal::Bytes(bits), operand.ty, ty).ok()?;
+                Some(Const { value: Value::ByVal(value), ty, ..operand })
+            }
+            Rvalue::UnaryOp(op, ref operand) => {
+                let operand = self.eval_operand(operand)?;
+                let bits = operand.to_bits()?;
+                let kind = self.ecx.ty_to_primval_kind(operand.ty).ok()?;
+                // `unary_op` negates with our own arithmetic, which must not
+                // overflow. MIR asserts against this right before.
+                if op == UnOp::Neg && is_signed_min(bits, kind) {
+                    return None
+                }
+                let value = unary_op(op, PrimVal::Bytes(bits), kind).ok()?;
+                Some(Const { value: Value::ByVal(value), ..operand })
+            }
+            Rvalue::BinaryOp(op, ref left, ref right) |
+            Rvalue::CheckedBinaryOp(op, ref left, ref right) => {
+                let left = self.eval_operand(left)?;
+                let right = self.eval_operand(right)?;
+                let (left_bits, right_bits) = (left.to_bits()?, right.to_bits()?);
+                // Division by zero would panic in our own arithmetic. MIR
+                // asserts against this right before.
+                let kind = self.ecx.ty_to_primval_kind(left.ty).ok()?;
+                if (op == BinOp::Div || op == BinOp::Rem) && kind.is_int() && right_bits == 0 {
+                    return None
+                }
+                let (value, overflowed) = self.ecx.binary_op(op,
+                                                              PrimVal::Bytes(left_bits),
+                                                              left.ty,
+                                                              PrimVal::Bytes(right_bits),
+                                                              right.ty).ok()?;
+                let propagated = left.propagated || right.propagated;
+                Some(if let Rvalue::CheckedBinaryOp(..) = *rvalue {
+                    Const {
+                        value: Value::ByValPair(value, PrimVal::from_bool(overflowed)),
+                        ty: rvalue.ty(self.mir, self.tcx),
+                        propagated,
+                    }
+                } else {
+                    Const {
+                        value: Value::ByVal(value),
+                        ty: rvalue.ty(self.mir, self.tcx),
+                        propagated,
+                    }
+                })
+            }
+            Rvalue::Cast(..) |
+            Rvalue::Repeat(..) |
+            Rvalue::Ref(..) |
+            Rvalue::NullaryOp(..) |
+            Rvalue::Discriminant(..) |
+            Rvalue::Aggregate(..) => None,
+        }
+    }
+
+    fn eval_operand(&self, operand: &Operand<'tcx>) -> Option<Const<'tcx>> {
+        match *operand {
+            Operand::Copy(ref place) |
+            Operand::Move(ref place) => self.eval_place(place),
+            Operand::Constant(ref constant) => {
+                let value = match constant.literal {
+                    Literal::Value { value } => value,
+                    Literal::Promoted { .. } => return None,
+                };
+                let primval = match value.val {
+                    ConstVal::Integral(int) => PrimVal::Bytes(int.to_u128_unchecked()),
+                    ConstVal::Float(float) => PrimVal::Bytes(float.bits),
+                    ConstVal::Bool(b) => PrimVal::from_bool(b),
+                    ConstVal::Char(c) => PrimVal::from_char(c),
+                    _ => return None,
+                };
+                Some(Const {
+                    value: Value::ByVal(primval),
+                    ty: constant.ty,
+                    propagated: false,
+                })
+            }
+        }
+    }
+
+    fn eval_place(&self, place: &Place<'tcx>) -> Option<Const<'tcx>> {
+        match *place {
+            Place::Local(local) => {
+                let value = self.places[local]?;
+                let propagated = value.propagated || match self.mir.local_kind(local) {
+                    LocalKind::Var | LocalKind::Arg => true,
+                    LocalKind::Temp | LocalKind::ReturnPointer => false,
+                };
+                Some(Const { propagated, ..value })
+            }
+            Place::Projection(ref projection) => {
+                let ty = match projection.elem {
+                    ProjectionElem::Field(_, ty) => ty,
+                    _ => return None,
+                };
+                let base = self.eval_place(&projection.base)?;
+                let value = match (base.value, &projection.elem) {
+                    (Value::ByValPair(a, _), &ProjectionElem::Field(field, _))
+                        if field.index() == 0 => a,
+                    (Value::ByValPair(_, b), &ProjectionElem::Field(field, _))
+                        if field.index() == 1 => b,
+                    _ => return None,
+                };
+                Some(Const { value: Value::ByVal(value), ty, ..base })
+            }
+            Place::Static(..) => None,
+        }
+    }
+
+    /// Turns a scalar value back into a MIR constant, if its type allows it.
+    fn to_constant(&self, value: Const<'tcx>, span: Span) -> Option<Constant<'tcx>> {
+        let bits = value.to_bits()?;
+        let val = match value.ty.sty {
+            ty::TyBool if bits <= 1 => ConstVal::Bool(bits == 1),
+            ty::TyChar => ConstVal::Char(::std::char::from_u32(bits as u32)?),
+            ty::TyInt(int_ty) => {
+                let isize_ty = self.tcx.sess.target.isize_ty;
+                ConstVal::Integral(ConstInt::new_signed_truncating(bits as i128,
+                                                                   int_ty,
+                                                                   isize_ty))
+            }
+            ty::TyUint(uint_ty) => {
+                let usize_ty = self.tcx.sess.target.usize_ty;
+                ConstVal::Integral(ConstInt::new_unsigned_truncating(bits, uint_ty, usize_ty))
+            }
+            ty::TyFloat(float_ty) => ConstVal::Float(ConstFloat { bits, ty: float_ty }),
+            _ => return None,
+        };
+        Some(Constant {
+            span,
+            ty: value.ty,
+            literal: Literal::Value {
+                value: self.tcx.mk_const(ty::Const { val, ty: value.ty }),
+            },
+        })
+    }
+
+    fn replace_operand(&mut self, operand: &Operand<'tcx>, location: Location, span: Span) {
+        let local = match *operand {
+            Operand::Copy(Place::Local(local)) |
+            Operand::Move(Place::Local(local)) => local,
+            _ => return,
+        };
+        let constant = self.places[local].and_then(|c| self.to_constant(c, span));
+        if let Some(constant) = constant {
+            self.optimizations.operands.insert((location, local), constant);
+        }
+    }
+}
+
+fn is_scalar(ty: Ty) -> bool {
+    match ty.sty {
+        ty::TyBool | ty::TyChar | ty::TyInt(_) | ty::TyUint(_) | ty::TyFloat(_) => true,
+        _ => false,
+    }
+}
+
+/// Whether `bits` is the smallest value of the signed integer type `kind`.
+fn is_signed_min(bits: u128, kind: PrimValKind) -> bool {
+    use rustc::mir::interpret::PrimValKind::*;
+    match kind {
+        I8 => bits as i8 == i8::min_value(),
+        I16 => bits as i16 == i16::min_value(),
+        I32 => bits as i32 == i32::min_value(),
+        I64 => bits as i64 == i64::min_value(),
+        I128 => bits as i128 == i128::min_value(),
+        _ => false,
+    }
+}
+
+struct ConstPropVisitor<'tcx> {
+    optimizations: OptimizationList<'tcx>,
+}
+
+impl<'tcx> MutVisitor<'tcx> for ConstPropVisitor<'tcx> {
+    fn visit_rvalue(&mut self, rvalue: &mut Rvalue<'tcx>, location: Location) {
+        if let Some(constant) = self.optimizations.rvalues.remove(&location) {
+            debug!("replacing {:?} with {:?}", rvalue, constant);
+            *rvalue = Rvalue::Use(Operand::Constant(box constant));
+            return
+        }
+        self.super_rvalue(rvalue, location);
+    }
+
+    fn visit_operand(&mut self, operand: &mut Operand<'tcx>, location: Location) {
+        let local = match *operand {
+            Operand::Copy(Place::Local(local)) |
+            Operand::Move(Place::Local(local)) => local,
+            _ => return self.super_operand(operand, location),
+        };
+        if let Some(constant) = self.optimizations.operands.get(&(location, local)) {
+            debug!("replacing {:?} with {:?}", operand, constant);
+            *operand = Operand::Constant(box constant.clone());
+        }
+    }
+
+    fn visit_terminator_kind(&mut self,
+                             block: BasicBlock,
+                             kind: &mut TerminatorKind<'tcx>,
+                             location: Location) {
+        if let Some(target) = self.optimizations.gotos.remove(&block) {
+            debug!("replacing {:?} with a goto {:?}", kind, target);
+            *kind = TerminatorKind::Goto { target };
+            return
+        }
+        self.super_terminator_kind(block, kind, location);
+    }
+}
@@ -0,0 +1,132 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Dead store elimination.
+//!
+//! This removes assignments to locals whose value is never read afterwards:
+//!
+//!     DEST = SRC
+//!     ...        // no use of DEST
+//!     DEST = X   // or StorageDead(DEST), or the end of the function
+//!
+//! Liveness only considers a borrowed local to be used at the point of the
+//! borrow, so locals which are ever borrowed are left alone, as are stores to
+//! the return place, which is implicitly read by `return`.
+
+use rustc::hir;
+use rustc::mir::{Local, Location, Mir, Place, ProjectionElem, RETURN_PLACE, StatementKind};
+use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc::ty::TyCtxt;
+use transform::{MirPass, MirSource};
+use util::liveness::{self, LivenessMode, LocalSet};
+
+pub struct DeadStoreElimination;
+
+impl MirPass for DeadStoreElimination {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        // Don't run on constant MIR, because trans might not be able to
+        // evaluate the modified MIR.
+        // FIXME(eddyb) Remove check after miri is merged.
+        let id = tcx.hir.as_local_node_id(source.def_id).unwrap();
+        match (tcx.hir.body_owner_kind(id), source.promoted) {
+            (hir::BodyOwnerKind::Fn, None) => {
+                if tcx.is_const_fn(source.def_id) {
+                    return
+                }
+            }
+            _ => return
+        }
+
+        // We only run when the MIR optimization level is > 1.
+        // Removing stores to user variables messes up debug info.
+        if tcx.sess.opts.debugging_opts.mir_opt_level <= 1 {
+            return;
+        }
+
+        let mut ignored = BorrowedLocals(LocalSet::new_empty(mir.local_decls.len()));
+        ignored.visit_mir(mir);
+        let mut ignored = ignored.0;
+        ignored.add(&RETURN_PLACE);
+
+        let mode = LivenessMode {
+            include_regular_use: true,
+            include_drops: true,
+        };
+
+        // Removing a store can make the stores feeding it dead as well, so
+        // keep going until nothing changes.
+        loop {
+            let liveness = liveness::liveness_of_locals(mir, mode);
+            let mut dead_stores = vec![];
+            for block in mir.basic_blocks().indices() {
+                let data = &mir[block];
+                // `simulate_block` hands us the locals live on entry to each
+                // location, which are the locals live on exit from the
+                // statement right before it.
+                liveness.simulate_block(mir, block, |location, live| {
+                    if location.statement_index == 0 {
+                        return;
+                    }
+                    let statement_index = location.statement_index - 1;
+                    if let StatementKind::Assign(Place::Local(local), _) =
+                        data.statements[statement_index].kind
+                    {
+                        if !live.contains(&local) && !ignored.contains(&local) {
+                            dead_stores.push(Location { block, statement_index });
+                        }
+                    }
+                });
+            }
+
+            if dead_stores.is_empty() {
+                break;
+            }
+            for location in dead_stores {
+                debug!("removing dead store at {:?}", location);
+                mir.make_statement_nop(location);
+            }
+        }
+    }
+}
+
+struct BorrowedLocals(LocalSet);
+
+impl<'tcx> Visitor<'tcx> for BorrowedLocals {
+    fn visit_place(&mut self,
+                   place: &Place<'tcx>,
+                   context: PlaceContext<'tcx>,
+                   location: Location) {
+        match context {
+            PlaceContext::Borrow { .. } |
+            PlaceContext::AsmOutput => {
+                if let Some(local) = base_local(place) {
+                    self.0.add(&local);
+                }
+            }
+            _ => {}
+        }
+        self.super_place(place, context, location)
+    }
+}
+
+/// Finds the local whose storage `place` lives in, if any.
+fn base_local(place: &Place) -> Option<Local> {
+    match *place {
+        Place::Local(local) => Some(local),
+        Place::Static(_) => None,
+        Place::Projection(ref proj) => match proj.elem {
+            ProjectionElem::Deref => None,
+            _ => base_local(&proj.base),
+        },
+    }
+}
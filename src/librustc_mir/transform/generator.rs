@@ -35,6 +35,10 @@
 //!         mir_locals...,
 //!     }
 //!
+//! MIR locals whose storage is never live at the same time are assigned the same storage slot,
+//! and share the same bytes in the generator struct. So a generator only needs as much space for
+//! its locals as the largest set of them which can be in use at once.
+//!
 //! This pass computes the meaning of the state field and the MIR locals which are live
 //! across a suspension point. There are however two hardcoded generator states:
 //!     0 - Generator have not been resumed yet
@@ -69,6 +73,7 @@ use rustc::ty::subst::{Kind, Substs};
 use util::dump_mir;
 use util::liveness::{self, LivenessMode};
 use rustc_const_math::ConstInt;
+use rustc_data_structures::bitvec::BitMatrix;
 use rustc_data_structures::indexed_vec::Idx;
 use rustc_data_structures::indexed_set::IdxSetBuf;
 use std::collections::HashMap;
@@ -332,11 +337,25 @@ impl<'tcx> Visitor<'tcx> for StorageIgnored {
     }
 }
 
+struct LivenessInfo {
+    /// Which locals are live across any suspension point.
+    live_locals: liveness::LocalSet,
+
+    /// The locals which are live across each suspension point, in block order.
+    live_locals_at_suspension_points: Vec<liveness::LocalSet>,
+
+    /// For every pair of locals in `live_locals`, whether their storage can be
+    /// live at the same time, in which case they must not overlap.
+    storage_conflicts: BitMatrix,
+
+    /// A map from a suspension point in a block to the locals which have live storage at that
+    /// point.
+    storage_liveness: HashMap<BasicBlock, liveness::LocalSet>,
+}
+
 fn locals_live_across_suspend_points<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                                                mir: &Mir<'tcx>,
-                                               source: MirSource) ->
-                                               (liveness::LocalSet,
-                                                HashMap<BasicBlock, liveness::LocalSet>) {
+                                               source: MirSource) -> LivenessInfo {
     let dead_unwinds = IdxSetBuf::new_empty(mir.basic_blocks().len());
     let node_id = tcx.hir.as_local_node_id(source.def_id).unwrap();
     let analysis = MaybeStorageLive::new(mir);
@@ -355,6 +374,7 @@ fn locals_live_across_suspend_points<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
     liveness::dump_mir(tcx, "generator_liveness", source, mir, &liveness);
 
     let mut storage_liveness_map = HashMap::new();
+    let mut live_locals_at_suspension_points = Vec::new();
 
     for (block, data) in mir.basic_blocks().iter_enumerated() {
         if let TerminatorKind::Yield { .. } = data.terminator().kind {
@@ -376,16 +396,66 @@ fn locals_live_across_suspend_points<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
             // and their storage is live
             live_locals.intersect(&liveness.outs[block]);
 
+            // The generator argument is ignored
+            live_locals.remove(&self_arg());
+
             // Add the locals life at this suspension point to the set of locals which live across
             // any suspension points
             set.union(&live_locals);
+
+            live_locals_at_suspension_points.push(live_locals);
+        }
+    }
+
+    // Find the saved locals whose storage is live at the same time anywhere in the body. Storage
+    // only becomes live at the start of a block or at a `StorageLive` statement, so those are the
+    // only places we need to look at.
+    let local_count = mir.local_decls.len();
+    let mut storage_conflicts = BitMatrix::new(local_count, local_count);
+    for (block, data) in mir.basic_blocks().iter_enumerated() {
+        let mut storage_live_here = storage_live.sets().on_entry_set_for(block.index()).to_owned();
+        record_storage_conflicts(&mut storage_conflicts, &storage_live_here, &ignored.0, &set);
+
+        for statement in &data.statements {
+            match statement.kind {
+                StatementKind::StorageLive(l) => {
+                    storage_live_here.add(&l);
+                    record_storage_conflicts(&mut storage_conflicts,
+                                             &storage_live_here,
+                                             &ignored.0,
+                                             &set);
+                }
+                StatementKind::StorageDead(l) => {
+                    storage_live_here.remove(&l);
+                }
+                _ => {}
+            }
         }
     }
 
-    // The generator argument is ignored
-    set.remove(&self_arg());
+    LivenessInfo {
+        live_locals: set,
+        live_locals_at_suspension_points,
+        storage_conflicts,
+        storage_liveness: storage_liveness_map,
+    }
+}
 
-    (set, storage_liveness_map)
+/// Marks all the saved locals whose storage is live in `storage_live` as conflicting with each
+/// other. Locals without storage statements are always live.
+fn record_storage_conflicts(storage_conflicts: &mut BitMatrix,
+                            storage_live: &liveness::LocalSet,
+                            ignored: &liveness::LocalSet,
+                            saved_locals: &liveness::LocalSet) {
+    let mut eligible = storage_live.clone();
+    eligible.union(ignored);
+    eligible.intersect(saved_locals);
+
+    for a in eligible.iter() {
+        for b in eligible.iter() {
+            storage_conflicts.add(a.index(), b.index());
+        }
+    }
 }
 
 fn compute_layout<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
@@ -397,7 +467,12 @@ fn compute_layout<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
         HashMap<BasicBlock, liveness::LocalSet>)
 {
     // Use a liveness analysis to compute locals which are live across a suspension point
-    let (live_locals, storage_liveness) = locals_live_across_suspend_points(tcx, mir, source);
+    let LivenessInfo {
+        live_locals,
+        live_locals_at_suspension_points,
+        storage_conflicts,
+        storage_liveness,
+    } = locals_live_across_suspend_points(tcx, mir, source);
 
     // Erase regions from the types passed in from typeck so we can compare them with
     // MIR types
@@ -420,6 +495,20 @@ fn compute_layout<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
         }
     }
 
+    // Assign each saved local to the first storage slot whose locals it doesn't conflict with.
+    let mut slot_locals: Vec<Vec<Local>> = Vec::new();
+    let slots = live_locals.iter().map(|local| {
+        let slot = slot_locals.iter().position(|locals| {
+            locals.iter().all(|other| !storage_conflicts.contains(local.index(), other.index()))
+        });
+        let slot = slot.unwrap_or_else(|| {
+            slot_locals.push(Vec::new());
+            slot_locals.len() - 1
+        });
+        slot_locals[slot].push(local);
+        slot
+    }).collect();
+
     let upvar_len = mir.upvar_decls.len();
     let dummy_local = LocalDecl::new_internal(tcx.mk_nil(), mir.span);
 
@@ -433,12 +522,19 @@ fn compute_layout<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
     // Create a map from local indices to generator struct indices.
     // These are offset by (upvar_len + 1) because of fields which comes before locals.
     // We also create a vector of the LocalDecls of these locals.
-    let (remap, vars) = live_decls.enumerate().map(|(idx, (local, var))| {
+    let (remap, vars): (HashMap<_, _>, _) = live_decls.enumerate().map(|(idx, (local, var))| {
         ((local, (var.ty, upvar_len + 1 + idx)), var)
     }).unzip();
 
+    // Record which of the saved locals are in use at each suspension point.
+    let variant_fields = live_locals_at_suspension_points.iter().map(|live| {
+        live.iter().map(|local| remap[&local].1 - (upvar_len + 1)).collect()
+    }).collect();
+
     let layout = GeneratorLayout {
-        fields: vars
+        fields: vars,
+        slots,
+        variant_fields,
     };
 
     (remap, layout, storage_liveness)
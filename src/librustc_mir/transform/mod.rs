@@ -41,6 +41,9 @@ pub mod dump_mir;
 pub mod deaggregator;
 pub mod instcombine;
 pub mod copy_prop;
+pub mod const_prop;
+pub mod sroa;
+pub mod dead_store;
 pub mod generator;
 pub mod inline;
 pub mod lower_128bit;
@@ -256,8 +259,11 @@ fn optimized_mir<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> &'tcx
         // Optimizations begin.
         inline::Inline,
         instcombine::InstCombine,
+        const_prop::ConstProp,
         deaggregator::Deaggregator,
         copy_prop::CopyPropagation,
+        sroa::ScalarReplacementOfAggregates,
+        dead_store::DeadStoreElimination,
         remove_noop_landing_pads::RemoveNoopLandingPads,
         simplify::SimplifyCfg::new("final"),
         simplify::SimplifyLocals,
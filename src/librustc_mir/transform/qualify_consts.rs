@@ -25,7 +25,7 @@ use rustc::ty::{self, TyCtxt, Ty, TypeFoldable};
 use rustc::ty::cast::CastTy;
 use rustc::ty::maps::Providers;
 use rustc::mir::*;
-use rustc::mir::traversal::ReversePostorder;
+use rustc::mir::traversal::{self, ReversePostorder};
 use rustc::mir::visit::{PlaceContext, Visitor};
 use rustc::middle::lang_items;
 use syntax::abi::Abi;
@@ -123,7 +123,12 @@ struct Qualifier<'a, 'gcx: 'a+'tcx, 'tcx: 'a> {
     qualif: Qualif,
     const_fn_arg_vars: BitVector,
     temp_promotion_state: IndexVec<Local, TempState>,
-    promotion_candidates: Vec<Candidate>
+    promotion_candidates: Vec<Candidate>,
+    /// Whether `if`, `match`, loops and `let` bindings are allowed,
+    /// i.e. `#![feature(const_control_flow)]` is enabled.
+    control_flow: bool,
+    /// Whether `box` is allowed, i.e. `#![feature(const_heap)]` is enabled.
+    heap: bool,
 }
 
 impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
@@ -158,7 +163,9 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
             qualif: Qualif::empty(),
             const_fn_arg_vars: BitVector::new(mir.local_decls.len()),
             temp_promotion_state: temps,
-            promotion_candidates: vec![]
+            promotion_candidates: vec![],
+            control_flow: tcx.sess.features.borrow().const_control_flow,
+            heap: tcx.sess.features.borrow().const_heap,
         }
     }
 
@@ -244,11 +251,18 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
     fn assign(&mut self, dest: &Place<'tcx>, location: Location) {
         let qualif = self.qualif;
         let span = self.span;
+        let control_flow = self.control_flow && self.mode != Mode::Fn;
         let store = |slot: &mut Option<Qualif>| {
-            if slot.is_some() {
-                span_bug!(span, "multiple assignments to {:?}", dest);
+            if let Some(previous) = *slot {
+                // With branches, the same place can be assigned
+                // on several paths, e.g. the result of an `if`.
+                if !control_flow {
+                    span_bug!(span, "multiple assignments to {:?}", dest);
+                }
+                *slot = Some(previous | qualif);
+            } else {
+                *slot = Some(qualif);
             }
-            *slot = Some(qualif);
         };
 
         // Only handle promotable temps in non-const functions.
@@ -272,6 +286,12 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
                 debug!("store to return place {:?}", index);
                 store(&mut self.return_qualif)
             }
+            Place::Local(index) if control_flow &&
+                                   self.mir.local_kind(index) == LocalKind::Var => {
+                // Reads of variables use the qualification of their
+                // type, see `visit_local`, so there's nothing to record.
+                debug!("store to var {:?}", index);
+            }
 
             Place::Projection(box Projection {
                 base: Place::Local(index),
@@ -300,6 +320,43 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
 
         let mir = self.mir;
 
+        if self.control_flow {
+            // Qualify every block which can be reached without unwinding,
+            // in reverse postorder so that, outside of loops, every place
+            // is assigned before it is read.
+            let mut rpo = traversal::reverse_postorder(mir);
+            while let Some((bb, data)) = rpo.next() {
+                if data.is_cleanup {
+                    continue;
+                }
+
+                self.visit_basic_block_data(bb, data);
+
+                match data.terminator().kind {
+                    TerminatorKind::Return => self.check_return(),
+                    TerminatorKind::DropAndReplace { .. } |
+                    TerminatorKind::Resume |
+                    TerminatorKind::Abort |
+                    TerminatorKind::GeneratorDrop |
+                    TerminatorKind::Yield { .. } => {
+                        self.span = data.terminator().source_info.span;
+                        self.not_const();
+                    }
+                    _ => {}
+                }
+            }
+        } else {
+            self.qualify_straight_line();
+        }
+
+        self.finish()
+    }
+
+    /// Qualify the single path through a constant which has no branches
+    /// or loops, erroring on the first one found.
+    fn qualify_straight_line(&mut self) {
+        let mir = self.mir;
+
         let mut seen_blocks = BitVector::new(mir.basic_blocks().len());
         let mut bb = START_BLOCK;
         loop {
@@ -330,30 +387,7 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
                 TerminatorKind::FalseEdges { .. } => None,
 
                 TerminatorKind::Return => {
-                    // Check for unused values. This usually means
-                    // there are extra statements in the AST.
-                    for temp in mir.temps_iter() {
-                        if self.temp_qualif[temp].is_none() {
-                            continue;
-                        }
-
-                        let state = self.temp_promotion_state[temp];
-                        if let TempState::Defined { location, uses: 0 } = state {
-                            let data = &mir[location.block];
-                            let stmt_idx = location.statement_index;
-
-                            // Get the span for the initialization.
-                            let source_info = if stmt_idx < data.statements.len() {
-                                data.statements[stmt_idx].source_info
-                            } else {
-                                data.terminator().source_info
-                            };
-                            self.span = source_info.span;
-
-                            // Treat this as a statement in the AST.
-                            self.statement_like();
-                        }
-                    }
+                    self.check_return();
 
                     // Make sure there are no extra unassigned variables.
                     self.qualif = Qualif::NOT_CONST;
@@ -382,6 +416,45 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
                 }
             }
         }
+    }
+
+    /// Check for unused values once the return is reached.
+    /// This usually means there are extra statements in the AST.
+    fn check_return(&mut self) {
+        let mir = self.mir;
+        for temp in mir.temps_iter() {
+            if self.temp_qualif[temp].is_none() {
+                continue;
+            }
+
+            // The value of an `if` or a loop used as a statement is `()`.
+            if self.control_flow && mir.local_decls[temp].ty.is_nil() {
+                continue;
+            }
+
+            let state = self.temp_promotion_state[temp];
+            if let TempState::Defined { location, uses: 0 } = state {
+                let data = &mir[location.block];
+                let stmt_idx = location.statement_index;
+
+                // Get the span for the initialization.
+                let source_info = if stmt_idx < data.statements.len() {
+                    data.statements[stmt_idx].source_info
+                } else {
+                    data.terminator().source_info
+                };
+                self.span = source_info.span;
+
+                // Treat this as a statement in the AST.
+                self.statement_like();
+            }
+        }
+    }
+
+    /// Compute the qualification of the returned value and
+    /// collect the temps which need promoting.
+    fn finish(&mut self) -> (Qualif, Rc<IdxSetBuf<Local>>) {
+        let mir = self.mir;
 
         self.qualif = self.return_qualif.unwrap_or(Qualif::NOT_CONST);
 
@@ -428,6 +501,13 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
             LocalKind::ReturnPointer => {
                 self.not_const();
             }
+            LocalKind::Var if self.control_flow && self.mode != Mode::Fn => {
+                // Variables can be reassigned in loops, so conservatively
+                // use the qualification of their type.
+                let mut qualif = Qualif::MUTABLE_INTERIOR | Qualif::NEEDS_DROP;
+                qualif.restrict(self.mir.local_decls[local].ty, self.tcx, self.param_env);
+                self.add(qualif | Qualif::NOT_PROMOTABLE);
+            }
             LocalKind::Var => {
                 self.add(Qualif::NOT_CONST);
             }
@@ -698,7 +778,7 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
 
             Rvalue::NullaryOp(NullOp::Box, _) => {
                 self.add(Qualif::NOT_CONST);
-                if self.mode != Mode::Fn {
+                if self.mode != Mode::Fn && !self.heap {
                     struct_span_err!(self.tcx.sess, self.span, E0010,
                                      "allocations are not allowed in {}s", self.mode)
                         .span_label(self.span, format!("allocation not allowed in {}s", self.mode))
@@ -737,7 +817,9 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
                     Abi::PlatformIntrinsic => {
                         assert!(!self.tcx.is_const_fn(def_id));
                         match &self.tcx.item_name(def_id)[..] {
-                            "size_of" | "min_align_of" => is_const_fn = Some(def_id),
+                            "size_of" | "min_align_of" | "needs_drop" => {
+                                is_const_fn = Some(def_id)
+                            }
 
                             name if name.starts_with("simd_shuffle") => {
                                 is_shuffle = true;
@@ -863,7 +945,11 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
                 if let Some(span) = needs_drop {
                     // Double-check the type being dropped, to minimize false positives.
                     let ty = place.ty(self.mir, self.tcx).to_ty(self.tcx);
-                    if ty.needs_drop(self.tcx, self.param_env) {
+                    // Dropping a `Box` frees it, which is fine as long
+                    // as its contents don't need dropping themselves.
+                    let frees_box = self.heap && ty.is_box() &&
+                                    !ty.boxed_ty().needs_drop(self.tcx, self.param_env);
+                    if ty.needs_drop(self.tcx, self.param_env) && !frees_box {
                         struct_span_err!(self.tcx.sess, span, E0493,
                                          "destructors cannot be evaluated at compile-time")
                             .span_label(span, format!("{}s cannot evaluate destructors",
@@ -887,7 +973,8 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
 
         // Check the allowed const fn argument forms.
         if let (Mode::ConstFn, &Place::Local(index)) = (self.mode, dest) {
-            if self.mir.local_kind(index) == LocalKind::Var &&
+            if !self.control_flow &&
+               self.mir.local_kind(index) == LocalKind::Var &&
                self.const_fn_arg_vars.insert(index.index()) {
 
                 // Direct use of an argument is permitted.
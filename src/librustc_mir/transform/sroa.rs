@@ -0,0 +1,235 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Scalar replacement of aggregates.
+//!
+//! This splits struct and tuple locals that are only ever accessed field by
+//! field into one local per field:
+//!
+//!     StorageLive(A)
+//!     A = (X, Y)
+//!     USE(A.1)
+//!     StorageDead(A)
+//!
+//! becomes
+//!
+//!     StorageLive(A0)
+//!     StorageLive(A1)
+//!     A0 = X
+//!     A1 = Y
+//!     USE(A1)
+//!     StorageDead(A0)
+//!     StorageDead(A1)
+//!
+//! A local is not split if it is used as a whole anywhere else (moved,
+//! copied, borrowed, dropped...), if it is an argument or the return place,
+//! or if its type needs dropping.
+
+use rustc::hir;
+use rustc::mir::*;
+use rustc::mir::visit::{MutVisitor, PlaceContext, Visitor};
+use rustc::ty::{self, Ty, TyCtxt};
+use rustc_data_structures::indexed_vec::{Idx, IndexVec};
+use transform::{MirPass, MirSource};
+
+pub struct ScalarReplacementOfAggregates;
+
+impl MirPass for ScalarReplacementOfAggregates {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        // Don't run on constant MIR, because trans might not be able to
+        // evaluate the modified MIR.
+        // FIXME(eddyb) Remove check after miri is merged.
+        let id = tcx.hir.as_local_node_id(source.def_id).unwrap();
+        match (tcx.hir.body_owner_kind(id), source.promoted) {
+            (hir::BodyOwnerKind::Fn, None) => {
+                if tcx.is_const_fn(source.def_id) {
+                    return
+                }
+            }
+            _ => return
+        }
+
+        // We only run when the MIR optimization level is > 1.
+        // Splitting user variables messes up debug info.
+        if tcx.sess.opts.debugging_opts.mir_opt_level <= 1 {
+            return;
+        }
+
+        let param_env = tcx.param_env(source.def_id);
+        let mut candidates: IndexVec<Local, Option<Vec<Ty<'tcx>>>> = mir.local_decls
+            .iter_enumerated()
+            .map(|(local, decl)| {
+                match mir.local_kind(local) {
+                    LocalKind::Var | LocalKind::Temp => {}
+                    LocalKind::Arg | LocalKind::ReturnPointer => return None,
+                }
+                if decl.ty.needs_drop(tcx, param_env) {
+                    return None;
+                }
+                field_tys(tcx, decl.ty)
+            })
+            .collect();
+
+        FindEscapes { candidates: &mut candidates }.visit_mir(mir);
+
+        // Allocate the per-field locals.
+        let mut replacements = IndexVec::from_elem(None, &mir.local_decls);
+        for (local, field_tys) in candidates.into_iter_enumerated() {
+            let field_tys = match field_tys {
+                Some(field_tys) => field_tys,
+                None => continue,
+            };
+            debug!("splitting {:?} into {} locals", local, field_tys.len());
+            let source_info = mir.local_decls[local].source_info;
+            let fields = field_tys.into_iter().map(|ty| {
+                mir.local_decls.push(LocalDecl::new_temp(ty, source_info.span))
+            }).collect::<Vec<_>>();
+            replacements[local] = Some(fields);
+        }
+        if replacements.iter().all(|fields| fields.is_none()) {
+            return;
+        }
+
+        // Expand the statements which refer to the split locals as a whole.
+        for data in mir.basic_blocks_mut() {
+            let statements = data.statements.split_off(0);
+            for statement in statements {
+                let source_info = statement.source_info;
+                let replaced = match statement.kind {
+                    StatementKind::StorageLive(local) |
+                    StatementKind::StorageDead(local) => {
+                        replacements[local].as_ref().map(|fields| {
+                            fields.iter().map(|&field| {
+                                let kind = match statement.kind {
+                                    StatementKind::StorageLive(_) => {
+                                        StatementKind::StorageLive(field)
+                                    }
+                                    _ => StatementKind::StorageDead(field),
+                                };
+                                Statement { source_info, kind }
+                            }).collect::<Vec<_>>()
+                        })
+                    }
+                    StatementKind::Assign(Place::Local(local),
+                                          Rvalue::Aggregate(_, ref operands)) => {
+                        replacements[local].as_ref().map(|fields| {
+                            fields.iter().zip(operands).map(|(&field, operand)| {
+                                Statement {
+                                    source_info,
+                                    kind: StatementKind::Assign(Place::Local(field),
+                                                                Rvalue::Use(operand.clone())),
+                                }
+                            }).collect::<Vec<_>>()
+                        })
+                    }
+                    _ => None,
+                };
+                match replaced {
+                    Some(replaced) => data.statements.extend(replaced),
+                    None => data.statements.push(statement),
+                }
+            }
+        }
+
+        // Rewrite the field projections.
+        ReplaceFields { replacements: &replacements }.visit_mir(mir);
+    }
+}
+
+/// Returns the types of the fields of `ty` if it is a struct or a tuple.
+fn field_tys<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, ty: Ty<'tcx>) -> Option<Vec<Ty<'tcx>>> {
+    let tys = match ty.sty {
+        ty::TyAdt(adt_def, substs) if adt_def.is_struct() && !adt_def.repr.simd() => {
+            adt_def.non_enum_variant().fields.iter().map(|field| field.ty(tcx, substs)).collect()
+        }
+        ty::TyTuple(tys, _) => tys.to_vec(),
+        _ => return None,
+    };
+    if tys.is_empty() {
+        None
+    } else {
+        Some(tys)
+    }
+}
+
+/// Rules out the candidates which are used as a whole.
+struct FindEscapes<'a, 'tcx: 'a> {
+    candidates: &'a mut IndexVec<Local, Option<Vec<Ty<'tcx>>>>,
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for FindEscapes<'a, 'tcx> {
+    fn visit_assign(&mut self,
+                    block: BasicBlock,
+                    place: &Place<'tcx>,
+                    rvalue: &Rvalue<'tcx>,
+                    location: Location) {
+        // Building the whole aggregate at once is fine, it can be expanded
+        // into one assignment per field.
+        if let (&Place::Local(_), &Rvalue::Aggregate(ref kind, _)) = (place, rvalue) {
+            match **kind {
+                AggregateKind::Tuple |
+                AggregateKind::Adt(_, _, _, None) => {
+                    self.visit_rvalue(rvalue, location);
+                    return;
+                }
+                _ => {}
+            }
+        }
+        self.super_assign(block, place, rvalue, location);
+    }
+
+    fn visit_place(&mut self,
+                   place: &Place<'tcx>,
+                   context: PlaceContext<'tcx>,
+                   location: Location) {
+        if let Place::Projection(ref proj) = *place {
+            if let (&Place::Local(_), &ProjectionElem::Field(..)) = (&proj.base, &proj.elem) {
+                return;
+            }
+        }
+        self.super_place(place, context, location);
+    }
+
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext<'tcx>, _: Location) {
+        match context {
+            PlaceContext::StorageLive |
+            PlaceContext::StorageDead => {}
+            _ => self.candidates[local] = None,
+        }
+    }
+}
+
+struct ReplaceFields<'a> {
+    replacements: &'a IndexVec<Local, Option<Vec<Local>>>,
+}
+
+impl<'a, 'tcx> MutVisitor<'tcx> for ReplaceFields<'a> {
+    fn visit_place(&mut self,
+                   place: &mut Place<'tcx>,
+                   context: PlaceContext<'tcx>,
+                   location: Location) {
+        let replacement = match *place {
+            Place::Projection(ref proj) => match (&proj.base, &proj.elem) {
+                (&Place::Local(local), &ProjectionElem::Field(field, _)) => {
+                    self.replacements[local].as_ref().map(|fields| fields[field.index()])
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+        match replacement {
+            Some(local) => *place = Place::Local(local),
+            None => self.super_place(place, context, location),
+        }
+    }
+}
@@ -24,8 +24,9 @@ use std::ops::{Deref, DerefMut};
 use Resolver;
 use resolve_imports::ImportDirectiveSubclass;
 
+use errors::Applicability;
 use rustc::{lint, ty};
-use rustc::util::nodemap::NodeMap;
+use rustc::util::nodemap::{NodeMap, NodeSet};
 use syntax::ast;
 use syntax::visit::{self, Visitor};
 use syntax_pos::{Span, MultiSpan, DUMMY_SP};
@@ -35,6 +36,10 @@ struct UnusedImportCheckVisitor<'a, 'b: 'a> {
     resolver: &'a mut Resolver<'b>,
     /// All the (so far) unused imports, grouped path list
     unused_imports: NodeMap<NodeMap<Span>>,
+    /// The `use` items with at least one import that is, or may still be, used
+    partly_used_items: NodeSet,
+    /// The span of every `use` item, keyed like `unused_imports`
+    use_item_spans: NodeMap<Span>,
     base_id: ast::NodeId,
     item_span: Span,
 }
@@ -63,6 +68,7 @@ impl<'a, 'b> UnusedImportCheckVisitor<'a, 'b> {
         if !used {
             if self.maybe_unused_trait_imports.contains(&id) {
                 // Check later.
+                self.partly_used_items.insert(item_id);
                 return;
             }
             self.unused_imports.entry(item_id).or_insert_with(NodeMap).insert(id, span);
@@ -70,6 +76,7 @@ impl<'a, 'b> UnusedImportCheckVisitor<'a, 'b> {
             // This trait import is definitely used, in a way other than
             // method resolution.
             self.maybe_unused_trait_imports.remove(&id);
+            self.partly_used_items.insert(item_id);
             if let Some(i) = self.unused_imports.get_mut(&item_id) {
                 i.remove(&id);
             }
@@ -99,6 +106,7 @@ impl<'a, 'b> Visitor<'a> for UnusedImportCheckVisitor<'a, 'b> {
         // This allows the grouping of all the lints in the same item
         if !nested {
             self.base_id = id;
+            self.use_item_spans.insert(id, self.item_span);
         }
 
         if let ast::UseTreeKind::Nested(ref items) = use_tree.kind {
@@ -138,6 +146,8 @@ pub fn check_crate(resolver: &mut Resolver, krate: &ast::Crate) {
     let mut visitor = UnusedImportCheckVisitor {
         resolver,
         unused_imports: NodeMap(),
+        partly_used_items: NodeSet(),
+        use_item_spans: NodeMap(),
         base_id: ast::DUMMY_NODE_ID,
         item_span: DUMMY_SP,
     };
@@ -163,6 +173,22 @@ pub fn check_crate(resolver: &mut Resolver, krate: &ast::Crate) {
                           } else {
                               String::new()
                           });
-        visitor.session.buffer_lint(lint::builtin::UNUSED_IMPORTS, *id, ms, &msg);
+        // Only offer a fix when it is the whole `use` item that goes away. Taking
+        // single imports out of a list would also have to deal with the commas
+        // and braces around them.
+        let item_span = visitor.use_item_spans.get(id).cloned();
+        match item_span {
+            Some(span) if !visitor.partly_used_items.contains(id) => {
+                let suggestion = lint::BufferedSuggestion {
+                    span,
+                    msg: "remove the whole `use` item".to_string(),
+                    replacement: String::new(),
+                    applicability: Applicability::MachineApplicable,
+                };
+                visitor.session.buffer_lint_with_suggestion(lint::builtin::UNUSED_IMPORTS,
+                                                            *id, ms, &msg, suggestion);
+            }
+            _ => visitor.session.buffer_lint(lint::builtin::UNUSED_IMPORTS, *id, ms, &msg),
+        }
     }
 }
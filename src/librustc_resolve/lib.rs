@@ -61,7 +61,7 @@ use syntax::feature_gate::{feature_err, emit_feature_err, GateIssue};
 use syntax::parse::token;
 
 use syntax_pos::{Span, DUMMY_SP, MultiSpan};
-use errors::{DiagnosticBuilder, DiagnosticId};
+use errors::{Applicability, DiagnosticBuilder, DiagnosticId};
 
 use std::cell::{Cell, RefCell};
 use std::cmp;
@@ -2622,8 +2622,11 @@ impl<'a> Resolver<'a> {
                                         enum_path);
                         err.help(&msg);
                     } else {
-                        err.span_suggestion(span, "you can try using the variant's enum",
-                                            enum_path);
+                        err.span_suggestion_with_applicability(
+                            span,
+                            "you can try using the variant's enum",
+                            enum_path,
+                            Applicability::MaybeIncorrect);
                     }
                 }
             }
@@ -2632,20 +2635,29 @@ impl<'a> Resolver<'a> {
                     let self_is_available = this.self_value_is_available(path[0].node.ctxt, span);
                     match candidate {
                         AssocSuggestion::Field => {
-                            err.span_suggestion(span, "try",
-                                                format!("self.{}", path_str));
+                            err.span_suggestion_with_applicability(
+                                span,
+                                "try",
+                                format!("self.{}", path_str),
+                                Applicability::MachineApplicable);
                             if !self_is_available {
                                 err.span_label(span, format!("`self` value is only available in \
                                                                methods with `self` parameter"));
                             }
                         }
                         AssocSuggestion::MethodWithSelf if self_is_available => {
-                            err.span_suggestion(span, "try",
-                                                format!("self.{}", path_str));
+                            err.span_suggestion_with_applicability(
+                                span,
+                                "try",
+                                format!("self.{}", path_str),
+                                Applicability::MachineApplicable);
                         }
                         AssocSuggestion::MethodWithSelf | AssocSuggestion::AssocItem => {
-                            err.span_suggestion(span, "try",
-                                                format!("Self::{}", path_str));
+                            err.span_suggestion_with_applicability(
+                                span,
+                                "try",
+                                format!("Self::{}", path_str),
+                                Applicability::MachineApplicable);
                         }
                     }
                     return (err, candidates);
@@ -2801,9 +2813,11 @@ impl<'a> Resolver<'a> {
                         err.span_label(base_span,
                                        "expecting a type here because of type ascription");
                         if line_sp != line_base_sp {
-                            err.span_suggestion_short(sp,
-                                                      "did you mean to use `;` here instead?",
-                                                      ";".to_string());
+                            err.span_suggestion_short_with_applicability(
+                                sp,
+                                "did you mean to use `;` here instead?",
+                                ";".to_string(),
+                                Applicability::MaybeIncorrect);
                         }
                         break;
                     } else if snippet.trim().len() != 0  {
@@ -3918,9 +3932,11 @@ impl<'a> Resolver<'a> {
 
             if let (Ok(snippet), false) = (cm.span_to_snippet(binding.span),
                                            binding.is_renamed_extern_crate()) {
-                err.span_suggestion(binding.span,
-                                    rename_msg,
-                                    format!("{} as Other{}", snippet, name));
+                err.span_suggestion_with_applicability(
+                    binding.span,
+                    rename_msg,
+                    format!("{} as Other{}", snippet, name),
+                    Applicability::MaybeIncorrect);
             } else {
                 err.span_label(binding.span, rename_msg);
             }
@@ -4050,7 +4066,8 @@ fn show_candidates(err: &mut DiagnosticBuilder,
             *candidate = format!("use {};\n{}", candidate, additional_newline);
         }
 
-        err.span_suggestions(span, &msg, path_strings);
+        err.span_suggestions_with_applicability(span, &msg, path_strings,
+                                                Applicability::MaybeIncorrect);
     } else {
         let mut msg = msg;
         msg.push(':');
@@ -557,7 +557,9 @@ pub fn type_metadata<'a, 'tcx>(cx: &CodegenCx<'a, 'tcx>,
                 Err(metadata) => return metadata,
             }
         }
-        ty::TyAdt(def, _) if def.is_box() => {
+        // A `Box` with an allocator which isn't zero-sized is described as a
+        // struct of its pointer and its allocator.
+        ty::TyAdt(def, _) if def.is_box() && cx.layout_of(t).field(cx, 1).is_zst() => {
             match ptr_metadata(t.boxed_ty()) {
                 Ok(res) => res,
                 Err(metadata) => return metadata,
@@ -118,11 +118,13 @@ impl<'mir, 'a, 'tcx> Visitor<'tcx> for LocalAnalyzer<'mir, 'a, 'tcx> {
                 }),
                 ref args, ..
             } if Some(def_id) == self.fx.cx.tcx.lang_items().box_free_fn() => {
-                // box_free(x) shares with `drop x` the property that it
+                // box_free(x.0, x.1) shares with `drop x` the property that it
                 // is not guaranteed to be statically dominated by the
                 // definition of x, so x must always be in an alloca.
-                if let mir::Operand::Move(ref place) = args[0] {
-                    self.visit_place(place, PlaceContext::Drop, location);
+                for arg in args {
+                    if let mir::Operand::Move(ref place) = *arg {
+                        self.visit_place(place, PlaceContext::Drop, location);
+                    }
                 }
             }
             _ => {}
@@ -9,13 +9,15 @@
 // except according to those terms.
 
 use llvm::{self, ValueRef};
-use rustc::middle::const_val::{ConstEvalErr, ConstVal, ErrKind};
+use rustc::middle::const_val::{ConstAggregate, ConstEvalErr, ConstVal, ErrKind};
 use rustc_const_math::ConstInt::*;
 use rustc_const_math::{ConstInt, ConstMathErr, MAX_F32_PLUS_HALF_ULP};
+use rustc::hir::def::Def;
 use rustc::hir::def_id::DefId;
 use rustc::infer::TransNormalize;
 use rustc::traits;
 use rustc::mir;
+use rustc::mir::interpret::{Allocation, MemoryPointer, read_target_uint};
 use rustc::mir::tcx::PlaceTy;
 use rustc::ty::{self, Ty, TyCtxt, TypeFoldable};
 use rustc::ty::layout::{self, LayoutOf, Size};
@@ -39,8 +41,10 @@ use value::Value;
 use syntax_pos::Span;
 use syntax::ast;
 
+use std::cell::Cell;
 use std::fmt;
 use std::ptr;
+use std::rc::Rc;
 
 use super::operand::{OperandRef, OperandValue};
 use super::FunctionCx;
@@ -103,6 +107,7 @@ impl<'a, 'tcx> Const<'tcx> {
             }
             ConstVal::Char(c) => C_uint(Type::char(cx), c as u64),
             ConstVal::Function(..) => C_undef(llty),
+            ConstVal::Pointer(ptr) => const_ptr_from_miri(cx, ptr, llty),
             ConstVal::Variant(_) |
             ConstVal::Aggregate(..) |
             ConstVal::Unevaluated(..) => {
@@ -116,7 +121,30 @@ impl<'a, 'tcx> Const<'tcx> {
     }
 
     fn get_field(&self, cx: &CodegenCx<'a, 'tcx>, i: usize) -> ValueRef {
+        self.get_field_of(cx, cx.layout_of(self.ty), i)
+    }
+
+    /// Like `get_field`, but for a field of the variant `variant_index`
+    /// of an enum, the constant having been built as that variant.
+    fn get_variant_field(&self,
+                         cx: &CodegenCx<'a, 'tcx>,
+                         variant_index: usize,
+                         i: usize)
+                         -> ValueRef {
         let layout = cx.layout_of(self.ty);
+        let variant = layout.for_variant(cx, variant_index);
+        match layout.variants {
+            layout::Variants::Tagged { .. } if !variant.field(cx, i).is_zst() => {
+                // `build_const_struct` put the tag first, followed
+                // by the padding and the value of each field.
+                const_get_elt(self.llval, 2 + 2 * variant.fields.memory_index(i) as u64)
+            }
+            _ => self.get_field_of(cx, variant, i),
+        }
+    }
+
+    fn get_field_of(&self, cx: &CodegenCx<'a, 'tcx>, layout: layout::TyLayout<'tcx>, i: usize)
+                    -> ValueRef {
         let field = layout.field(cx, i);
         if field.is_zst() {
             return C_undef(field.immediate_llvm_type(cx));
@@ -145,6 +173,41 @@ impl<'a, 'tcx> Const<'tcx> {
         }
     }
 
+    /// Reads the discriminant of an enum built by `trans_const_adt`.
+    fn get_discr(&self, cx: &CodegenCx<'a, 'tcx>) -> u128 {
+        let (adt_def, substs) = match self.ty.sty {
+            ty::TyAdt(adt_def, substs) if adt_def.is_enum() => (adt_def, substs),
+            _ => bug!("discriminant of non-enum `{}` in constant", self.ty)
+        };
+        let layout = cx.layout_of(self.ty);
+        let variant_index = match layout.variants {
+            layout::Variants::Single { index } => index,
+            layout::Variants::Tagged { ref discr, .. } => {
+                let lltag = match layout.abi {
+                    layout::Abi::Scalar(_) => self.llval,
+                    _ => const_get_elt(self.llval, 0),
+                };
+                let signed = match discr.value {
+                    layout::Int(_, signed) => signed,
+                    _ => false,
+                };
+                return const_to_opt_u128(lltag, signed).unwrap_or_else(|| {
+                    bug!("non-integral tag {:?} in constant", Value(lltag))
+                });
+            }
+            layout::Variants::NicheFilling { dataful_variant, ref niche_variants, .. } => {
+                // The other variants have no data, so there's a single constant
+                // for each of them, which LLVM uniques.
+                (niche_variants.start..niche_variants.end + 1).find(|&i| {
+                    let kind = mir::AggregateKind::Adt(adt_def, i, substs, None);
+                    i != dataful_variant &&
+                        trans_const_adt(cx, self.ty, &kind, &[]).llval == self.llval
+                }).unwrap_or(dataful_variant)
+            }
+        };
+        adt_def.discriminant_for_variant(cx.tcx, variant_index).to_u128_unchecked()
+    }
+
     fn get_pair(&self, cx: &CodegenCx<'a, 'tcx>) -> (ValueRef, ValueRef) {
         (self.get_field(cx, 0), self.get_field(cx, 1))
     }
@@ -246,6 +309,9 @@ impl<'tcx> ConstPlace<'tcx> {
     }
 }
 
+/// What `MirConstContext` can't evaluate, but miri can.
+const HEAP_ALLOCATION: &'static str = "heap allocation";
+
 /// Machinery for translating a constant's MIR to LLVM values.
 /// FIXME(eddyb) use miri and lower its allocations to LLVM.
 struct MirConstContext<'a, 'tcx: 'a> {
@@ -256,7 +322,17 @@ struct MirConstContext<'a, 'tcx: 'a> {
     substs: &'tcx Substs<'tcx>,
 
     /// Values of locals in a constant or const fn.
-    locals: IndexVec<mir::Local, Option<Result<Const<'tcx>, ConstEvalErr<'tcx>>>>
+    locals: IndexVec<mir::Local, Option<Result<Const<'tcx>, ConstEvalErr<'tcx>>>>,
+
+    /// The steps left before reaching `#![const_eval_limit]`. Like miri's
+    /// step limit, they're shared by the constant and all the const fn
+    /// calls and other constants its evaluation goes through.
+    steps_remaining: Rc<Cell<usize>>,
+}
+
+/// A fresh budget of steps for translating a constant.
+fn const_eval_steps(cx: &CodegenCx) -> Rc<Cell<usize>> {
+    Rc::new(Cell::new(cx.tcx.sess.const_eval_limit.get()))
 }
 
 fn add_err<'tcx, U, V>(failure: &mut Result<U, ConstEvalErr<'tcx>>,
@@ -273,13 +349,15 @@ impl<'a, 'tcx> MirConstContext<'a, 'tcx> {
     fn new(cx: &'a CodegenCx<'a, 'tcx>,
            mir: &'a mir::Mir<'tcx>,
            substs: &'tcx Substs<'tcx>,
-           args: IndexVec<mir::Local, Result<Const<'tcx>, ConstEvalErr<'tcx>>>)
+           args: IndexVec<mir::Local, Result<Const<'tcx>, ConstEvalErr<'tcx>>>,
+           steps_remaining: Rc<Cell<usize>>)
            -> MirConstContext<'a, 'tcx> {
         let mut context = MirConstContext {
             cx,
             mir,
             substs,
             locals: (0..mir.local_decls.len()).map(|_| None).collect(),
+            steps_remaining,
         };
         for (i, arg) in args.into_iter().enumerate() {
             // Locals after local 0 are the function arguments
@@ -292,14 +370,44 @@ impl<'a, 'tcx> MirConstContext<'a, 'tcx> {
     fn trans_def(cx: &'a CodegenCx<'a, 'tcx>,
                  def_id: DefId,
                  substs: &'tcx Substs<'tcx>,
-                 args: IndexVec<mir::Local, Result<Const<'tcx>, ConstEvalErr<'tcx>>>)
+                 args: IndexVec<mir::Local, Result<Const<'tcx>, ConstEvalErr<'tcx>>>,
+                 steps_remaining: Rc<Cell<usize>>)
                  -> Result<Const<'tcx>, ConstEvalErr<'tcx>> {
         let instance = ty::Instance::resolve(cx.tcx,
                                              ty::ParamEnv::empty(traits::Reveal::All),
                                              def_id,
                                              substs).unwrap();
         let mir = cx.tcx.instance_mir(instance.def);
-        MirConstContext::new(cx, &mir, instance.substs, args).trans()
+        MirConstContext::new(cx, &mir, instance.substs, args, steps_remaining).trans()
+    }
+
+    /// Translates the constant or static `def_id`. Constants which allocate
+    /// on the heap are evaluated by miri instead.
+    fn trans_global(cx: &'a CodegenCx<'a, 'tcx>,
+                    def_id: DefId,
+                    substs: &'tcx Substs<'tcx>,
+                    steps_remaining: Rc<Cell<usize>>)
+                    -> Result<Const<'tcx>, ConstEvalErr<'tcx>> {
+        let result = MirConstContext::trans_def(cx, def_id, substs, IndexVec::new(),
+                                                steps_remaining);
+        let err = match result {
+            Err(err @ ConstEvalErr {
+                kind: ErrKind::UnimplementedConstVal(HEAP_ALLOCATION), ..
+            }) => err,
+            result => return result,
+        };
+        // Reported by `trans_static`.
+        if let Some(Def::Static(..)) = cx.tcx.describe_def(def_id) {
+            return Err(err);
+        }
+        let param_env = ty::ParamEnv::empty(traits::Reveal::All);
+        match cx.tcx.const_eval(param_env.and((def_id, substs))) {
+            Ok(value) => Ok(const_from_miri(cx, value)),
+            Err(err) => {
+                err.report(cx.tcx, err.span, "constant");
+                Err(err)
+            }
+        }
     }
 
     fn monomorphize<T>(&self, value: &T) -> T
@@ -318,9 +426,29 @@ impl<'a, 'tcx> MirConstContext<'a, 'tcx> {
 
         loop {
             let data = &self.mir[bb];
+            // Constants with loops may not terminate.
+            let steps_remaining = self.steps_remaining.get();
+            if steps_remaining == 0 {
+                // The limit may have been reached by a callee, which reported it.
+                failure?;
+                let span = data.terminator().source_info.span;
+                let err = ConstEvalErr { span, kind: ErrKind::ExecutionTimeLimitReached };
+                err.report(tcx, span, "expression");
+                return Err(err);
+            }
+            self.steps_remaining.set(steps_remaining - 1);
+
             for statement in &data.statements {
                 let span = statement.source_info.span;
                 match statement.kind {
+                    mir::StatementKind::Assign(_, mir::Rvalue::NullaryOp(mir::NullOp::Box, _)) => {
+                        // Only miri can evaluate heap allocations, `trans_global`
+                        // hands the whole constant over to it.
+                        return Err(ConstEvalErr {
+                            span,
+                            kind: ErrKind::UnimplementedConstVal(HEAP_ALLOCATION),
+                        });
+                    }
                     mir::StatementKind::Assign(ref dest, ref rvalue) => {
                         let ty = dest.ty(self.mir, tcx);
                         let ty = self.monomorphize(&ty).to_ty(tcx);
@@ -344,7 +472,8 @@ impl<'a, 'tcx> MirConstContext<'a, 'tcx> {
             let span = terminator.source_info.span;
             bb = match terminator.kind {
                 mir::TerminatorKind::Drop { target, .. } | // No dropping.
-                mir::TerminatorKind::Goto { target } => target,
+                mir::TerminatorKind::Goto { target } |
+                mir::TerminatorKind::FalseEdges { real_target: target, .. } => target,
                 mir::TerminatorKind::Return => {
                     failure?;
                     return self.locals[mir::RETURN_PLACE].clone().unwrap_or_else(|| {
@@ -352,6 +481,23 @@ impl<'a, 'tcx> MirConstContext<'a, 'tcx> {
                     });
                 }
 
+                mir::TerminatorKind::SwitchInt {
+                    ref discr, switch_ty, ref values, ref targets
+                } => {
+                    let discr = self.const_operand(discr, span)?;
+                    let signed = match switch_ty.sty {
+                        ty::TyInt(_) => true,
+                        _ => false,
+                    };
+                    let discr = const_to_opt_u128(discr.llval, signed).unwrap_or_else(|| {
+                        span_bug!(span, "switch on non-integral {:?} in constant", discr)
+                    });
+                    let index = values.iter().position(|value| {
+                        value.to_u128_unchecked() == discr
+                    }).unwrap_or(values.len());
+                    targets[index]
+                }
+
                 mir::TerminatorKind::Assert { ref cond, expected, ref msg, target, .. } => {
                     let cond = self.const_operand(cond, span)?;
                     let cond_bool = common::const_to_uint(cond.llval) != 0;
@@ -408,6 +554,11 @@ impl<'a, 'tcx> MirConstContext<'a, 'tcx> {
                                         self.cx.align_of(substs.type_at(0)).abi());
                                     Ok(Const::new(llval, tcx.types.usize))
                                 }
+                                "needs_drop" => {
+                                    let llval = C_bool(self.cx,
+                                        self.cx.type_needs_drop(substs.type_at(0)));
+                                    Ok(Const::new(llval, tcx.types.bool))
+                                }
                                 _ => span_bug!(span, "{:?} in constant", terminator.kind)
                             }
                         } else if let Some((op, is_checked)) = self.is_binop_lang_item(def_id) {
@@ -447,7 +598,8 @@ impl<'a, 'tcx> MirConstContext<'a, 'tcx> {
                                 }
                             })()
                         } else {
-                            MirConstContext::trans_def(self.cx, def_id, substs, arg_vals)
+                            MirConstContext::trans_def(self.cx, def_id, substs, arg_vals,
+                                                       self.steps_remaining.clone())
                         };
                         add_err(&mut failure, &result);
                         self.store(dest, result, span);
@@ -562,7 +714,12 @@ impl<'a, 'tcx> MirConstContext<'a, 'tcx> {
                         }
                     }
                     mir::ProjectionElem::Field(ref field, _) => {
-                        let llprojected = base.get_field(self.cx, field.index());
+                        let llprojected = match projection.base {
+                            mir::Place::Projection(box mir::Projection {
+                                elem: mir::ProjectionElem::Downcast(_, variant_index), ..
+                            }) => base.get_variant_field(self.cx, variant_index, field.index()),
+                            _ => base.get_field(self.cx, field.index()),
+                        };
                         let llextra = if !has_metadata {
                             ptr::null_mut()
                         } else {
@@ -590,6 +747,10 @@ impl<'a, 'tcx> MirConstContext<'a, 'tcx> {
 
                         (Base::Value(llelem), ptr::null_mut())
                     }
+                    mir::ProjectionElem::Downcast(..) => {
+                        // The variant is only needed by the fields, see above.
+                        (Base::Value(base.llval), ptr::null_mut())
+                    }
                     _ => span_bug!(span, "{:?} in constant", projection.elem)
                 };
                 ConstPlace {
@@ -616,12 +777,14 @@ impl<'a, 'tcx> MirConstContext<'a, 'tcx> {
                 match constant.literal.clone() {
                     mir::Literal::Promoted { index } => {
                         let mir = &self.mir.promoted[index];
-                        MirConstContext::new(self.cx, mir, self.substs, IndexVec::new()).trans()
+                        MirConstContext::new(self.cx, mir, self.substs, IndexVec::new(),
+                                             self.steps_remaining.clone()).trans()
                     }
                     mir::Literal::Value { value } => {
                         if let ConstVal::Unevaluated(def_id, substs) = value.val {
                             let substs = self.monomorphize(&substs);
-                            MirConstContext::trans_def(self.cx, def_id, substs, IndexVec::new())
+                            MirConstContext::trans_global(self.cx, def_id, substs,
+                                                          self.steps_remaining.clone())
                         } else {
                             Ok(Const::from_constval(self.cx, &value.val, ty))
                         }
@@ -885,6 +1048,12 @@ impl<'a, 'tcx> MirConstContext<'a, 'tcx> {
                 Const::new(tr_place.len(self.cx), tcx.types.usize)
             }
 
+            mir::Rvalue::Discriminant(ref place) => {
+                let discr = self.const_place(place, span)?.to_const(span).get_discr(self.cx);
+                let llty = self.cx.layout_of(dest_ty).llvm_type(self.cx);
+                Const::new(C_uint_big(llty, discr), dest_ty)
+            }
+
             mir::Rvalue::BinaryOp(op, ref lhs, ref rhs) => {
                 let lhs = self.const_operand(lhs, span)?;
                 let rhs = self.const_operand(rhs, span)?;
@@ -1129,12 +1298,13 @@ impl<'a, 'tcx> FunctionCx<'a, 'tcx> {
         let result = match constant.literal.clone() {
             mir::Literal::Promoted { index } => {
                 let mir = &self.mir.promoted[index];
-                MirConstContext::new(bx.cx, mir, self.param_substs, IndexVec::new()).trans()
+                MirConstContext::new(bx.cx, mir, self.param_substs, IndexVec::new(),
+                                     const_eval_steps(bx.cx)).trans()
             }
             mir::Literal::Value { value } => {
                 if let ConstVal::Unevaluated(def_id, substs) = value.val {
                     let substs = self.monomorphize(&substs);
-                    MirConstContext::trans_def(bx.cx, def_id, substs, IndexVec::new())
+                    MirConstContext::trans_global(bx.cx, def_id, substs, const_eval_steps(bx.cx))
                 } else {
                     Ok(Const::from_constval(bx.cx, &value.val, ty))
                 }
@@ -1158,10 +1328,92 @@ pub fn trans_static_initializer<'a, 'tcx>(
     def_id: DefId)
     -> Result<ValueRef, ConstEvalErr<'tcx>>
 {
-    MirConstContext::trans_def(cx, def_id, Substs::empty(), IndexVec::new())
+    MirConstContext::trans_global(cx, def_id, Substs::empty(), const_eval_steps(cx))
         .map(|c| c.llval)
 }
 
+/// Translates the value of a constant evaluated by miri. Unlike in MIR, its
+/// aggregates haven't been expanded into rvalues.
+fn const_from_miri<'a, 'tcx>(cx: &CodegenCx<'a, 'tcx>,
+                             value: &ty::Const<'tcx>)
+                             -> Const<'tcx> {
+    match value.val {
+        ConstVal::Aggregate(ConstAggregate::Tuple(fields)) => {
+            let fields = fields.iter().map(|field| const_from_miri(cx, field))
+                .collect::<Vec<_>>();
+            trans_const_adt(cx, value.ty, &mir::AggregateKind::Tuple, &fields)
+        }
+        ConstVal::Aggregate(ConstAggregate::Struct(fields)) => {
+            // The fields are in declaration order.
+            let fields = fields.iter().map(|&(_, field)| const_from_miri(cx, field))
+                .collect::<Vec<_>>();
+            trans_const_adt(cx, value.ty, &mir::AggregateKind::Tuple, &fields)
+        }
+        ConstVal::Aggregate(ConstAggregate::Array(elems)) => {
+            let elem_ty = value.ty.builtin_index().unwrap();
+            let llunitty = cx.layout_of(elem_ty).llvm_type(cx);
+            let elems = elems.iter().map(|elem| const_from_miri(cx, elem).llval)
+                .collect::<Vec<_>>();
+            // If the array contains enums, an LLVM array won't work.
+            let val = if elems.iter().all(|&elem| val_ty(elem) == llunitty) {
+                C_array(llunitty, &elems)
+            } else {
+                C_struct(cx, &elems, false)
+            };
+            Const::new(val, value.ty)
+        }
+        _ => Const::from_constval(cx, &value.val, value.ty),
+    }
+}
+
+/// Translates a pointer into an allocation interned by miri
+/// to a pointer into the global it's lowered to.
+fn const_ptr_from_miri<'a, 'tcx>(cx: &CodegenCx<'a, 'tcx>,
+                                 ptr: MemoryPointer,
+                                 llty: Type)
+                                 -> ValueRef {
+    let (instance, alloc) = {
+        let interner = cx.tcx.interpret_interner.borrow();
+        (interner.get_fn(ptr.alloc_id), interner.get_alloc(ptr.alloc_id))
+    };
+    let base = match (instance, alloc) {
+        (Some(instance), _) => callee::get_fn(cx, instance),
+        (None, Some(alloc)) => const_alloc_to_llvm(cx, alloc),
+        (None, None) => bug!("pointer into the uninterned allocation {}", ptr.alloc_id),
+    };
+    let base = consts::ptrcast(base, Type::i8p(cx));
+    let llval = unsafe {
+        llvm::LLVMConstInBoundsGEP(base, &C_usize(cx, ptr.offset), 1)
+    };
+    consts::ptrcast(llval, llty)
+}
+
+/// Lowers an allocation interned by miri, e.g. the heap allocation of a
+/// `Box` in a constant, to a global holding its bytes. The pointers in it
+/// are lowered to pointers into the globals of the allocations they point to.
+fn const_alloc_to_llvm<'a, 'tcx>(cx: &CodegenCx<'a, 'tcx>, alloc: &Allocation) -> ValueRef {
+    let dl = &cx.tcx.data_layout;
+    let pointer_size = dl.pointer_size.bytes() as usize;
+    let mut llvals = Vec::with_capacity(alloc.relocations.len() * 2 + 1);
+    let mut next_offset = 0;
+    for (&offset, &alloc_id) in &alloc.relocations {
+        let offset = offset as usize;
+        if offset > next_offset {
+            llvals.push(C_bytes(cx, &alloc.bytes[next_offset..offset]));
+        }
+        // The bytes of a pointer hold its offset into the allocation.
+        let ptr_offset = read_target_uint(dl.endian, &alloc.bytes[offset..offset + pointer_size])
+            .expect("could not read the offset of a pointer") as u64;
+        let ptr = MemoryPointer::new(alloc_id, ptr_offset);
+        llvals.push(const_ptr_from_miri(cx, ptr, Type::i8p(cx)));
+        next_offset = offset + pointer_size;
+    }
+    if alloc.bytes.len() > next_offset {
+        llvals.push(C_bytes(cx, &alloc.bytes[next_offset..]));
+    }
+    consts::addr_of(cx, C_struct(cx, &llvals, true), alloc.align, "const_alloc")
+}
+
 /// Construct a constant value, suitable for initializing a
 /// GlobalVariable, given a case and constant values for its fields.
 /// Note that this may have a different LLVM type (and different
@@ -116,6 +116,26 @@ impl<'a, 'tcx> OperandRef<'tcx> {
         }
     }
 
+    /// Returns the pointer of a `Box` whose allocator isn't zero-sized, as such
+    /// a `Box` is a pair or an aggregate of its pointer and its allocator.
+    /// Other pointers, including boxes using `Heap`, are returned as they are.
+    pub fn box_ptr(self, bx: &Builder<'a, 'tcx>) -> OperandRef<'tcx> {
+        if !self.layout.ty.is_box() || self.layout.field(bx.cx, 1).is_zst() {
+            return self;
+        }
+        // Go through the `Unique<T>` down to the raw pointer it wraps.
+        let mut ptr = self;
+        while !ptr.layout.ty.is_unsafe_ptr() {
+            ptr = match ptr.val {
+                OperandValue::Ref(llval, align) => {
+                    PlaceRef::new_sized(llval, ptr.layout, align).project_field(bx, 0).load(bx)
+                }
+                _ => ptr.extract_field(bx, 0),
+            };
+        }
+        ptr
+    }
+
     /// If this operand is a `Pair`, we return an aggregate with the two values.
     /// For other cases, see `immediate`.
     pub fn immediate_or_packed_pair(self, bx: &Builder<'a, 'tcx>) -> ValueRef {
@@ -18,7 +18,7 @@ use base;
 use builder::Builder;
 use common::{CodegenCx, C_usize, C_u8, C_u32, C_uint, C_int, C_null, C_uint_big};
 use consts;
-use type_of::LayoutLlvmExt;
+use type_of::{self, LayoutLlvmExt};
 use type_::Type;
 use value::Value;
 use glue;
@@ -172,6 +172,9 @@ impl<'a, 'tcx> PlaceRef<'tcx> {
                 // Offsets have to match either first or second field.
                 assert_eq!(offset, a.value.size(cx).abi_align(b.value.align(cx)));
                 bx.struct_gep(self.llval, 1)
+            } else if type_of::is_overlapping_generator(cx, self.layout) {
+                let byte_ptr = bx.pointercast(self.llval, Type::i8p(cx));
+                bx.inbounds_gep(byte_ptr, &[C_usize(cx, offset.bytes())])
             } else {
                 bx.struct_gep(self.llval, self.layout.llvm_field_index(ix))
             };
@@ -431,7 +434,7 @@ impl<'a, 'tcx> FunctionCx<'a, 'tcx> {
                 elem: mir::ProjectionElem::Deref
             }) => {
                 // Load the pointer from its location.
-                self.trans_consume(bx, base).deref(bx.cx)
+                self.trans_consume(bx, base).box_ptr(bx).deref(bx.cx)
             }
             mir::Place::Projection(ref projection) => {
                 let tr_base = self.trans_place(bx, &projection.base);
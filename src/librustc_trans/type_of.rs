@@ -110,10 +110,26 @@ fn uncached_llvm_type<'a, 'tcx>(cx: &CodegenCx<'a, 'tcx>,
     }
 }
 
+/// Returns true if `layout` is a generator whose saved locals share storage.
+/// Such fields can't be described by an LLVM struct, so the generator gets an
+/// opaque body and its fields are accessed by byte offset.
+pub fn is_overlapping_generator<'a, 'tcx>(cx: &CodegenCx<'a, 'tcx>,
+                                          layout: TyLayout<'tcx>)
+                                          -> bool {
+    match layout.ty.sty {
+        ty::TyGenerator(def_id, ..) => cx.tcx.generator_layout(def_id).has_overlap(),
+        _ => false,
+    }
+}
+
 fn struct_llfields<'a, 'tcx>(cx: &CodegenCx<'a, 'tcx>,
                              layout: TyLayout<'tcx>)
                              -> (Vec<Type>, bool) {
     debug!("struct_llfields: {:#?}", layout);
+    if is_overlapping_generator(cx, layout) {
+        return (vec![Type::padding_filler(cx, layout.size, layout.align)], false);
+    }
+
     let field_count = layout.fields.count();
 
     let mut packed = false;
@@ -341,7 +357,9 @@ impl<'tcx> LayoutLlvmExt<'tcx> for TyLayout<'tcx> {
             ty::TyRawPtr(_) => {
                 return self.field(cx, index).llvm_type(cx);
             }
-            ty::TyAdt(def, _) if def.is_box() => {
+            // A `Box` with an allocator which isn't zero-sized is a pair of
+            // its pointer and its allocator instead.
+            ty::TyAdt(def, _) if def.is_box() && self.field(cx, 1).is_zst() => {
                 let ptr_ty = cx.tcx.mk_mut_ptr(self.ty.boxed_ty());
                 return cx.layout_of(ptr_ty).scalar_pair_element_llvm_type(cx, index);
             }
@@ -487,7 +505,7 @@ impl<'tcx> LayoutLlvmExt<'tcx> for TyLayout<'tcx> {
                 // FIXME(eddyb) This should be for `ptr::Unique<T>`, not `Box<T>`.
                 if let Some(ref mut pointee) = result {
                     if let ty::TyAdt(def, _) = self.ty.sty {
-                        if def.is_box() && offset.bytes() == 0 {
+                        if def.is_box() && offset == self.fields.offset(0) {
                             pointee.safe = Some(PointerKind::UniqueOwned);
                         }
                     }
@@ -16,6 +16,7 @@ use rustc::infer::type_variable::TypeVariableOrigin;
 use rustc::traits::ObligationCauseCode;
 use rustc::ty::{self, Ty, TypeFoldable, LvaluePreference};
 use check::{FnCtxt, Expectation, Diverges};
+use errors::Applicability;
 use check::coercion::CoerceMany;
 use util::nodemap::FxHashMap;
 
@@ -144,9 +145,11 @@ impl<'a, 'gcx, 'tcx> FnCtxt<'a, 'gcx, 'tcx> {
                         "non-reference pattern used to match a reference",
                     );
                     if let Ok(snippet) = tcx.sess.codemap().span_to_snippet(sp) {
-                        err.span_suggestion(sp,
-                                            "consider using a reference",
-                                            format!("&{}", &snippet));
+                        err.span_suggestion_with_applicability(
+                            sp,
+                            "consider using a reference",
+                            format!("&{}", &snippet),
+                            Applicability::MachineApplicable);
                     }
                     err.emit();
                 }
@@ -20,7 +20,7 @@ use rustc::hir;
 use rustc::hir::print;
 use rustc::hir::def::Def;
 use rustc::ty::{self, Ty, AssociatedItem};
-use errors::{DiagnosticBuilder, CodeMapper};
+use errors::{Applicability, DiagnosticBuilder, CodeMapper};
 
 use super::method::probe;
 
@@ -129,14 +129,17 @@ impl<'a, 'gcx, 'tcx> FnCtxt<'a, 'gcx, 'tcx> {
                 let expr_text = print::to_string(print::NO_ANN, |s| s.print_expr(expr));
                 let suggestions = compatible_variants.iter()
                     .map(|v| format!("{}({})", v, expr_text)).collect::<Vec<_>>();
-                err.span_suggestions(expr.span,
-                                     "try using a variant of the expected type",
-                                     suggestions);
+                err.span_suggestions_with_applicability(
+                    expr.span,
+                    "try using a variant of the expected type",
+                    suggestions,
+                    Applicability::MaybeIncorrect);
             }
         }
 
         if let Some((msg, suggestion)) = self.check_ref(expr, checked_ty, expected) {
-            err.span_suggestion(expr.span, msg, suggestion);
+            err.span_suggestion_with_applicability(expr.span, msg, suggestion,
+                                                   Applicability::MachineApplicable);
         } else {
             let methods = self.get_conversion_methods(expected, checked_ty);
             if let Ok(expr_text) = self.tcx.sess.codemap().span_to_snippet(expr.span) {
@@ -144,9 +147,11 @@ impl<'a, 'gcx, 'tcx> FnCtxt<'a, 'gcx, 'tcx> {
                     .map(|(receiver, method)| format!("{}.{}()", receiver, method.name))
                     .collect::<Vec<_>>();
                 if !suggestions.is_empty() {
-                    err.span_suggestions(expr.span,
-                                         "try using a conversion method",
-                                         suggestions);
+                    err.span_suggestions_with_applicability(
+                        expr.span,
+                        "try using a conversion method",
+                        suggestions,
+                        Applicability::MaybeIncorrect);
                 }
             }
         }
@@ -22,7 +22,7 @@ use rustc::traits::{Obligation, SelectionContext};
 use util::nodemap::FxHashSet;
 
 use syntax::ast;
-use errors::DiagnosticBuilder;
+use errors::{Applicability, DiagnosticBuilder};
 use syntax_pos::Span;
 
 use rustc::hir;
@@ -226,13 +226,15 @@ impl<'a, 'gcx, 'tcx> FnCtxt<'a, 'gcx, 'tcx> {
                                     .unwrap_or("<numeric literal>".to_string());
                                 // FIXME: use the literal for missing snippet
 
-                                err.span_suggestion(expr.span,
-                                                    &format!("you must specify a concrete type for \
-                                                              this numeric value, like `{}`",
-                                                             concrete_type),
-                                                    format!("{}_{}",
-                                                            snippet,
-                                                            concrete_type));
+                                err.span_suggestion_with_applicability(
+                                    expr.span,
+                                    &format!("you must specify a concrete type for \
+                                              this numeric value, like `{}`",
+                                             concrete_type),
+                                    format!("{}_{}",
+                                            snippet,
+                                            concrete_type),
+                                    Applicability::MaybeIncorrect);
                             }
                             hir::ExprPath(ref qpath) => {  // local binding
                                 if let &hir::QPath::Resolved(_, ref path) = &qpath {
@@ -446,7 +448,8 @@ impl<'a, 'gcx, 'tcx> FnCtxt<'a, 'gcx, 'tcx> {
                 format!("use {};\n{}", self.tcx.item_path_str(*did), additional_newline)
             }).collect();
 
-            err.span_suggestions(span, &msg, path_strings);
+            err.span_suggestions_with_applicability(span, &msg, path_strings,
+                                                    Applicability::MaybeIncorrect);
         } else {
             let limit = if candidates.len() == 5 { 5 } else { 4 };
             for (i, trait_did) in candidates.iter().take(limit).enumerate() {
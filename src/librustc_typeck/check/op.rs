@@ -325,17 +325,21 @@ impl<'a, 'gcx, 'tcx> FnCtxt<'a, 'gcx, 'tcx> {
                     err.span_label(expr.span,
                         "`+` can't be used to concatenate two `&str` strings");
                     let codemap = self.tcx.sess.codemap();
-                    let suggestion =
+                    let (suggestion, applicability) =
                         match codemap.span_to_snippet(lhs_expr.span) {
-                            Ok(lstring) => format!("{}.to_owned()", lstring),
-                            _ => format!("<expression>")
+                            Ok(lstring) => (format!("{}.to_owned()", lstring),
+                                            errors::Applicability::MachineApplicable),
+                            _ => (format!("<expression>"),
+                                  errors::Applicability::HasPlaceholders),
                         };
-                    err.span_suggestion(lhs_expr.span,
+                    err.span_suggestion_with_applicability(lhs_expr.span,
                         &format!("`to_owned()` can be used to create an owned `String` \
                                   from a string reference. String concatenation \
                                   appends the string on the right to the string \
                                   on the left and may require reallocation. This \
-                                  requires ownership of the string on the left"), suggestion);
+                                  requires ownership of the string on the left"),
+                        suggestion,
+                        applicability);
                     is_string_addition = true;
                 }
 
@@ -540,6 +540,7 @@ impl Collector {
                 // compiler failures are test failures
                 should_panic: testing::ShouldPanic::No,
                 allow_fail,
+                timeout: None,
             },
             testfn: testing::DynTestFn(box move || {
                 let panic = io::set_panic(None);
@@ -11,6 +11,8 @@
 use self::Entry::*;
 use self::VacantEntryState::*;
 
+use alloc::allocator::CollectionAllocErr;
+use alloc::heap::{Alloc, Heap};
 use cell::Cell;
 use borrow::Borrow;
 use cmp::max;
@@ -18,13 +20,15 @@ use fmt::{self, Debug};
 #[allow(deprecated)]
 use hash::{Hash, Hasher, BuildHasher, SipHasher13};
 use iter::{FromIterator, FusedIterator};
-use mem::{self, replace};
+use mem;
 use ops::{Deref, Index, InPlace, Place, Placer};
 use ptr;
 use sys;
 
 use super::table::{self, Bucket, EmptyBucket, FullBucket, FullBucketMut, RawTable, SafeHash};
 use super::table::BucketState::{Empty, Full};
+use super::table::Fallibility::{Fallible, Infallible};
+use super::table::Fallibility;
 
 const MIN_NONZERO_RAW_CAPACITY: usize = 32;     // must be a power of two
 
@@ -42,21 +46,28 @@ impl DefaultResizePolicy {
     /// provide that capacity, accounting for maximum loading. The raw capacity
     /// is always zero or a power of two.
     #[inline]
-    fn raw_capacity(&self, len: usize) -> usize {
+    fn try_raw_capacity(&self, len: usize) -> Result<usize, CollectionAllocErr> {
         if len == 0 {
-            0
+            Ok(0)
         } else {
             // 1. Account for loading: `raw_capacity >= len * 1.1`.
             // 2. Ensure it is a power of two.
             // 3. Ensure it is at least the minimum size.
-            let mut raw_cap = len * 11 / 10;
-            assert!(raw_cap >= len, "raw_cap overflow");
-            raw_cap = raw_cap.checked_next_power_of_two().expect("raw_capacity overflow");
+            let mut raw_cap = len.checked_mul(11)
+                .map(|l| l / 10)
+                .and_then(|l| l.checked_next_power_of_two())
+                .ok_or(CollectionAllocErr::CapacityOverflow)?;
+
             raw_cap = max(MIN_NONZERO_RAW_CAPACITY, raw_cap);
-            raw_cap
+            Ok(raw_cap)
         }
     }
 
+    #[inline]
+    fn raw_capacity(&self, len: usize) -> usize {
+        self.try_raw_capacity(len).expect("raw_capacity overflow")
+    }
+
     /// The capacity of the given raw capacity.
     #[inline]
     fn capacity(&self, raw_cap: usize) -> usize {
@@ -388,19 +399,20 @@ const DISPLACEMENT_THRESHOLD: usize = 128;
 
 #[derive(Clone)]
 #[stable(feature = "rust1", since = "1.0.0")]
-pub struct HashMap<K, V, S = RandomState> {
+pub struct HashMap<K, V, S = RandomState, A: Alloc = Heap> {
     // All hashes are keyed on these values, to prevent hash collision attacks.
     hash_builder: S,
 
-    table: RawTable<K, V>,
+    table: RawTable<K, V, A>,
 
     resize_policy: DefaultResizePolicy,
 }
 
 /// Search for a pre-hashed key.
 #[inline]
-fn search_hashed<K, V, M, F>(table: M, hash: SafeHash, mut is_match: F) -> InternalEntry<K, V, M>
-    where M: Deref<Target = RawTable<K, V>>,
+fn search_hashed<K, V, A, M, F>(table: M, hash: SafeHash, mut is_match: F) -> InternalEntry<K, V, M>
+    where M: Deref<Target = RawTable<K, V, A>>,
+          A: Alloc,
           F: FnMut(&K) -> bool
 {
     // This is the only function where capacity can be zero. To avoid
@@ -451,8 +463,8 @@ fn search_hashed<K, V, M, F>(table: M, hash: SafeHash, mut is_match: F) -> Inter
     }
 }
 
-fn pop_internal<K, V>(starting_bucket: FullBucketMut<K, V>)
-    -> (K, V, &mut RawTable<K, V>)
+fn pop_internal<K, V, A: Alloc>(starting_bucket: FullBucketMut<K, V, A>)
+    -> (K, V, &mut RawTable<K, V, A>)
 {
     let (empty, retkey, retval) = starting_bucket.take();
     let mut gap = match empty.gap_peek() {
@@ -477,12 +489,12 @@ fn pop_internal<K, V>(starting_bucket: FullBucketMut<K, V>)
 /// also pass that bucket's displacement so we don't have to recalculate it.
 ///
 /// `hash`, `key`, and `val` are the elements to "robin hood" into the hashtable.
-fn robin_hood<'a, K: 'a, V: 'a>(bucket: FullBucketMut<'a, K, V>,
-                                mut displacement: usize,
-                                mut hash: SafeHash,
-                                mut key: K,
-                                mut val: V)
-                                -> FullBucketMut<'a, K, V> {
+fn robin_hood<'a, K: 'a, V: 'a, A: Alloc + 'a>(bucket: FullBucketMut<'a, K, V, A>,
+                                               mut displacement: usize,
+                                               mut hash: SafeHash,
+                                               mut key: K,
+                                               mut val: V)
+                                               -> FullBucketMut<'a, K, V, A> {
     let size = bucket.table().size();
     let raw_capacity = bucket.table().capacity();
     // There can be at most `size - dib` buckets to displace, because
@@ -532,9 +544,10 @@ fn robin_hood<'a, K: 'a, V: 'a>(bucket: FullBucketMut<'a, K, V>,
     }
 }
 
-impl<K, V, S> HashMap<K, V, S>
+impl<K, V, S, A> HashMap<K, V, S, A>
     where K: Eq + Hash,
-          S: BuildHasher
+          S: BuildHasher,
+          A: Alloc
 {
     fn make_hash<X: ?Sized>(&self, x: &X) -> SafeHash
         where X: Hash
@@ -546,7 +559,7 @@ impl<K, V, S> HashMap<K, V, S>
     /// If you already have the hash for the key lying around, use
     /// search_hashed.
     #[inline]
-    fn search<'a, Q: ?Sized>(&'a self, q: &Q) -> InternalEntry<K, V, &'a RawTable<K, V>>
+    fn search<'a, Q: ?Sized>(&'a self, q: &Q) -> InternalEntry<K, V, &'a RawTable<K, V, A>>
         where K: Borrow<Q>,
               Q: Eq + Hash
     {
@@ -555,7 +568,8 @@ impl<K, V, S> HashMap<K, V, S>
     }
 
     #[inline]
-    fn search_mut<'a, Q: ?Sized>(&'a mut self, q: &Q) -> InternalEntry<K, V, &'a mut RawTable<K, V>>
+    fn search_mut<'a, Q: ?Sized>(&'a mut self, q: &Q)
+        -> InternalEntry<K, V, &'a mut RawTable<K, V, A>>
         where K: Borrow<Q>,
               Q: Eq + Hash
     {
@@ -621,6 +635,51 @@ impl<K: Hash + Eq, V> HashMap<K, V, RandomState> {
     }
 }
 
+impl<K: Hash + Eq, V, A: Alloc> HashMap<K, V, RandomState, A> {
+    /// Like `new` but parameterized over the choice of allocator for the
+    /// returned `HashMap`.
+    ///
+    /// The hash map will not allocate until it is first inserted into.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    ///
+    /// use std::collections::HashMap;
+    /// use std::heap::Heap;
+    ///
+    /// let mut map = HashMap::new_in(Heap);
+    /// map.insert("a", 1);
+    /// assert_eq!(map["a"], 1);
+    /// ```
+    #[inline]
+    #[unstable(feature = "allocator_api", issue = "32838")]
+    pub fn new_in(a: A) -> HashMap<K, V, RandomState, A> {
+        HashMap::with_hasher_in(Default::default(), a)
+    }
+
+    /// Like `with_capacity` but parameterized over the choice of allocator
+    /// for the returned `HashMap`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    ///
+    /// use std::collections::HashMap;
+    /// use std::heap::Heap;
+    ///
+    /// let map: HashMap<&str, isize, _, Heap> = HashMap::with_capacity_in(10, Heap);
+    /// assert!(map.capacity() >= 10);
+    /// ```
+    #[inline]
+    #[unstable(feature = "allocator_api", issue = "32838")]
+    pub fn with_capacity_in(capacity: usize, a: A) -> HashMap<K, V, RandomState, A> {
+        HashMap::with_capacity_and_hasher_in(capacity, Default::default(), a)
+    }
+}
+
 impl<K, V, S> HashMap<K, V, S>
     where K: Eq + Hash,
           S: BuildHasher
@@ -648,11 +707,7 @@ impl<K, V, S> HashMap<K, V, S>
     #[inline]
     #[stable(feature = "hashmap_build_hasher", since = "1.7.0")]
     pub fn with_hasher(hash_builder: S) -> HashMap<K, V, S> {
-        HashMap {
-            hash_builder,
-            resize_policy: DefaultResizePolicy::new(),
-            table: RawTable::new(0),
-        }
+        HashMap::with_hasher_in(hash_builder, Heap)
     }
 
     /// Creates an empty `HashMap` with the specified capacity, using `hash_builder`
@@ -679,15 +734,77 @@ impl<K, V, S> HashMap<K, V, S>
     #[inline]
     #[stable(feature = "hashmap_build_hasher", since = "1.7.0")]
     pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> HashMap<K, V, S> {
+        HashMap::with_capacity_and_hasher_in(capacity, hash_builder, Heap)
+    }
+}
+
+impl<K, V, S, A> HashMap<K, V, S, A>
+    where K: Eq + Hash,
+          S: BuildHasher,
+          A: Alloc
+{
+    /// Like `with_hasher` but parameterized over the choice of allocator for
+    /// the returned `HashMap`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    ///
+    /// use std::collections::HashMap;
+    /// use std::collections::hash_map::RandomState;
+    /// use std::heap::Heap;
+    ///
+    /// let s = RandomState::new();
+    /// let mut map = HashMap::with_hasher_in(s, Heap);
+    /// map.insert(1, 2);
+    /// ```
+    #[inline]
+    #[unstable(feature = "allocator_api", issue = "32838")]
+    pub fn with_hasher_in(hash_builder: S, a: A) -> HashMap<K, V, S, A> {
+        HashMap {
+            hash_builder,
+            resize_policy: DefaultResizePolicy::new(),
+            table: RawTable::new_in(0, a),
+        }
+    }
+
+    /// Like `with_capacity_and_hasher` but parameterized over the choice of
+    /// allocator for the returned `HashMap`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    ///
+    /// use std::collections::HashMap;
+    /// use std::collections::hash_map::RandomState;
+    /// use std::heap::Heap;
+    ///
+    /// let s = RandomState::new();
+    /// let mut map = HashMap::with_capacity_and_hasher_in(10, s, Heap);
+    /// map.insert(1, 2);
+    /// ```
+    #[inline]
+    #[unstable(feature = "allocator_api", issue = "32838")]
+    pub fn with_capacity_and_hasher_in(capacity: usize, hash_builder: S, a: A)
+                                       -> HashMap<K, V, S, A> {
         let resize_policy = DefaultResizePolicy::new();
         let raw_cap = resize_policy.raw_capacity(capacity);
         HashMap {
             hash_builder,
             resize_policy,
-            table: RawTable::new(raw_cap),
+            table: RawTable::new_in(raw_cap, a),
         }
     }
 
+    /// Returns a reference to the allocator backing this map.
+    #[inline]
+    #[unstable(feature = "allocator_api", issue = "32838")]
+    pub fn allocator(&self) -> &A {
+        self.table.allocator()
+    }
+
     /// Returns a reference to the map's [`BuildHasher`].
     ///
     /// [`BuildHasher`]: ../../std/hash/trait.BuildHasher.html
@@ -750,17 +867,51 @@ impl<K, V, S> HashMap<K, V, S>
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn reserve(&mut self, additional: usize) {
+        match self.reserve_internal(additional, Infallible) {
+            Err(CollectionAllocErr::CapacityOverflow) => panic!("capacity overflow"),
+            Err(CollectionAllocErr::AllocErr(_)) => unreachable!(),
+            Ok(()) => {}
+        }
+    }
+
+    /// Tries to reserve capacity for at least `additional` more elements to be inserted
+    /// in the given `HashMap<K,V>`. The collection may reserve more space to avoid
+    /// frequent reallocations.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    /// use std::collections::HashMap;
+    /// let mut map: HashMap<&str, isize> = HashMap::new();
+    /// map.try_reserve(10).expect("why is the test harness OOMing on 10 bytes?");
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue = "0")]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), CollectionAllocErr> {
+        self.reserve_internal(additional, Fallible)
+    }
+
+    fn reserve_internal(&mut self, additional: usize, fallibility: Fallibility)
+        -> Result<(), CollectionAllocErr> {
+
         let remaining = self.capacity() - self.len(); // this can't overflow
         if remaining < additional {
-            let min_cap = self.len().checked_add(additional).expect("reserve overflow");
-            let raw_cap = self.resize_policy.raw_capacity(min_cap);
-            self.resize(raw_cap);
+            let min_cap = self.len().checked_add(additional)
+                .ok_or(CollectionAllocErr::CapacityOverflow)?;
+            let raw_cap = self.resize_policy.try_raw_capacity(min_cap)?;
+            self.try_resize(raw_cap, fallibility)?;
         } else if self.table.tag() && remaining <= self.len() {
             // Probe sequence is too long and table is half full,
             // resize early to reduce probing length.
             let new_capacity = self.table.capacity() * 2;
-            self.resize(new_capacity);
+            self.try_resize(new_capacity, fallibility)?;
         }
+        Ok(())
     }
 
     /// Resizes the internal vectors to a new capacity. It's your
@@ -770,49 +921,58 @@ impl<K, V, S> HashMap<K, V, S>
     ///   2) Ensure `new_raw_cap` is a power of two or zero.
     #[inline(never)]
     #[cold]
-    fn resize(&mut self, new_raw_cap: usize) {
+    fn try_resize(
+        &mut self,
+        new_raw_cap: usize,
+        fallibility: Fallibility,
+    ) -> Result<(), CollectionAllocErr> {
         assert!(self.table.size() <= new_raw_cap);
         assert!(new_raw_cap.is_power_of_two() || new_raw_cap == 0);
 
-        let mut old_table = replace(&mut self.table, RawTable::new(new_raw_cap));
+        let mut old_table = self.table.replace_buckets(new_raw_cap, fallibility)?;
         let old_size = old_table.size();
 
         if old_table.size() == 0 {
-            return;
+            self.table.free_buckets(old_table);
+            return Ok(());
         }
 
-        let mut bucket = Bucket::head_bucket(&mut old_table);
-
-        // This is how the buckets might be laid out in memory:
-        // ($ marks an initialized bucket)
-        //  ________________
-        // |$$$_$$$$$$_$$$$$|
-        //
-        // But we've skipped the entire initial cluster of buckets
-        // and will continue iteration in this order:
-        //  ________________
-        //     |$$$$$$_$$$$$
-        //                  ^ wrap around once end is reached
-        //  ________________
-        //  $$$_____________|
-        //    ^ exit once table.size == 0
-        loop {
-            bucket = match bucket.peek() {
-                Full(bucket) => {
-                    let h = bucket.hash();
-                    let (b, k, v) = bucket.take();
-                    self.insert_hashed_ordered(h, k, v);
-                    if b.table().size() == 0 {
-                        break;
+        {
+            let mut bucket = Bucket::head_bucket(&mut old_table);
+
+            // This is how the buckets might be laid out in memory:
+            // ($ marks an initialized bucket)
+            //  ________________
+            // |$$$_$$$$$$_$$$$$|
+            //
+            // But we've skipped the entire initial cluster of buckets
+            // and will continue iteration in this order:
+            //  ________________
+            //     |$$$$$$_$$$$$
+            //                  ^ wrap around once end is reached
+            //  ________________
+            //  $$$_____________|
+            //    ^ exit once table.size == 0
+            loop {
+                bucket = match bucket.peek() {
+                    Full(bucket) => {
+                        let h = bucket.hash();
+                        let (b, k, v) = bucket.take();
+                        self.insert_hashed_ordered(h, k, v);
+                        if b.table().size() == 0 {
+                            break;
+                        }
+                        b.into_bucket()
                     }
-                    b.into_bucket()
-                }
-                Empty(b) => b.into_bucket(),
-            };
-            bucket.next();
+                    Empty(b) => b.into_bucket(),
+                };
+                bucket.next();
+            }
         }
 
         assert_eq!(self.table.size(), old_size);
+        self.table.free_buckets(old_table);
+        Ok(())
     }
 
     /// Shrinks the capacity of the map as much as possible. It will drop
@@ -835,15 +995,20 @@ impl<K, V, S> HashMap<K, V, S>
     pub fn shrink_to_fit(&mut self) {
         let new_raw_cap = self.resize_policy.raw_capacity(self.len());
         if self.raw_capacity() != new_raw_cap {
-            let old_table = replace(&mut self.table, RawTable::new(new_raw_cap));
+            let mut old_table = match self.table.replace_buckets(new_raw_cap, Infallible) {
+                Err(CollectionAllocErr::CapacityOverflow) => panic!("capacity overflow"),
+                Err(CollectionAllocErr::AllocErr(_)) => unreachable!(),
+                Ok(table) => table,
+            };
             let old_size = old_table.size();
 
             // Shrink the table. Naive algorithm for resizing:
-            for (h, k, v) in old_table.into_iter() {
+            for (h, k, v) in old_table.drain() {
                 self.insert_hashed_nocheck(h, k, v);
             }
 
             debug_assert_eq!(self.table.size(), old_size);
+            self.table.free_buckets(old_table);
         }
     }
 
@@ -1006,7 +1171,7 @@ impl<K, V, S> HashMap<K, V, S>
     /// assert_eq!(letters.get(&'y'), None);
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
-    pub fn entry(&mut self, key: K) -> Entry<K, V> {
+    pub fn entry(&mut self, key: K) -> Entry<K, V, A> {
         // Gotta resize now.
         self.reserve(1);
         let hash = self.make_hash(&key);
@@ -1014,6 +1179,104 @@ impl<K, V, S> HashMap<K, V, S>
             .into_entry(key).expect("unreachable")
     }
 
+    /// Creates a raw entry builder for the `HashMap`.
+    ///
+    /// Raw entries provide the lowest level of control for searching and
+    /// manipulating a map. They must be manually initialized with a hash and
+    /// then manually searched. After this, insertions into a vacant entry
+    /// still require an owned key to be provided.
+    ///
+    /// Raw entries are useful for such exotic situations as:
+    ///
+    /// * Hash memoization
+    /// * Deferring the creation of an owned key until it is known to be required
+    /// * Using a search key that doesn't work with the `Borrow` trait
+    /// * Using custom comparison logic without newtype wrappers
+    ///
+    /// Because raw entries provide much more low-level control, it's much easier
+    /// to put the `HashMap` into an inconsistent state which, while memory-safe,
+    /// will cause the map to produce seemingly random results. Higher-level and
+    /// more foolproof APIs like `entry` should be preferred when possible.
+    ///
+    /// In particular, the hash used to initialize the raw entry must still be
+    /// consistent with the hash of the key that is ultimately stored in the entry.
+    /// This is because implementations of `HashMap` may need to recompute hashes
+    /// when resizing, at which point only the keys are available.
+    ///
+    /// Raw entries give mutable access to the keys. This must not be used
+    /// to modify how the key would compare or hash, as the map will not re-evaluate
+    /// where the key should go, meaning the keys may become "lost" if their
+    /// location does not reflect their state. For instance, if you change a key
+    /// so that the map now contains keys which compare equal, search may start
+    /// acting erratically, with two keys randomly masking each other.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(hash_raw_entry)]
+    /// use std::collections::HashMap;
+    /// use std::collections::hash_map::RawEntryMut;
+    ///
+    /// let mut map: HashMap<String, u32> = HashMap::new();
+    ///
+    /// // Only allocate an owned key when the string is not in the map yet.
+    /// for word in "the cat sat on the mat".split(' ') {
+    ///     match map.raw_entry_mut().from_key(word) {
+    ///         RawEntryMut::Occupied(mut o) => *o.get_mut() += 1,
+    ///         RawEntryMut::Vacant(v) => { v.insert(word.to_string(), 1); }
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(map["the"], 2);
+    /// assert_eq!(map["mat"], 1);
+    /// ```
+    #[inline]
+    #[unstable(feature = "hash_raw_entry", issue = "0")]
+    pub fn raw_entry_mut(&mut self) -> RawEntryBuilderMut<K, V, S, A> {
+        // Gotta resize now, as a vacant entry must be able to insert.
+        self.reserve(1);
+        RawEntryBuilderMut { map: self }
+    }
+
+    /// Creates a raw immutable entry builder for the `HashMap`.
+    ///
+    /// Raw entries provide the lowest level of control for searching and
+    /// manipulating a map. They must be manually initialized with a hash and
+    /// then manually searched.
+    ///
+    /// This is useful for
+    /// * Hash memoization
+    /// * Using a search key that doesn't work with the `Borrow` trait
+    /// * Using custom comparison logic without newtype wrappers
+    ///
+    /// Unless you are in such a situation, higher-level and more foolproof APIs like
+    /// `get` should be preferred.
+    ///
+    /// Immutable raw entries have very limited use; you might instead want `raw_entry_mut`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(hash_raw_entry)]
+    /// use std::collections::HashMap;
+    /// use std::hash::{BuildHasher, Hash, Hasher};
+    ///
+    /// let mut map = HashMap::new();
+    /// map.insert("a", 100);
+    ///
+    /// let mut hasher = map.hasher().build_hasher();
+    /// "a".hash(&mut hasher);
+    /// let hash = hasher.finish();
+    ///
+    /// assert_eq!(map.raw_entry().from_hash(hash, |k| *k == "a"), Some((&"a", &100)));
+    /// assert_eq!(map.raw_entry().from_hash(hash, |k| *k == "b"), None);
+    /// ```
+    #[inline]
+    #[unstable(feature = "hash_raw_entry", issue = "0")]
+    pub fn raw_entry(&self) -> RawEntryBuilder<K, V, S, A> {
+        RawEntryBuilder { map: self }
+    }
+
     /// Returns the number of elements in the map.
     ///
     /// # Examples
@@ -1070,7 +1333,7 @@ impl<K, V, S> HashMap<K, V, S>
     /// ```
     #[inline]
     #[stable(feature = "drain", since = "1.6.0")]
-    pub fn drain(&mut self) -> Drain<K, V> {
+    pub fn drain(&mut self) -> Drain<K, V, A> {
         Drain { inner: self.table.drain() }
     }
 
@@ -1332,12 +1595,13 @@ impl<K, V, S> HashMap<K, V, S>
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<K, V, S> PartialEq for HashMap<K, V, S>
+impl<K, V, S, A> PartialEq for HashMap<K, V, S, A>
     where K: Eq + Hash,
           V: PartialEq,
-          S: BuildHasher
+          S: BuildHasher,
+          A: Alloc
 {
-    fn eq(&self, other: &HashMap<K, V, S>) -> bool {
+    fn eq(&self, other: &HashMap<K, V, S, A>) -> bool {
         if self.len() != other.len() {
             return false;
         }
@@ -1347,18 +1611,20 @@ impl<K, V, S> PartialEq for HashMap<K, V, S>
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<K, V, S> Eq for HashMap<K, V, S>
+impl<K, V, S, A> Eq for HashMap<K, V, S, A>
     where K: Eq + Hash,
           V: Eq,
-          S: BuildHasher
+          S: BuildHasher,
+          A: Alloc
 {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<K, V, S> Debug for HashMap<K, V, S>
+impl<K, V, S, A> Debug for HashMap<K, V, S, A>
     where K: Eq + Hash + Debug,
           V: Debug,
-          S: BuildHasher
+          S: BuildHasher,
+          A: Alloc
 {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_map().entries(self.iter()).finish()
@@ -1377,10 +1643,11 @@ impl<K, V, S> Default for HashMap<K, V, S>
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<'a, K, Q: ?Sized, V, S> Index<&'a Q> for HashMap<K, V, S>
+impl<'a, K, Q: ?Sized, V, S, A> Index<&'a Q> for HashMap<K, V, S, A>
     where K: Eq + Hash + Borrow<Q>,
           Q: Eq + Hash,
-          S: BuildHasher
+          S: BuildHasher,
+          A: Alloc
 {
     type Output = V;
 
@@ -1439,8 +1706,8 @@ pub struct IterMut<'a, K: 'a, V: 'a> {
 /// [`into_iter`]: struct.HashMap.html#method.into_iter
 /// [`HashMap`]: struct.HashMap.html
 #[stable(feature = "rust1", since = "1.0.0")]
-pub struct IntoIter<K, V> {
-    pub(super) inner: table::IntoIter<K, V>,
+pub struct IntoIter<K, V, A: Alloc = Heap> {
+    pub(super) inner: table::IntoIter<K, V, A>,
 }
 
 /// An iterator over the keys of a `HashMap`.
@@ -1509,8 +1776,8 @@ impl<'a, K, V: Debug> fmt::Debug for Values<'a, K, V> {
 /// [`drain`]: struct.HashMap.html#method.drain
 /// [`HashMap`]: struct.HashMap.html
 #[stable(feature = "drain", since = "1.6.0")]
-pub struct Drain<'a, K: 'a, V: 'a> {
-    pub(super) inner: table::Drain<'a, K, V>,
+pub struct Drain<'a, K: 'a, V: 'a, A: Alloc + 'a = Heap> {
+    pub(super) inner: table::Drain<'a, K, V, A>,
 }
 
 /// A mutable iterator over the values of a `HashMap`.
@@ -1544,9 +1811,9 @@ impl<K, V, M> InternalEntry<K, V, M> {
     }
 }
 
-impl<'a, K, V> InternalEntry<K, V, &'a mut RawTable<K, V>> {
+impl<'a, K, V, A: Alloc> InternalEntry<K, V, &'a mut RawTable<K, V, A>> {
     #[inline]
-    fn into_entry(self, key: K) -> Option<Entry<'a, K, V>> {
+    fn into_entry(self, key: K) -> Option<Entry<'a, K, V, A>> {
         match self {
             InternalEntry::Occupied { elem } => {
                 Some(Occupied(OccupiedEntry {
@@ -1573,20 +1840,20 @@ impl<'a, K, V> InternalEntry<K, V, &'a mut RawTable<K, V>> {
 /// [`HashMap`]: struct.HashMap.html
 /// [`entry`]: struct.HashMap.html#method.entry
 #[stable(feature = "rust1", since = "1.0.0")]
-pub enum Entry<'a, K: 'a, V: 'a> {
+pub enum Entry<'a, K: 'a, V: 'a, A: Alloc + 'a = Heap> {
     /// An occupied entry.
     #[stable(feature = "rust1", since = "1.0.0")]
     Occupied(#[stable(feature = "rust1", since = "1.0.0")]
-             OccupiedEntry<'a, K, V>),
+             OccupiedEntry<'a, K, V, A>),
 
     /// A vacant entry.
     #[stable(feature = "rust1", since = "1.0.0")]
     Vacant(#[stable(feature = "rust1", since = "1.0.0")]
-           VacantEntry<'a, K, V>),
+           VacantEntry<'a, K, V, A>),
 }
 
 #[stable(feature= "debug_hash_map", since = "1.12.0")]
-impl<'a, K: 'a + Debug, V: 'a + Debug> Debug for Entry<'a, K, V> {
+impl<'a, K: 'a + Debug, V: 'a + Debug, A: Alloc> Debug for Entry<'a, K, V, A> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             Vacant(ref v) => {
@@ -1608,13 +1875,13 @@ impl<'a, K: 'a + Debug, V: 'a + Debug> Debug for Entry<'a, K, V> {
 ///
 /// [`Entry`]: enum.Entry.html
 #[stable(feature = "rust1", since = "1.0.0")]
-pub struct OccupiedEntry<'a, K: 'a, V: 'a> {
+pub struct OccupiedEntry<'a, K: 'a, V: 'a, A: Alloc + 'a = Heap> {
     key: Option<K>,
-    elem: FullBucket<K, V, &'a mut RawTable<K, V>>,
+    elem: FullBucket<K, V, &'a mut RawTable<K, V, A>>,
 }
 
 #[stable(feature= "debug_hash_map", since = "1.12.0")]
-impl<'a, K: 'a + Debug, V: 'a + Debug> Debug for OccupiedEntry<'a, K, V> {
+impl<'a, K: 'a + Debug, V: 'a + Debug, A: Alloc> Debug for OccupiedEntry<'a, K, V, A> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("OccupiedEntry")
             .field("key", self.key())
@@ -1628,14 +1895,14 @@ impl<'a, K: 'a + Debug, V: 'a + Debug> Debug for OccupiedEntry<'a, K, V> {
 ///
 /// [`Entry`]: enum.Entry.html
 #[stable(feature = "rust1", since = "1.0.0")]
-pub struct VacantEntry<'a, K: 'a, V: 'a> {
+pub struct VacantEntry<'a, K: 'a, V: 'a, A: Alloc + 'a = Heap> {
     hash: SafeHash,
     key: K,
-    elem: VacantEntryState<K, V, &'a mut RawTable<K, V>>,
+    elem: VacantEntryState<K, V, &'a mut RawTable<K, V, A>>,
 }
 
 #[stable(feature= "debug_hash_map", since = "1.12.0")]
-impl<'a, K: 'a + Debug, V: 'a> Debug for VacantEntry<'a, K, V> {
+impl<'a, K: 'a + Debug, V: 'a, A: Alloc> Debug for VacantEntry<'a, K, V, A> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_tuple("VacantEntry")
             .field(self.key())
@@ -1652,10 +1919,120 @@ enum VacantEntryState<K, V, M> {
     NoElem(EmptyBucket<K, V, M>, usize),
 }
 
+/// A builder for computing where in a [`HashMap`] a key-value pair would be stored.
+///
+/// See the [`HashMap::raw_entry_mut`] docs for usage examples.
+///
+/// [`HashMap`]: struct.HashMap.html
+/// [`HashMap::raw_entry_mut`]: struct.HashMap.html#method.raw_entry_mut
+#[unstable(feature = "hash_raw_entry", issue = "0")]
+pub struct RawEntryBuilderMut<'a, K: 'a, V: 'a, S: 'a, A: Alloc + 'a = Heap> {
+    map: &'a mut HashMap<K, V, S, A>,
+}
+
+/// A view into a single entry in a map, which may either be vacant or occupied.
+///
+/// This is a lower-level version of [`Entry`].
+///
+/// This `enum` is constructed from the [`raw_entry_mut`] method on [`HashMap`],
+/// then calling one of the methods of that [`RawEntryBuilderMut`].
+///
+/// [`HashMap`]: struct.HashMap.html
+/// [`Entry`]: enum.Entry.html
+/// [`raw_entry_mut`]: struct.HashMap.html#method.raw_entry_mut
+/// [`RawEntryBuilderMut`]: struct.RawEntryBuilderMut.html
+#[unstable(feature = "hash_raw_entry", issue = "0")]
+pub enum RawEntryMut<'a, K: 'a, V: 'a, S: 'a, A: Alloc + 'a = Heap> {
+    /// An occupied entry.
+    Occupied(RawOccupiedEntryMut<'a, K, V, A>),
+    /// A vacant entry.
+    Vacant(RawVacantEntryMut<'a, K, V, S, A>),
+}
+
+/// A view into an occupied entry in a `HashMap`.
+/// It is part of the [`RawEntryMut`] enum.
+///
+/// [`RawEntryMut`]: enum.RawEntryMut.html
+#[unstable(feature = "hash_raw_entry", issue = "0")]
+pub struct RawOccupiedEntryMut<'a, K: 'a, V: 'a, A: Alloc + 'a = Heap> {
+    elem: FullBucket<K, V, &'a mut RawTable<K, V, A>>,
+}
+
+/// A view into a vacant entry in a `HashMap`.
+/// It is part of the [`RawEntryMut`] enum.
+///
+/// [`RawEntryMut`]: enum.RawEntryMut.html
+#[unstable(feature = "hash_raw_entry", issue = "0")]
+pub struct RawVacantEntryMut<'a, K: 'a, V: 'a, S: 'a, A: Alloc + 'a = Heap> {
+    elem: VacantEntryState<K, V, &'a mut RawTable<K, V, A>>,
+    hash_builder: &'a S,
+}
+
+/// A builder for computing where in a [`HashMap`] a key-value pair would be stored.
+///
+/// See the [`HashMap::raw_entry`] docs for usage examples.
+///
+/// [`HashMap`]: struct.HashMap.html
+/// [`HashMap::raw_entry`]: struct.HashMap.html#method.raw_entry
+#[unstable(feature = "hash_raw_entry", issue = "0")]
+pub struct RawEntryBuilder<'a, K: 'a, V: 'a, S: 'a, A: Alloc + 'a = Heap> {
+    map: &'a HashMap<K, V, S, A>,
+}
+
+#[unstable(feature = "hash_raw_entry", issue = "0")]
+impl<'a, K, V, S, A: Alloc> fmt::Debug for RawEntryBuilderMut<'a, K, V, S, A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RawEntryBuilderMut").finish()
+    }
+}
+
+#[unstable(feature = "hash_raw_entry", issue = "0")]
+impl<'a, K: Debug, V: Debug, S, A: Alloc> fmt::Debug for RawEntryMut<'a, K, V, S, A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RawEntryMut::Vacant(ref v) => {
+                f.debug_tuple("RawEntry")
+                    .field(v)
+                    .finish()
+            }
+            RawEntryMut::Occupied(ref o) => {
+                f.debug_tuple("RawEntry")
+                    .field(o)
+                    .finish()
+            }
+        }
+    }
+}
+
+#[unstable(feature = "hash_raw_entry", issue = "0")]
+impl<'a, K: Debug, V: Debug, A: Alloc> fmt::Debug for RawOccupiedEntryMut<'a, K, V, A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RawOccupiedEntryMut")
+            .field("key", self.key())
+            .field("value", self.get())
+            .finish()
+    }
+}
+
+#[unstable(feature = "hash_raw_entry", issue = "0")]
+impl<'a, K, V, S, A: Alloc> fmt::Debug for RawVacantEntryMut<'a, K, V, S, A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RawVacantEntryMut").finish()
+    }
+}
+
+#[unstable(feature = "hash_raw_entry", issue = "0")]
+impl<'a, K, V, S, A: Alloc> fmt::Debug for RawEntryBuilder<'a, K, V, S, A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RawEntryBuilder").finish()
+    }
+}
+
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<'a, K, V, S> IntoIterator for &'a HashMap<K, V, S>
+impl<'a, K, V, S, A> IntoIterator for &'a HashMap<K, V, S, A>
     where K: Eq + Hash,
-          S: BuildHasher
+          S: BuildHasher,
+          A: Alloc
 {
     type Item = (&'a K, &'a V);
     type IntoIter = Iter<'a, K, V>;
@@ -1666,9 +2043,10 @@ impl<'a, K, V, S> IntoIterator for &'a HashMap<K, V, S>
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<'a, K, V, S> IntoIterator for &'a mut HashMap<K, V, S>
+impl<'a, K, V, S, A> IntoIterator for &'a mut HashMap<K, V, S, A>
     where K: Eq + Hash,
-          S: BuildHasher
+          S: BuildHasher,
+          A: Alloc
 {
     type Item = (&'a K, &'a mut V);
     type IntoIter = IterMut<'a, K, V>;
@@ -1679,12 +2057,13 @@ impl<'a, K, V, S> IntoIterator for &'a mut HashMap<K, V, S>
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<K, V, S> IntoIterator for HashMap<K, V, S>
+impl<K, V, S, A> IntoIterator for HashMap<K, V, S, A>
     where K: Eq + Hash,
-          S: BuildHasher
+          S: BuildHasher,
+          A: Alloc
 {
     type Item = (K, V);
-    type IntoIter = IntoIter<K, V>;
+    type IntoIter = IntoIter<K, V, A>;
 
     /// Creates a consuming iterator, that is, one that moves each key-value
     /// pair out of the map in arbitrary order. The map cannot be used after
@@ -1703,7 +2082,7 @@ impl<K, V, S> IntoIterator for HashMap<K, V, S>
     /// // Not possible with .iter()
     /// let vec: Vec<(&str, isize)> = map.into_iter().collect();
     /// ```
-    fn into_iter(self) -> IntoIter<K, V> {
+    fn into_iter(self) -> IntoIter<K, V, A> {
         IntoIter { inner: self.table.into_iter() }
     }
 }
@@ -1768,7 +2147,7 @@ impl<'a, K, V> fmt::Debug for IterMut<'a, K, V>
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<K, V> Iterator for IntoIter<K, V> {
+impl<K, V, A: Alloc> Iterator for IntoIter<K, V, A> {
     type Item = (K, V);
 
     #[inline]
@@ -1781,17 +2160,17 @@ impl<K, V> Iterator for IntoIter<K, V> {
     }
 }
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<K, V> ExactSizeIterator for IntoIter<K, V> {
+impl<K, V, A: Alloc> ExactSizeIterator for IntoIter<K, V, A> {
     #[inline]
     fn len(&self) -> usize {
         self.inner.len()
     }
 }
 #[unstable(feature = "fused", issue = "35602")]
-impl<K, V> FusedIterator for IntoIter<K, V> {}
+impl<K, V, A: Alloc> FusedIterator for IntoIter<K, V, A> {}
 
 #[stable(feature = "std_debug", since = "1.16.0")]
-impl<K: Debug, V: Debug> fmt::Debug for IntoIter<K, V> {
+impl<K: Debug, V: Debug, A: Alloc> fmt::Debug for IntoIter<K, V, A> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_list()
             .entries(self.inner.iter())
@@ -1881,7 +2260,7 @@ impl<'a, K, V> fmt::Debug for ValuesMut<'a, K, V>
 }
 
 #[stable(feature = "drain", since = "1.6.0")]
-impl<'a, K, V> Iterator for Drain<'a, K, V> {
+impl<'a, K, V, A: Alloc> Iterator for Drain<'a, K, V, A> {
     type Item = (K, V);
 
     #[inline]
@@ -1894,19 +2273,20 @@ impl<'a, K, V> Iterator for Drain<'a, K, V> {
     }
 }
 #[stable(feature = "drain", since = "1.6.0")]
-impl<'a, K, V> ExactSizeIterator for Drain<'a, K, V> {
+impl<'a, K, V, A: Alloc> ExactSizeIterator for Drain<'a, K, V, A> {
     #[inline]
     fn len(&self) -> usize {
         self.inner.len()
     }
 }
 #[unstable(feature = "fused", issue = "35602")]
-impl<'a, K, V> FusedIterator for Drain<'a, K, V> {}
+impl<'a, K, V, A: Alloc> FusedIterator for Drain<'a, K, V, A> {}
 
 #[stable(feature = "std_debug", since = "1.16.0")]
-impl<'a, K, V> fmt::Debug for Drain<'a, K, V>
+impl<'a, K, V, A> fmt::Debug for Drain<'a, K, V, A>
     where K: fmt::Debug,
           V: fmt::Debug,
+          A: Alloc,
 {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_list()
@@ -1922,14 +2302,14 @@ impl<'a, K, V> fmt::Debug for Drain<'a, K, V>
 #[unstable(feature = "collection_placement",
            reason = "struct name and placement protocol is subject to change",
            issue = "30172")]
-pub struct EntryPlace<'a, K: 'a, V: 'a> {
-    bucket: FullBucketMut<'a, K, V>,
+pub struct EntryPlace<'a, K: 'a, V: 'a, A: Alloc + 'a = Heap> {
+    bucket: FullBucketMut<'a, K, V, A>,
 }
 
 #[unstable(feature = "collection_placement",
            reason = "struct name and placement protocol is subject to change",
            issue = "30172")]
-impl<'a, K: 'a + Debug, V: 'a + Debug> Debug for EntryPlace<'a, K, V> {
+impl<'a, K: 'a + Debug, V: 'a + Debug, A: Alloc> Debug for EntryPlace<'a, K, V, A> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("EntryPlace")
             .field("key", self.bucket.read().0)
@@ -1941,7 +2321,7 @@ impl<'a, K: 'a + Debug, V: 'a + Debug> Debug for EntryPlace<'a, K, V> {
 #[unstable(feature = "collection_placement",
            reason = "struct name and placement protocol is subject to change",
            issue = "30172")]
-impl<'a, K, V> Drop for EntryPlace<'a, K, V> {
+impl<'a, K, V, A: Alloc + 'a> Drop for EntryPlace<'a, K, V, A> {
     fn drop(&mut self) {
         // Inplacement insertion failed. Only key need to drop.
         // The value is failed to insert into map.
@@ -1952,10 +2332,10 @@ impl<'a, K, V> Drop for EntryPlace<'a, K, V> {
 #[unstable(feature = "collection_placement",
            reason = "placement protocol is subject to change",
            issue = "30172")]
-impl<'a, K, V> Placer<V> for Entry<'a, K, V> {
-    type Place = EntryPlace<'a, K, V>;
+impl<'a, K, V, A: Alloc> Placer<V> for Entry<'a, K, V, A> {
+    type Place = EntryPlace<'a, K, V, A>;
 
-    fn make_place(self) -> EntryPlace<'a, K, V> {
+    fn make_place(self) -> EntryPlace<'a, K, V, A> {
         let b = match self {
             Occupied(mut o) => {
                 unsafe { ptr::drop_in_place(o.elem.read_mut().1); }
@@ -1972,7 +2352,7 @@ impl<'a, K, V> Placer<V> for Entry<'a, K, V> {
 #[unstable(feature = "collection_placement",
            reason = "placement protocol is subject to change",
            issue = "30172")]
-impl<'a, K, V> Place<V> for EntryPlace<'a, K, V> {
+impl<'a, K, V, A: Alloc> Place<V> for EntryPlace<'a, K, V, A> {
     fn pointer(&mut self) -> *mut V {
         self.bucket.read_mut().1
     }
@@ -1981,7 +2361,7 @@ impl<'a, K, V> Place<V> for EntryPlace<'a, K, V> {
 #[unstable(feature = "collection_placement",
            reason = "placement protocol is subject to change",
            issue = "30172")]
-impl<'a, K, V> InPlace<V> for EntryPlace<'a, K, V> {
+impl<'a, K, V, A: Alloc> InPlace<V> for EntryPlace<'a, K, V, A> {
     type Owner = ();
 
     unsafe fn finalize(self) {
@@ -1989,7 +2369,7 @@ impl<'a, K, V> InPlace<V> for EntryPlace<'a, K, V> {
     }
 }
 
-impl<'a, K, V> Entry<'a, K, V> {
+impl<'a, K, V, A: Alloc> Entry<'a, K, V, A> {
     #[stable(feature = "rust1", since = "1.0.0")]
     /// Ensures a value is in the entry by inserting the default if empty, and returns
     /// a mutable reference to the value in the entry.
@@ -2091,7 +2471,7 @@ impl<'a, K, V> Entry<'a, K, V> {
 
 }
 
-impl<'a, K, V: Default> Entry<'a, K, V> {
+impl<'a, K, V: Default, A: Alloc> Entry<'a, K, V, A> {
     #[unstable(feature = "entry_or_default", issue = "44324")]
     /// Ensures a value is in the entry by inserting the default value if empty,
     /// and returns a mutable reference to the value in the entry.
@@ -2118,7 +2498,7 @@ impl<'a, K, V: Default> Entry<'a, K, V> {
 
 }
 
-impl<'a, K, V> OccupiedEntry<'a, K, V> {
+impl<'a, K, V, A: Alloc> OccupiedEntry<'a, K, V, A> {
     /// Gets a reference to the key in the entry.
     ///
     /// # Examples
@@ -2342,7 +2722,7 @@ impl<'a, K, V> OccupiedEntry<'a, K, V> {
     }
 }
 
-impl<'a, K: 'a, V: 'a> VacantEntry<'a, K, V> {
+impl<'a, K: 'a, V: 'a, A: Alloc> VacantEntry<'a, K, V, A> {
     /// Gets a reference to the key that would be used when inserting a value
     /// through the `VacantEntry`.
     ///
@@ -2415,7 +2795,7 @@ impl<'a, K: 'a, V: 'a> VacantEntry<'a, K, V> {
 
     // Only used for InPlacement insert. Avoid unnecessary value copy.
     // The value remains uninitialized.
-    unsafe fn insert_key(self) -> FullBucketMut<'a, K, V> {
+    unsafe fn insert_key(self) -> FullBucketMut<'a, K, V, A> {
         match self.elem {
             NeqElem(mut bucket, disp) => {
                 if disp >= DISPLACEMENT_THRESHOLD {
@@ -2434,6 +2814,327 @@ impl<'a, K: 'a, V: 'a> VacantEntry<'a, K, V> {
     }
 }
 
+impl<'a, K, V, S, A> RawEntryBuilderMut<'a, K, V, S, A>
+    where S: BuildHasher,
+          A: Alloc
+{
+    /// Creates a `RawEntryMut` from the given key.
+    #[unstable(feature = "hash_raw_entry", issue = "0")]
+    pub fn from_key<Q: ?Sized>(self, k: &Q) -> RawEntryMut<'a, K, V, S, A>
+        where K: Borrow<Q>,
+              Q: Hash + Eq
+    {
+        let hash = table::make_hash(&self.map.hash_builder, k);
+        self.search(hash, |q| k.eq(q.borrow()))
+    }
+
+    /// Creates a `RawEntryMut` from the given key and its hash.
+    #[unstable(feature = "hash_raw_entry", issue = "0")]
+    pub fn from_key_hashed_nocheck<Q: ?Sized>(self, hash: u64, k: &Q) -> RawEntryMut<'a, K, V, S, A>
+        where K: Borrow<Q>,
+              Q: Eq
+    {
+        self.from_hash(hash, |q| k.eq(q.borrow()))
+    }
+
+    /// Creates a `RawEntryMut` from the given hash, using `is_match` to tell
+    /// the key being looked for apart from others with the same hash.
+    #[unstable(feature = "hash_raw_entry", issue = "0")]
+    pub fn from_hash<F>(self, hash: u64, is_match: F) -> RawEntryMut<'a, K, V, S, A>
+        where F: FnMut(&K) -> bool
+    {
+        self.search(SafeHash::new(hash), is_match)
+    }
+
+    #[inline]
+    fn search<F>(self, hash: SafeHash, is_match: F) -> RawEntryMut<'a, K, V, S, A>
+        where F: FnMut(&K) -> bool
+    {
+        let map = self.map;
+        match search_hashed(&mut map.table, hash, is_match) {
+            InternalEntry::Occupied { elem } => {
+                RawEntryMut::Occupied(RawOccupiedEntryMut { elem })
+            }
+            InternalEntry::Vacant { elem, .. } => {
+                RawEntryMut::Vacant(RawVacantEntryMut {
+                    elem,
+                    hash_builder: &map.hash_builder,
+                })
+            }
+            // `raw_entry_mut` reserved room for one element.
+            InternalEntry::TableIsEmpty => unreachable!(),
+        }
+    }
+}
+
+impl<'a, K, V, S, A> RawEntryBuilder<'a, K, V, S, A>
+    where S: BuildHasher,
+          A: Alloc
+{
+    /// Access an entry by key.
+    #[unstable(feature = "hash_raw_entry", issue = "0")]
+    pub fn from_key<Q: ?Sized>(self, k: &Q) -> Option<(&'a K, &'a V)>
+        where K: Borrow<Q>,
+              Q: Hash + Eq
+    {
+        let hash = table::make_hash(&self.map.hash_builder, k);
+        self.search(hash, |q| k.eq(q.borrow()))
+    }
+
+    /// Access an entry by a key and its hash.
+    #[unstable(feature = "hash_raw_entry", issue = "0")]
+    pub fn from_key_hashed_nocheck<Q: ?Sized>(self, hash: u64, k: &Q) -> Option<(&'a K, &'a V)>
+        where K: Borrow<Q>,
+              Q: Eq
+    {
+        self.from_hash(hash, |q| k.eq(q.borrow()))
+    }
+
+    /// Access an entry by hash, using `is_match` to tell the key being looked
+    /// for apart from others with the same hash.
+    #[unstable(feature = "hash_raw_entry", issue = "0")]
+    pub fn from_hash<F>(self, hash: u64, is_match: F) -> Option<(&'a K, &'a V)>
+        where F: FnMut(&K) -> bool
+    {
+        self.search(SafeHash::new(hash), is_match)
+    }
+
+    #[inline]
+    fn search<F>(self, hash: SafeHash, is_match: F) -> Option<(&'a K, &'a V)>
+        where F: FnMut(&K) -> bool
+    {
+        search_hashed(&self.map.table, hash, is_match)
+            .into_occupied_bucket()
+            .map(|bucket| bucket.into_refs())
+    }
+}
+
+impl<'a, K, V, S, A: Alloc> RawEntryMut<'a, K, V, S, A> {
+    /// Ensures a value is in the entry by inserting the default if empty, and returns
+    /// mutable references to the key and value in the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(hash_raw_entry)]
+    /// use std::collections::HashMap;
+    ///
+    /// let mut map: HashMap<&str, u32> = HashMap::new();
+    ///
+    /// map.raw_entry_mut().from_key("poneyland").or_insert("poneyland", 3);
+    /// assert_eq!(map["poneyland"], 3);
+    ///
+    /// *map.raw_entry_mut().from_key("poneyland").or_insert("poneyland", 10).1 *= 2;
+    /// assert_eq!(map["poneyland"], 6);
+    /// ```
+    #[unstable(feature = "hash_raw_entry", issue = "0")]
+    pub fn or_insert(self, default_key: K, default_val: V) -> (&'a mut K, &'a mut V)
+        where K: Hash,
+              S: BuildHasher
+    {
+        match self {
+            RawEntryMut::Occupied(entry) => entry.into_key_value(),
+            RawEntryMut::Vacant(entry) => entry.insert(default_key, default_val),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the result of the default function if empty,
+    /// and returns mutable references to the key and value in the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(hash_raw_entry)]
+    /// use std::collections::HashMap;
+    ///
+    /// let mut map: HashMap<&str, String> = HashMap::new();
+    ///
+    /// map.raw_entry_mut().from_key("poneyland").or_insert_with(|| {
+    ///     ("poneyland", "hoho".to_string())
+    /// });
+    ///
+    /// assert_eq!(map["poneyland"], "hoho".to_string());
+    /// ```
+    #[unstable(feature = "hash_raw_entry", issue = "0")]
+    pub fn or_insert_with<F>(self, default: F) -> (&'a mut K, &'a mut V)
+        where F: FnOnce() -> (K, V),
+              K: Hash,
+              S: BuildHasher
+    {
+        match self {
+            RawEntryMut::Occupied(entry) => entry.into_key_value(),
+            RawEntryMut::Vacant(entry) => {
+                let (k, v) = default();
+                entry.insert(k, v)
+            }
+        }
+    }
+
+    /// Provides in-place mutable access to an occupied entry before any
+    /// potential inserts into the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(hash_raw_entry)]
+    /// use std::collections::HashMap;
+    ///
+    /// let mut map: HashMap<&str, u32> = HashMap::new();
+    ///
+    /// map.raw_entry_mut()
+    ///    .from_key("poneyland")
+    ///    .and_modify(|_k, v| { *v += 1 })
+    ///    .or_insert("poneyland", 42);
+    /// assert_eq!(map["poneyland"], 42);
+    ///
+    /// map.raw_entry_mut()
+    ///    .from_key("poneyland")
+    ///    .and_modify(|_k, v| { *v += 1 })
+    ///    .or_insert("poneyland", 0);
+    /// assert_eq!(map["poneyland"], 43);
+    /// ```
+    #[unstable(feature = "hash_raw_entry", issue = "0")]
+    pub fn and_modify<F>(self, f: F) -> Self
+        where F: FnOnce(&mut K, &mut V)
+    {
+        match self {
+            RawEntryMut::Occupied(mut entry) => {
+                {
+                    let (k, v) = entry.get_key_value_mut();
+                    f(k, v);
+                }
+                RawEntryMut::Occupied(entry)
+            },
+            RawEntryMut::Vacant(entry) => RawEntryMut::Vacant(entry),
+        }
+    }
+}
+
+impl<'a, K, V, A: Alloc> RawOccupiedEntryMut<'a, K, V, A> {
+    /// Gets a reference to the key in the entry.
+    #[unstable(feature = "hash_raw_entry", issue = "0")]
+    pub fn key(&self) -> &K {
+        self.elem.read().0
+    }
+
+    /// Gets a mutable reference to the key in the entry.
+    #[unstable(feature = "hash_raw_entry", issue = "0")]
+    pub fn key_mut(&mut self) -> &mut K {
+        self.elem.read_mut().0
+    }
+
+    /// Converts the entry into a mutable reference to the key in the entry
+    /// with a lifetime bound to the map itself.
+    #[unstable(feature = "hash_raw_entry", issue = "0")]
+    pub fn into_key(self) -> &'a mut K {
+        self.elem.into_mut_refs().0
+    }
+
+    /// Gets a reference to the value in the entry.
+    #[unstable(feature = "hash_raw_entry", issue = "0")]
+    pub fn get(&self) -> &V {
+        self.elem.read().1
+    }
+
+    /// Converts the OccupiedEntry into a mutable reference to the value in the entry
+    /// with a lifetime bound to the map itself.
+    #[unstable(feature = "hash_raw_entry", issue = "0")]
+    pub fn into_mut(self) -> &'a mut V {
+        self.elem.into_mut_refs().1
+    }
+
+    /// Gets a mutable reference to the value in the entry.
+    #[unstable(feature = "hash_raw_entry", issue = "0")]
+    pub fn get_mut(&mut self) -> &mut V {
+        self.elem.read_mut().1
+    }
+
+    /// Gets a reference to the key and value in the entry.
+    #[unstable(feature = "hash_raw_entry", issue = "0")]
+    pub fn get_key_value(&self) -> (&K, &V) {
+        self.elem.read()
+    }
+
+    /// Gets a mutable reference to the key and value in the entry.
+    #[unstable(feature = "hash_raw_entry", issue = "0")]
+    pub fn get_key_value_mut(&mut self) -> (&mut K, &mut V) {
+        self.elem.read_mut()
+    }
+
+    /// Converts the OccupiedEntry into a mutable reference to the key and value in the entry
+    /// with a lifetime bound to the map itself.
+    #[unstable(feature = "hash_raw_entry", issue = "0")]
+    pub fn into_key_value(self) -> (&'a mut K, &'a mut V) {
+        self.elem.into_mut_refs()
+    }
+
+    /// Sets the value of the entry, and returns the entry's old value.
+    #[unstable(feature = "hash_raw_entry", issue = "0")]
+    pub fn insert(&mut self, value: V) -> V {
+        mem::replace(self.get_mut(), value)
+    }
+
+    /// Sets the key of the entry, and returns the entry's old key.
+    #[unstable(feature = "hash_raw_entry", issue = "0")]
+    pub fn insert_key(&mut self, key: K) -> K {
+        mem::replace(self.key_mut(), key)
+    }
+
+    /// Takes the value out of the entry, and returns it.
+    #[unstable(feature = "hash_raw_entry", issue = "0")]
+    pub fn remove(self) -> V {
+        pop_internal(self.elem).1
+    }
+
+    /// Take the ownership of the key and value from the map.
+    #[unstable(feature = "hash_raw_entry", issue = "0")]
+    pub fn remove_entry(self) -> (K, V) {
+        let (k, v, _) = pop_internal(self.elem);
+        (k, v)
+    }
+}
+
+impl<'a, K, V, S, A: Alloc> RawVacantEntryMut<'a, K, V, S, A> {
+    /// Sets the value of the entry with the VacantEntry's key,
+    /// and returns a mutable reference to it.
+    #[unstable(feature = "hash_raw_entry", issue = "0")]
+    pub fn insert(self, key: K, value: V) -> (&'a mut K, &'a mut V)
+        where K: Hash,
+              S: BuildHasher
+    {
+        let hash = table::make_hash(self.hash_builder, &key);
+        self.insert_hashed(hash, key, value)
+    }
+
+    /// Sets the value of the entry with the VacantEntry's key,
+    /// and returns a mutable reference to it.
+    ///
+    /// The caller must make sure `hash` is the hash of `key` under the map's
+    /// hasher, and the one the entry was looked up with.
+    #[unstable(feature = "hash_raw_entry", issue = "0")]
+    pub fn insert_hashed_nocheck(self, hash: u64, key: K, value: V) -> (&'a mut K, &'a mut V) {
+        self.insert_hashed(SafeHash::new(hash), key, value)
+    }
+
+    fn insert_hashed(self, hash: SafeHash, key: K, value: V) -> (&'a mut K, &'a mut V) {
+        let b = match self.elem {
+            NeqElem(mut bucket, disp) => {
+                if disp >= DISPLACEMENT_THRESHOLD {
+                    bucket.table_mut().set_tag(true);
+                }
+                robin_hood(bucket, disp, hash, key, value)
+            },
+            NoElem(mut bucket, disp) => {
+                if disp >= DISPLACEMENT_THRESHOLD {
+                    bucket.table_mut().set_tag(true);
+                }
+                bucket.put(hash, key, value)
+            },
+        };
+        b.into_mut_refs()
+    }
+}
+
 #[stable(feature = "rust1", since = "1.0.0")]
 impl<K, V, S> FromIterator<(K, V)> for HashMap<K, V, S>
     where K: Eq + Hash,
@@ -2447,9 +3148,10 @@ impl<K, V, S> FromIterator<(K, V)> for HashMap<K, V, S>
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<K, V, S> Extend<(K, V)> for HashMap<K, V, S>
+impl<K, V, S, A> Extend<(K, V)> for HashMap<K, V, S, A>
     where K: Eq + Hash,
-          S: BuildHasher
+          S: BuildHasher,
+          A: Alloc
 {
     fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
         // Keys may be already present or show multiple times in the iterator.
@@ -2470,10 +3172,11 @@ impl<K, V, S> Extend<(K, V)> for HashMap<K, V, S>
 }
 
 #[stable(feature = "hash_extend_copy", since = "1.4.0")]
-impl<'a, K, V, S> Extend<(&'a K, &'a V)> for HashMap<K, V, S>
+impl<'a, K, V, S, A> Extend<(&'a K, &'a V)> for HashMap<K, V, S, A>
     where K: Eq + Hash + Copy,
           V: Copy,
-          S: BuildHasher
+          S: BuildHasher,
+          A: Alloc
 {
     fn extend<T: IntoIterator<Item = (&'a K, &'a V)>>(&mut self, iter: T) {
         self.extend(iter.into_iter().map(|(&key, &value)| (key, value)));
@@ -2698,9 +3401,11 @@ mod test_map {
     use super::HashMap;
     use super::Entry::{Occupied, Vacant};
     use super::RandomState;
+    use super::CollectionAllocErr;
     use cell::RefCell;
     use rand::{thread_rng, Rng};
     use panic;
+    use usize;
 
     #[test]
     fn test_zero_capacities() {
@@ -3266,6 +3971,106 @@ mod test_map {
         assert_eq!(m.raw_capacity(), initial_raw_cap);
     }
 
+    #[test]
+    fn test_raw_entry() {
+        use super::RawEntryMut::{Occupied, Vacant};
+
+        let xs = [(1i32, 10i32), (2, 20), (3, 30), (4, 40), (5, 50), (6, 60)];
+
+        let mut map: HashMap<_, _> = xs.iter().cloned().collect();
+
+        let compute_hash = |map: &HashMap<i32, i32>, k: i32| -> u64 {
+            use hash::{BuildHasher, Hash, Hasher};
+
+            let mut hasher = map.hasher().build_hasher();
+            k.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        // Existing key (insert)
+        match map.raw_entry_mut().from_key(&1) {
+            Vacant(_) => unreachable!(),
+            Occupied(mut view) => {
+                assert_eq!(view.get(), &10);
+                assert_eq!(view.insert(100), 10);
+            }
+        }
+        let hash1 = compute_hash(&map, 1);
+        assert_eq!(map.raw_entry().from_key(&1).unwrap(), (&1, &100));
+        assert_eq!(map.raw_entry().from_hash(hash1, |k| *k == 1).unwrap(), (&1, &100));
+        assert_eq!(map.raw_entry().from_key_hashed_nocheck(hash1, &1).unwrap(), (&1, &100));
+        assert_eq!(map.len(), 6);
+
+        // Existing key (update)
+        match map.raw_entry_mut().from_key(&2) {
+            Vacant(_) => unreachable!(),
+            Occupied(mut view) => {
+                let v = view.get_mut();
+                let new_v = (*v) * 10;
+                *v = new_v;
+            }
+        }
+        let hash2 = compute_hash(&map, 2);
+        assert_eq!(map.raw_entry().from_key(&2).unwrap(), (&2, &200));
+        assert_eq!(map.raw_entry().from_hash(hash2, |k| *k == 2).unwrap(), (&2, &200));
+        assert_eq!(map.len(), 6);
+
+        // Existing key (take)
+        let hash3 = compute_hash(&map, 3);
+        match map.raw_entry_mut().from_key_hashed_nocheck(hash3, &3) {
+            Vacant(_) => unreachable!(),
+            Occupied(view) => {
+                assert_eq!(view.remove_entry(), (3, 30));
+            }
+        }
+        assert_eq!(map.raw_entry().from_key(&3), None);
+        assert_eq!(map.raw_entry().from_hash(hash3, |k| *k == 3), None);
+        assert_eq!(map.len(), 5);
+
+        // Nonexistent key (insert)
+        let hash10 = compute_hash(&map, 10);
+        match map.raw_entry_mut().from_hash(hash10, |k| *k == 10) {
+            Occupied(_) => unreachable!(),
+            Vacant(view) => {
+                assert_eq!(view.insert_hashed_nocheck(hash10, 10, 1000), (&mut 10, &mut 1000));
+            }
+        }
+        assert_eq!(map.raw_entry().from_key(&10).unwrap(), (&10, &1000));
+        assert_eq!(map.len(), 6);
+
+        // Ensure all lookup methods produce equivalent results.
+        for k in 0..12 {
+            let hash = compute_hash(&map, k);
+            let v = map.get(&k).cloned();
+            let kv = v.as_ref().map(|v| (&k, v));
+
+            assert_eq!(map.raw_entry().from_key(&k), kv);
+            assert_eq!(map.raw_entry().from_hash(hash, |q| *q == k), kv);
+            assert_eq!(map.raw_entry().from_key_hashed_nocheck(hash, &k), kv);
+
+            match map.raw_entry_mut().from_key(&k) {
+                Occupied(o) => assert_eq!(Some(o.get_key_value()), kv),
+                Vacant(_) => assert_eq!(v, None),
+            }
+        }
+    }
+
+    #[test]
+    fn test_try_reserve() {
+        let mut empty_bytes: HashMap<u8, u8> = HashMap::new();
+
+        assert_eq!(empty_bytes.try_reserve(usize::MAX),
+                   Err(CollectionAllocErr::CapacityOverflow));
+
+        empty_bytes.insert(1, 1);
+        assert_eq!(empty_bytes.try_reserve(usize::MAX),
+                   Err(CollectionAllocErr::CapacityOverflow));
+        assert_eq!(empty_bytes.len(), 1);
+
+        assert_eq!(empty_bytes.try_reserve(16), Ok(()));
+        assert!(empty_bytes.capacity() >= 17);
+    }
+
     #[test]
     fn test_reserve_shrink_to_fit() {
         let mut m = HashMap::new();
@@ -3635,4 +4440,64 @@ mod test_map {
         let _ = panic::catch_unwind(panic::AssertUnwindSafe(|| { hm.entry(0) <- makepanic(); }));
         assert_eq!(hm.len(), 0);
     }
+
+    #[test]
+    fn test_alloc_param() {
+        use cell::Cell;
+        use heap::{Alloc, AllocErr, Heap, Layout};
+        use rc::Rc;
+
+        // Forwards to `Heap`, keeping track of the number of live allocations.
+        #[derive(Clone)]
+        struct CountingAlloc {
+            live: Rc<Cell<usize>>,
+        }
+
+        unsafe impl Alloc for CountingAlloc {
+            unsafe fn alloc(&mut self, layout: Layout) -> Result<*mut u8, AllocErr> {
+                let ptr = Heap.alloc(layout)?;
+                self.live.set(self.live.get() + 1);
+                Ok(ptr)
+            }
+
+            unsafe fn dealloc(&mut self, ptr: *mut u8, layout: Layout) {
+                self.live.set(self.live.get() - 1);
+                Heap.dealloc(ptr, layout)
+            }
+        }
+
+        let live = Rc::new(Cell::new(0));
+        let a = CountingAlloc { live: live.clone() };
+        {
+            let mut map = HashMap::new_in(a);
+            assert_eq!(live.get(), 0);
+
+            // Grows the table several times, freeing the old buckets each time.
+            for i in 0..100 {
+                map.insert(i, i * 10);
+            }
+            assert_eq!(live.get(), 1);
+            assert_eq!(map[&42], 420);
+
+            let copy = map.clone();
+            assert_eq!(live.get(), 2);
+            assert_eq!(copy, map);
+
+            map.retain(|&k, _| k < 10);
+            map.shrink_to_fit();
+            assert_eq!(live.get(), 2);
+            assert_eq!(map.len(), 10);
+            assert_eq!(map[&9], 90);
+
+            let mut iter = copy.into_iter();
+            assert!(iter.next().is_some());
+            drop(iter);
+            assert_eq!(live.get(), 1);
+
+            map.drain();
+            assert!(map.is_empty());
+            assert_eq!(live.get(), 1);
+        }
+        assert_eq!(live.get(), 0);
+    }
 }
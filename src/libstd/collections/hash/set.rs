@@ -8,6 +8,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use alloc::allocator::CollectionAllocErr;
 use borrow::Borrow;
 use fmt;
 use hash::{Hash, BuildHasher};
@@ -271,6 +272,28 @@ impl<T, S> HashSet<T, S>
         self.map.reserve(additional)
     }
 
+    /// Tries to reserve capacity for at least `additional` more elements to be inserted
+    /// in the given `HashSet<T>`. The collection may reserve more space to avoid
+    /// frequent reallocations.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    /// use std::collections::HashSet;
+    /// let mut set: HashSet<i32> = HashSet::new();
+    /// set.try_reserve(10).expect("why is the test harness OOMing on 10 bytes?");
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue = "0")]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), CollectionAllocErr> {
+        self.map.try_reserve(additional)
+    }
+
     /// Shrinks the capacity of the set as much as possible. It will drop
     /// down as much as possible while maintaining the internal rules
     /// and possibly leaving some space in accordance with the resize policy.
@@ -1374,7 +1397,9 @@ fn assert_covariance() {
 #[cfg(test)]
 mod test_set {
     use super::HashSet;
+    use super::CollectionAllocErr;
     use super::super::map::RandomState;
+    use usize;
 
     #[test]
     fn test_zero_capacities() {
@@ -1752,4 +1777,17 @@ mod test_set {
         assert!(set.contains(&4));
         assert!(set.contains(&6));
     }
+
+    #[test]
+    fn test_try_reserve() {
+        let mut set: HashSet<u8> = HashSet::new();
+        assert_eq!(set.try_reserve(usize::MAX), Err(CollectionAllocErr::CapacityOverflow));
+
+        set.insert(1);
+        assert_eq!(set.try_reserve(usize::MAX), Err(CollectionAllocErr::CapacityOverflow));
+        assert_eq!(set.len(), 1);
+
+        assert_eq!(set.try_reserve(16), Ok(()));
+        assert!(set.capacity() >= 17);
+    }
 }
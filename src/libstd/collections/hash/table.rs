@@ -8,7 +8,8 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use alloc::heap::{Heap, Alloc, Layout};
+use alloc::heap::{Heap, Alloc, AllocErr, Layout};
+use alloc::allocator::CollectionAllocErr;
 
 use cmp;
 use hash::{BuildHasher, Hash, Hasher};
@@ -20,6 +21,16 @@ use ptr::{self, Unique, NonNull};
 
 use self::BucketState::*;
 
+/// Whether an allocation failure should be reported to the caller, or abort
+/// the process through `oom`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Fallibility {
+    Fallible,
+    Infallible,
+}
+
+use self::Fallibility::*;
+
 /// Integer type used for stored hash values.
 ///
 /// No more than bit_width(usize) bits are needed to select a bucket.
@@ -113,7 +124,7 @@ impl TaggedHashUintPtr {
 /// The hashtable also exposes a special boolean tag. The tag defaults to false
 /// when the RawTable is created and is accessible with the `tag` and `set_tag`
 /// functions.
-pub struct RawTable<K, V> {
+pub struct RawTable<K, V, A: Alloc = Heap> {
     capacity_mask: usize,
     size: usize,
     hashes: TaggedHashUintPtr,
@@ -121,6 +132,23 @@ pub struct RawTable<K, V> {
     // Because K/V do not appear directly in any of the types in the struct,
     // inform rustc that in fact instances of K and V are reachable from here.
     marker: marker::PhantomData<(K, V)>,
+
+    alloc: A,
+}
+
+/// The allocator of the buckets returned by `RawTable::replace_buckets`.
+///
+/// Those buckets are released through the table they were taken from, with
+/// `RawTable::free_buckets`. If that doesn't happen because of a panic, they
+/// are leaked.
+pub struct Detached;
+
+unsafe impl Alloc for Detached {
+    unsafe fn alloc(&mut self, _: Layout) -> Result<*mut u8, AllocErr> {
+        Err(AllocErr::invalid_input("detached buckets can't allocate"))
+    }
+
+    unsafe fn dealloc(&mut self, _: *mut u8, _: Layout) {}
 }
 
 // An unsafe view of a RawTable bucket
@@ -162,7 +190,7 @@ pub struct FullBucket<K, V, M> {
     table: M,
 }
 
-pub type FullBucketMut<'table, K, V> = FullBucket<K, V, &'table mut RawTable<K, V>>;
+pub type FullBucketMut<'table, K, V, A = Heap> = FullBucket<K, V, &'table mut RawTable<K, V, A>>;
 
 pub enum BucketState<K, V, M> {
     Empty(EmptyBucket<K, V, M>),
@@ -290,11 +318,11 @@ impl<K, V, M> Bucket<K, V, M> {
     }
 }
 
-impl<K, V, M> Deref for FullBucket<K, V, M>
-    where M: Deref<Target = RawTable<K, V>>
+impl<K, V, A: Alloc, M> Deref for FullBucket<K, V, M>
+    where M: Deref<Target = RawTable<K, V, A>>
 {
-    type Target = RawTable<K, V>;
-    fn deref(&self) -> &RawTable<K, V> {
+    type Target = RawTable<K, V, A>;
+    fn deref(&self) -> &RawTable<K, V, A> {
         &self.table
     }
 }
@@ -302,12 +330,14 @@ impl<K, V, M> Deref for FullBucket<K, V, M>
 /// `Put` is implemented for types which provide access to a table and cannot be invalidated
 ///  by filling a bucket. A similar implementation for `Take` is possible.
 pub trait Put<K, V> {
-    unsafe fn borrow_table_mut(&mut self) -> &mut RawTable<K, V>;
+    type Allocator: Alloc;
+    unsafe fn borrow_table_mut(&mut self) -> &mut RawTable<K, V, Self::Allocator>;
 }
 
 
-impl<'t, K, V> Put<K, V> for &'t mut RawTable<K, V> {
-    unsafe fn borrow_table_mut(&mut self) -> &mut RawTable<K, V> {
+impl<'t, K, V, A: Alloc> Put<K, V> for &'t mut RawTable<K, V, A> {
+    type Allocator = A;
+    unsafe fn borrow_table_mut(&mut self) -> &mut RawTable<K, V, A> {
         *self
     }
 }
@@ -315,7 +345,8 @@ impl<'t, K, V> Put<K, V> for &'t mut RawTable<K, V> {
 impl<K, V, M> Put<K, V> for Bucket<K, V, M>
     where M: Put<K, V>
 {
-    unsafe fn borrow_table_mut(&mut self) -> &mut RawTable<K, V> {
+    type Allocator = M::Allocator;
+    unsafe fn borrow_table_mut(&mut self) -> &mut RawTable<K, V, M::Allocator> {
         self.table.borrow_table_mut()
     }
 }
@@ -323,12 +354,13 @@ impl<K, V, M> Put<K, V> for Bucket<K, V, M>
 impl<K, V, M> Put<K, V> for FullBucket<K, V, M>
     where M: Put<K, V>
 {
-    unsafe fn borrow_table_mut(&mut self) -> &mut RawTable<K, V> {
+    type Allocator = M::Allocator;
+    unsafe fn borrow_table_mut(&mut self) -> &mut RawTable<K, V, M::Allocator> {
         self.table.borrow_table_mut()
     }
 }
 
-impl<K, V, M: Deref<Target = RawTable<K, V>>> Bucket<K, V, M> {
+impl<K, V, A: Alloc, M: Deref<Target = RawTable<K, V, A>>> Bucket<K, V, M> {
     pub fn new(table: M, hash: SafeHash) -> Bucket<K, V, M> {
         Bucket::at_index(table, hash.inspect() as usize)
     }
@@ -427,7 +459,7 @@ impl<K, V, M: Deref<Target = RawTable<K, V>>> Bucket<K, V, M> {
     }
 }
 
-impl<K, V, M: Deref<Target = RawTable<K, V>>> EmptyBucket<K, V, M> {
+impl<K, V, A: Alloc, M: Deref<Target = RawTable<K, V, A>>> EmptyBucket<K, V, M> {
     #[inline]
     pub fn next(self) -> Bucket<K, V, M> {
         let mut bucket = self.into_bucket();
@@ -501,7 +533,7 @@ impl<K, V, M> EmptyBucket<K, V, M>
     }
 }
 
-impl<K, V, M: Deref<Target = RawTable<K, V>>> FullBucket<K, V, M> {
+impl<K, V, A: Alloc, M: Deref<Target = RawTable<K, V, A>>> FullBucket<K, V, M> {
     #[inline]
     pub fn next(self) -> Bucket<K, V, M> {
         let mut bucket = self.into_bucket();
@@ -555,12 +587,12 @@ impl<K, V, M: Deref<Target = RawTable<K, V>>> FullBucket<K, V, M> {
 // We take a mutable reference to the table instead of accepting anything that
 // implements `DerefMut` to prevent fn `take` from being called on `stash`ed
 // buckets.
-impl<'t, K, V> FullBucket<K, V, &'t mut RawTable<K, V>> {
+impl<'t, K, V, A: Alloc> FullBucket<K, V, &'t mut RawTable<K, V, A>> {
     /// Removes this bucket's key and value from the hashtable.
     ///
     /// This works similarly to `put`, building an `EmptyBucket` out of the
     /// taken bucket.
-    pub fn take(self) -> (EmptyBucket<K, V, &'t mut RawTable<K, V>>, K, V) {
+    pub fn take(self) -> (EmptyBucket<K, V, &'t mut RawTable<K, V, A>>, K, V) {
         self.table.size -= 1;
 
         unsafe {
@@ -602,8 +634,8 @@ impl<K, V, M> FullBucket<K, V, M>
     }
 }
 
-impl<K, V, M> FullBucket<K, V, M>
-    where M: Deref<Target = RawTable<K, V>> + DerefMut
+impl<K, V, A: Alloc, M> FullBucket<K, V, M>
+    where M: Deref<Target = RawTable<K, V, A>> + DerefMut
 {
     /// Gets mutable references to the key and value at a given index.
     pub fn read_mut(&mut self) -> (&mut K, &mut V) {
@@ -614,8 +646,8 @@ impl<K, V, M> FullBucket<K, V, M>
     }
 }
 
-impl<'t, K, V, M> FullBucket<K, V, M>
-    where M: Deref<Target = RawTable<K, V>> + 't
+impl<'t, K, V, A: Alloc, M> FullBucket<K, V, M>
+    where M: Deref<Target = RawTable<K, V, A>> + 't
 {
     /// Exchange a bucket state for immutable references into the table.
     /// Because the underlying reference to the table is also consumed,
@@ -630,8 +662,8 @@ impl<'t, K, V, M> FullBucket<K, V, M>
     }
 }
 
-impl<'t, K, V, M> FullBucket<K, V, M>
-    where M: Deref<Target = RawTable<K, V>> + DerefMut + 't
+impl<'t, K, V, A: Alloc, M> FullBucket<K, V, M>
+    where M: Deref<Target = RawTable<K, V, A>> + DerefMut + 't
 {
     /// This works similarly to `into_refs`, exchanging a bucket state
     /// for mutable references into the table.
@@ -643,8 +675,8 @@ impl<'t, K, V, M> FullBucket<K, V, M>
     }
 }
 
-impl<K, V, M> GapThenFull<K, V, M>
-    where M: Deref<Target = RawTable<K, V>>
+impl<K, V, A: Alloc, M> GapThenFull<K, V, M>
+    where M: Deref<Target = RawTable<K, V, A>>
 {
     #[inline]
     pub fn full(&self) -> &FullBucket<K, V, M> {
@@ -738,17 +770,35 @@ fn test_offset_calculation() {
     assert_eq!(calculate_offsets(6, 12, 4), (8, 20, false));
 }
 
-impl<K, V> RawTable<K, V> {
+impl<K, V, A: Alloc> RawTable<K, V, A> {
     /// Does not initialize the buckets. The caller should ensure they,
     /// at the very least, set every hash to EMPTY_BUCKET.
-    unsafe fn new_uninitialized(capacity: usize) -> RawTable<K, V> {
+    /// Returns an error if it cannot allocate or capacity overflows.
+    unsafe fn new_uninitialized_internal(
+        capacity: usize,
+        fallibility: Fallibility,
+        mut a: A,
+    ) -> Result<RawTable<K, V, A>, CollectionAllocErr> {
+        let hashes = Self::alloc_hashes(capacity, fallibility, &mut a)?;
+
+        Ok(RawTable {
+            capacity_mask: capacity.wrapping_sub(1),
+            size: 0,
+            hashes: TaggedHashUintPtr::new(hashes),
+            marker: marker::PhantomData,
+            alloc: a,
+        })
+    }
+
+    /// Allocates the hashes and pairs arrays for `capacity` buckets with `a`,
+    /// returning a pointer to the hashes.
+    unsafe fn alloc_hashes(
+        capacity: usize,
+        fallibility: Fallibility,
+        a: &mut A,
+    ) -> Result<*mut HashUint, CollectionAllocErr> {
         if capacity == 0 {
-            return RawTable {
-                size: 0,
-                capacity_mask: capacity.wrapping_sub(1),
-                hashes: TaggedHashUintPtr::new(EMPTY as *mut HashUint),
-                marker: marker::PhantomData,
-            };
+            return Ok(EMPTY as *mut HashUint);
         }
 
         // No need for `checked_mul` before a more restrictive check performed
@@ -768,25 +818,59 @@ impl<K, V> RawTable<K, V> {
                                                            align_of::<HashUint>(),
                                                            pairs_size,
                                                            align_of::<(K, V)>());
-        assert!(!oflo, "capacity overflow");
+        if oflo {
+            return Err(CollectionAllocErr::CapacityOverflow);
+        }
 
         // One check for overflow that covers calculation and rounding of size.
-        let size_of_bucket = size_of::<HashUint>().checked_add(size_of::<(K, V)>()).unwrap();
-        assert!(size >=
-                capacity.checked_mul(size_of_bucket)
-                    .expect("capacity overflow"),
-                "capacity overflow");
+        let size_of_bucket = size_of::<HashUint>().checked_add(size_of::<(K, V)>())
+            .ok_or(CollectionAllocErr::CapacityOverflow)?;
+        let capacity_mul_size_of_bucket = capacity.checked_mul(size_of_bucket);
+        if capacity_mul_size_of_bucket.is_none() || size < capacity_mul_size_of_bucket.unwrap() {
+            return Err(CollectionAllocErr::CapacityOverflow);
+        }
 
-        let buffer = Heap.alloc(Layout::from_size_align(size, alignment).unwrap())
-            .unwrap_or_else(|e| Heap.oom(e));
+        let layout = Layout::from_size_align(size, alignment)
+            .ok_or(CollectionAllocErr::CapacityOverflow)?;
+        let buffer = match a.alloc(layout) {
+            Ok(buffer) => buffer,
+            Err(e) => match fallibility {
+                Infallible => a.oom(e),
+                Fallible => return Err(e.into()),
+            },
+        };
 
-        let hashes = buffer as *mut HashUint;
+        Ok(buffer as *mut HashUint)
+    }
 
-        RawTable {
-            capacity_mask: capacity.wrapping_sub(1),
-            size: 0,
-            hashes: TaggedHashUintPtr::new(hashes),
-            marker: marker::PhantomData,
+    /// Deallocates the arrays of `capacity` buckets starting at `hashes`
+    /// with `a`.
+    unsafe fn dealloc_hashes(capacity: usize, hashes: *mut HashUint, a: &mut A) {
+        if capacity == 0 {
+            return;
+        }
+
+        let hashes_size = capacity * size_of::<HashUint>();
+        let pairs_size = capacity * size_of::<(K, V)>();
+        let (align, size, oflo) = calculate_allocation(hashes_size,
+                                                       align_of::<HashUint>(),
+                                                       pairs_size,
+                                                       align_of::<(K, V)>());
+
+        debug_assert!(!oflo, "should be impossible");
+
+        // Remember how everything was allocated out of one buffer
+        // during initialization? We only need one call to free here.
+        a.dealloc(hashes as *mut u8, Layout::from_size_align(size, align).unwrap());
+    }
+
+    /// Does not initialize the buckets. The caller should ensure they,
+    /// at the very least, set every hash to EMPTY_BUCKET.
+    unsafe fn new_uninitialized(capacity: usize, a: A) -> RawTable<K, V, A> {
+        match Self::new_uninitialized_internal(capacity, Infallible, a) {
+            Err(CollectionAllocErr::CapacityOverflow) => panic!("capacity overflow"),
+            Err(CollectionAllocErr::AllocErr(_)) => unreachable!(),
+            Ok(table) => table,
         }
     }
 
@@ -809,16 +893,73 @@ impl<K, V> RawTable<K, V> {
         }
     }
 
-    /// Creates a new raw table from a given capacity. All buckets are
-    /// initially empty.
-    pub fn new(capacity: usize) -> RawTable<K, V> {
+    /// Tries to create a new raw table from a given capacity. If it cannot
+    /// allocate and `fallibility` is `Fallible`, it returns with an error
+    /// instead of aborting. All buckets are initially empty.
+    pub fn new_internal(
+        capacity: usize,
+        fallibility: Fallibility,
+        a: A,
+    ) -> Result<RawTable<K, V, A>, CollectionAllocErr> {
         unsafe {
-            let ret = RawTable::new_uninitialized(capacity);
+            let ret = RawTable::new_uninitialized_internal(capacity, fallibility, a)?;
             ptr::write_bytes(ret.hashes.ptr(), 0, capacity);
-            ret
+            Ok(ret)
+        }
+    }
+
+    /// Creates a new raw table from a given capacity, allocating with `a`.
+    /// All buckets are initially empty.
+    pub fn new_in(capacity: usize, a: A) -> RawTable<K, V, A> {
+        match Self::new_internal(capacity, Infallible, a) {
+            Err(CollectionAllocErr::CapacityOverflow) => panic!("capacity overflow"),
+            Err(CollectionAllocErr::AllocErr(_)) => unreachable!(),
+            Ok(table) => table,
+        }
+    }
+
+    /// Swaps the buckets of this table for `capacity` empty ones, allocated
+    /// with the same allocator, and returns the old buckets. Once emptied,
+    /// they must be given back to `free_buckets`.
+    pub fn replace_buckets(
+        &mut self,
+        capacity: usize,
+        fallibility: Fallibility,
+    ) -> Result<RawTable<K, V, Detached>, CollectionAllocErr> {
+        unsafe {
+            let hashes = Self::alloc_hashes(capacity, fallibility, &mut self.alloc)?;
+            let hashes = TaggedHashUintPtr::new(hashes);
+            ptr::write_bytes(hashes.ptr(), 0, capacity);
+
+            let old = RawTable {
+                capacity_mask: self.capacity_mask,
+                size: self.size,
+                hashes: TaggedHashUintPtr(self.hashes.0),
+                marker: marker::PhantomData,
+                alloc: Detached,
+            };
+            self.capacity_mask = capacity.wrapping_sub(1);
+            self.size = 0;
+            self.hashes = hashes;
+            Ok(old)
         }
     }
 
+    /// Deallocates the buckets returned by `replace_buckets`, which must
+    /// be empty.
+    pub fn free_buckets(&mut self, old: RawTable<K, V, Detached>) {
+        debug_assert_eq!(old.size(), 0);
+        unsafe {
+            Self::dealloc_hashes(old.capacity(), old.hashes.ptr(), &mut self.alloc);
+        }
+        mem::forget(old);
+    }
+
+    /// Returns a reference to the allocator backing this table.
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
     /// The hashtable's capacity, similar to a vector's.
     pub fn capacity(&self) -> usize {
         self.capacity_mask.wrapping_add(1)
@@ -851,7 +992,7 @@ impl<K, V> RawTable<K, V> {
         }
     }
 
-    pub fn into_iter(self) -> IntoIter<K, V> {
+    pub fn into_iter(self) -> IntoIter<K, V, A> {
         let RawBuckets { raw, elems_left, .. } = self.raw_buckets();
         // Replace the marker regardless of lifetime bounds on parameters.
         IntoIter {
@@ -864,7 +1005,7 @@ impl<K, V> RawTable<K, V> {
         }
     }
 
-    pub fn drain(&mut self) -> Drain<K, V> {
+    pub fn drain(&mut self) -> Drain<K, V, A> {
         let RawBuckets { raw, elems_left, .. } = self.raw_buckets();
         // Replace the marker regardless of lifetime bounds on parameters.
         Drain {
@@ -1002,15 +1143,15 @@ impl<'a, K: 'a, V: 'a> IterMut<'a, K, V> {
 }
 
 /// Iterator over the entries in a table, consuming the table.
-pub struct IntoIter<K, V> {
-    table: RawTable<K, V>,
+pub struct IntoIter<K, V, A: Alloc = Heap> {
+    table: RawTable<K, V, A>,
     iter: RawBuckets<'static, K, V>,
 }
 
-unsafe impl<K: Sync, V: Sync> Sync for IntoIter<K, V> {}
-unsafe impl<K: Send, V: Send> Send for IntoIter<K, V> {}
+unsafe impl<K: Sync, V: Sync, A: Alloc + Sync> Sync for IntoIter<K, V, A> {}
+unsafe impl<K: Send, V: Send, A: Alloc + Send> Send for IntoIter<K, V, A> {}
 
-impl<K, V> IntoIter<K, V> {
+impl<K, V, A: Alloc> IntoIter<K, V, A> {
     pub fn iter(&self) -> Iter<K, V> {
         Iter {
             iter: self.iter.clone(),
@@ -1019,16 +1160,16 @@ impl<K, V> IntoIter<K, V> {
 }
 
 /// Iterator over the entries in a table, clearing the table.
-pub struct Drain<'a, K: 'a, V: 'a> {
-    table: NonNull<RawTable<K, V>>,
+pub struct Drain<'a, K: 'a, V: 'a, A: Alloc + 'a = Heap> {
+    table: NonNull<RawTable<K, V, A>>,
     iter: RawBuckets<'static, K, V>,
-    marker: marker::PhantomData<&'a RawTable<K, V>>,
+    marker: marker::PhantomData<&'a RawTable<K, V, A>>,
 }
 
-unsafe impl<'a, K: Sync, V: Sync> Sync for Drain<'a, K, V> {}
-unsafe impl<'a, K: Send, V: Send> Send for Drain<'a, K, V> {}
+unsafe impl<'a, K: Sync, V: Sync, A: Alloc + Sync> Sync for Drain<'a, K, V, A> {}
+unsafe impl<'a, K: Send, V: Send, A: Alloc + Send> Send for Drain<'a, K, V, A> {}
 
-impl<'a, K, V> Drain<'a, K, V> {
+impl<'a, K, V, A: Alloc> Drain<'a, K, V, A> {
     pub fn iter(&self) -> Iter<K, V> {
         Iter {
             iter: self.iter.clone(),
@@ -1078,7 +1219,7 @@ impl<'a, K, V> ExactSizeIterator for IterMut<'a, K, V> {
     }
 }
 
-impl<K, V> Iterator for IntoIter<K, V> {
+impl<K, V, A: Alloc> Iterator for IntoIter<K, V, A> {
     type Item = (SafeHash, K, V);
 
     fn next(&mut self) -> Option<(SafeHash, K, V)> {
@@ -1096,13 +1237,13 @@ impl<K, V> Iterator for IntoIter<K, V> {
     }
 }
 
-impl<K, V> ExactSizeIterator for IntoIter<K, V> {
+impl<K, V, A: Alloc> ExactSizeIterator for IntoIter<K, V, A> {
     fn len(&self) -> usize {
         self.iter().len()
     }
 }
 
-impl<'a, K, V> Iterator for Drain<'a, K, V> {
+impl<'a, K, V, A: Alloc> Iterator for Drain<'a, K, V, A> {
     type Item = (SafeHash, K, V);
 
     #[inline]
@@ -1121,23 +1262,23 @@ impl<'a, K, V> Iterator for Drain<'a, K, V> {
     }
 }
 
-impl<'a, K, V> ExactSizeIterator for Drain<'a, K, V> {
+impl<'a, K, V, A: Alloc> ExactSizeIterator for Drain<'a, K, V, A> {
     fn len(&self) -> usize {
         self.iter.len()
     }
 }
 
-impl<'a, K: 'a, V: 'a> Drop for Drain<'a, K, V> {
+impl<'a, K: 'a, V: 'a, A: Alloc + 'a> Drop for Drain<'a, K, V, A> {
     fn drop(&mut self) {
         for _ in self {}
     }
 }
 
-impl<K: Clone, V: Clone> Clone for RawTable<K, V> {
-    fn clone(&self) -> RawTable<K, V> {
+impl<K: Clone, V: Clone, A: Alloc + Clone> Clone for RawTable<K, V, A> {
+    fn clone(&self) -> RawTable<K, V, A> {
         unsafe {
             let cap = self.capacity();
-            let mut new_ht = RawTable::new_uninitialized(cap);
+            let mut new_ht = RawTable::new_uninitialized(cap, self.alloc.clone());
 
             let mut new_buckets = new_ht.raw_bucket_at(0);
             let mut buckets = self.raw_bucket_at(0);
@@ -1160,7 +1301,7 @@ impl<K: Clone, V: Clone> Clone for RawTable<K, V> {
     }
 }
 
-unsafe impl<#[may_dangle] K, #[may_dangle] V> Drop for RawTable<K, V> {
+unsafe impl<#[may_dangle] K, #[may_dangle] V, A: Alloc> Drop for RawTable<K, V, A> {
     fn drop(&mut self) {
         if self.capacity() == 0 {
             return;
@@ -1178,20 +1319,9 @@ unsafe impl<#[may_dangle] K, #[may_dangle] V> Drop for RawTable<K, V> {
             }
         }
 
-        let hashes_size = self.capacity() * size_of::<HashUint>();
-        let pairs_size = self.capacity() * size_of::<(K, V)>();
-        let (align, size, oflo) = calculate_allocation(hashes_size,
-                                                       align_of::<HashUint>(),
-                                                       pairs_size,
-                                                       align_of::<(K, V)>());
-
-        debug_assert!(!oflo, "should be impossible");
-
+        let capacity = self.capacity();
         unsafe {
-            Heap.dealloc(self.hashes.ptr() as *mut u8,
-                         Layout::from_size_align(size, align).unwrap());
-            // Remember how everything was allocated out of one buffer
-            // during initialization? We only need one call to free here.
+            Self::dealloc_hashes(capacity, self.hashes.ptr(), &mut self.alloc);
         }
     }
 }
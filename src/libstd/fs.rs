@@ -19,7 +19,7 @@
 
 use fmt;
 use ffi::OsString;
-use io::{self, SeekFrom, Seek, Read, Initializer, Write};
+use io::{self, SeekFrom, Seek, Read, Initializer, IoSlice, IoSliceMut, Write};
 use path::{Path, PathBuf};
 use sys::fs as fs_imp;
 use sys_common::{AsInnerMut, FromInner, AsInner, IntoInner};
@@ -539,6 +539,183 @@ impl File {
     pub fn set_permissions(&self, perm: Permissions) -> io::Result<()> {
         self.inner.set_permissions(perm.0)
     }
+
+    /// Acquires an exclusive advisory lock on the file, blocking until it
+    /// can be acquired.
+    ///
+    /// At most one handle can hold an exclusive lock on a file at a time, and
+    /// no handle can hold a shared lock on it in the meantime. The lock is
+    /// released by [`unlock`], or when every handle to the open file
+    /// description (including those from [`try_clone`]) has been closed.
+    ///
+    /// Advisory locks only affect other callers of these locking methods;
+    /// they do not prevent anyone from reading or writing the file.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix
+    /// with the `LOCK_EX` flag, and the `LockFileEx` function on Windows with
+    /// the `LOCKFILE_EXCLUSIVE_LOCK` flag. On Unix, calling it while holding
+    /// a shared lock converts that lock into an exclusive one; on Windows the
+    /// shared lock must be released first, or this call will block forever.
+    /// Note that, this [may change in the future][changes].
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    /// [`unlock`]: #method.unlock
+    /// [`try_clone`]: #method.try_clone
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    ///
+    /// # fn foo() -> std::io::Result<()> {
+    /// let f = File::create("foo.lock")?;
+    /// f.lock_exclusive()?;
+    /// // ... nobody else holds a lock on `foo.lock` here ...
+    /// f.unlock()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "0")]
+    pub fn lock_exclusive(&self) -> io::Result<()> {
+        self.inner.lock_exclusive()
+    }
+
+    /// Acquires a shared advisory lock on the file, blocking until it can be
+    /// acquired.
+    ///
+    /// Any number of handles can hold a shared lock on a file at the same
+    /// time, but not while an exclusive lock is held. See [`lock_exclusive`]
+    /// for how the lock is released.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix
+    /// with the `LOCK_SH` flag, and the `LockFileEx` function on Windows.
+    /// Note that, this [may change in the future][changes].
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    /// [`lock_exclusive`]: #method.lock_exclusive
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    ///
+    /// # fn foo() -> std::io::Result<()> {
+    /// let f = File::open("foo.lock")?;
+    /// f.lock_shared()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "0")]
+    pub fn lock_shared(&self) -> io::Result<()> {
+        self.inner.lock_shared()
+    }
+
+    /// Attempts to acquire an exclusive advisory lock on the file without
+    /// blocking.
+    ///
+    /// Returns `Ok(true)` if the lock was acquired, and `Ok(false)` if
+    /// another handle holds a lock on the file. See [`lock_exclusive`] for
+    /// the semantics of the lock itself.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix
+    /// with the `LOCK_EX | LOCK_NB` flags, and the `LockFileEx` function on
+    /// Windows with the `LOCKFILE_EXCLUSIVE_LOCK` and
+    /// `LOCKFILE_FAIL_IMMEDIATELY` flags. Note that, this
+    /// [may change in the future][changes].
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    /// [`lock_exclusive`]: #method.lock_exclusive
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    ///
+    /// # fn foo() -> std::io::Result<()> {
+    /// let f = File::create("foo.lock")?;
+    /// if !f.try_lock_exclusive()? {
+    ///     println!("waiting for another process to release foo.lock");
+    ///     f.lock_exclusive()?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "0")]
+    pub fn try_lock_exclusive(&self) -> io::Result<bool> {
+        self.inner.try_lock_exclusive()
+    }
+
+    /// Attempts to acquire a shared advisory lock on the file without
+    /// blocking.
+    ///
+    /// Returns `Ok(true)` if the lock was acquired, and `Ok(false)` if
+    /// another handle holds an exclusive lock on the file. See
+    /// [`lock_shared`] for the semantics of the lock itself.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix
+    /// with the `LOCK_SH | LOCK_NB` flags, and the `LockFileEx` function on
+    /// Windows with the `LOCKFILE_FAIL_IMMEDIATELY` flag. Note that, this
+    /// [may change in the future][changes].
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    /// [`lock_shared`]: #method.lock_shared
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    ///
+    /// # fn foo() -> std::io::Result<()> {
+    /// let f = File::open("foo.lock")?;
+    /// let locked = f.try_lock_shared()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "0")]
+    pub fn try_lock_shared(&self) -> io::Result<bool> {
+        self.inner.try_lock_shared()
+    }
+
+    /// Releases a lock previously acquired on the file through this handle.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix
+    /// with the `LOCK_UN` flag, and the `UnlockFile` function on Windows. On
+    /// Windows, calling it without holding a lock returns an error. Note
+    /// that, this [may change in the future][changes].
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    ///
+    /// # fn foo() -> std::io::Result<()> {
+    /// let f = File::create("foo.lock")?;
+    /// f.lock_exclusive()?;
+    /// f.unlock()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "0")]
+    pub fn unlock(&self) -> io::Result<()> {
+        self.inner.unlock()
+    }
 }
 
 impl AsInner<fs_imp::File> for File {
@@ -568,6 +745,10 @@ impl Read for File {
         self.inner.read(buf)
     }
 
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        self.inner.read_vectored(bufs)
+    }
+
     #[inline]
     unsafe fn initializer(&self) -> Initializer {
         Initializer::nop()
@@ -578,6 +759,9 @@ impl Write for File {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         self.inner.write(buf)
     }
+    fn write_vectored(&mut self, bufs: &[IoSlice]) -> io::Result<usize> {
+        self.inner.write_vectored(bufs)
+    }
     fn flush(&mut self) -> io::Result<()> { self.inner.flush() }
 }
 #[stable(feature = "rust1", since = "1.0.0")]
@@ -592,6 +776,10 @@ impl<'a> Read for &'a File {
         self.inner.read(buf)
     }
 
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        self.inner.read_vectored(bufs)
+    }
+
     #[inline]
     unsafe fn initializer(&self) -> Initializer {
         Initializer::nop()
@@ -602,6 +790,9 @@ impl<'a> Write for &'a File {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         self.inner.write(buf)
     }
+    fn write_vectored(&mut self, bufs: &[IoSlice]) -> io::Result<usize> {
+        self.inner.write_vectored(bufs)
+    }
     fn flush(&mut self) -> io::Result<()> { self.inner.flush() }
 }
 #[stable(feature = "rust1", since = "1.0.0")]
@@ -1996,7 +2187,7 @@ mod tests {
     use io::prelude::*;
 
     use fs::{self, File, OpenOptions};
-    use io::{ErrorKind, SeekFrom};
+    use io::{ErrorKind, IoSlice, IoSliceMut, SeekFrom};
     use path::Path;
     use rand::{StdRng, Rng};
     use str;
@@ -2080,6 +2271,37 @@ mod tests {
         check!(fs::remove_file(filename));
     }
 
+    #[test]
+    fn file_test_io_read_write_vectored() {
+        let tmpdir = tmpdir();
+        let filename = &tmpdir.join("file_rt_io_file_test_vectored.txt");
+        // Only Unix writes several buffers at once, the other platforms write
+        // the first one which isn't empty.
+        let expected: &[u8] = if cfg!(unix) { b"it's alright" } else { b"it's " };
+        {
+            let mut write_stream = check!(File::create(filename));
+            let bufs = [IoSlice::new(&[]), IoSlice::new(b"it's "), IoSlice::new(&[]),
+                        IoSlice::new(b"alright"), IoSlice::new(&[])];
+            assert_eq!(check!(write_stream.write_vectored(&bufs)), expected.len());
+        }
+        {
+            let mut read_stream = check!(File::open(filename));
+            let mut buf1 = [0; 3];
+            let mut buf2 = [0; 16];
+            let nread = check!(read_stream.read_vectored(&mut [IoSliceMut::new(&mut []),
+                                                               IoSliceMut::new(&mut buf1),
+                                                               IoSliceMut::new(&mut []),
+                                                               IoSliceMut::new(&mut buf2)]));
+            let mut read = buf1.to_vec();
+            read.extend_from_slice(&buf2);
+            if cfg!(unix) {
+                assert_eq!(nread, expected.len());
+            }
+            assert_eq!(&read[..nread], &expected[..nread]);
+        }
+        check!(fs::remove_file(filename));
+    }
+
     #[test]
     fn invalid_path_raises() {
         let tmpdir = tmpdir();
@@ -2655,6 +2877,63 @@ mod tests {
                    check!(out.metadata()).permissions());
     }
 
+    #[test]
+    fn copy_file_with_holes() {
+        let tmpdir = tmpdir();
+        let input = tmpdir.join("in.txt");
+        let out = tmpdir.join("out.txt");
+
+        {
+            let mut f = check!(File::create(&input));
+            check!(f.write(b"head"));
+            check!(f.seek(SeekFrom::Start(1 << 20)));
+            check!(f.write(b"middle"));
+            check!(f.set_len(3 << 20));
+        }
+        assert_eq!(check!(fs::copy(&input, &out)), 3 << 20);
+
+        let mut expected = Vec::new();
+        check!(check!(File::open(&input)).read_to_end(&mut expected));
+        let mut v = Vec::new();
+        check!(check!(File::open(&out)).read_to_end(&mut v));
+        assert_eq!(v.len(), 3 << 20);
+        assert!(v == expected);
+
+        // Only check that the holes survived if the filesystem has them.
+        #[cfg(target_os = "linux")]
+        {
+            use os::unix::fs::MetadataExt;
+            let in_blocks = check!(input.metadata()).blocks();
+            if in_blocks * 512 < 3 << 20 {
+                assert!(check!(out.metadata()).blocks() * 512 < 3 << 20);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn copy_file_shorter_than_its_size() {
+        // sysfs files report a size of a page, but hold much less data.
+        let input = Path::new("/sys/kernel/mm/transparent_hugepage/enabled");
+        let expected = match File::open(input) {
+            Ok(mut f) => {
+                let mut expected = Vec::new();
+                check!(f.read_to_end(&mut expected));
+                expected
+            }
+            Err(_) => return,
+        };
+        if check!(input.metadata()).len() <= expected.len() as u64 {
+            return
+        }
+        let tmpdir = tmpdir();
+        let out = tmpdir.join("out.txt");
+        assert_eq!(check!(fs::copy(input, &out)), expected.len() as u64);
+        let mut v = Vec::new();
+        check!(check!(File::open(&out)).read_to_end(&mut v));
+        assert!(v == expected);
+    }
+
     #[test]
     fn copy_file_dst_dir() {
         let tmpdir = tmpdir();
@@ -2861,6 +3140,28 @@ mod tests {
         check!(file.set_permissions(p));
     }
 
+    #[test]
+    fn file_lock_multiple_handles() {
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("lock.txt");
+
+        let f1 = check!(File::create(&path));
+        let f2 = check!(File::open(&path));
+
+        check!(f1.lock_shared());
+        assert!(check!(f2.try_lock_shared()));
+        check!(f2.unlock());
+        assert!(!check!(f2.try_lock_exclusive()));
+        check!(f1.unlock());
+
+        check!(f1.lock_exclusive());
+        assert!(!check!(f2.try_lock_shared()));
+        assert!(!check!(f2.try_lock_exclusive()));
+        check!(f1.unlock());
+        assert!(check!(f2.try_lock_exclusive()));
+        check!(f2.unlock());
+    }
+
     #[test]
     fn sync_doesnt_kill_anything() {
         let tmpdir = tmpdir();
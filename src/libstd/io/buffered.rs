@@ -15,7 +15,7 @@ use io::prelude::*;
 use cmp;
 use error;
 use fmt;
-use io::{self, Initializer, DEFAULT_BUF_SIZE, Error, ErrorKind, SeekFrom};
+use io::{self, Initializer, DEFAULT_BUF_SIZE, Error, ErrorKind, SeekFrom, IoSlice};
 use memchr;
 
 /// The `BufReader` struct adds buffering to any reader.
@@ -554,6 +554,22 @@ impl<W: Write> Write for BufWriter<W> {
             Write::write(&mut self.buf, buf)
         }
     }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice]) -> io::Result<usize> {
+        let total_len = bufs.iter().map(|b| b.len()).sum::<usize>();
+        if self.buf.len() + total_len > self.buf.capacity() {
+            self.flush_buf()?;
+        }
+        if total_len >= self.buf.capacity() {
+            self.panicked = true;
+            let r = self.inner.as_mut().unwrap().write_vectored(bufs);
+            self.panicked = false;
+            r
+        } else {
+            Write::write_vectored(&mut self.buf, bufs)
+        }
+    }
+
     fn flush(&mut self) -> io::Result<()> {
         self.flush_buf().and_then(|()| self.get_mut().flush())
     }
@@ -910,7 +926,7 @@ impl<W: Write> fmt::Debug for LineWriter<W> where W: fmt::Debug {
 #[cfg(test)]
 mod tests {
     use io::prelude::*;
-    use io::{self, BufReader, BufWriter, LineWriter, SeekFrom};
+    use io::{self, BufReader, BufWriter, IoSlice, LineWriter, SeekFrom};
     use sync::atomic::{AtomicUsize, Ordering};
     use thread;
     use test;
@@ -1079,6 +1095,26 @@ mod tests {
         assert_eq!(*writer.get_ref(), [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]);
     }
 
+    #[test]
+    fn test_buffered_writer_vectored() {
+        let mut writer = BufWriter::with_capacity(4, Vec::new());
+
+        let bufs = [IoSlice::new(&[0]), IoSlice::new(&[1, 2])];
+        assert_eq!(writer.write_vectored(&bufs).unwrap(), 3);
+        assert!(writer.get_ref().is_empty());
+
+        let bufs = [IoSlice::new(&[3, 4])];
+        assert_eq!(writer.write_vectored(&bufs).unwrap(), 2);
+        assert_eq!(*writer.get_ref(), [0, 1, 2]);
+
+        let bufs = [IoSlice::new(&[5, 6]), IoSlice::new(&[7, 8, 9])];
+        assert_eq!(writer.write_vectored(&bufs).unwrap(), 5);
+        assert_eq!(*writer.get_ref(), [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+
+        writer.flush().unwrap();
+        assert_eq!(*writer.get_ref(), [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    }
+
     #[test]
     fn test_buffered_writer_inner_flushes() {
         let mut w = BufWriter::with_capacity(3, Vec::new());
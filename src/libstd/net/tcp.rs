@@ -11,7 +11,7 @@
 use io::prelude::*;
 
 use fmt;
-use io::{self, Initializer};
+use io::{self, Initializer, IoSlice, IoSliceMut};
 use net::{ToSocketAddrs, SocketAddr, Shutdown};
 use sys_common::net as net_imp;
 use sys_common::{AsInner, FromInner, IntoInner};
@@ -548,6 +548,10 @@ impl TcpStream {
 impl Read for TcpStream {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> { self.0.read(buf) }
 
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        self.0.read_vectored(bufs)
+    }
+
     #[inline]
     unsafe fn initializer(&self) -> Initializer {
         Initializer::nop()
@@ -556,12 +560,19 @@ impl Read for TcpStream {
 #[stable(feature = "rust1", since = "1.0.0")]
 impl Write for TcpStream {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> { self.0.write(buf) }
+    fn write_vectored(&mut self, bufs: &[IoSlice]) -> io::Result<usize> {
+        self.0.write_vectored(bufs)
+    }
     fn flush(&mut self) -> io::Result<()> { Ok(()) }
 }
 #[stable(feature = "rust1", since = "1.0.0")]
 impl<'a> Read for &'a TcpStream {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> { self.0.read(buf) }
 
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        self.0.read_vectored(bufs)
+    }
+
     #[inline]
     unsafe fn initializer(&self) -> Initializer {
         Initializer::nop()
@@ -570,6 +581,9 @@ impl<'a> Read for &'a TcpStream {
 #[stable(feature = "rust1", since = "1.0.0")]
 impl<'a> Write for &'a TcpStream {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> { self.0.write(buf) }
+    fn write_vectored(&mut self, bufs: &[IoSlice]) -> io::Result<usize> {
+        self.0.write_vectored(bufs)
+    }
     fn flush(&mut self) -> io::Result<()> { Ok(()) }
 }
 
@@ -887,7 +901,7 @@ impl fmt::Debug for TcpListener {
 
 #[cfg(all(test, not(any(target_os = "cloudabi", target_os = "emscripten"))))]
 mod tests {
-    use io::ErrorKind;
+    use io::{ErrorKind, IoSlice, IoSliceMut};
     use io::prelude::*;
     use net::*;
     use net::test::{next_test_ip4, next_test_ip6};
@@ -969,6 +983,31 @@ mod tests {
         })
     }
 
+    #[test]
+    fn read_write_vectored() {
+        each_ip(&mut |addr| {
+            let acceptor = t!(TcpListener::bind(&addr));
+
+            let _t = thread::spawn(move|| {
+                let mut stream = t!(TcpStream::connect(&addr));
+                let bufs = [IoSlice::new(&[]), IoSlice::new(&[1, 2]), IoSlice::new(&[]),
+                            IoSlice::new(&[3, 4, 5]), IoSlice::new(&[])];
+                assert_eq!(t!(stream.write_vectored(&bufs)), 5);
+            });
+
+            let mut stream = t!(acceptor.accept()).0;
+            let mut buf1 = [0; 1];
+            let mut buf2 = [0; 8];
+            let nread = t!(stream.read_vectored(&mut [IoSliceMut::new(&mut []),
+                                                      IoSliceMut::new(&mut buf1),
+                                                      IoSliceMut::new(&mut []),
+                                                      IoSliceMut::new(&mut buf2)]));
+            assert_eq!(nread, 5);
+            assert_eq!(buf1, [1]);
+            assert_eq!(&buf2[..4], &[2, 3, 4, 5]);
+        })
+    }
+
     #[test]
     fn smoke_test() {
         each_ip(&mut |addr| {
@@ -121,7 +121,7 @@ use io::prelude::*;
 use ffi::OsStr;
 use fmt;
 use fs;
-use io::{self, Initializer};
+use io::{self, Initializer, IoSlice, IoSliceMut};
 use path::Path;
 use str;
 use sys::pipe::{read2, AnonPipe};
@@ -187,6 +187,10 @@ impl AsInner<imp::Process> for Child {
     fn as_inner(&self) -> &imp::Process { &self.handle }
 }
 
+impl AsInnerMut<imp::Process> for Child {
+    fn as_inner_mut(&mut self) -> &mut imp::Process { &mut self.handle }
+}
+
 impl FromInner<(imp::Process, imp::StdioPipes)> for Child {
     fn from_inner((handle, io): (imp::Process, imp::StdioPipes)) -> Child {
         Child {
@@ -235,6 +239,10 @@ impl Write for ChildStdin {
         self.inner.write(buf)
     }
 
+    fn write_vectored(&mut self, bufs: &[IoSlice]) -> io::Result<usize> {
+        self.inner.write_vectored(bufs)
+    }
+
     fn flush(&mut self) -> io::Result<()> {
         Ok(())
     }
@@ -281,6 +289,9 @@ impl Read for ChildStdout {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         self.inner.read(buf)
     }
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        self.inner.read_vectored(bufs)
+    }
     #[inline]
     unsafe fn initializer(&self) -> Initializer {
         Initializer::nop()
@@ -328,6 +339,9 @@ impl Read for ChildStderr {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         self.inner.read(buf)
     }
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        self.inner.read_vectored(bufs)
+    }
     #[inline]
     unsafe fn initializer(&self) -> Initializer {
         Initializer::nop()
@@ -1456,6 +1470,75 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg(unix)]
+    #[cfg_attr(target_os = "android", ignore)]
+    fn signal_sends_requested_signal() {
+        use os::unix::process::{ChildExt, ExitStatusExt};
+        use libc;
+
+        let mut p = Command::new("sleep").arg("100").spawn().unwrap();
+        p.signal(libc::SIGTERM).unwrap();
+        let status = p.wait().unwrap();
+        assert_eq!(status.signal(), Some(libc::SIGTERM));
+        assert!(!status.core_dumped());
+        assert_eq!(status.stopped_signal(), None);
+
+        // the pid may have been recycled by now, so refuse to signal it
+        assert!(p.signal(libc::SIGTERM).is_err());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn exit_status_from_raw_stopped() {
+        use os::unix::process::ExitStatusExt;
+        use process::ExitStatus;
+        use libc;
+
+        // the encoding `waitpid` uses for a process stopped by SIGSTOP
+        let status = ExitStatus::from_raw((libc::SIGSTOP << 8) | 0x7f);
+        assert_eq!(status.stopped_signal(), Some(libc::SIGSTOP));
+        assert_eq!(status.signal(), None);
+        assert_eq!(status.code(), None);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    #[cfg_attr(target_os = "android", ignore)]
+    fn process_group_works() {
+        use os::unix::prelude::*;
+        use libc;
+
+        let mut p = Command::new("sleep").arg("100")
+                            .process_group(0)
+                            .spawn().unwrap();
+        let pgid = unsafe { libc::getpgid(p.id() as libc::pid_t) };
+        p.kill().unwrap();
+        p.wait().unwrap();
+        assert_eq!(pgid, p.id() as libc::pid_t);
+        assert!(pgid != unsafe { libc::getpgrp() });
+    }
+
+    #[test]
+    #[cfg(unix)]
+    #[cfg_attr(target_os = "android", ignore)]
+    fn setsid_works() {
+        use os::unix::prelude::*;
+        use libc;
+
+        let mut p = Command::new("sleep").arg("100")
+                            .setsid(true)
+                            .spawn().unwrap();
+        let sid = unsafe { libc::getsid(p.id() as libc::pid_t) };
+        p.kill().unwrap();
+        p.wait().unwrap();
+        assert_eq!(sid, p.id() as libc::pid_t);
+
+        // a session leader can't be moved to another process group
+        assert!(Command::new("true").setsid(true).process_group(0)
+                                    .spawn().is_err());
+    }
+
     pub fn run_output(mut cmd: Command) -> String {
         let p = cmd.spawn();
         assert!(p.is_ok());
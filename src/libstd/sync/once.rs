@@ -148,9 +148,9 @@ struct Waiter {
 
 // Helper struct used to clean up after a closure call with a `Drop`
 // implementation to also run on panic.
-struct Finish {
+struct Finish<'a> {
     panicked: bool,
-    me: &'static Once,
+    me: &'a Once,
 }
 
 impl Once {
@@ -163,6 +163,14 @@ impl Once {
         }
     }
 
+    /// Returns whether some initialization has run and completed successfully.
+    ///
+    /// A `Once` which is poisoned or whose initialization is still running
+    /// is not completed.
+    pub(crate) fn is_completed(&self) -> bool {
+        self.state.load(Ordering::SeqCst) == COMPLETE
+    }
+
     /// Performs an initialization routine once and only once. The given closure
     /// will be executed if this is the first time `call_once` has been called,
     /// and otherwise the routine will *not* be invoked.
@@ -297,10 +305,13 @@ impl Once {
     // Finally, this takes an `FnMut` instead of a `FnOnce` because there's
     // currently no way to take an `FnOnce` and call it via virtual dispatch
     // without some allocation overhead.
+    //
+    // Unlike `call_once`, it doesn't need a `'static` receiver, which
+    // `OnceLock` relies on.
     #[cold]
-    fn call_inner(&'static self,
-                  ignore_poisoning: bool,
-                  init: &mut FnMut(bool)) {
+    pub(crate) fn call_inner(&self,
+                             ignore_poisoning: bool,
+                             init: &mut FnMut(bool)) {
         let mut state = self.state.load(Ordering::SeqCst);
 
         'outer: loop {
@@ -389,7 +400,7 @@ impl fmt::Debug for Once {
     }
 }
 
-impl Drop for Finish {
+impl<'a> Drop for Finish<'a> {
     fn drop(&mut self) {
         // Swap out our state with however we finished. We should only ever see
         // an old state which was RUNNING.
@@ -0,0 +1,462 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Thread-safe cells which can be written to only once.
+//!
+//! These are the counterparts of `core::cell::OnceCell` and `core::cell::Lazy`
+//! which can be shared between threads, and in particular be put in a
+//! `static`. The value is stored next to a `Once`, which decides which thread
+//! gets to run the initializer and makes all the others wait for it.
+
+use cell::{Cell, UnsafeCell};
+use fmt;
+use mem;
+use ops::Deref;
+use panic::{RefUnwindSafe, UnwindSafe};
+use sync::Once;
+
+/// A synchronization primitive which can be written to only once.
+///
+/// This type is a thread-safe `OnceCell`, and can be used in statics.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(once_cell)]
+///
+/// use std::sync::OnceLock;
+///
+/// static CELL: OnceLock<String> = OnceLock::new();
+/// assert!(CELL.get().is_none());
+///
+/// std::thread::spawn(|| {
+///     let value: &String = CELL.get_or_init(|| "Hello, World!".to_string());
+///     assert_eq!(value, "Hello, World!");
+/// }).join().unwrap();
+///
+/// let value: Option<&String> = CELL.get();
+/// assert!(value.is_some());
+/// assert_eq!(value.unwrap().as_str(), "Hello, World!");
+/// ```
+#[unstable(feature = "once_cell", issue = "0")]
+pub struct OnceLock<T> {
+    once: Once,
+    // Only written to while `once` is running, and only read from once `once`
+    // has completed.
+    value: UnsafeCell<Option<T>>,
+}
+
+// Sharing a `OnceLock<T>` hands out `&T` to every thread (hence `Sync`), and
+// lets any of them store the value (hence `Send`).
+#[unstable(feature = "once_cell", issue = "0")]
+unsafe impl<T: Sync + Send> Sync for OnceLock<T> {}
+#[unstable(feature = "once_cell", issue = "0")]
+unsafe impl<T: Send> Send for OnceLock<T> {}
+
+#[unstable(feature = "once_cell", issue = "0")]
+impl<T: RefUnwindSafe + UnwindSafe> RefUnwindSafe for OnceLock<T> {}
+#[unstable(feature = "once_cell", issue = "0")]
+impl<T: UnwindSafe> UnwindSafe for OnceLock<T> {}
+
+impl<T> OnceLock<T> {
+    /// Creates a new, empty cell.
+    #[unstable(feature = "once_cell", issue = "0")]
+    pub const fn new() -> OnceLock<T> {
+        OnceLock {
+            once: Once::new(),
+            value: UnsafeCell::new(None),
+        }
+    }
+
+    /// Gets a reference to the underlying value.
+    ///
+    /// Returns `None` if the cell is empty, or being initialized. This
+    /// method never blocks.
+    #[unstable(feature = "once_cell", issue = "0")]
+    pub fn get(&self) -> Option<&T> {
+        if self.once.is_completed() {
+            unsafe { (*self.value.get()).as_ref() }
+        } else {
+            None
+        }
+    }
+
+    /// Gets a mutable reference to the underlying value.
+    ///
+    /// Returns `None` if the cell is empty. This method never blocks.
+    #[unstable(feature = "once_cell", issue = "0")]
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        unsafe { (*self.value.get()).as_mut() }
+    }
+
+    /// Sets the contents of this cell to `value`.
+    ///
+    /// May block if another thread is currently attempting to initialize the
+    /// cell. The cell is guaranteed to contain a value when `set` returns,
+    /// though not necessarily the one provided.
+    ///
+    /// Returns `Ok(())` if the cell was empty and `Err(value)` if it was full.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(once_cell)]
+    ///
+    /// use std::sync::OnceLock;
+    ///
+    /// static CELL: OnceLock<i32> = OnceLock::new();
+    ///
+    /// fn main() {
+    ///     assert!(CELL.get().is_none());
+    ///
+    ///     std::thread::spawn(|| {
+    ///         assert_eq!(CELL.set(92), Ok(()));
+    ///     }).join().unwrap();
+    ///
+    ///     assert_eq!(CELL.set(62), Err(62));
+    ///     assert_eq!(CELL.get(), Some(&92));
+    /// }
+    /// ```
+    #[unstable(feature = "once_cell", issue = "0")]
+    pub fn set(&self, value: T) -> Result<(), T> {
+        let mut value = Some(value);
+        self.get_or_init(|| value.take().unwrap());
+        match value {
+            None => Ok(()),
+            Some(value) => Err(value),
+        }
+    }
+
+    /// Gets the contents of the cell, initializing it with `f` if the cell
+    /// was empty.
+    ///
+    /// Many threads may call `get_or_init` concurrently with different
+    /// initializing functions, but it is guaranteed that only one function
+    /// will be executed.
+    ///
+    /// # Panics
+    ///
+    /// If `f` panics, the panic is propagated to the caller, and the cell
+    /// remains uninitialized: a later call will run its own initializer.
+    ///
+    /// It is an error to reentrantly initialize the cell from `f`. The exact
+    /// outcome is unspecified. Current implementation deadlocks, but this may
+    /// be changed to a panic in the future.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(once_cell)]
+    ///
+    /// use std::sync::OnceLock;
+    ///
+    /// let cell = OnceLock::new();
+    /// let value = cell.get_or_init(|| 92);
+    /// assert_eq!(value, &92);
+    /// let value = cell.get_or_init(|| unreachable!());
+    /// assert_eq!(value, &92);
+    /// ```
+    #[unstable(feature = "once_cell", issue = "0")]
+    pub fn get_or_init<F>(&self, f: F) -> &T where F: FnOnce() -> T {
+        if let Some(value) = self.get() {
+            return value;
+        }
+        self.initialize(f);
+        self.get().expect("OnceLock initializer completed without a value")
+    }
+
+    /// Consumes the `OnceLock`, returning the wrapped value.
+    ///
+    /// Returns `None` if the cell was empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(once_cell)]
+    ///
+    /// use std::sync::OnceLock;
+    ///
+    /// let cell: OnceLock<String> = OnceLock::new();
+    /// assert_eq!(cell.into_inner(), None);
+    ///
+    /// let cell = OnceLock::new();
+    /// cell.set("hello".to_string()).unwrap();
+    /// assert_eq!(cell.into_inner(), Some("hello".to_string()));
+    /// ```
+    #[unstable(feature = "once_cell", issue = "0")]
+    pub fn into_inner(self) -> Option<T> {
+        unsafe { self.value.into_inner() }
+    }
+
+    /// Takes the value out of this `OnceLock`, moving it back to an
+    /// uninitialized state.
+    ///
+    /// Has no effect and returns `None` if the `OnceLock` hasn't been
+    /// initialized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(once_cell)]
+    ///
+    /// use std::sync::OnceLock;
+    ///
+    /// let mut cell = OnceLock::new();
+    /// cell.set("hello".to_string()).unwrap();
+    /// assert_eq!(cell.take(), Some("hello".to_string()));
+    /// assert_eq!(cell.get(), None);
+    /// ```
+    #[unstable(feature = "once_cell", issue = "0")]
+    pub fn take(&mut self) -> Option<T> {
+        mem::replace(self, OnceLock::new()).into_inner()
+    }
+
+    // Kept out of line so that the fast path in `get_or_init` stays small.
+    //
+    // `Once::call_once_force` needs a `&'static Once`, so this goes through
+    // the non-`'static` `call_inner` instead. Poisoning is ignored like in
+    // `call_once_force`: a panicking initializer leaves the value unset, so
+    // there is nothing to poison and the next caller can simply try again.
+    #[cold]
+    fn initialize<F>(&self, f: F) where F: FnOnce() -> T {
+        let slot = &self.value;
+        let mut f = Some(f);
+        self.once.call_inner(true, &mut |_| {
+            let value = f.take().unwrap()();
+            unsafe { *slot.get() = Some(value) };
+        });
+    }
+}
+
+#[unstable(feature = "once_cell", issue = "0")]
+impl<T> Default for OnceLock<T> {
+    fn default() -> OnceLock<T> {
+        OnceLock::new()
+    }
+}
+
+#[unstable(feature = "once_cell", issue = "0")]
+impl<T: fmt::Debug> fmt::Debug for OnceLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("OnceLock")
+            .field("value", &self.get())
+            .finish()
+    }
+}
+
+#[unstable(feature = "once_cell", issue = "0")]
+impl<T> From<T> for OnceLock<T> {
+    fn from(value: T) -> OnceLock<T> {
+        let cell = OnceLock::new();
+        let _ = cell.set(value);
+        cell
+    }
+}
+
+/// A value which is initialized on the first access.
+///
+/// This type is a thread-safe `Lazy`, and can be used in statics.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(once_cell)]
+///
+/// use std::collections::HashMap;
+/// use std::sync::Lazy;
+///
+/// static HASHMAP: Lazy<HashMap<i32, String>> = Lazy::new(|| {
+///     println!("initializing");
+///     let mut m = HashMap::new();
+///     m.insert(13, "Spica".to_string());
+///     m.insert(74, "Hoyten".to_string());
+///     m
+/// });
+///
+/// fn main() {
+///     println!("ready");
+///     std::thread::spawn(|| {
+///         println!("{:?}", HASHMAP.get(&13));
+///     }).join().unwrap();
+///     println!("{:?}", HASHMAP.get(&74));
+///
+///     // Prints:
+///     //   ready
+///     //   initializing
+///     //   Some("Spica")
+///     //   Some("Hoyten")
+/// }
+/// ```
+#[unstable(feature = "once_cell", issue = "0")]
+pub struct Lazy<T, F = fn() -> T> {
+    cell: OnceLock<T>,
+    init: Cell<Option<F>>,
+}
+
+// `init` is only ever touched from inside the `Once` guarding `cell`, so
+// at most one thread accesses it at a time.
+#[unstable(feature = "once_cell", issue = "0")]
+unsafe impl<T, F: Send> Sync for Lazy<T, F> where OnceLock<T>: Sync {}
+
+#[unstable(feature = "once_cell", issue = "0")]
+impl<T, F: UnwindSafe> RefUnwindSafe for Lazy<T, F> where OnceLock<T>: RefUnwindSafe {}
+
+impl<T, F> Lazy<T, F> {
+    /// Creates a new lazy value with the given initializing function.
+    #[unstable(feature = "once_cell", issue = "0")]
+    pub const fn new(f: F) -> Lazy<T, F> {
+        Lazy {
+            cell: OnceLock::new(),
+            init: Cell::new(Some(f)),
+        }
+    }
+}
+
+impl<T, F: FnOnce() -> T> Lazy<T, F> {
+    /// Forces the evaluation of this lazy value and returns a reference to
+    /// the result.
+    ///
+    /// This is equivalent to the `Deref` impl, but is explicit.
+    ///
+    /// # Panics
+    ///
+    /// If the initializing function panicked on an earlier access, this
+    /// panics as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(once_cell)]
+    ///
+    /// use std::sync::Lazy;
+    ///
+    /// let lazy = Lazy::new(|| 92);
+    ///
+    /// assert_eq!(Lazy::force(&lazy), &92);
+    /// assert_eq!(&*lazy, &92);
+    /// ```
+    #[unstable(feature = "once_cell", issue = "0")]
+    pub fn force(this: &Lazy<T, F>) -> &T {
+        this.cell.get_or_init(|| match this.init.take() {
+            Some(f) => f(),
+            None => panic!("Lazy instance has previously been poisoned"),
+        })
+    }
+}
+
+#[unstable(feature = "once_cell", issue = "0")]
+impl<T, F: FnOnce() -> T> Deref for Lazy<T, F> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        Lazy::force(self)
+    }
+}
+
+#[unstable(feature = "once_cell", issue = "0")]
+impl<T: Default> Default for Lazy<T> {
+    /// Creates a new lazy value using `Default` as the initializing function.
+    fn default() -> Lazy<T> {
+        Lazy::new(T::default)
+    }
+}
+
+#[unstable(feature = "once_cell", issue = "0")]
+impl<T: fmt::Debug, F> fmt::Debug for Lazy<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Lazy")
+            .field("cell", &self.cell)
+            .finish()
+    }
+}
+
+#[cfg(all(test, not(target_os = "emscripten")))]
+mod tests {
+    use panic;
+    use sync::atomic::{AtomicUsize, Ordering};
+    use sync::mpsc::channel;
+    use thread;
+    use super::{Lazy, OnceLock};
+
+    #[test]
+    fn smoke_once_lock() {
+        static CELL: OnceLock<i32> = OnceLock::new();
+        assert!(CELL.get().is_none());
+        assert_eq!(CELL.get_or_init(|| 92), &92);
+        assert_eq!(CELL.get_or_init(|| panic!()), &92);
+        assert_eq!(CELL.set(62), Err(62));
+        assert_eq!(CELL.get(), Some(&92));
+    }
+
+    #[test]
+    fn stampede_once_lock() {
+        static CELL: OnceLock<usize> = OnceLock::new();
+        static RUNS: AtomicUsize = AtomicUsize::new(0);
+
+        let (tx, rx) = channel();
+        for i in 0..10 {
+            let tx = tx.clone();
+            thread::spawn(move|| {
+                for _ in 0..4 { thread::yield_now() }
+                let value = CELL.get_or_init(|| {
+                    RUNS.fetch_add(1, Ordering::SeqCst);
+                    i
+                });
+                tx.send(*value).unwrap();
+            });
+        }
+
+        let first = rx.recv().unwrap();
+        for _ in 1..10 {
+            assert_eq!(rx.recv().unwrap(), first);
+        }
+        assert_eq!(RUNS.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn panicking_init_is_retried() {
+        let cell = OnceLock::new();
+
+        let t = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            cell.get_or_init(|| panic!());
+        }));
+        assert!(t.is_err());
+        assert!(cell.get().is_none());
+
+        assert_eq!(cell.get_or_init(|| 92), &92);
+    }
+
+    #[test]
+    fn into_inner_and_take() {
+        let mut cell = OnceLock::new();
+        assert_eq!(cell.take(), None::<String>);
+        cell.set("hello".to_string()).unwrap();
+        assert_eq!(cell.get_mut().map(|s| s.len()), Some(5));
+        assert_eq!(cell.take(), Some("hello".to_string()));
+        assert!(cell.get().is_none());
+
+        cell.set("world".to_string()).unwrap();
+        assert_eq!(cell.into_inner(), Some("world".to_string()));
+    }
+
+    #[test]
+    fn lazy_static() {
+        static RUNS: AtomicUsize = AtomicUsize::new(0);
+        static XS: Lazy<Vec<i32>> = Lazy::new(|| {
+            RUNS.fetch_add(1, Ordering::SeqCst);
+            vec![1, 2, 3]
+        });
+
+        let t = thread::spawn(|| XS.len());
+        assert_eq!(XS.len(), 3);
+        assert_eq!(t.join().unwrap(), 3);
+        assert_eq!(RUNS.load(Ordering::SeqCst), 1);
+    }
+}
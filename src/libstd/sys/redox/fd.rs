@@ -10,7 +10,7 @@
 
 #![unstable(reason = "not public", issue = "0", feature = "fd")]
 
-use io::{self, Read};
+use io::{self, Read, IoSlice, IoSliceMut};
 use mem;
 use sys::{cvt, syscall};
 use sys_common::AsInner;
@@ -37,6 +37,10 @@ impl FileDesc {
         cvt(syscall::read(self.fd, buf))
     }
 
+    pub fn read_vectored(&self, bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        io::default_read_vectored(|buf| self.read(buf), bufs)
+    }
+
     pub fn read_to_end(&self, buf: &mut Vec<u8>) -> io::Result<usize> {
         let mut me = self;
         (&mut me).read_to_end(buf)
@@ -46,6 +50,10 @@ impl FileDesc {
         cvt(syscall::write(self.fd, buf))
     }
 
+    pub fn write_vectored(&self, bufs: &[IoSlice]) -> io::Result<usize> {
+        io::default_write_vectored(|buf| self.write(buf), bufs)
+    }
+
     pub fn duplicate(&self) -> io::Result<FileDesc> {
         let new_fd = cvt(syscall::dup(self.fd, &[]))?;
         Ok(FileDesc::new(new_fd))
@@ -12,7 +12,7 @@ use os::unix::prelude::*;
 
 use ffi::{OsString, OsStr};
 use fmt;
-use io::{self, Error, ErrorKind, SeekFrom};
+use io::{self, Error, ErrorKind, IoSlice, IoSliceMut, SeekFrom};
 use path::{Path, PathBuf};
 use sync::Arc;
 use sys::fd::FileDesc;
@@ -285,10 +285,18 @@ impl File {
         self.0.read(buf)
     }
 
+    pub fn read_vectored(&self, bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        self.0.read_vectored(bufs)
+    }
+
     pub fn write(&self, buf: &[u8]) -> io::Result<usize> {
         self.0.write(buf)
     }
 
+    pub fn write_vectored(&self, bufs: &[IoSlice]) -> io::Result<usize> {
+        self.0.write_vectored(bufs)
+    }
+
     pub fn flush(&self) -> io::Result<()> { Ok(()) }
 
     pub fn seek(&self, pos: SeekFrom) -> io::Result<u64> {
@@ -316,6 +324,26 @@ impl File {
         set_perm(&self.path()?, perm)
     }
 
+    pub fn lock_exclusive(&self) -> io::Result<()> {
+        Err(Error::new(ErrorKind::Other, "File::lock_exclusive not implemented"))
+    }
+
+    pub fn lock_shared(&self) -> io::Result<()> {
+        Err(Error::new(ErrorKind::Other, "File::lock_shared not implemented"))
+    }
+
+    pub fn try_lock_exclusive(&self) -> io::Result<bool> {
+        Err(Error::new(ErrorKind::Other, "File::try_lock_exclusive not implemented"))
+    }
+
+    pub fn try_lock_shared(&self) -> io::Result<bool> {
+        Err(Error::new(ErrorKind::Other, "File::try_lock_shared not implemented"))
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        Err(Error::new(ErrorKind::Other, "File::unlock not implemented"))
+    }
+
     pub fn path(&self) -> io::Result<PathBuf> {
         let mut buf: [u8; 4096] = [0; 4096];
         let count = cvt(syscall::fpath(*self.fd().as_inner() as usize, &mut buf))?;
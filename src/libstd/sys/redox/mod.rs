@@ -25,6 +25,8 @@ pub mod ext;
 pub mod fast_thread_local;
 pub mod fd;
 pub mod fs;
+#[path = "../wasm/io.rs"]
+pub mod io;
 pub mod memchr;
 pub mod mutex;
 pub mod net;
@@ -63,4 +63,6 @@ pub mod prelude {
     pub use super::thread::JoinHandleExt;
     #[doc(no_inline)] #[stable(feature = "rust1", since = "1.0.0")]
     pub use super::process::{CommandExt, ExitStatusExt};
+    #[doc(no_inline)] #[unstable(feature = "unix_process_signal", issue = "0")]
+    pub use super::process::ChildExt;
 }
@@ -20,20 +20,25 @@ use libc;
 mod libc {
     pub use libc::c_int;
     pub type socklen_t = u32;
+    pub type uid_t = u32;
+    pub type gid_t = u32;
+    pub type pid_t = i32;
     pub struct sockaddr;
     #[derive(Clone)]
     pub struct sockaddr_un;
 }
 
 use ascii;
+use cmp;
 use ffi::OsStr;
 use fmt;
-use io::{self, Initializer};
+use io::{self, Initializer, IoSlice, IoSliceMut};
 use mem;
 use net::{self, Shutdown};
 use os::unix::ffi::OsStrExt;
 use os::unix::io::{RawFd, AsRawFd, FromRawFd, IntoRawFd};
 use path::Path;
+use ptr;
 use time::Duration;
 use sys::{self, cvt};
 use sys::net::Socket;
@@ -519,6 +524,157 @@ impl UnixStream {
     pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
         self.0.shutdown(how)
     }
+
+    /// Returns the credentials of the process on the other end of the
+    /// connection.
+    ///
+    /// The credentials are the ones in effect when the connection was
+    /// established. The process ID is only available on Linux and Android.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(peer_credentials_unix_socket)]
+    /// use std::os::unix::net::UnixStream;
+    ///
+    /// let (sock1, _sock2) = UnixStream::pair().unwrap();
+    /// let cred = sock1.peer_cred().expect("peer_cred function failed");
+    /// println!("peer uid: {}, gid: {}", cred.uid, cred.gid);
+    /// ```
+    #[cfg(any(target_os = "linux", target_os = "android",
+              target_os = "macos", target_os = "ios",
+              target_os = "freebsd", target_os = "dragonfly",
+              target_os = "openbsd", target_os = "netbsd"))]
+    #[unstable(feature = "peer_credentials_unix_socket", issue = "0")]
+    pub fn peer_cred(&self) -> io::Result<UCred> {
+        peer_cred(&self.0)
+    }
+
+    /// Sends data and ancillary data on the socket.
+    ///
+    /// The data is gathered from `bufs` in order. The control messages
+    /// added to `ancillary` are sent along with it, for example file
+    /// descriptors added with [`SocketAncillary::add_fds`].
+    ///
+    /// On success, returns the number of bytes written.
+    ///
+    /// [`SocketAncillary::add_fds`]: struct.SocketAncillary.html#method.add_fds
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(unix_socket_ancillary_data, iovec)]
+    /// use std::io::IoSlice;
+    /// use std::os::unix::net::{UnixStream, SocketAncillary};
+    /// use std::os::unix::io::AsRawFd;
+    ///
+    /// let (sock1, sock2) = UnixStream::pair().unwrap();
+    /// let mut buf = [0; 128];
+    /// let mut ancillary = SocketAncillary::new(&mut buf);
+    /// assert!(ancillary.add_fds(&[sock1.as_raw_fd()]));
+    /// let bufs = [IoSlice::new(b"hello"), IoSlice::new(b" world")];
+    /// sock1.send_vectored_with_ancillary(&bufs, &ancillary)
+    ///     .expect("send_vectored_with_ancillary function failed");
+    /// ```
+    #[cfg(any(target_os = "linux", target_os = "android",
+              target_os = "macos", target_os = "ios",
+              target_os = "freebsd", target_os = "dragonfly",
+              target_os = "openbsd", target_os = "netbsd"))]
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+    pub fn send_vectored_with_ancillary(&self, bufs: &[IoSlice], ancillary: &SocketAncillary)
+                                        -> io::Result<usize> {
+        send_with_ancillary(&self.0, bufs, ancillary)
+    }
+
+    /// Receives data and ancillary data from the socket.
+    ///
+    /// The data is scattered into `bufs` in order. The control messages
+    /// received along with it replace the contents of `ancillary`, and can be
+    /// read back with [`SocketAncillary::messages`]. Received file
+    /// descriptors are owned by the caller and have the close-on-exec flag
+    /// set.
+    ///
+    /// On success, returns the number of bytes read.
+    ///
+    /// [`SocketAncillary::messages`]: struct.SocketAncillary.html#method.messages
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(unix_socket_ancillary_data, iovec)]
+    /// use std::io::{IoSlice, IoSliceMut};
+    /// use std::os::unix::net::{UnixStream, SocketAncillary, AncillaryData};
+    /// use std::os::unix::io::AsRawFd;
+    ///
+    /// let (sock1, sock2) = UnixStream::pair().unwrap();
+    /// let mut buf = [0; 128];
+    /// let mut ancillary = SocketAncillary::new(&mut buf);
+    /// ancillary.add_fds(&[sock1.as_raw_fd()]);
+    /// sock1.send_vectored_with_ancillary(&[IoSlice::new(b"hello")], &ancillary).unwrap();
+    ///
+    /// let mut data = [0; 5];
+    /// let mut buf = [0; 128];
+    /// let mut ancillary = SocketAncillary::new(&mut buf);
+    /// let size = {
+    ///     let mut bufs = [IoSliceMut::new(&mut data)];
+    ///     sock2.recv_vectored_with_ancillary(&mut bufs, &mut ancillary)
+    ///         .expect("recv_vectored_with_ancillary function failed")
+    /// };
+    /// assert_eq!(&data[..size], b"hello");
+    /// for message in ancillary.messages() {
+    ///     if let Ok(AncillaryData::ScmRights(fds)) = message {
+    ///         for fd in fds {
+    ///             println!("received file descriptor {}", fd);
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    #[cfg(any(target_os = "linux", target_os = "android",
+              target_os = "macos", target_os = "ios",
+              target_os = "freebsd", target_os = "dragonfly",
+              target_os = "openbsd", target_os = "netbsd"))]
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+    pub fn recv_vectored_with_ancillary(&self,
+                                        bufs: &mut [IoSliceMut],
+                                        ancillary: &mut SocketAncillary)
+                                        -> io::Result<usize> {
+        recv_with_ancillary(&self.0, bufs, ancillary)
+    }
+
+    /// Enables or disables the reception of the credentials of the sending
+    /// process as `SCM_CREDENTIALS` ancillary data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(unix_socket_ancillary_data)]
+    /// use std::os::unix::net::UnixStream;
+    ///
+    /// let (sock1, sock2) = UnixStream::pair().unwrap();
+    /// sock2.set_passcred(true).expect("set_passcred function failed");
+    /// assert_eq!(sock2.passcred().unwrap(), true);
+    /// # drop(sock1);
+    /// ```
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+    pub fn set_passcred(&self, passcred: bool) -> io::Result<()> {
+        sys_common::net::setsockopt(&self.0, libc::SOL_SOCKET, libc::SO_PASSCRED,
+                                    passcred as libc::c_int)
+    }
+
+    /// Returns whether the credentials of the sending process are received
+    /// as `SCM_CREDENTIALS` ancillary data.
+    ///
+    /// For more information about this option, see [`set_passcred`].
+    ///
+    /// [`set_passcred`]: #method.set_passcred
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+    pub fn passcred(&self) -> io::Result<bool> {
+        let raw: libc::c_int = sys_common::net::getsockopt(&self.0, libc::SOL_SOCKET,
+                                                           libc::SO_PASSCRED)?;
+        Ok(raw != 0)
+    }
 }
 
 #[stable(feature = "unix_socket", since = "1.10.0")]
@@ -527,6 +683,10 @@ impl io::Read for UnixStream {
         io::Read::read(&mut &*self, buf)
     }
 
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        io::Read::read_vectored(&mut &*self, bufs)
+    }
+
     #[inline]
     unsafe fn initializer(&self) -> Initializer {
         Initializer::nop()
@@ -539,6 +699,10 @@ impl<'a> io::Read for &'a UnixStream {
         self.0.read(buf)
     }
 
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        self.0.read_vectored(bufs)
+    }
+
     #[inline]
     unsafe fn initializer(&self) -> Initializer {
         Initializer::nop()
@@ -551,6 +715,10 @@ impl io::Write for UnixStream {
         io::Write::write(&mut &*self, buf)
     }
 
+    fn write_vectored(&mut self, bufs: &[IoSlice]) -> io::Result<usize> {
+        io::Write::write_vectored(&mut &*self, bufs)
+    }
+
     fn flush(&mut self) -> io::Result<()> {
         io::Write::flush(&mut &*self)
     }
@@ -562,6 +730,10 @@ impl<'a> io::Write for &'a UnixStream {
         self.0.write(buf)
     }
 
+    fn write_vectored(&mut self, bufs: &[IoSlice]) -> io::Result<usize> {
+        self.0.write_vectored(bufs)
+    }
+
     fn flush(&mut self) -> io::Result<()> {
         Ok(())
     }
@@ -1384,6 +1556,132 @@ impl UnixDatagram {
     pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
         self.0.shutdown(how)
     }
+
+    /// Sends data and ancillary data on the socket.
+    ///
+    /// The data is gathered from `bufs` in order. The control messages
+    /// added to `ancillary` are sent along with it, for example file
+    /// descriptors added with [`SocketAncillary::add_fds`].
+    ///
+    /// On success, returns the number of bytes written.
+    ///
+    /// [`SocketAncillary::add_fds`]: struct.SocketAncillary.html#method.add_fds
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(unix_socket_ancillary_data, iovec)]
+    /// use std::io::IoSlice;
+    /// use std::os::unix::net::{UnixDatagram, SocketAncillary};
+    /// use std::os::unix::io::AsRawFd;
+    ///
+    /// let (sock1, sock2) = UnixDatagram::pair().unwrap();
+    /// let mut buf = [0; 128];
+    /// let mut ancillary = SocketAncillary::new(&mut buf);
+    /// assert!(ancillary.add_fds(&[sock1.as_raw_fd()]));
+    /// let bufs = [IoSlice::new(b"hello"), IoSlice::new(b" world")];
+    /// sock1.send_vectored_with_ancillary(&bufs, &ancillary)
+    ///     .expect("send_vectored_with_ancillary function failed");
+    /// ```
+    #[cfg(any(target_os = "linux", target_os = "android",
+              target_os = "macos", target_os = "ios",
+              target_os = "freebsd", target_os = "dragonfly",
+              target_os = "openbsd", target_os = "netbsd"))]
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+    pub fn send_vectored_with_ancillary(&self, bufs: &[IoSlice], ancillary: &SocketAncillary)
+                                        -> io::Result<usize> {
+        send_with_ancillary(&self.0, bufs, ancillary)
+    }
+
+    /// Receives data and ancillary data from the socket.
+    ///
+    /// The data is scattered into `bufs` in order. The control messages
+    /// received along with it replace the contents of `ancillary`, and can be
+    /// read back with [`SocketAncillary::messages`]. Received file
+    /// descriptors are owned by the caller and have the close-on-exec flag
+    /// set.
+    ///
+    /// On success, returns the number of bytes read.
+    ///
+    /// [`SocketAncillary::messages`]: struct.SocketAncillary.html#method.messages
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(unix_socket_ancillary_data, iovec)]
+    /// use std::io::{IoSlice, IoSliceMut};
+    /// use std::os::unix::net::{UnixDatagram, SocketAncillary, AncillaryData};
+    /// use std::os::unix::io::AsRawFd;
+    ///
+    /// let (sock1, sock2) = UnixDatagram::pair().unwrap();
+    /// let mut buf = [0; 128];
+    /// let mut ancillary = SocketAncillary::new(&mut buf);
+    /// ancillary.add_fds(&[sock1.as_raw_fd()]);
+    /// sock1.send_vectored_with_ancillary(&[IoSlice::new(b"hello")], &ancillary).unwrap();
+    ///
+    /// let mut data = [0; 5];
+    /// let mut buf = [0; 128];
+    /// let mut ancillary = SocketAncillary::new(&mut buf);
+    /// let size = {
+    ///     let mut bufs = [IoSliceMut::new(&mut data)];
+    ///     sock2.recv_vectored_with_ancillary(&mut bufs, &mut ancillary)
+    ///         .expect("recv_vectored_with_ancillary function failed")
+    /// };
+    /// assert_eq!(&data[..size], b"hello");
+    /// for message in ancillary.messages() {
+    ///     if let Ok(AncillaryData::ScmRights(fds)) = message {
+    ///         for fd in fds {
+    ///             println!("received file descriptor {}", fd);
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    #[cfg(any(target_os = "linux", target_os = "android",
+              target_os = "macos", target_os = "ios",
+              target_os = "freebsd", target_os = "dragonfly",
+              target_os = "openbsd", target_os = "netbsd"))]
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+    pub fn recv_vectored_with_ancillary(&self,
+                                        bufs: &mut [IoSliceMut],
+                                        ancillary: &mut SocketAncillary)
+                                        -> io::Result<usize> {
+        recv_with_ancillary(&self.0, bufs, ancillary)
+    }
+
+    /// Enables or disables the reception of the credentials of the sending
+    /// process as `SCM_CREDENTIALS` ancillary data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(unix_socket_ancillary_data)]
+    /// use std::os::unix::net::UnixDatagram;
+    ///
+    /// let (sock1, sock2) = UnixDatagram::pair().unwrap();
+    /// sock2.set_passcred(true).expect("set_passcred function failed");
+    /// assert_eq!(sock2.passcred().unwrap(), true);
+    /// # drop(sock1);
+    /// ```
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+    pub fn set_passcred(&self, passcred: bool) -> io::Result<()> {
+        sys_common::net::setsockopt(&self.0, libc::SOL_SOCKET, libc::SO_PASSCRED,
+                                    passcred as libc::c_int)
+    }
+
+    /// Returns whether the credentials of the sending process are received
+    /// as `SCM_CREDENTIALS` ancillary data.
+    ///
+    /// For more information about this option, see [`set_passcred`].
+    ///
+    /// [`set_passcred`]: #method.set_passcred
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+    pub fn passcred(&self) -> io::Result<bool> {
+        let raw: libc::c_int = sys_common::net::getsockopt(&self.0, libc::SOL_SOCKET,
+                                                           libc::SO_PASSCRED)?;
+        Ok(raw != 0)
+    }
 }
 
 #[stable(feature = "unix_socket", since = "1.10.0")]
@@ -1407,6 +1705,439 @@ impl IntoRawFd for UnixDatagram {
     }
 }
 
+/// The credentials of a Unix process.
+///
+/// This `struct` is returned by [`UnixStream::peer_cred`], and is the item of
+/// [`ScmCredentials`].
+///
+/// [`UnixStream::peer_cred`]: struct.UnixStream.html#method.peer_cred
+/// [`ScmCredentials`]: struct.ScmCredentials.html
+#[unstable(feature = "peer_credentials_unix_socket", issue = "0")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct UCred {
+    /// The user ID of the process.
+    pub uid: libc::uid_t,
+    /// The group ID of the process.
+    pub gid: libc::gid_t,
+    /// The process ID, if the platform provides it.
+    pub pid: Option<libc::pid_t>,
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn peer_cred(socket: &Socket) -> io::Result<UCred> {
+    let cred: libc::ucred = sys_common::net::getsockopt(socket, libc::SOL_SOCKET,
+                                                        libc::SO_PEERCRED)?;
+    Ok(UCred { uid: cred.uid, gid: cred.gid, pid: Some(cred.pid) })
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios",
+          target_os = "freebsd", target_os = "dragonfly",
+          target_os = "openbsd", target_os = "netbsd"))]
+fn peer_cred(socket: &Socket) -> io::Result<UCred> {
+    let mut uid = 0;
+    let mut gid = 0;
+    cvt(unsafe { libc::getpeereid(*socket.as_inner(), &mut uid, &mut gid) })?;
+    Ok(UCred { uid, gid, pid: None })
+}
+
+// Rounds `len` up to the alignment of the headers and payloads in a control
+// message buffer, like `CMSG_ALIGN` does.
+#[cfg(any(target_os = "linux", target_os = "android",
+          target_os = "macos", target_os = "ios",
+          target_os = "freebsd", target_os = "dragonfly",
+          target_os = "openbsd", target_os = "netbsd"))]
+fn cmsg_align(len: usize) -> usize {
+    #[cfg(any(target_os = "macos", target_os = "ios"))]
+    let align = mem::size_of::<u32>();
+    #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+    let align = mem::size_of::<usize>();
+
+    (len + align - 1) & !(align - 1)
+}
+
+// The offset of the payload from the start of a control message.
+#[cfg(any(target_os = "linux", target_os = "android",
+          target_os = "macos", target_os = "ios",
+          target_os = "freebsd", target_os = "dragonfly",
+          target_os = "openbsd", target_os = "netbsd"))]
+fn cmsg_data_offset() -> usize {
+    cmsg_align(mem::size_of::<libc::cmsghdr>())
+}
+
+/// A buffer for the ancillary data sent and received alongside regular data
+/// on a Unix socket.
+///
+/// Control messages are appended with methods such as [`add_fds`] before
+/// sending, and read back with [`messages`] after receiving. The buffer is
+/// provided by the caller and limits how much ancillary data can be added or
+/// received.
+///
+/// [`add_fds`]: #method.add_fds
+/// [`messages`]: #method.messages
+///
+/// # Examples
+///
+/// ```
+/// #![feature(unix_socket_ancillary_data)]
+/// use std::os::unix::net::SocketAncillary;
+///
+/// let mut buf = [0; 128];
+/// let mut ancillary = SocketAncillary::new(&mut buf);
+/// assert!(ancillary.add_fds(&[0, 1]));
+/// assert!(!ancillary.is_empty());
+/// ```
+#[cfg(any(target_os = "linux", target_os = "android",
+          target_os = "macos", target_os = "ios",
+          target_os = "freebsd", target_os = "dragonfly",
+          target_os = "openbsd", target_os = "netbsd"))]
+#[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+#[derive(Debug)]
+pub struct SocketAncillary<'a> {
+    buffer: &'a mut [u8],
+    length: usize,
+    truncated: bool,
+}
+
+#[cfg(any(target_os = "linux", target_os = "android",
+          target_os = "macos", target_os = "ios",
+          target_os = "freebsd", target_os = "dragonfly",
+          target_os = "openbsd", target_os = "netbsd"))]
+impl<'a> SocketAncillary<'a> {
+    /// Creates an empty `SocketAncillary` backed by `buffer`.
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+    pub fn new(buffer: &'a mut [u8]) -> Self {
+        SocketAncillary { buffer, length: 0, truncated: false }
+    }
+
+    /// Returns the size of the underlying buffer.
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+    pub fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns the number of bytes of the buffer holding control messages.
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Returns `true` if there are no control messages in the buffer.
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Returns `true` if the last receive had to discard control messages
+    /// because the buffer was too small.
+    ///
+    /// Any file descriptors in the discarded messages are closed by the
+    /// operating system.
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+    pub fn truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Returns an iterator over the control messages in the buffer.
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+    pub fn messages(&self) -> Messages {
+        Messages { buffer: &self.buffer[..self.length] }
+    }
+
+    /// Removes all control messages from the buffer.
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+    pub fn clear(&mut self) {
+        self.length = 0;
+        self.truncated = false;
+    }
+
+    /// Adds an `SCM_RIGHTS` control message passing the file descriptors
+    /// `fds` to the receiving process.
+    ///
+    /// Returns `false` if the buffer is too small to hold the message, in
+    /// which case nothing is added.
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+    pub fn add_fds(&mut self, fds: &[RawFd]) -> bool {
+        self.add_to_buffer(libc::SCM_RIGHTS, fds)
+    }
+
+    /// Adds an `SCM_CREDENTIALS` control message passing `creds` to the
+    /// receiving process.
+    ///
+    /// A credential without a process ID is sent with the ID of the current
+    /// process. Unless the sending process is privileged, the operating system
+    /// only accepts its own credentials.
+    ///
+    /// Returns `false` if the buffer is too small to hold the message, in
+    /// which case nothing is added.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+    pub fn add_creds(&mut self, creds: &[UCred]) -> bool {
+        let creds = creds.iter().map(|cred| libc::ucred {
+            pid: cred.pid.unwrap_or_else(|| unsafe { libc::getpid() }),
+            uid: cred.uid,
+            gid: cred.gid,
+        }).collect::<Vec<_>>();
+        self.add_to_buffer(libc::SCM_CREDENTIALS, &creds)
+    }
+
+    fn add_to_buffer<T>(&mut self, cmsg_type: libc::c_int, items: &[T]) -> bool {
+        let data_len = items.len() * mem::size_of::<T>();
+        let space = cmsg_align(cmsg_data_offset() + data_len);
+        let end = match self.length.checked_add(space) {
+            Some(end) if end <= self.buffer.len() => end,
+            _ => return false,
+        };
+
+        // The buffer is not necessarily aligned, hence the unaligned write of
+        // the header.
+        unsafe {
+            let start = self.buffer.as_mut_ptr().offset(self.length as isize);
+            ptr::write_bytes(start, 0, space);
+            let mut header: libc::cmsghdr = mem::zeroed();
+            header.cmsg_len = (cmsg_data_offset() + data_len) as _;
+            header.cmsg_level = libc::SOL_SOCKET;
+            header.cmsg_type = cmsg_type;
+            ptr::write_unaligned(start as *mut libc::cmsghdr, header);
+            ptr::copy_nonoverlapping(items.as_ptr() as *const u8,
+                                     start.offset(cmsg_data_offset() as isize),
+                                     data_len);
+        }
+        self.length = end;
+        true
+    }
+}
+
+/// An iterator over the control messages in a [`SocketAncillary`].
+///
+/// This `struct` is created by the [`messages`] method on
+/// [`SocketAncillary`].
+///
+/// [`SocketAncillary`]: struct.SocketAncillary.html
+/// [`messages`]: struct.SocketAncillary.html#method.messages
+#[cfg(any(target_os = "linux", target_os = "android",
+          target_os = "macos", target_os = "ios",
+          target_os = "freebsd", target_os = "dragonfly",
+          target_os = "openbsd", target_os = "netbsd"))]
+#[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+#[derive(Debug)]
+pub struct Messages<'a> {
+    buffer: &'a [u8],
+}
+
+#[cfg(any(target_os = "linux", target_os = "android",
+          target_os = "macos", target_os = "ios",
+          target_os = "freebsd", target_os = "dragonfly",
+          target_os = "openbsd", target_os = "netbsd"))]
+#[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+impl<'a> Iterator for Messages<'a> {
+    type Item = Result<AncillaryData<'a>, AncillaryError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buffer.len() < mem::size_of::<libc::cmsghdr>() {
+            return None;
+        }
+        let header: libc::cmsghdr = unsafe {
+            ptr::read_unaligned(self.buffer.as_ptr() as *const libc::cmsghdr)
+        };
+        let len = header.cmsg_len as usize;
+        if len < cmsg_data_offset() || len > self.buffer.len() {
+            return None;
+        }
+        let data = &self.buffer[cmsg_data_offset()..len];
+        let next = cmp::min(cmsg_align(len), self.buffer.len());
+        self.buffer = &self.buffer[next..];
+
+        Some(match (header.cmsg_level, header.cmsg_type) {
+            (libc::SOL_SOCKET, libc::SCM_RIGHTS) => {
+                Ok(AncillaryData::ScmRights(ScmRights { data }))
+            }
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            (libc::SOL_SOCKET, libc::SCM_CREDENTIALS) => {
+                Ok(AncillaryData::ScmCredentials(ScmCredentials { data }))
+            }
+            (cmsg_level, cmsg_type) => {
+                Err(AncillaryError::Unknown { cmsg_level, cmsg_type })
+            }
+        })
+    }
+}
+
+/// A control message read from a [`SocketAncillary`].
+///
+/// [`SocketAncillary`]: struct.SocketAncillary.html
+#[cfg(any(target_os = "linux", target_os = "android",
+          target_os = "macos", target_os = "ios",
+          target_os = "freebsd", target_os = "dragonfly",
+          target_os = "openbsd", target_os = "netbsd"))]
+#[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+#[derive(Debug)]
+pub enum AncillaryData<'a> {
+    /// File descriptors passed with `SCM_RIGHTS`.
+    ScmRights(ScmRights<'a>),
+    /// Process credentials passed with `SCM_CREDENTIALS`.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    ScmCredentials(ScmCredentials<'a>),
+}
+
+/// The error returned for a control message that is not understood.
+#[cfg(any(target_os = "linux", target_os = "android",
+          target_os = "macos", target_os = "ios",
+          target_os = "freebsd", target_os = "dragonfly",
+          target_os = "openbsd", target_os = "netbsd"))]
+#[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AncillaryError {
+    /// A control message with an unsupported level or type.
+    Unknown {
+        /// The `cmsg_level` of the message.
+        cmsg_level: i32,
+        /// The `cmsg_type` of the message.
+        cmsg_type: i32,
+    },
+}
+
+// Reads the next `T` from a control message payload.
+#[cfg(any(target_os = "linux", target_os = "android",
+          target_os = "macos", target_os = "ios",
+          target_os = "freebsd", target_os = "dragonfly",
+          target_os = "openbsd", target_os = "netbsd"))]
+fn next_item<T>(data: &mut &[u8]) -> Option<T> {
+    let size = mem::size_of::<T>();
+    if data.len() < size {
+        return None;
+    }
+    let item = unsafe { ptr::read_unaligned(data.as_ptr() as *const T) };
+    *data = &data[size..];
+    Some(item)
+}
+
+/// An iterator over the file descriptors of an `SCM_RIGHTS` control message.
+///
+/// The file descriptors are owned by the receiving process, which is
+/// responsible for closing them.
+#[cfg(any(target_os = "linux", target_os = "android",
+          target_os = "macos", target_os = "ios",
+          target_os = "freebsd", target_os = "dragonfly",
+          target_os = "openbsd", target_os = "netbsd"))]
+#[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+#[derive(Debug)]
+pub struct ScmRights<'a> {
+    data: &'a [u8],
+}
+
+#[cfg(any(target_os = "linux", target_os = "android",
+          target_os = "macos", target_os = "ios",
+          target_os = "freebsd", target_os = "dragonfly",
+          target_os = "openbsd", target_os = "netbsd"))]
+#[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+impl<'a> Iterator for ScmRights<'a> {
+    type Item = RawFd;
+
+    fn next(&mut self) -> Option<RawFd> {
+        next_item(&mut self.data)
+    }
+}
+
+/// An iterator over the credentials of an `SCM_CREDENTIALS` control message.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+#[derive(Debug)]
+pub struct ScmCredentials<'a> {
+    data: &'a [u8],
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+impl<'a> Iterator for ScmCredentials<'a> {
+    type Item = UCred;
+
+    fn next(&mut self) -> Option<UCred> {
+        next_item(&mut self.data).map(|cred: libc::ucred| {
+            UCred { uid: cred.uid, gid: cred.gid, pid: Some(cred.pid) }
+        })
+    }
+}
+
+// Returns a zeroed buffer of at least `len` bytes for `msg_control`. The
+// buffer of a `SocketAncillary` may not be aligned for `cmsghdr`, which
+// `sendmsg` and `recvmsg` require, so the messages are copied through this one.
+#[cfg(any(target_os = "linux", target_os = "android",
+          target_os = "macos", target_os = "ios",
+          target_os = "freebsd", target_os = "dragonfly",
+          target_os = "openbsd", target_os = "netbsd"))]
+fn aligned_cmsg_buffer(len: usize) -> Vec<usize> {
+    debug_assert!(mem::align_of::<libc::cmsghdr>() <= mem::align_of::<usize>());
+    vec![0; (len + mem::size_of::<usize>() - 1) / mem::size_of::<usize>()]
+}
+
+#[cfg(any(target_os = "linux", target_os = "android",
+          target_os = "macos", target_os = "ios",
+          target_os = "freebsd", target_os = "dragonfly",
+          target_os = "openbsd", target_os = "netbsd"))]
+fn send_with_ancillary(socket: &Socket, bufs: &[IoSlice], ancillary: &SocketAncillary)
+                       -> io::Result<usize> {
+    let mut control = aligned_cmsg_buffer(ancillary.length);
+    unsafe {
+        let mut msg: libc::msghdr = mem::zeroed();
+        msg.msg_iov = bufs.as_ptr() as *mut libc::iovec;
+        msg.msg_iovlen = bufs.len() as _;
+        if !ancillary.is_empty() {
+            ptr::copy_nonoverlapping(ancillary.buffer.as_ptr(),
+                                     control.as_mut_ptr() as *mut u8,
+                                     ancillary.length);
+            msg.msg_control = control.as_mut_ptr() as *mut libc::c_void;
+            msg.msg_controllen = ancillary.length as _;
+        }
+        let count = cvt(libc::sendmsg(*socket.as_inner(), &msg, MSG_NOSIGNAL))?;
+        Ok(count as usize)
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android",
+          target_os = "macos", target_os = "ios",
+          target_os = "freebsd", target_os = "dragonfly",
+          target_os = "openbsd", target_os = "netbsd"))]
+fn recv_with_ancillary(socket: &Socket, bufs: &mut [IoSliceMut], ancillary: &mut SocketAncillary)
+                       -> io::Result<usize> {
+    // Received file descriptors are close-on-exec, like all the ones opened by
+    // the standard library. Where the platform cannot do this atomically, the
+    // flag is set right after receiving them.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    let flags = libc::MSG_CMSG_CLOEXEC;
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    let flags = 0;
+
+    let mut control = aligned_cmsg_buffer(ancillary.buffer.len());
+    let count = unsafe {
+        let mut msg: libc::msghdr = mem::zeroed();
+        msg.msg_iov = bufs.as_mut_ptr() as *mut libc::iovec;
+        msg.msg_iovlen = bufs.len() as _;
+        if !ancillary.buffer.is_empty() {
+            msg.msg_control = control.as_mut_ptr() as *mut libc::c_void;
+            msg.msg_controllen = ancillary.buffer.len() as _;
+        }
+        let count = cvt(libc::recvmsg(*socket.as_inner(), &mut msg, flags))?;
+        ancillary.length = cmp::min(msg.msg_controllen as usize, ancillary.buffer.len());
+        ptr::copy_nonoverlapping(control.as_ptr() as *const u8,
+                                 ancillary.buffer.as_mut_ptr(),
+                                 ancillary.length);
+        ancillary.truncated = msg.msg_flags & libc::MSG_CTRUNC == libc::MSG_CTRUNC;
+        count
+    };
+
+    if cfg!(not(any(target_os = "linux", target_os = "android"))) {
+        for message in ancillary.messages() {
+            if let Ok(AncillaryData::ScmRights(fds)) = message {
+                for fd in fds {
+                    unsafe { libc::ioctl(fd, libc::FIOCLEX); }
+                }
+            }
+        }
+    }
+
+    Ok(count as usize)
+}
+
 #[cfg(all(test, not(target_os = "emscripten")))]
 mod test {
     use thread;
@@ -1477,6 +2208,25 @@ mod test {
         thread.join().unwrap();
     }
 
+    #[test]
+    fn read_write_vectored() {
+        let (mut s1, mut s2) = or_panic!(UnixStream::pair());
+
+        let bufs = [IoSlice::new(&[]), IoSlice::new(b"hello"), IoSlice::new(&[]),
+                    IoSlice::new(b" world"), IoSlice::new(&[])];
+        assert_eq!(or_panic!(s1.write_vectored(&bufs)), 11);
+
+        let mut buf1 = [0; 3];
+        let mut buf2 = [0; 16];
+        let read = or_panic!(s2.read_vectored(&mut [IoSliceMut::new(&mut []),
+                                                     IoSliceMut::new(&mut buf1),
+                                                     IoSliceMut::new(&mut []),
+                                                     IoSliceMut::new(&mut buf2)]));
+        assert_eq!(read, 11);
+        assert_eq!(&buf1, b"hel");
+        assert_eq!(&buf2[..8], b"lo world");
+    }
+
     #[test]
     fn try_clone() {
         let dir = tmpdir();
@@ -1716,4 +2466,109 @@ mod test {
     fn abstract_namespace_not_allowed() {
         assert!(UnixStream::connect("\0asdf").is_err());
     }
+
+    #[test]
+    #[cfg(any(target_os = "linux", target_os = "android",
+              target_os = "macos", target_os = "ios",
+              target_os = "freebsd", target_os = "dragonfly",
+              target_os = "openbsd", target_os = "netbsd"))]
+    fn send_fd_with_ancillary() {
+        let (s1, s2) = or_panic!(UnixStream::pair());
+        let (mut passed, mut kept) = or_panic!(UnixStream::pair());
+
+        let mut buf = [0; 128];
+        let mut ancillary = SocketAncillary::new(&mut buf);
+        assert!(ancillary.add_fds(&[passed.as_raw_fd()]));
+        let bufs = [IoSlice::new(b"hello "), IoSlice::new(b"fd")];
+        assert_eq!(or_panic!(s1.send_vectored_with_ancillary(&bufs, &ancillary)), 8);
+        drop(passed);
+
+        let mut data1 = [0; 3];
+        let mut data2 = [0; 5];
+        let mut buf = [0; 128];
+        let mut ancillary = SocketAncillary::new(&mut buf);
+        let size = {
+            let mut bufs = [IoSliceMut::new(&mut data1), IoSliceMut::new(&mut data2)];
+            or_panic!(s2.recv_vectored_with_ancillary(&mut bufs, &mut ancillary))
+        };
+        assert_eq!(size, 8);
+        assert_eq!(&data1, b"hel");
+        assert_eq!(&data2, b"lo fd");
+        assert!(!ancillary.truncated());
+
+        let mut messages = ancillary.messages();
+        let fd = match messages.next() {
+            Some(Ok(AncillaryData::ScmRights(mut fds))) => {
+                let fd = fds.next().unwrap();
+                assert!(fds.next().is_none());
+                fd
+            }
+            other => panic!("unexpected control message {:?}", other),
+        };
+        assert!(messages.next().is_none());
+
+        passed = unsafe { UnixStream::from_raw_fd(fd) };
+        or_panic!(passed.write_all(b"hi"));
+        let mut buf = [0; 2];
+        or_panic!(kept.read_exact(&mut buf));
+        assert_eq!(&buf, b"hi");
+    }
+
+    #[test]
+    #[cfg(any(target_os = "linux", target_os = "android",
+              target_os = "macos", target_os = "ios",
+              target_os = "freebsd", target_os = "dragonfly",
+              target_os = "openbsd", target_os = "netbsd"))]
+    fn ancillary_buffer_too_small() {
+        let mut buf = [0; 4];
+        let mut ancillary = SocketAncillary::new(&mut buf);
+        assert!(!ancillary.add_fds(&[0]));
+        assert!(ancillary.is_empty());
+        assert!(ancillary.messages().next().is_none());
+    }
+
+    #[test]
+    #[cfg(any(target_os = "linux", target_os = "android",
+              target_os = "macos", target_os = "ios",
+              target_os = "freebsd", target_os = "dragonfly",
+              target_os = "openbsd", target_os = "netbsd"))]
+    fn peer_cred() {
+        let (s1, _s2) = or_panic!(UnixStream::pair());
+        let cred = or_panic!(s1.peer_cred());
+        assert_eq!(cred.uid, unsafe { ::libc::geteuid() });
+        assert_eq!(cred.gid, unsafe { ::libc::getegid() });
+        if cfg!(any(target_os = "linux", target_os = "android")) {
+            assert_eq!(cred.pid, Some(unsafe { ::libc::getpid() }));
+        }
+    }
+
+    #[test]
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn send_creds_with_ancillary() {
+        let (s1, s2) = or_panic!(UnixDatagram::pair());
+        or_panic!(s2.set_passcred(true));
+        assert!(or_panic!(s2.passcred()));
+
+        let cred = UCred {
+            uid: unsafe { ::libc::getuid() },
+            gid: unsafe { ::libc::getgid() },
+            pid: None,
+        };
+        let mut buf = [0; 128];
+        let mut ancillary = SocketAncillary::new(&mut buf);
+        assert!(ancillary.add_creds(&[cred]));
+        or_panic!(s1.send_vectored_with_ancillary(&[IoSlice::new(b"x")], &ancillary));
+
+        let mut data = [0; 1];
+        let mut buf = [0; 128];
+        let mut ancillary = SocketAncillary::new(&mut buf);
+        or_panic!(s2.recv_vectored_with_ancillary(&mut [IoSliceMut::new(&mut data)],
+                                                  &mut ancillary));
+        let received = ancillary.messages().filter_map(|message| match message {
+            Ok(AncillaryData::ScmCredentials(creds)) => Some(creds.collect::<Vec<_>>()),
+            _ => None,
+        }).next();
+        let expected = UCred { pid: Some(unsafe { ::libc::getpid() }), ..cred };
+        assert_eq!(received, Some(vec![expected]));
+    }
 }
@@ -32,6 +32,40 @@ pub trait CommandExt {
     #[stable(feature = "rust1", since = "1.0.0")]
     fn gid(&mut self, id: u32) -> &mut process::Command;
 
+    /// Sets the process group ID of the child process. This translates to a
+    /// `setpgid` call in the child process.
+    ///
+    /// A `pgroup` of 0 puts the child in a new process group whose ID is the
+    /// child's process ID, so that signals sent to that group reach the child
+    /// and its descendants, but not the parent. Failure in the `setpgid` call
+    /// will cause the spawn to fail.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(process_group)]
+    ///
+    /// use std::process::Command;
+    /// use std::os::unix::process::CommandExt;
+    ///
+    /// Command::new("sleep")
+    ///     .arg("10")
+    ///     .process_group(0)
+    ///     .spawn()
+    ///     .expect("sleep failed to start");
+    /// ```
+    #[unstable(feature = "process_group", issue = "0")]
+    fn process_group(&mut self, pgroup: i32) -> &mut process::Command;
+
+    /// Makes the child process the leader of a new session, detached from
+    /// the controlling terminal of the parent. This translates to a `setsid`
+    /// call in the child process.
+    ///
+    /// The new session leader is also the leader of a new process group, so
+    /// this cannot be combined with `process_group`: the spawn will fail.
+    #[unstable(feature = "process_group", issue = "0")]
+    fn setsid(&mut self, setsid: bool) -> &mut process::Command;
+
     /// Schedules a closure to be run just before the `exec` function is
     /// invoked.
     ///
@@ -105,6 +139,16 @@ impl CommandExt for process::Command {
         self
     }
 
+    fn process_group(&mut self, pgroup: i32) -> &mut process::Command {
+        self.as_inner_mut().pgroup(pgroup);
+        self
+    }
+
+    fn setsid(&mut self, setsid: bool) -> &mut process::Command {
+        self.as_inner_mut().setsid(setsid);
+        self
+    }
+
     fn before_exec<F>(&mut self, f: F) -> &mut process::Command
         where F: FnMut() -> io::Result<()> + Send + Sync + 'static
     {
@@ -128,6 +172,18 @@ pub trait ExitStatusExt {
     /// If the process was terminated by a signal, returns that signal.
     #[stable(feature = "rust1", since = "1.0.0")]
     fn signal(&self) -> Option<i32>;
+
+    /// If the process was terminated by a signal, says whether it dumped core.
+    #[unstable(feature = "unix_process_signal", issue = "0")]
+    fn core_dumped(&self) -> bool;
+
+    /// If the process was stopped by a signal, returns that signal.
+    ///
+    /// This is only possible if the status came from a `wait` system call
+    /// which was passed `WUNTRACED`, and was then converted into an
+    /// `ExitStatus` with `from_raw`.
+    #[unstable(feature = "unix_process_signal", issue = "0")]
+    fn stopped_signal(&self) -> Option<i32>;
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
@@ -139,6 +195,53 @@ impl ExitStatusExt for process::ExitStatus {
     fn signal(&self) -> Option<i32> {
         self.as_inner().signal()
     }
+
+    fn core_dumped(&self) -> bool {
+        self.as_inner().core_dumped()
+    }
+
+    fn stopped_signal(&self) -> Option<i32> {
+        self.as_inner().stopped_signal()
+    }
+}
+
+/// Unix-specific extensions to `std::process::Child`
+#[unstable(feature = "unix_process_signal", issue = "0")]
+pub trait ChildExt {
+    /// Sends the signal `signal` to the child process.
+    ///
+    /// Unlike [`Child::kill`], which always sends `SIGKILL`, this allows
+    /// asking the child to shut down gracefully, e.g. with `SIGTERM`, or
+    /// sending it any other signal it handles. An error is returned if the
+    /// child has already been waited on, or if the signal could not be sent.
+    ///
+    /// [`Child::kill`]: ../../../process/struct.Child.html#method.kill
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(unix_process_signal)]
+    /// # #![feature(libc)]
+    /// # extern crate libc;
+    ///
+    /// use std::process::Command;
+    /// use std::os::unix::process::{ChildExt, ExitStatusExt};
+    ///
+    /// # fn main() {
+    /// let mut child = Command::new("sleep").arg("100").spawn().unwrap();
+    /// child.signal(libc::SIGTERM).unwrap();
+    /// assert_eq!(child.wait().unwrap().signal(), Some(libc::SIGTERM));
+    /// # }
+    /// ```
+    #[unstable(feature = "unix_process_signal", issue = "0")]
+    fn signal(&mut self, signal: i32) -> io::Result<()>;
+}
+
+#[unstable(feature = "unix_process_signal", issue = "0")]
+impl ChildExt for process::Child {
+    fn signal(&mut self, signal: i32) -> io::Result<()> {
+        self.as_inner_mut().signal(signal)
+    }
 }
 
 #[stable(feature = "process_extensions", since = "1.2.0")]
@@ -10,9 +10,10 @@
 
 use os::unix::prelude::*;
 
+use cmp;
 use ffi::{CString, CStr, OsString, OsStr};
 use fmt;
-use io::{self, Error, ErrorKind, SeekFrom};
+use io::{self, Error, ErrorKind, IoSlice, IoSliceMut, SeekFrom};
 use libc::{self, c_int, mode_t};
 use mem;
 use path::{Path, PathBuf};
@@ -496,6 +497,10 @@ impl File {
         self.0.read(buf)
     }
 
+    pub fn read_vectored(&self, bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        self.0.read_vectored(bufs)
+    }
+
     pub fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
         self.0.read_at(buf, offset)
     }
@@ -504,6 +509,10 @@ impl File {
         self.0.write(buf)
     }
 
+    pub fn write_vectored(&self, bufs: &[IoSlice]) -> io::Result<usize> {
+        self.0.write_vectored(bufs)
+    }
+
     pub fn write_at(&self, buf: &[u8], offset: u64) -> io::Result<usize> {
         self.0.write_at(buf, offset)
     }
@@ -536,6 +545,36 @@ impl File {
         cvt_r(|| unsafe { libc::fchmod(self.0.raw(), perm.mode) })?;
         Ok(())
     }
+
+    pub fn lock_exclusive(&self) -> io::Result<()> {
+        self.flock(libc::LOCK_EX).map(|_| ())
+    }
+
+    pub fn lock_shared(&self) -> io::Result<()> {
+        self.flock(libc::LOCK_SH).map(|_| ())
+    }
+
+    pub fn try_lock_exclusive(&self) -> io::Result<bool> {
+        self.flock(libc::LOCK_EX | libc::LOCK_NB)
+    }
+
+    pub fn try_lock_shared(&self) -> io::Result<bool> {
+        self.flock(libc::LOCK_SH | libc::LOCK_NB)
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        self.flock(libc::LOCK_UN).map(|_| ())
+    }
+
+    // Returns `Ok(false)` if a non-blocking request would have had to wait.
+    fn flock(&self, operation: c_int) -> io::Result<bool> {
+        match cvt_r(|| unsafe { libc::flock(self.0.raw(), operation) }) {
+            Ok(_) => Ok(true),
+            Err(ref e) if operation & libc::LOCK_NB != 0 &&
+                          e.raw_os_error() == Some(libc::EWOULDBLOCK) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
 }
 
 impl DirBuilder {
@@ -761,6 +800,7 @@ pub fn canonicalize(p: &Path) -> io::Result<PathBuf> {
     Ok(PathBuf::from(OsString::from_vec(buf)))
 }
 
+#[cfg(not(target_os = "linux"))]
 pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
     use fs::{File, set_permissions};
     if !from.is_file() {
@@ -776,3 +816,171 @@ pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
     set_permissions(to, perm)?;
     Ok(ret)
 }
+
+#[cfg(target_os = "linux")]
+pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
+    use fs::{File, set_permissions};
+    if !from.is_file() {
+        return Err(Error::new(ErrorKind::InvalidInput,
+                              "the source path is not an existing regular file"))
+    }
+
+    let mut reader = File::open(from)?;
+    let mut writer = File::create(to)?;
+    let metadata = reader.metadata()?;
+    let len = metadata.len();
+
+    // Files in pseudo filesystems such as procfs report a size of zero even
+    // though reading them produces data, so only trust the size if it is
+    // nonzero.
+    if len == 0 {
+        let ret = io::copy(&mut reader, &mut writer)?;
+        set_permissions(to, metadata.permissions())?;
+        return Ok(ret)
+    }
+
+    let mut pos = 0;
+    while pos < len {
+        let (start, end) = match next_data_region(reader.as_raw_fd(), pos, len)? {
+            Some(region) => region,
+            None => break,
+        };
+        let copied = copy_region(&reader, &writer, start, end - start)?;
+        if copied < end - start {
+            // The source is shorter than its metadata claimed, or it shrank
+            // while being copied. Don't pad the destination with zeroes up
+            // to the old size.
+            writer.set_len(start + copied)?;
+            set_permissions(to, metadata.permissions())?;
+            return Ok(start + copied)
+        }
+        pos = end;
+    }
+
+    // Holes were skipped rather than written out, so extend the destination
+    // over a trailing one.
+    writer.set_len(len)?;
+    set_permissions(to, metadata.permissions())?;
+    Ok(len)
+}
+
+/// Finds the first region of `fd` at or after `pos` which contains data,
+/// as a `(start, end)` pair of offsets. Returns `None` if the rest of the file
+/// is a hole.
+#[cfg(target_os = "linux")]
+fn next_data_region(fd: c_int, pos: u64, len: u64) -> io::Result<Option<(u64, u64)>> {
+    const SEEK_DATA: c_int = 3;
+    const SEEK_HOLE: c_int = 4;
+
+    let start = unsafe { lseek64(fd, pos as off64_t, SEEK_DATA) };
+    if start == -1 {
+        let err = io::Error::last_os_error();
+        return match err.raw_os_error() {
+            Some(libc::ENXIO) => Ok(None),
+            // Kernels before 3.1 don't know about holes at all.
+            Some(libc::EINVAL) => Ok(Some((pos, len))),
+            _ => Err(err),
+        }
+    }
+    let end = cvt(unsafe { lseek64(fd, start, SEEK_HOLE) })?;
+    Ok(Some((start as u64, cmp::min(end as u64, len))))
+}
+
+/// Copies `len` bytes at `offset` in `reader` to the same offset in `writer`.
+///
+/// This tries `copy_file_range` first, which lets the kernel (or the
+/// filesystem, for reflinks and server-side copies) do all of the work, then
+/// `sendfile`, and finally falls back to copying through a buffer. Returns
+/// the number of bytes copied, which is less than `len` if the source turned
+/// out to be shorter than expected.
+#[cfg(target_os = "linux")]
+fn copy_region(reader: &::fs::File, writer: &::fs::File, offset: u64, len: u64)
+               -> io::Result<u64> {
+    use io::Read;
+    use sync::atomic::{AtomicBool, ATOMIC_BOOL_INIT, Ordering};
+
+    // Set once `copy_file_range` is known not to exist in the running kernel.
+    static NO_COPY_FILE_RANGE: AtomicBool = ATOMIC_BOOL_INIT;
+
+    unsafe fn copy_file_range(fd_in: c_int, off_in: *mut off64_t,
+                              fd_out: c_int, off_out: *mut off64_t,
+                              len: usize, flags: libc::c_uint) -> libc::c_long {
+        libc::syscall(libc::SYS_copy_file_range,
+                      fd_in, off_in, fd_out, off_out, len, flags)
+    }
+
+    // The kernel never transfers more than this in a single call anyway.
+    const MAX_CHUNK: u64 = 0x7fff_f000;
+
+    let (fd_in, fd_out) = (reader.as_raw_fd(), writer.as_raw_fd());
+    let end = offset + len;
+    let mut off_in = offset as off64_t;
+    let mut off_out = offset as off64_t;
+    let mut try_copy_file_range = !NO_COPY_FILE_RANGE.load(Ordering::Relaxed);
+    let mut try_sendfile = true;
+
+    while (off_in as u64) < end {
+        let chunk = cmp::min(end - off_in as u64, MAX_CHUNK) as usize;
+
+        let ret = if try_copy_file_range {
+            let ret = cvt(unsafe {
+                copy_file_range(fd_in, &mut off_in, fd_out, &mut off_out, chunk, 0)
+            });
+            match ret {
+                Err(ref e) if e.raw_os_error() == Some(libc::ENOSYS) => {
+                    NO_COPY_FILE_RANGE.store(true, Ordering::Relaxed);
+                    try_copy_file_range = false;
+                    continue
+                }
+                // Copies across filesystems are only supported since Linux
+                // 5.3, and some filesystems refuse them entirely.
+                Err(ref e) if e.raw_os_error() == Some(libc::EXDEV) ||
+                              e.raw_os_error() == Some(libc::EINVAL) ||
+                              e.raw_os_error() == Some(libc::EOPNOTSUPP) => {
+                    try_copy_file_range = false;
+                    continue
+                }
+                ret => ret.map(|n| n as usize),
+            }
+        } else if try_sendfile {
+            cvt(unsafe { lseek64(fd_in, off_in, libc::SEEK_SET) })?;
+            cvt(unsafe { lseek64(fd_out, off_out, libc::SEEK_SET) })?;
+            let ret = cvt(unsafe {
+                libc::sendfile(fd_out, fd_in, ptr::null_mut(), chunk)
+            });
+            match ret {
+                Err(ref e) if e.raw_os_error() == Some(libc::EINVAL) ||
+                              e.raw_os_error() == Some(libc::ENOSYS) => {
+                    try_sendfile = false;
+                    continue
+                }
+                Ok(n) => {
+                    off_in += n as off64_t;
+                    off_out += n as off64_t;
+                    Ok(n as usize)
+                }
+                Err(e) => Err(e),
+            }
+        } else {
+            cvt(unsafe { lseek64(fd_in, off_in, libc::SEEK_SET) })?;
+            cvt(unsafe { lseek64(fd_out, off_out, libc::SEEK_SET) })?;
+            let remaining = end - off_in as u64;
+            let copied = io::copy(&mut reader.take(remaining), &mut &*writer)?;
+            return Ok(off_in as u64 + copied - offset)
+        };
+
+        match ret {
+            // Some files, e.g. in sysfs, report a size but can't be copied by
+            // the kernel, and a source which shrank ends early. Let reading
+            // through a buffer tell these apart.
+            Ok(0) => {
+                try_copy_file_range = false;
+                try_sendfile = false;
+            }
+            Ok(_) => {}
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(len)
+}
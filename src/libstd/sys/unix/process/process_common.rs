@@ -13,7 +13,7 @@ use os::unix::prelude::*;
 use ffi::{OsString, OsStr, CString, CStr};
 use fmt;
 use io;
-use libc::{self, c_int, gid_t, uid_t, c_char};
+use libc::{self, c_int, gid_t, pid_t, uid_t, c_char};
 use ptr;
 use sys::fd::FileDesc;
 use sys::fs::{File, OpenOptions};
@@ -51,6 +51,8 @@ pub struct Command {
     cwd: Option<CString>,
     uid: Option<uid_t>,
     gid: Option<gid_t>,
+    pgroup: Option<pid_t>,
+    setsid: bool,
     saw_nul: bool,
     closures: Vec<Box<FnMut() -> io::Result<()> + Send + Sync>>,
     stdin: Option<Stdio>,
@@ -99,6 +101,8 @@ impl Command {
             cwd: None,
             uid: None,
             gid: None,
+            pgroup: None,
+            setsid: false,
             saw_nul,
             closures: Vec::new(),
             stdin: None,
@@ -128,6 +132,12 @@ impl Command {
     pub fn gid(&mut self, id: gid_t) {
         self.gid = Some(id);
     }
+    pub fn pgroup(&mut self, pgroup: pid_t) {
+        self.pgroup = Some(pgroup);
+    }
+    pub fn setsid(&mut self, setsid: bool) {
+        self.setsid = setsid;
+    }
 
     pub fn saw_nul(&self) -> bool {
         self.saw_nul
@@ -148,6 +158,14 @@ impl Command {
     pub fn get_gid(&self) -> Option<gid_t> {
         self.gid
     }
+    #[allow(dead_code)]
+    pub fn get_pgroup(&self) -> Option<pid_t> {
+        self.pgroup
+    }
+    #[allow(dead_code)]
+    pub fn get_setsid(&self) -> bool {
+        self.setsid
+    }
 
     pub fn get_closures(&mut self) -> &mut Vec<Box<FnMut() -> io::Result<()> + Send + Sync>> {
         &mut self.closures
@@ -362,12 +380,24 @@ impl ExitStatus {
     }
 
     pub fn signal(&self) -> Option<i32> {
-        if !self.exited() {
+        if unsafe { libc::WIFSIGNALED(self.0) } {
             Some(unsafe { libc::WTERMSIG(self.0) })
         } else {
             None
         }
     }
+
+    pub fn core_dumped(&self) -> bool {
+        unsafe { libc::WIFSIGNALED(self.0) && libc::WCOREDUMP(self.0) }
+    }
+
+    pub fn stopped_signal(&self) -> Option<i32> {
+        if unsafe { libc::WIFSTOPPED(self.0) } {
+            Some(unsafe { libc::WSTOPSIG(self.0) })
+        } else {
+            None
+        }
+    }
 }
 
 impl From<c_int> for ExitStatus {
@@ -380,9 +410,16 @@ impl fmt::Display for ExitStatus {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         if let Some(code) = self.code() {
             write!(f, "exit code: {}", code)
+        } else if let Some(signal) = self.signal() {
+            if self.core_dumped() {
+                write!(f, "signal: {} (core dumped)", signal)
+            } else {
+                write!(f, "signal: {}", signal)
+            }
+        } else if let Some(signal) = self.stopped_signal() {
+            write!(f, "stopped (not terminated) by signal: {}", signal)
         } else {
-            let signal = self.signal().unwrap();
-            write!(f, "signal: {}", signal)
+            write!(f, "unrecognised wait status: {} {:#x}", self.0, self.0)
         }
     }
 }
@@ -9,7 +9,7 @@
 // except according to those terms.
 
 use io;
-use libc::{self, size_t};
+use libc::{self, c_int, size_t};
 use mem;
 use ptr;
 
@@ -145,6 +145,11 @@ impl Process {
         Ok(())
     }
 
+    pub fn signal(&mut self, _signal: c_int) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::Other,
+                           "signals are not supported on Fuchsia"))
+    }
+
     pub fn wait(&mut self) -> io::Result<ExitStatus> {
         use default::Default;
         use sys::process::zircon::*;
@@ -185,6 +185,15 @@ impl Command {
                 t!(cvt(libc::setuid(u as uid_t)));
             }
         }
+        // A session leader is also the leader of its process group, and
+        // cannot be moved to another one, so asking for both fails with
+        // `EPERM` here.
+        if self.get_setsid() {
+            t!(cvt(libc::setsid()));
+        }
+        if let Some(pgroup) = self.get_pgroup() {
+            t!(cvt(libc::setpgid(0, pgroup)));
+        }
         if let Some(ref cwd) = *self.get_cwd() {
             t!(cvt(libc::chdir(cwd.as_ptr())));
         }
@@ -247,14 +256,18 @@ impl Process {
     }
 
     pub fn kill(&mut self) -> io::Result<()> {
+        self.signal(libc::SIGKILL)
+    }
+
+    pub fn signal(&mut self, signal: c_int) -> io::Result<()> {
         // If we've already waited on this process then the pid can be recycled
-        // and used for another process, and we probably shouldn't be killing
+        // and used for another process, and we probably shouldn't be signaling
         // random processes, so just return an error.
         if self.status.is_some() {
             Err(Error::new(ErrorKind::InvalidInput,
-                           "invalid argument: can't kill an exited process"))
+                           "invalid argument: can't signal an exited process"))
         } else {
-            cvt(unsafe { libc::kill(self.pid, libc::SIGKILL) }).map(|_| ())
+            cvt(unsafe { libc::kill(self.pid, signal) }).map(|_| ())
         }
     }
 
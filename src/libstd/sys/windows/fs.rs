@@ -12,7 +12,7 @@ use os::windows::prelude::*;
 
 use ffi::OsString;
 use fmt;
-use io::{self, Error, SeekFrom};
+use io::{self, Error, IoSlice, IoSliceMut, SeekFrom};
 use mem;
 use path::{Path, PathBuf};
 use ptr;
@@ -320,6 +320,10 @@ impl File {
         self.handle.read(buf)
     }
 
+    pub fn read_vectored(&self, bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        self.handle.read_vectored(bufs)
+    }
+
     pub fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
         self.handle.read_at(buf, offset)
     }
@@ -328,6 +332,10 @@ impl File {
         self.handle.write(buf)
     }
 
+    pub fn write_vectored(&self, bufs: &[IoSlice]) -> io::Result<usize> {
+        self.handle.write_vectored(bufs)
+    }
+
     pub fn write_at(&self, buf: &[u8], offset: u64) -> io::Result<usize> {
         self.handle.write_at(buf, offset)
     }
@@ -432,6 +440,47 @@ impl File {
         })?;
         Ok(())
     }
+
+    pub fn lock_exclusive(&self) -> io::Result<()> {
+        self.lock_file(c::LOCKFILE_EXCLUSIVE_LOCK).map(|_| ())
+    }
+
+    pub fn lock_shared(&self) -> io::Result<()> {
+        self.lock_file(0).map(|_| ())
+    }
+
+    pub fn try_lock_exclusive(&self) -> io::Result<bool> {
+        self.lock_file(c::LOCKFILE_EXCLUSIVE_LOCK | c::LOCKFILE_FAIL_IMMEDIATELY)
+    }
+
+    pub fn try_lock_shared(&self) -> io::Result<bool> {
+        self.lock_file(c::LOCKFILE_FAIL_IMMEDIATELY)
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        cvt(unsafe {
+            c::UnlockFile(self.handle.raw(), 0, 0,
+                          c::DWORD::max_value(), c::DWORD::max_value())
+        })?;
+        Ok(())
+    }
+
+    // Locks the whole file, returning `Ok(false)` if a request made with
+    // `LOCKFILE_FAIL_IMMEDIATELY` would have had to wait.
+    fn lock_file(&self, flags: c::DWORD) -> io::Result<bool> {
+        let res = unsafe {
+            let mut overlapped: c::OVERLAPPED = mem::zeroed();
+            cvt(c::LockFileEx(self.handle.raw(), flags, 0,
+                              c::DWORD::max_value(), c::DWORD::max_value(),
+                              &mut overlapped))
+        };
+        match res {
+            Ok(_) => Ok(true),
+            Err(ref e) if flags & c::LOCKFILE_FAIL_IMMEDIATELY != 0 &&
+                          e.raw_os_error() == Some(c::ERROR_LOCK_VIOLATION as i32) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
 }
 
 impl FromInner<c::HANDLE> for File {
@@ -167,10 +167,11 @@
 #![stable(feature = "rust1", since = "1.0.0")]
 
 use any::Any;
-use cell::UnsafeCell;
+use cell::{RefCell, UnsafeCell};
 use ffi::{CStr, CString};
 use fmt;
 use io;
+use marker::PhantomData;
 use panic;
 use panicking;
 use str;
@@ -383,6 +384,57 @@ impl Builder {
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn spawn<F, T>(self, f: F) -> io::Result<JoinHandle<T>> where
         F: FnOnce() -> T, F: Send + 'static, T: Send + 'static
+    {
+        unsafe { self.spawn_inner(f).map(JoinHandle) }
+    }
+
+    /// Spawns a new scoped thread using the settings set through this `Builder`.
+    ///
+    /// Unlike [`Scope::spawn`], this method yields an [`io::Result`] to
+    /// capture any failure to create the thread at the OS level.
+    ///
+    /// [`Scope::spawn`]: ../../std/thread/struct.Scope.html#method.spawn
+    /// [`io::Result`]: ../../std/io/type.Result.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(scoped_threads)]
+    /// use std::thread;
+    ///
+    /// let mut a = vec![1, 2, 3];
+    ///
+    /// thread::scope(|s| {
+    ///     thread::Builder::new()
+    ///         .name("first".into())
+    ///         .spawn_scoped(s, || a.push(4))
+    ///         .unwrap();
+    /// });
+    ///
+    /// assert_eq!(a, [1, 2, 3, 4]);
+    /// ```
+    #[unstable(feature = "scoped_threads", issue = "0")]
+    pub fn spawn_scoped<'scope, 'env, F, T>(self, scope: &'scope Scope<'env>, f: F)
+                                            -> io::Result<ScopedJoinHandle<'scope, T>> where
+        F: FnOnce() -> T, F: Send + 'env, T: Send + 'env
+    {
+        // The thread is joined by the scope at the latest, before anything
+        // borrowed for `'env` can go away.
+        let inner = Arc::new(Mutex::new(Some(unsafe { self.spawn_inner(f)? })));
+        let join = inner.clone();
+        scope.joins.borrow_mut().push(Box::new(move || {
+            match join.lock().unwrap().take() {
+                Some(mut inner) => inner.join().is_err(),
+                None => false,
+            }
+        }));
+        Ok(ScopedJoinHandle { inner, scope: PhantomData })
+    }
+
+    // Spawns the thread without requiring `'static` data. The caller must
+    // make sure the thread is joined before anything it borrows goes away.
+    unsafe fn spawn_inner<'a, F, T>(self, f: F) -> io::Result<JoinInner<T>> where
+        F: FnOnce() -> T, F: Send + 'a, T: Send + 'a
     {
         let Builder { name, stack_size } = self;
 
@@ -399,25 +451,21 @@ impl Builder {
             if let Some(name) = their_thread.cname() {
                 imp::Thread::set_name(name);
             }
-            unsafe {
-                thread_info::set(imp::guard::current(), their_thread);
-                #[cfg(feature = "backtrace")]
-                let try_result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
-                    ::sys_common::backtrace::__rust_begin_short_backtrace(f)
-                }));
-                #[cfg(not(feature = "backtrace"))]
-                let try_result = panic::catch_unwind(panic::AssertUnwindSafe(f));
-                *their_packet.get() = Some(try_result);
-            }
+            thread_info::set(imp::guard::current(), their_thread);
+            #[cfg(feature = "backtrace")]
+            let try_result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+                ::sys_common::backtrace::__rust_begin_short_backtrace(f)
+            }));
+            #[cfg(not(feature = "backtrace"))]
+            let try_result = panic::catch_unwind(panic::AssertUnwindSafe(f));
+            *their_packet.get() = Some(try_result);
         };
 
-        Ok(JoinHandle(JoinInner {
-            native: unsafe {
-                Some(imp::Thread::new(stack_size, Box::new(main))?)
-            },
+        Ok(JoinInner {
+            native: Some(imp::Thread::new(stack_size, Box::new(main))?),
             thread: my_thread,
             packet: Packet(my_packet),
-        }))
+        })
     }
 }
 
@@ -1338,6 +1386,181 @@ impl<T> fmt::Debug for JoinHandle<T> {
     }
 }
 
+////////////////////////////////////////////////////////////////////////////////
+// Scoped threads
+////////////////////////////////////////////////////////////////////////////////
+
+/// Creates a scope for spawning threads that may borrow non-`'static` data.
+///
+/// The closure is passed a [`Scope`], through which threads can be spawned
+/// with [`Scope::spawn`]. Unlike threads spawned with [`thread::spawn`], these
+/// may borrow anything that outlives the call to `scope`, because all of them
+/// are joined before `scope` returns.
+///
+/// # Panics
+///
+/// If the closure panics, the panic is propagated once all the spawned
+/// threads have been joined. Otherwise, if a spawned thread panicked and was
+/// not joined through its [`ScopedJoinHandle`], `scope` panics after joining
+/// the remaining threads.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(scoped_threads)]
+/// use std::thread;
+///
+/// let mut a = vec![1, 2, 3];
+/// let mut x = 0;
+///
+/// thread::scope(|s| {
+///     s.spawn(|| {
+///         println!("hello from the first scoped thread");
+///         // We can borrow `a` here.
+///         println!("{:?}", a);
+///     });
+///     s.spawn(|| {
+///         println!("hello from the second scoped thread");
+///         // We can even mutably borrow `x` here,
+///         // because no other threads are using it.
+///         x += a[0] + a[2];
+///     });
+///     println!("hello from the main thread");
+/// });
+///
+/// // After the scope, we can modify and access our variables again:
+/// a.push(4);
+/// assert_eq!(x, a.len());
+/// ```
+///
+/// [`Scope`]: ../../std/thread/struct.Scope.html
+/// [`Scope::spawn`]: ../../std/thread/struct.Scope.html#method.spawn
+/// [`ScopedJoinHandle`]: ../../std/thread/struct.ScopedJoinHandle.html
+/// [`thread::spawn`]: ../../std/thread/fn.spawn.html
+#[unstable(feature = "scoped_threads", issue = "0")]
+pub fn scope<'env, F, T>(f: F) -> T where F: FnOnce(&Scope<'env>) -> T {
+    let scope = Scope {
+        joins: RefCell::new(Vec::new()),
+        env: PhantomData,
+    };
+
+    // The spawned threads may borrow from the caller, so they have to be
+    // joined even if `f` unwinds.
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| f(&scope)));
+    let a_thread_panicked = scope.join_all();
+
+    match result {
+        Err(payload) => panic::resume_unwind(payload),
+        Ok(_) if a_thread_panicked => panic!("a scoped thread panicked"),
+        Ok(result) => result,
+    }
+}
+
+/// A scope to spawn scoped threads in.
+///
+/// See [`scope`] for details.
+///
+/// [`scope`]: ../../std/thread/fn.scope.html
+#[unstable(feature = "scoped_threads", issue = "0")]
+pub struct Scope<'env> {
+    // Joins a thread unless it was already joined through its handle, and
+    // returns whether it panicked.
+    joins: RefCell<Vec<Box<FnMut() -> bool + 'env>>>,
+    // `'env` must be invariant, so that the spawned threads cannot be given
+    // data that lives for less than the whole scope.
+    env: PhantomData<&'env mut &'env ()>,
+}
+
+impl<'env> Scope<'env> {
+    /// Spawns a new thread within the scope, returning a [`ScopedJoinHandle`]
+    /// for it.
+    ///
+    /// Unlike [`thread::spawn`], the closure may borrow anything that
+    /// outlives the scope. The thread is joined when the scope ends, if it
+    /// was not joined through its handle before.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the OS fails to create a thread; use [`Builder::spawn_scoped`]
+    /// to recover from such errors.
+    ///
+    /// [`ScopedJoinHandle`]: ../../std/thread/struct.ScopedJoinHandle.html
+    /// [`thread::spawn`]: ../../std/thread/fn.spawn.html
+    /// [`Builder::spawn_scoped`]: ../../std/thread/struct.Builder.html#method.spawn_scoped
+    #[unstable(feature = "scoped_threads", issue = "0")]
+    pub fn spawn<'scope, F, T>(&'scope self, f: F) -> ScopedJoinHandle<'scope, T> where
+        F: FnOnce() -> T, F: Send + 'env, T: Send + 'env
+    {
+        Builder::new().spawn_scoped(self, f).unwrap()
+    }
+
+    fn join_all(&self) -> bool {
+        let mut a_thread_panicked = false;
+        for mut join in self.joins.borrow_mut().drain(..) {
+            a_thread_panicked |= join();
+        }
+        a_thread_panicked
+    }
+}
+
+#[unstable(feature = "scoped_threads", issue = "0")]
+impl<'env> fmt::Debug for Scope<'env> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad("Scope { .. }")
+    }
+}
+
+/// An owned permission to join on a scoped thread (block on its termination).
+///
+/// This `struct` is created by the [`Scope::spawn`] method and the
+/// [`Builder::spawn_scoped`] method. Dropping it does not detach the thread:
+/// it is still joined when the scope ends.
+///
+/// [`Scope::spawn`]: ../../std/thread/struct.Scope.html#method.spawn
+/// [`Builder::spawn_scoped`]: ../../std/thread/struct.Builder.html#method.spawn_scoped
+#[unstable(feature = "scoped_threads", issue = "0")]
+pub struct ScopedJoinHandle<'scope, T: 'scope> {
+    // Shared with the scope, which joins the thread if this handle does not.
+    inner: Arc<Mutex<Option<JoinInner<T>>>>,
+    scope: PhantomData<&'scope ()>,
+}
+
+impl<'scope, T> ScopedJoinHandle<'scope, T> {
+    /// Waits for the associated thread to finish.
+    ///
+    /// If the child thread panics, [`Err`] is returned with the parameter given
+    /// to [`panic`], and the panic is not propagated by the scope.
+    ///
+    /// [`Err`]: ../../std/result/enum.Result.html#variant.Err
+    /// [`panic`]: ../../std/macro.panic.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(scoped_threads)]
+    /// use std::thread;
+    ///
+    /// thread::scope(|s| {
+    ///     let t = s.spawn(|| {
+    ///         panic!("oh no");
+    ///     });
+    ///     assert!(t.join().is_err());
+    /// });
+    /// ```
+    #[unstable(feature = "scoped_threads", issue = "0")]
+    pub fn join(self) -> Result<T> {
+        let inner = self.inner.lock().unwrap().take();
+        inner.expect("scoped thread joined twice").join()
+    }
+}
+
+#[unstable(feature = "scoped_threads", issue = "0")]
+impl<'scope, T> fmt::Debug for ScopedJoinHandle<'scope, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad("ScopedJoinHandle { .. }")
+    }
+}
+
 fn _assert_sync_and_send() {
     fn _assert_both<T: Send + Sync>() {}
     _assert_both::<JoinHandle<()>>();
@@ -1595,6 +1818,40 @@ mod tests {
         assert!(thread::current().id() != spawned_id);
     }
 
+    #[test]
+    fn test_scope_borrows() {
+        let mut counts = vec![0; 4];
+        let total = {
+            let data = [1, 2, 3, 4];
+            thread::scope(|s| {
+                for (count, x) in counts.iter_mut().zip(&data) {
+                    s.spawn(move || *count += *x);
+                }
+                data.iter().sum::<i32>()
+            })
+        };
+        assert_eq!(counts, [1, 2, 3, 4]);
+        assert_eq!(total, 10);
+    }
+
+    #[test]
+    fn test_scope_join() {
+        let x = 5;
+        thread::scope(|s| {
+            let t = s.spawn(|| x * 2);
+            assert_eq!(t.join().ok(), Some(10));
+            assert!(s.spawn(|| panic!()).join().is_err());
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "a scoped thread panicked")]
+    fn test_scope_propagates_panic() {
+        thread::scope(|s| {
+            s.spawn(|| panic!());
+        });
+    }
+
     // NOTE: the corresponding test for stderr is in run-pass/thread-stderr, due
     // to the test harness apparently interfering with stderr configuration.
 }
@@ -452,6 +452,18 @@ declare_features! (
 
     // `extern` in paths
     (active, extern_in_paths, "1.23.0", Some(44660)),
+
+    // Allows a test to override the harness's `--test-timeout`
+    (active, test_timeout, "1.25.0", None),
+
+    // Allows `if`, `match`, loops and `let` bindings in constants and `const fn`
+    (active, const_control_flow, "1.25.0", None),
+
+    // Allows `box` in constants and `const fn`
+    (active, const_heap, "1.25.0", None),
+
+    // Allows the `#![const_eval_limit]` attribute
+    (active, const_eval_limit, "1.25.0", None),
 );
 
 declare_features! (
@@ -963,6 +975,11 @@ pub const BUILTIN_ATTRIBUTES: &'static [(&'static str, AttributeType, AttributeG
                                  "allow_fail attribute is currently unstable",
                                  cfg_fn!(allow_fail))),
 
+    ("test_timeout", Normal, Gated(Stability::Unstable,
+                                   "test_timeout",
+                                   "test_timeout attribute is currently unstable",
+                                   cfg_fn!(test_timeout))),
+
     ("rustc_std_internal_symbol", Whitelisted, Gated(Stability::Unstable,
                                      "rustc_attrs",
                                      "this is an internal attribute that will \
@@ -991,6 +1008,11 @@ pub const BUILTIN_ATTRIBUTES: &'static [(&'static str, AttributeType, AttributeG
     ("no_builtins", CrateLevel, Ungated),
     ("recursion_limit", CrateLevel, Ungated),
     ("type_length_limit", CrateLevel, Ungated),
+    ("const_eval_limit", CrateLevel, Gated(Stability::Unstable,
+                                           "const_eval_limit",
+                                           "the `#![const_eval_limit]` attribute is \
+                                            experimental",
+                                           cfg_fn!(const_eval_limit))),
 ];
 
 // cfg(...)'s that are feature gated
@@ -53,6 +53,7 @@ struct Test {
     ignore: bool,
     should_panic: ShouldPanic,
     allow_fail: bool,
+    timeout: Option<u64>,
 }
 
 struct TestCtxt<'a> {
@@ -62,6 +63,8 @@ struct TestCtxt<'a> {
     testfns: Vec<Test>,
     reexport_test_harness_main: Option<Symbol>,
     is_libtest: bool,
+    // whether the crate is compiled with `-C panic=abort`
+    panic_abort: bool,
     ctxt: SyntaxContext,
 
     // top-level re-export submodule, filled out after folding is finished
@@ -73,6 +76,7 @@ struct TestCtxt<'a> {
 pub fn modify_for_testing(sess: &ParseSess,
                           resolver: &mut Resolver,
                           should_test: bool,
+                          panic_abort: bool,
                           krate: ast::Crate,
                           span_diagnostic: &errors::Handler) -> ast::Crate {
     // Check for #[reexport_test_harness_main = "some_name"] which
@@ -84,7 +88,8 @@ pub fn modify_for_testing(sess: &ParseSess,
                                            "reexport_test_harness_main");
 
     if should_test {
-        generate_test_harness(sess, resolver, reexport_test_harness_main, krate, span_diagnostic)
+        generate_test_harness(sess, resolver, reexport_test_harness_main, panic_abort, krate,
+                              span_diagnostic)
     } else {
         krate
     }
@@ -134,6 +139,7 @@ impl<'a> fold::Folder for TestHarnessGenerator<'a> {
                         ignore: is_ignored(&i),
                         should_panic: should_panic(&i, &self.cx),
                         allow_fail: is_allowed_fail(&i),
+                        timeout: test_timeout(&i, &self.cx),
                     };
                     self.cx.testfns.push(test);
                     self.tests.push(i.ident);
@@ -264,6 +270,7 @@ fn mk_reexport_mod(cx: &mut TestCtxt,
 fn generate_test_harness(sess: &ParseSess,
                          resolver: &mut Resolver,
                          reexport_test_harness_main: Option<Symbol>,
+                         panic_abort: bool,
                          krate: ast::Crate,
                          sd: &errors::Handler) -> ast::Crate {
     // Remove the entry points
@@ -280,6 +287,7 @@ fn generate_test_harness(sess: &ParseSess,
         reexport_test_harness_main,
         // NB: doesn't consider the value of `--crate-name` passed on the command line.
         is_libtest: attr::find_crate_name(&krate.attrs).map(|s| s == "test").unwrap_or(false),
+        panic_abort,
         toplevel_reexport: None,
         ctxt: SyntaxContext::empty().apply_mark(mark),
     };
@@ -393,6 +401,23 @@ fn is_allowed_fail(i: &ast::Item) -> bool {
     attr::contains_name(&i.attrs, "allow_fail")
 }
 
+fn test_timeout(i: &ast::Item, cx: &TestCtxt) -> Option<u64> {
+    let attr = match attr::find_by_name(&i.attrs, "test_timeout") {
+        Some(attr) => attr,
+        None => return None,
+    };
+    match attr.value_str().and_then(|secs| secs.as_str().parse::<u64>().ok()) {
+        Some(secs) if secs > 0 => Some(secs),
+        _ => {
+            cx.span_diagnostic.span_err(attr.span(),
+                                        "attribute must be of the form: \
+                                         `#[test_timeout = \"N\"]` where N is a \
+                                         positive number of seconds");
+            None
+        }
+    }
+}
+
 fn should_panic(i: &ast::Item, cx: &TestCtxt) -> ShouldPanic {
     match attr::find_by_name(&i.attrs, "should_panic") {
         Some(attr) => {
@@ -488,9 +513,13 @@ fn mk_main(cx: &mut TestCtxt) -> P<ast::Item> {
     let sp = ignored_span(cx, DUMMY_SP);
     let ecx = &cx.ext_cx;
 
+    // Tests compiled with `panic=abort` need to be run in their own process,
+    // which `test_main_static_abort` arranges for.
+    let test_main = if cx.panic_abort { "test_main_static_abort" } else { "test_main_static" };
+
     // test::test_main_static
     let test_main_path =
-        ecx.path(sp, vec![Ident::from_str("test"), Ident::from_str("test_main_static")]);
+        ecx.path(sp, vec![Ident::from_str("test"), Ident::from_str(test_main)]);
 
     // test::test_main_static(...)
     let test_main_path_expr = ecx.expr_path(test_main_path);
@@ -680,6 +709,10 @@ fn mk_test_desc_and_fn_rec(cx: &TestCtxt, test: &Test) -> P<ast::Expr> {
         }
     };
     let allow_fail_expr = ecx.expr_bool(span, test.allow_fail);
+    let timeout_expr = match test.timeout {
+        Some(secs) => ecx.expr_some(span, ecx.expr_u64(span, secs)),
+        None => ecx.expr_none(span),
+    };
 
     // self::test::TestDesc { ... }
     let desc_expr = ecx.expr_struct(
@@ -688,7 +721,8 @@ fn mk_test_desc_and_fn_rec(cx: &TestCtxt, test: &Test) -> P<ast::Expr> {
         vec![field("name", name_expr),
              field("ignore", ignore_expr),
              field("should_panic", fail_expr),
-             field("allow_fail", allow_fail_expr)]);
+             field("allow_fail", allow_fail_expr),
+             field("timeout", timeout_expr)]);
 
 
     let mut visible_path = match cx.toplevel_reexport {
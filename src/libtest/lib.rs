@@ -38,24 +38,23 @@
 #![feature(fnbox)]
 #![cfg_attr(any(unix, target_os = "cloudabi"), feature(libc))]
 #![feature(set_stdio)]
-#![feature(panic_unwind)]
 #![feature(staged_api)]
 
 extern crate getopts;
 extern crate term;
 #[cfg(any(unix, target_os = "cloudabi"))]
 extern crate libc;
-extern crate panic_unwind;
 
 pub use self::TestFn::*;
 pub use self::ColorConfig::*;
+pub use self::OutputFormat::*;
 pub use self::TestResult::*;
 pub use self::TestName::*;
 use self::TestEvent::*;
 use self::NamePadding::*;
 use self::OutputLocation::*;
 
-use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::panic::{self, catch_unwind, AssertUnwindSafe};
 use std::any::Any;
 use std::boxed::FnBox;
 use std::cmp;
@@ -66,21 +65,40 @@ use std::fs::File;
 use std::io::prelude::*;
 use std::io;
 use std::iter::repeat;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
 use std::sync::mpsc::{channel, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Instant, Duration};
 
 const TEST_WARN_TIMEOUT_S: u64 = 60;
+
+// Number of resamples used to bootstrap the confidence intervals of benchmarks,
+// and the seed used so the intervals are reproducible.
+const BENCH_RESAMPLES: usize = 1000;
+const BENCH_RESAMPLE_SEED: u64 = 0x5eed;
+// Relative changes of the median smaller than this are not reported as
+// improvements or regressions when comparing against a baseline.
+const BENCH_NOISE_THRESHOLD: f64 = 0.01;
 const QUIET_MODE_MAX_COLUMN: usize = 100; // insert a '\n' after 100 tests in quiet mode
 
+// When this environment variable is set the test binary runs the single test
+// it names and reports the result through its exit code, see
+// `run_test_in_spawned_subprocess`.
+const SECONDARY_TEST_INVOKER_VAR: &'static str = "__RUST_TEST_INVOKE";
+
+// Exit codes used by a test running in a child process to report its result.
+const TR_OK: i32 = 50;
+const TR_FAILED: i32 = 51;
+
 // to be used by rustc to compile tests in libtest
 pub mod test {
     pub use {Bencher, TestName, TestResult, TestDesc, TestDescAndFn, TestOpts, TrFailed,
-             TrFailedMsg, TrIgnored, TrOk, Metric, MetricMap, StaticTestFn, StaticTestName,
-             DynTestName, DynTestFn, run_test, test_main, test_main_static, filter_tests,
-             parse_opts, StaticBenchFn, ShouldPanic, Options};
+             TrFailedMsg, TrIgnored, TrOk, TrTimedOut, Metric, MetricMap, StaticTestFn,
+             StaticTestName, DynTestName, DynTestFn, run_test, test_main, test_main_static,
+             test_main_static_abort, filter_tests, parse_opts, StaticBenchFn, ShouldPanic,
+             Options, OutputFormat, BenchOpts};
 }
 
 pub mod stats;
@@ -176,7 +194,8 @@ impl fmt::Debug for TestFn {
 #[derive(Clone)]
 pub struct Bencher {
     mode: BenchMode,
-    summary: Option<stats::Summary>,
+    opts: BenchOpts,
+    samples: Option<Vec<f64>>,
     pub bytes: u64,
 }
 
@@ -201,6 +220,9 @@ pub struct TestDesc {
     pub ignore: bool,
     pub should_panic: ShouldPanic,
     pub allow_fail: bool,
+    /// Number of seconds after which the test is considered to have failed,
+    /// overriding `--test-timeout`.
+    pub timeout: Option<u64>,
 }
 
 #[derive(Debug)]
@@ -228,12 +250,14 @@ impl Metric {
 #[derive(Copy, Clone, Debug)]
 pub struct Options {
     display_output: bool,
+    panic_abort: bool,
 }
 
 impl Options {
     pub fn new() -> Options {
         Options {
             display_output: false,
+            panic_abort: false,
         }
     }
 
@@ -241,17 +265,30 @@ impl Options {
         self.display_output = display_output;
         self
     }
+
+    /// Whether the tests were compiled with `-C panic=abort`. Such tests can
+    /// only be run in their own process, so this implies `--isolate`.
+    pub fn panic_abort(mut self, panic_abort: bool) -> Options {
+        self.panic_abort = panic_abort;
+        self
+    }
 }
 
 // The default console test runner. It accepts the command line
 // arguments and a vector of test_descs.
 pub fn test_main(args: &[String], tests: Vec<TestDescAndFn>, options: Options) {
+    if let Ok(name) = env::var(SECONDARY_TEST_INVOKER_VAR) {
+        run_test_in_spawned_subprocess(&name, tests, options);
+    }
     let mut opts = match parse_opts(args) {
         Some(Ok(o)) => o,
         Some(Err(msg)) => panic!("{:?}", msg),
         None => return,
     };
     opts.options = options;
+    if options.panic_abort {
+        opts.isolate = true;
+    }
     if opts.list {
         if let Err(e) = list_tests_console(&opts, tests) {
             panic!("io error when listing tests: {:?}", e);
@@ -273,6 +310,16 @@ pub fn test_main(args: &[String], tests: Vec<TestDescAndFn>, options: Options) {
 // semantics into parallel test runners, which in turn requires a Vec<>
 // rather than a &[].
 pub fn test_main_static(tests: &[TestDescAndFn]) {
+    test_main_static_with_options(tests, Options::new())
+}
+
+// The entry point used instead of `test_main_static` by test harnesses
+// compiled with `-C panic=abort`.
+pub fn test_main_static_abort(tests: &[TestDescAndFn]) {
+    test_main_static_with_options(tests, Options::new().panic_abort(true))
+}
+
+fn test_main_static_with_options(tests: &[TestDescAndFn], options: Options) {
     let args = env::args().collect::<Vec<_>>();
     let owned_tests = tests.iter()
                            .map(|t| {
@@ -293,7 +340,7 @@ pub fn test_main_static(tests: &[TestDescAndFn]) {
                                }
                            })
                            .collect();
-    test_main(&args, owned_tests, Options::new())
+    test_main(&args, owned_tests, options)
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -303,6 +350,41 @@ pub enum ColorConfig {
     NeverColor,
 }
 
+/// Format of the output produced while running tests.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Verbose output, one line per test.
+    Pretty,
+    /// Quiet output, one character per test.
+    Terse,
+    /// One JSON object per line for every test event.
+    Json,
+}
+
+/// Options controlling how benchmarks are measured and compared.
+#[derive(Clone, Debug)]
+pub struct BenchOpts {
+    /// How long each benchmark runs before measurements are taken.
+    pub warm_up: Duration,
+    /// Number of samples taken in each measurement round.
+    pub samples: usize,
+    /// File the samples of every benchmark are saved to.
+    pub save_baseline: Option<PathBuf>,
+    /// File of samples saved by an earlier run to compare against.
+    pub baseline: Option<PathBuf>,
+}
+
+impl BenchOpts {
+    pub fn new() -> BenchOpts {
+        BenchOpts {
+            warm_up: Duration::new(0, 0),
+            samples: 50,
+            save_baseline: None,
+            baseline: None,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct TestOpts {
     pub list: bool,
@@ -314,8 +396,12 @@ pub struct TestOpts {
     pub logfile: Option<PathBuf>,
     pub nocapture: bool,
     pub color: ColorConfig,
-    pub quiet: bool,
+    pub format: OutputFormat,
     pub test_threads: Option<usize>,
+    pub test_timeout: Option<Duration>,
+    pub isolate: bool,
+    pub shuffle_seed: Option<u64>,
+    pub bench_opts: BenchOpts,
     pub skip: Vec<String>,
     pub options: Options,
 }
@@ -333,8 +419,12 @@ impl TestOpts {
             logfile: None,
             nocapture: false,
             color: AutoColor,
-            quiet: false,
+            format: OutputFormat::Pretty,
             test_threads: None,
+            test_timeout: None,
+            isolate: false,
+            shuffle_seed: None,
+            bench_opts: BenchOpts::new(),
             skip: vec![],
             options: Options::new(),
         }
@@ -357,6 +447,22 @@ fn optgroups() -> getopts::Options {
                                    task, allow printing directly")
         .optopt("", "test-threads", "Number of threads used for running tests \
                                      in parallel", "n_threads")
+        .optopt("", "test-timeout", "Fail tests that run for longer than this many \
+                                     seconds", "SECONDS")
+        .optflag("", "isolate", "Run each test in its own process, so that tests which \
+                                 crash or exceed their timeout are killed and reported \
+                                 without affecting the rest of the run")
+        .optflag("", "test-timeout-kill", "Alias for --isolate")
+        .optopt("", "bench-warm-up", "Run each benchmark for this many milliseconds \
+                                      before measuring it", "MS")
+        .optopt("", "bench-samples", "Number of samples taken in each measurement \
+                                      round of a benchmark (default: 50)", "N")
+        .optopt("", "save-baseline", "Save the samples of every benchmark to PATH", "PATH")
+        .optopt("", "baseline", "Compare every benchmark against the samples saved \
+                                 in PATH by --save-baseline", "PATH")
+        .optflag("", "shuffle", "Run tests in a random order")
+        .optopt("", "shuffle-seed", "Run tests in a random order determined by SEED, \
+                                     implies --shuffle", "SEED")
         .optmulti("", "skip", "Skip tests whose names contain FILTER (this flag can \
                                be used multiple times)","FILTER")
         .optflag("q", "quiet", "Display one character per test instead of one line")
@@ -364,7 +470,11 @@ fn optgroups() -> getopts::Options {
         .optopt("", "color", "Configure coloring of output:
             auto   = colorize if stdout is a tty and tests are run on serially (default);
             always = always colorize output;
-            never  = never colorize output;", "auto|always|never");
+            never  = never colorize output;", "auto|always|never")
+        .optopt("", "format", "Configure formatting of output:
+            pretty = Print verbose output;
+            terse  = Display one character per test;
+            json   = Output one JSON object per test event;", "pretty|terse|json");
     return opts
 }
 
@@ -379,10 +489,30 @@ By default, all tests are run in parallel. This can be altered with the
 --test-threads flag or the RUST_TEST_THREADS environment variable when running
 tests (set it to 1).
 
+Benchmarks report the median time per iteration together with its 95%
+confidence interval and the outliers found among the samples. The samples of a
+run can be saved with --save-baseline and a later run compared against them
+with --baseline, which reports whether each benchmark improved or regressed.
+
+Tests run in alphabetical order unless --shuffle is given, in which case the
+seed of the random order is printed at the end of the run so that it can be
+replayed with --shuffle-seed.
+
+Tests normally run on threads of a single process. With --isolate every test
+is run in a new process instead, so a test that aborts, is killed by a signal
+or exits the process is reported as a failure and the other tests still run.
+
 All tests have their standard output and standard error captured by default.
 This can be overridden with the --nocapture flag or setting RUST_TEST_NOCAPTURE
 environment variable to a value other than "0". Logging is not captured by default.
 
+Passing --format json prints one JSON object per line for every test event
+(suite start, test start, test result, benchmark result and suite summary)
+instead of the human readable output. With --list, it prints a "discovered"
+event for every test and benchmark followed by their counts. The output of the
+tests is always captured with --format json, so it can't be combined with
+--nocapture.
+
 Test Attributes:
 
     #[test]        - Indicates a function is a test to be run. This function
@@ -396,7 +526,9 @@ Test Attributes:
     #[ignore]      - When applied to a function which is already attributed as a
                      test, then the test runner will ignore these tests during
                      normal test runs. Running with --ignored will run these
-                     tests."#,
+                     tests.
+    #[test_timeout = "N"] - This test fails if it runs for longer than N
+                     seconds, regardless of the --test-timeout flag."#,
              usage = options.usage(&message));
 }
 
@@ -453,6 +585,65 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
             None,
     };
 
+    let test_timeout = match matches.opt_str("test-timeout") {
+        Some(secs) =>
+            match secs.parse::<u64>() {
+                Ok(0) =>
+                    return Some(Err(format!("argument for --test-timeout must not be 0"))),
+                Ok(n) => Some(Duration::from_secs(n)),
+                Err(e) =>
+                    return Some(Err(format!("argument for --test-timeout must be a number > 0 \
+                                             (error: {})", e)))
+            },
+        None =>
+            None,
+    };
+
+    let warm_up = match matches.opt_str("bench-warm-up") {
+        Some(ms) =>
+            match ms.parse::<u64>() {
+                Ok(ms) => Duration::from_millis(ms),
+                Err(e) =>
+                    return Some(Err(format!("argument for --bench-warm-up must be a number \
+                                             (error: {})", e)))
+            },
+        None =>
+            Duration::new(0, 0),
+    };
+
+    let samples = match matches.opt_str("bench-samples") {
+        Some(n_str) =>
+            match n_str.parse::<usize>() {
+                Ok(n) if n < 2 =>
+                    return Some(Err(format!("argument for --bench-samples must be at least 2"))),
+                Ok(n) => n,
+                Err(e) =>
+                    return Some(Err(format!("argument for --bench-samples must be a number \
+                                             (error: {})", e)))
+            },
+        None =>
+            BenchOpts::new().samples,
+    };
+
+    let bench_opts = BenchOpts {
+        warm_up,
+        samples,
+        save_baseline: matches.opt_str("save-baseline").map(PathBuf::from),
+        baseline: matches.opt_str("baseline").map(PathBuf::from),
+    };
+
+    let shuffle_seed = match matches.opt_str("shuffle-seed") {
+        Some(seed) =>
+            match seed.parse::<u64>() {
+                Ok(seed) => Some(seed),
+                Err(e) =>
+                    return Some(Err(format!("argument for --shuffle-seed must be a number \
+                                             (error: {})", e)))
+            },
+        None if matches.opt_present("shuffle") => Some(random_seed()),
+        None => None,
+    };
+
     let color = match matches.opt_str("color").as_ref().map(|s| &**s) {
         Some("auto") | None => AutoColor,
         Some("always") => AlwaysColor,
@@ -465,6 +656,28 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
         }
     };
 
+    let format = match matches.opt_str("format").as_ref().map(|s| &**s) {
+        None if quiet => OutputFormat::Terse,
+        Some("pretty") | None => OutputFormat::Pretty,
+        Some("terse") => OutputFormat::Terse,
+        Some("json") => OutputFormat::Json,
+
+        Some(v) => {
+            return Some(Err(format!("argument for --format must be pretty, terse, or json (was \
+                                     {})",
+                                    v)))
+        }
+    };
+
+    // The output of the tests would end up in between the JSON events, so the
+    // output is always captured, and reported in the events, with `json`.
+    if nocapture && format == OutputFormat::Json {
+        if matches.opt_present("nocapture") {
+            return Some(Err("--nocapture can't be used with --format json".to_string()));
+        }
+        nocapture = false;
+    }
+
     let test_opts = TestOpts {
         list,
         filter,
@@ -475,8 +688,12 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
         logfile,
         nocapture,
         color,
-        quiet,
+        format,
         test_threads,
+        test_timeout,
+        isolate: matches.opt_present("isolate") || matches.opt_present("test-timeout-kill"),
+        shuffle_seed,
+        bench_opts,
         skip: matches.opt_strs("skip"),
         options: Options::new(),
     };
@@ -487,9 +704,98 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
 #[derive(Clone, PartialEq)]
 pub struct BenchSamples {
     ns_iter_summ: stats::Summary,
+    // 95% confidence interval of the median
+    ns_iter_median_ci: (f64, f64),
+    outliers: stats::Outliers,
+    samples: Vec<f64>,
     mb_s: usize,
 }
 
+/// How a benchmark changed compared to the baseline it was compared against.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct BenchComparison {
+    /// Relative change of the median, e.g. `0.1` if it got 10% slower.
+    /// `None` if the median of the baseline is zero.
+    pub change: Option<f64>,
+    /// 95% confidence interval of `change`, `None` if the median of any of
+    /// the resampled baselines is zero.
+    pub change_ci: Option<(f64, f64)>,
+    pub verdict: BenchVerdict,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BenchVerdict {
+    Improved,
+    Regressed,
+    NoChange,
+    /// The relative change can't be computed, e.g. because the benchmark
+    /// took no time at all in the baseline.
+    Inconclusive,
+}
+
+impl BenchComparison {
+    pub fn new(baseline: &[f64], samples: &[f64]) -> BenchComparison {
+        use stats::Stats;
+
+        let baseline_median = baseline.median();
+        let change = if baseline_median == 0.0 {
+            None
+        } else {
+            Some(samples.median() / baseline_median - 1.0)
+        };
+        let change_ci = stats::bootstrap_median_change_ci(baseline,
+                                                          samples,
+                                                          BENCH_RESAMPLES,
+                                                          95.0,
+                                                          BENCH_RESAMPLE_SEED);
+        // Resamples with a zero median make the bounds infinite or NaN.
+        let change_ci = if change_ci.0.is_finite() && change_ci.1.is_finite() {
+            Some(change_ci)
+        } else {
+            None
+        };
+        // Only changes that are both statistically significant and large
+        // enough to matter are reported.
+        let verdict = match (change, change_ci) {
+            (Some(_), Some((lower, _))) if lower > BENCH_NOISE_THRESHOLD => {
+                BenchVerdict::Regressed
+            }
+            (Some(_), Some((_, upper))) if upper < -BENCH_NOISE_THRESHOLD => {
+                BenchVerdict::Improved
+            }
+            (Some(_), Some(_)) => BenchVerdict::NoChange,
+            _ => BenchVerdict::Inconclusive,
+        };
+        BenchComparison {
+            change,
+            change_ci,
+            verdict,
+        }
+    }
+}
+
+/// How the process of a test run with `--isolate` ended when the test did
+/// not report a result.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Crash {
+    /// The process exited on its own, e.g. through `process::exit`.
+    Exit(i32),
+    /// The process aborted.
+    Abort,
+    /// The process was terminated by a signal.
+    Signal(i32),
+}
+
+impl fmt::Display for Crash {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Crash::Exit(code) => write!(f, "exited with status code {}", code),
+            Crash::Abort => f.write_str("aborted"),
+            Crash::Signal(signal) => write!(f, "terminated by signal {}", signal),
+        }
+    }
+}
+
 #[derive(Clone, PartialEq)]
 pub enum TestResult {
     TrOk,
@@ -497,6 +803,8 @@ pub enum TestResult {
     TrFailedMsg(String),
     TrIgnored,
     TrAllowedFail,
+    TrTimedOut,
+    TrCrashed(Crash),
     TrBench(BenchSamples),
 }
 
@@ -511,7 +819,7 @@ struct ConsoleTestState<T> {
     log_out: Option<File>,
     out: OutputLocation<T>,
     use_color: bool,
-    quiet: bool,
+    format: OutputFormat,
     total: usize,
     passed: usize,
     failed: usize,
@@ -523,6 +831,11 @@ struct ConsoleTestState<T> {
     failures: Vec<(TestDesc, Vec<u8>)>,
     not_failures: Vec<(TestDesc, Vec<u8>)>,
     max_name_len: usize, // number of columns to fill when aligning names
+    shuffle_seed: Option<u64>,
+    // samples of a previous run to compare benchmarks against
+    baseline: Option<BTreeMap<String, Vec<f64>>>,
+    save_baseline: Option<PathBuf>,
+    bench_samples: Vec<(String, Vec<f64>)>,
     options: Options,
 }
 
@@ -536,12 +849,16 @@ impl<T: Write> ConsoleTestState<T> {
             None => Raw(io::stdout()),
             Some(t) => Pretty(t),
         };
+        let baseline = match opts.bench_opts.baseline {
+            Some(ref path) => Some(load_baseline(path)?),
+            None => None,
+        };
 
         Ok(ConsoleTestState {
             out,
             log_out,
             use_color: use_color(opts),
-            quiet: opts.quiet,
+            format: opts.format,
             total: 0,
             passed: 0,
             failed: 0,
@@ -553,6 +870,10 @@ impl<T: Write> ConsoleTestState<T> {
             failures: Vec::new(),
             not_failures: Vec::new(),
             max_name_len: 0,
+            shuffle_seed: opts.shuffle_seed,
+            baseline,
+            save_baseline: opts.bench_opts.save_baseline.clone(),
+            bench_samples: Vec::new(),
             options: opts.options,
         })
     }
@@ -573,13 +894,21 @@ impl<T: Write> ConsoleTestState<T> {
         self.write_short_result("FAILED (allowed)", "a", term::color::YELLOW)
     }
 
+    pub fn write_timed_out(&mut self) -> io::Result<()> {
+        self.write_short_result("FAILED (timed out)", "T", term::color::RED)
+    }
+
+    pub fn write_crashed(&mut self) -> io::Result<()> {
+        self.write_short_result("FAILED (crashed)", "C", term::color::RED)
+    }
+
     pub fn write_bench(&mut self) -> io::Result<()> {
         self.write_pretty("bench", term::color::CYAN)
     }
 
     pub fn write_short_result(&mut self, verbose: &str, quiet: &str, color: term::color::Color)
                               -> io::Result<()> {
-        if self.quiet {
+        if self.format == OutputFormat::Terse {
             self.write_pretty(quiet, color)?;
             if self.current_test_count() % QUIET_MODE_MAX_COLUMN == QUIET_MODE_MAX_COLUMN - 1 {
                 // we insert a new line every 100 dots in order to flush the
@@ -627,8 +956,36 @@ impl<T: Write> ConsoleTestState<T> {
         }
     }
 
+    fn write_json<S: AsRef<str>>(&mut self, s: S) -> io::Result<()> {
+        self.write_plain(s)?;
+        self.write_plain("\n")
+    }
+
+    fn write_json_event(&mut self,
+                        ty: &str,
+                        name: &str,
+                        evt: &str,
+                        extra: Option<String>) -> io::Result<()> {
+        if let Some(extra) = extra {
+            self.write_json(format!(r#"{{ "type": "{}", "name": "{}", "event": "{}", {} }}"#,
+                                    ty,
+                                    EscapedString(name),
+                                    evt,
+                                    extra))
+        } else {
+            self.write_json(format!(r#"{{ "type": "{}", "name": "{}", "event": "{}" }}"#,
+                                    ty,
+                                    EscapedString(name),
+                                    evt))
+        }
+    }
+
     pub fn write_run_start(&mut self, len: usize) -> io::Result<()> {
         self.total = len;
+        if self.format == OutputFormat::Json {
+            return self.write_json(format!(
+                r#"{{ "type": "suite", "event": "started", "test_count": {} }}"#, len));
+        }
         let noun = if len != 1 {
             "tests"
         } else {
@@ -638,7 +995,9 @@ impl<T: Write> ConsoleTestState<T> {
     }
 
     pub fn write_test_start(&mut self, test: &TestDesc, align: NamePadding) -> io::Result<()> {
-        if self.quiet && align != PadOnRight {
+        if self.format == OutputFormat::Json {
+            self.write_json_event("test", test.name.as_slice(), "started", None)
+        } else if self.format == OutputFormat::Terse && align != PadOnRight {
             Ok(())
         } else {
             let name = test.padded_name(self.max_name_len, align);
@@ -646,20 +1005,129 @@ impl<T: Write> ConsoleTestState<T> {
         }
     }
 
-    pub fn write_result(&mut self, result: &TestResult) -> io::Result<()> {
+    pub fn write_result(&mut self, test: &TestDesc, result: &TestResult, stdout: &[u8])
+                        -> io::Result<()> {
+        if self.format == OutputFormat::Json {
+            return self.write_json_result(test, result, stdout);
+        }
         match *result {
             TrOk => self.write_ok(),
             TrFailed | TrFailedMsg(_) => self.write_failed(),
             TrIgnored => self.write_ignored(),
             TrAllowedFail => self.write_allowed_fail(),
+            TrTimedOut => self.write_timed_out(),
+            TrCrashed(_) => self.write_crashed(),
             TrBench(ref bs) => {
                 self.write_bench()?;
-                self.write_plain(&format!(": {}\n", fmt_bench_samples(bs)))
+                self.write_plain(&format!(": {}", fmt_bench_samples(bs)))?;
+                if let Some(cmp) = self.compare_to_baseline(test, bs) {
+                    self.write_plain(&format!(" ({})", fmt_bench_comparison(&cmp)))?;
+                }
+                self.write_plain("\n")?;
+                if self.format == OutputFormat::Pretty && bs.outliers.total() > 0 {
+                    self.write_plain(&format!("    {}\n", fmt_outliers(bs)))?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn compare_to_baseline(&self, test: &TestDesc, bs: &BenchSamples)
+                           -> Option<BenchComparison> {
+        let baseline = self.baseline.as_ref()?.get(test.name.as_slice())?;
+        if baseline.is_empty() || bs.samples.is_empty() {
+            return None;
+        }
+        Some(BenchComparison::new(baseline, &bs.samples))
+    }
+
+    fn write_json_result(&mut self, test: &TestDesc, result: &TestResult, stdout: &[u8])
+                         -> io::Result<()> {
+        let name = test.name.as_slice();
+        // Successful tests only report their output when asked to, just
+        // like the human-readable formats do.
+        let stdout = if stdout.is_empty() {
+            None
+        } else {
+            Some(format!(r#""stdout": "{}""#, EscapedString(String::from_utf8_lossy(stdout))))
+        };
+        match *result {
+            TrOk => {
+                let stdout = if self.options.display_output { stdout } else { None };
+                self.write_json_event("test", name, "ok", stdout)
+            }
+            TrFailed => self.write_json_event("test", name, "failed", stdout),
+            TrFailedMsg(ref msg) => {
+                let message = format!(r#""message": "{}""#, EscapedString(msg));
+                let extra = match stdout {
+                    Some(stdout) => format!("{}, {}", stdout, message),
+                    None => message,
+                };
+                self.write_json_event("test", name, "failed", Some(extra))
+            }
+            TrIgnored => self.write_json_event("test", name, "ignored", None),
+            TrAllowedFail => self.write_json_event("test", name, "allowed_failure", stdout),
+            TrTimedOut => self.write_json_event("test", name, "timed_out", stdout),
+            TrCrashed(crash) => {
+                let reason = format!(r#""crash": "{}""#, crash);
+                let extra = match stdout {
+                    Some(stdout) => format!("{}, {}", stdout, reason),
+                    None => reason,
+                };
+                self.write_json_event("test", name, "crashed", Some(extra))
+            }
+            TrBench(ref bs) => {
+                let median = bs.ns_iter_summ.median as usize;
+                let deviation = (bs.ns_iter_summ.max - bs.ns_iter_summ.min) as usize;
+                let mbps = if bs.mb_s == 0 {
+                    "".into()
+                } else {
+                    format!(r#", "mib_per_second": {}"#, bs.mb_s)
+                };
+                let comparison = match self.compare_to_baseline(test, bs) {
+                    Some(cmp) => {
+                        // JSON has no representation for NaN or infinity.
+                        let json_f64 = |x: Option<f64>| match x {
+                            Some(x) => x.to_string(),
+                            None => "null".to_string(),
+                        };
+                        format!(r#", "change": {}, "change_lower": {}, "change_upper": {}, "#,
+                                json_f64(cmp.change),
+                                json_f64(cmp.change_ci.map(|ci| ci.0)),
+                                json_f64(cmp.change_ci.map(|ci| ci.1))) +
+                        &format!(r#""verdict": "{}""#, match cmp.verdict {
+                            BenchVerdict::Improved => "improved",
+                            BenchVerdict::Regressed => "regressed",
+                            BenchVerdict::NoChange => "no_change",
+                            BenchVerdict::Inconclusive => "inconclusive",
+                        })
+                    }
+                    None => "".into(),
+                };
+                self.write_json(format!(
+                    "{{ \"type\": \"bench\", \
+                        \"name\": \"{}\", \
+                        \"median\": {}, \
+                        \"deviation\": {}, \
+                        \"median_lower\": {}, \
+                        \"median_upper\": {}, \
+                        \"outliers\": {}{}{} }}",
+                    EscapedString(name),
+                    median,
+                    deviation,
+                    bs.ns_iter_median_ci.0,
+                    bs.ns_iter_median_ci.1,
+                    bs.outliers.total(),
+                    mbps,
+                    comparison))
             }
         }
     }
 
     pub fn write_timeout(&mut self, desc: &TestDesc) -> io::Result<()> {
+        if self.format == OutputFormat::Json {
+            return self.write_json_event("test", desc.name.as_slice(), "timeout", None);
+        }
         self.write_plain(&format!("test {} has been running for over {} seconds\n",
                                   desc.name,
                                   TEST_WARN_TIMEOUT_S))
@@ -682,6 +1150,8 @@ impl<T: Write> ConsoleTestState<T> {
                         TrFailedMsg(ref msg) => format!("failed: {}", msg),
                         TrIgnored => "ignored".to_owned(),
                         TrAllowedFail => "failed (allowed)".to_owned(),
+                        TrTimedOut => "failed (timed out)".to_owned(),
+                        TrCrashed(crash) => format!("failed: test process {}", crash),
                         TrBench(ref bs) => fmt_bench_samples(bs),
                     },
                     test.name))
@@ -745,15 +1215,49 @@ impl<T: Write> ConsoleTestState<T> {
 
     pub fn write_run_finish(&mut self) -> io::Result<bool> {
         assert!(self.current_test_count() == self.total);
+        let success = self.failed == 0;
+
+        if let Some(ref path) = self.save_baseline {
+            save_baseline(path, &self.bench_samples)?;
+        }
+
+        if self.format == OutputFormat::Json {
+            self.write_json(format!(
+                "{{ \"type\": \"suite\", \
+                    \"event\": \"{}\", \
+                    \"passed\": {}, \
+                    \"failed\": {}, \
+                    \"allowed_fail\": {}, \
+                    \"ignored\": {}, \
+                    \"measured\": {}, \
+                    \"filtered_out\": {}{} }}",
+                if success { "ok" } else { "failed" },
+                self.passed,
+                self.failed,
+                self.allowed_fail,
+                self.ignored,
+                self.measured,
+                self.filtered_out,
+                match self.shuffle_seed {
+                    Some(seed) => format!(", \"shuffle_seed\": {}", seed),
+                    None => String::new(),
+                }))?;
+            return Ok(success);
+        }
 
         if self.options.display_output {
             self.write_outputs()?;
         }
-        let success = self.failed == 0;
         if !success {
             self.write_failures()?;
         }
 
+        if let Some(seed) = self.shuffle_seed {
+            self.write_plain(&format!("\ntests were shuffled, rerun with --shuffle-seed {} \
+                                       to run them in the same order\n",
+                                      seed))?;
+        }
+
         self.write_plain("\ntest result: ")?;
         if success {
             // There's no parallelism at this point so it's safe to use color
@@ -784,6 +1288,46 @@ impl<T: Write> ConsoleTestState<T> {
     }
 }
 
+/// A formatting wrapper that escapes a string for inclusion in a JSON
+/// string literal.
+struct EscapedString<S: AsRef<str>>(S);
+
+impl<S: AsRef<str>> fmt::Display for EscapedString<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = self.0.as_ref();
+        let mut start = 0;
+
+        for (i, byte) in s.bytes().enumerate() {
+            let escaped = match byte {
+                b'"' => "\\\"",
+                b'\\' => "\\\\",
+                b'\n' => "\\n",
+                b'\r' => "\\r",
+                b'\t' => "\\t",
+                b'\x08' => "\\b",
+                b'\x0c' => "\\f",
+                0x00...0x1f | 0x7f => "",
+                _ => continue,
+            };
+
+            if start < i {
+                f.write_str(&s[start..i])?;
+            }
+            if escaped.is_empty() {
+                write!(f, "\\u{:04x}", byte)?;
+            } else {
+                f.write_str(escaped)?;
+            }
+            start = i + 1;
+        }
+
+        if start != s.len() {
+            f.write_str(&s[start..])?;
+        }
+        Ok(())
+    }
+}
+
 // Format a number with thousands separators
 fn fmt_thousands_sep(mut n: usize, sep: char) -> String {
     use std::fmt::Write;
@@ -825,10 +1369,93 @@ pub fn fmt_bench_samples(bs: &BenchSamples) -> String {
     output
 }
 
+fn fmt_bench_comparison(cmp: &BenchComparison) -> String {
+    let verdict = match cmp.verdict {
+        BenchVerdict::Improved => "improved",
+        BenchVerdict::Regressed => "regressed",
+        BenchVerdict::NoChange => "no change",
+        BenchVerdict::Inconclusive => "inconclusive",
+    };
+    match (cmp.change, cmp.change_ci) {
+        (Some(change), Some((lower, upper))) => {
+            format!("change: {:+.2}% [{:+.2}%, {:+.2}%], {}",
+                    change * 100.0,
+                    lower * 100.0,
+                    upper * 100.0,
+                    verdict)
+        }
+        (Some(change), None) => format!("change: {:+.2}%, {}", change * 100.0, verdict),
+        (None, _) => format!("change: unknown, {}", verdict),
+    }
+}
+
+fn fmt_outliers(bs: &BenchSamples) -> String {
+    let o = &bs.outliers;
+    let kinds = [(o.low_severe, "low severe"),
+                 (o.low_mild, "low mild"),
+                 (o.high_mild, "high mild"),
+                 (o.high_severe, "high severe")];
+    let kinds = kinds.iter()
+                     .filter(|&&(n, _)| n > 0)
+                     .map(|&(n, kind)| format!("{} {}", n, kind))
+                     .collect::<Vec<_>>();
+    format!("found {} outliers among {} samples ({})",
+            o.total(),
+            bs.samples.len(),
+            kinds.join(", "))
+}
+
+// Baselines are saved as one line per benchmark: the number of samples, the
+// samples and finally the name, all separated by spaces. The name comes last
+// so that it can contain spaces, as dynamic test names can.
+fn load_baseline(path: &Path) -> io::Result<BTreeMap<String, Vec<f64>>> {
+    let mut contents = String::new();
+    File::open(path)?.read_to_string(&mut contents)?;
+
+    let malformed = |msg: String| {
+        io::Error::new(io::ErrorKind::InvalidData,
+                       format!("malformed baseline {}: {}", path.display(), msg))
+    };
+    let mut baseline = BTreeMap::new();
+    for line in contents.lines().filter(|l| !l.trim().is_empty()) {
+        let mut parts = line.splitn(2, ' ');
+        let count = parts.next().unwrap().parse::<usize>().map_err(|e| malformed(e.to_string()))?;
+        let mut parts = parts.next().unwrap_or("").splitn(count + 1, ' ');
+        let samples = parts.by_ref()
+                           .take(count)
+                           .map(|s| s.parse::<f64>())
+                           .collect::<Result<Vec<_>, _>>()
+                           .map_err(|e| malformed(e.to_string()))?;
+        let name = match parts.next() {
+            Some(name) if samples.len() == count => name.to_string(),
+            _ => return Err(malformed(format!("missing name in `{}`", line))),
+        };
+        baseline.insert(name, samples);
+    }
+    Ok(baseline)
+}
+
+fn save_baseline(path: &Path, benches: &[(String, Vec<f64>)]) -> io::Result<()> {
+    let mut out = File::create(path)?;
+    for &(ref name, ref samples) in benches {
+        write!(out, "{}", samples.len())?;
+        for sample in samples {
+            write!(out, " {}", sample)?;
+        }
+        writeln!(out, " {}", name)?;
+    }
+    Ok(())
+}
+
 // List the tests to console, and optionally to logfile. Filters are honored.
 pub fn list_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Result<()> {
     let mut st = ConsoleTestState::new(opts, None::<io::Stdout>)?;
+    list_tests(&mut st, opts, tests)
+}
 
+fn list_tests<T: Write>(st: &mut ConsoleTestState<T>,
+                        opts: &TestOpts,
+                        tests: Vec<TestDescAndFn>) -> io::Result<()> {
     let mut ntest = 0;
     let mut nbench = 0;
 
@@ -837,12 +1464,16 @@ pub fn list_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Res
 
         let TestDescAndFn { desc: TestDesc { name, .. }, testfn } = test;
 
-        let fntype = match testfn {
-            StaticTestFn(..) | DynTestFn(..) => { ntest += 1; "test" },
-            StaticBenchFn(..) | DynBenchFn(..) => { nbench += 1; "benchmark" },
+        let (fntype, json_type) = match testfn {
+            StaticTestFn(..) | DynTestFn(..) => { ntest += 1; ("test", "test") },
+            StaticBenchFn(..) | DynBenchFn(..) => { nbench += 1; ("benchmark", "bench") },
         };
 
-        st.write_plain(format!("{}: {}\n", name, fntype))?;
+        if opts.format == OutputFormat::Json {
+            st.write_json_event(json_type, name.as_slice(), "discovered", None)?;
+        } else {
+            st.write_plain(format!("{}: {}\n", name, fntype))?;
+        }
         st.write_log(format!("{} {}\n", fntype, name))?;
     }
 
@@ -853,13 +1484,18 @@ pub fn list_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Res
         }
     }
 
-    if !opts.quiet {
+    if opts.format == OutputFormat::Pretty {
         if ntest != 0 || nbench != 0 {
             st.write_plain("\n")?;
         }
         st.write_plain(format!("{}, {}\n",
             plural(ntest, "test"),
             plural(nbench, "benchmark")))?;
+    } else if opts.format == OutputFormat::Json {
+        st.write_json(format!(
+            r#"{{ "type": "suite", "event": "discovered", "test_count": {}, "bench_count": {} }}"#,
+            ntest,
+            nbench))?;
     }
 
     Ok(())
@@ -876,7 +1512,7 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
             TeTimeout(ref test) => st.write_timeout(test),
             TeResult(test, result, stdout) => {
                 st.write_log_result(&test, &result)?;
-                st.write_result(&result)?;
+                st.write_result(&test, &result, &stdout)?;
                 match result {
                     TrOk => {
                         st.passed += 1;
@@ -888,6 +1524,7 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
                         st.metrics.insert_metric(test.name.as_slice(),
                                                  bs.ns_iter_summ.median,
                                                  bs.ns_iter_summ.max - bs.ns_iter_summ.min);
+                        st.bench_samples.push((test.name.to_string(), bs.samples));
                         st.measured += 1
                     }
                     TrFailed => {
@@ -902,6 +1539,20 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
                         );
                         st.failures.push((test, stdout));
                     }
+                    TrTimedOut => {
+                        st.failed += 1;
+                        let mut stdout = stdout;
+                        stdout.extend_from_slice(b"note: test did not finish within its timeout");
+                        st.failures.push((test, stdout));
+                    }
+                    TrCrashed(crash) => {
+                        st.failed += 1;
+                        let mut stdout = stdout;
+                        stdout.extend_from_slice(
+                            format!("note: test process {}", crash).as_bytes()
+                        );
+                        st.failures.push((test, stdout));
+                    }
                 }
                 Ok(())
             }
@@ -930,6 +1581,7 @@ fn should_sort_failures_before_printing_them() {
         ignore: false,
         should_panic: ShouldPanic::No,
         allow_fail: false,
+        timeout: None,
     };
 
     let test_b = TestDesc {
@@ -937,13 +1589,14 @@ fn should_sort_failures_before_printing_them() {
         ignore: false,
         should_panic: ShouldPanic::No,
         allow_fail: false,
+        timeout: None,
     };
 
     let mut st = ConsoleTestState {
         log_out: None,
         out: Raw(Vec::new()),
         use_color: false,
-        quiet: false,
+        format: OutputFormat::Pretty,
         total: 0,
         passed: 0,
         failed: 0,
@@ -952,6 +1605,10 @@ fn should_sort_failures_before_printing_them() {
         filtered_out: 0,
         measured: 0,
         max_name_len: 10,
+        shuffle_seed: None,
+        baseline: None,
+        save_baseline: None,
+        bench_samples: Vec::new(),
         metrics: MetricMap::new(),
         failures: vec![(test_b, Vec::new()), (test_a, Vec::new())],
         options: Options::new(),
@@ -969,6 +1626,114 @@ fn should_sort_failures_before_printing_them() {
     assert!(apos < bpos);
 }
 
+#[test]
+fn json_output_escapes_names_and_output() {
+    let test = TestDesc {
+        name: StaticTestName("quote\"d"),
+        ignore: false,
+        should_panic: ShouldPanic::No,
+        allow_fail: false,
+        timeout: None,
+    };
+
+    let mut st = ConsoleTestState {
+        log_out: None,
+        out: Raw(Vec::new()),
+        use_color: false,
+        format: OutputFormat::Json,
+        total: 0,
+        passed: 0,
+        failed: 0,
+        ignored: 0,
+        allowed_fail: 0,
+        filtered_out: 0,
+        measured: 0,
+        max_name_len: 10,
+        shuffle_seed: None,
+        baseline: None,
+        save_baseline: None,
+        bench_samples: Vec::new(),
+        metrics: MetricMap::new(),
+        failures: Vec::new(),
+        options: Options::new(),
+        not_failures: Vec::new(),
+    };
+
+    st.write_test_start(&test, PadNone).unwrap();
+    st.write_result(&test, &TrFailed, b"line\n\tx").unwrap();
+    let s = match st.out {
+        Raw(ref m) => String::from_utf8_lossy(&m[..]),
+        Pretty(_) => unreachable!(),
+    };
+
+    assert_eq!(s, "{ \"type\": \"test\", \"name\": \"quote\\\"d\", \"event\": \"started\" }\n\
+                   { \"type\": \"test\", \"name\": \"quote\\\"d\", \"event\": \"failed\", \
+                   \"stdout\": \"line\\n\\tx\" }\n");
+}
+
+#[test]
+fn json_list_output() {
+    fn test() {}
+    fn bench(_: &mut Bencher) {}
+
+    let tests = vec![TestDescAndFn {
+                         desc: TestDesc {
+                             name: StaticTestName("a"),
+                             ignore: false,
+                             should_panic: ShouldPanic::No,
+                             allow_fail: false,
+                             timeout: None,
+                         },
+                         testfn: StaticTestFn(test),
+                     },
+                     TestDescAndFn {
+                         desc: TestDesc {
+                             name: StaticTestName("b"),
+                             ignore: false,
+                             should_panic: ShouldPanic::No,
+                             allow_fail: false,
+                             timeout: None,
+                         },
+                         testfn: StaticBenchFn(bench),
+                     }];
+    let mut opts = TestOpts::new();
+    opts.format = OutputFormat::Json;
+
+    let mut st = ConsoleTestState {
+        log_out: None,
+        out: Raw(Vec::new()),
+        use_color: false,
+        format: OutputFormat::Json,
+        total: 0,
+        passed: 0,
+        failed: 0,
+        ignored: 0,
+        allowed_fail: 0,
+        filtered_out: 0,
+        measured: 0,
+        max_name_len: 10,
+        shuffle_seed: None,
+        baseline: None,
+        save_baseline: None,
+        bench_samples: Vec::new(),
+        metrics: MetricMap::new(),
+        failures: Vec::new(),
+        options: Options::new(),
+        not_failures: Vec::new(),
+    };
+
+    list_tests(&mut st, &opts, tests).unwrap();
+    let s = match st.out {
+        Raw(ref m) => String::from_utf8_lossy(&m[..]),
+        Pretty(_) => unreachable!(),
+    };
+
+    assert_eq!(s, "{ \"type\": \"test\", \"name\": \"a\", \"event\": \"discovered\" }\n\
+                   { \"type\": \"bench\", \"name\": \"b\", \"event\": \"discovered\" }\n\
+                   { \"type\": \"suite\", \"event\": \"discovered\", \"test_count\": 1, \
+                   \"bench_count\": 1 }\n");
+}
+
 fn use_color(opts: &TestOpts) -> bool {
     match opts.color {
         AutoColor => !opts.nocapture && stdout_isatty(),
@@ -1021,7 +1786,7 @@ pub type MonitorMsg = (TestDesc, TestResult, Vec<u8>);
 pub fn run_tests<F>(opts: &TestOpts, tests: Vec<TestDescAndFn>, mut callback: F) -> io::Result<()>
     where F: FnMut(TestEvent) -> io::Result<()>
 {
-    use std::collections::HashMap;
+    use std::collections::{HashMap, HashSet};
     use std::sync::mpsc::RecvTimeoutError;
 
     let tests_len = tests.len();
@@ -1030,6 +1795,9 @@ pub fn run_tests<F>(opts: &TestOpts, tests: Vec<TestDescAndFn>, mut callback: F)
     if !opts.bench_benchmarks {
         filtered_tests = convert_benchmarks_to_tests(filtered_tests);
     }
+    if let Some(seed) = opts.shuffle_seed {
+        shuffle_tests(seed, &mut filtered_tests);
+    }
 
     let filtered_out = tests_len - filtered_tests.len();
     callback(TeFilteredOut(filtered_out))?;
@@ -1059,7 +1827,13 @@ pub fn run_tests<F>(opts: &TestOpts, tests: Vec<TestDescAndFn>, mut callback: F)
 
     let (tx, rx) = channel::<MonitorMsg>();
 
+    // Tests that should get a warning once they have run for too long.
     let mut running_tests: HashMap<TestDesc, Instant> = HashMap::new();
+    // Tests that are reported as failed once they have run for too long.
+    let mut deadlines: HashMap<TestDesc, Instant> = HashMap::new();
+    // Tests that were reported as timed out but whose thread is still
+    // running; their result is dropped if it ever arrives.
+    let mut abandoned: HashSet<TestDesc> = HashSet::new();
 
     fn get_timed_out_tests(running_tests: &mut HashMap<TestDesc, Instant>) -> Vec<TestDesc> {
         let now = Instant::now();
@@ -1072,8 +1846,9 @@ pub fn run_tests<F>(opts: &TestOpts, tests: Vec<TestDescAndFn>, mut callback: F)
         timed_out
     };
 
-    fn calc_timeout(running_tests: &HashMap<TestDesc, Instant>) -> Option<Duration> {
-        running_tests.values().min().map(|next_timeout| {
+    fn calc_timeout(running_tests: &HashMap<TestDesc, Instant>,
+                    deadlines: &HashMap<TestDesc, Instant>) -> Option<Duration> {
+        running_tests.values().chain(deadlines.values()).min().map(|next_timeout| {
             let now = Instant::now();
             if *next_timeout >= now {
                 *next_timeout - now
@@ -1082,47 +1857,62 @@ pub fn run_tests<F>(opts: &TestOpts, tests: Vec<TestDescAndFn>, mut callback: F)
             }})
     };
 
-    if concurrency == 1 {
-        while !remaining.is_empty() {
+    while pending > 0 || !remaining.is_empty() {
+        while pending < concurrency && !remaining.is_empty() {
             let test = remaining.pop().unwrap();
-            callback(TeWait(test.desc.clone(), test.testfn.padding()))?;
-            run_test(opts, !opts.run_tests, test, tx.clone());
-            let (test, result, stdout) = rx.recv().unwrap();
-            callback(TeResult(test, result, stdout))?;
-        }
-    } else {
-        while pending > 0 || !remaining.is_empty() {
-            while pending < concurrency && !remaining.is_empty() {
-                let test = remaining.pop().unwrap();
-                let timeout = Instant::now() + Duration::from_secs(TEST_WARN_TIMEOUT_S);
+            let now = Instant::now();
+            if concurrency == 1 {
+                // Tests run one at a time, so the name can be printed
+                // before the test starts.
+                callback(TeWait(test.desc.clone(), test.testfn.padding()))?;
+            } else {
+                let timeout = now + Duration::from_secs(TEST_WARN_TIMEOUT_S);
                 running_tests.insert(test.desc.clone(), timeout);
-                run_test(opts, !opts.run_tests, test, tx.clone());
-                pending += 1;
             }
-
-            let mut res;
-            loop {
-                if let Some(timeout) = calc_timeout(&running_tests) {
-                    res = rx.recv_timeout(timeout);
-                    for test in get_timed_out_tests(&mut running_tests) {
-                        callback(TeTimeout(test))?;
-                    }
-                    if res != Err(RecvTimeoutError::Timeout) {
-                        break;
-                    }
-                } else {
-                    res = rx.recv().map_err(|_| RecvTimeoutError::Disconnected);
-                    break;
+            // Tests running in a child process enforce their own timeout.
+            if !opts.isolate {
+                if let Some(timeout) = test_timeout(opts, &test.desc) {
+                    deadlines.insert(test.desc.clone(), now + timeout);
                 }
             }
+            run_test(opts, !opts.run_tests, test, tx.clone());
+            pending += 1;
+        }
 
-            let (desc, result, stdout) = res.unwrap();
-            running_tests.remove(&desc);
+        let res = match calc_timeout(&running_tests, &deadlines) {
+            Some(timeout) => rx.recv_timeout(timeout),
+            None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
 
-            callback(TeWait(desc.clone(), PadNone))?;
-            callback(TeResult(desc, result, stdout))?;
+        for test in get_timed_out_tests(&mut running_tests) {
+            callback(TeTimeout(test))?;
+        }
+        for test in get_timed_out_tests(&mut deadlines) {
+            running_tests.remove(&test);
+            abandoned.insert(test.clone());
+            if concurrency != 1 {
+                callback(TeWait(test.clone(), PadNone))?;
+            }
+            let result = if test.allow_fail { TrAllowedFail } else { TrTimedOut };
+            callback(TeResult(test, result, Vec::new()))?;
             pending -= 1;
         }
+
+        let (desc, result, stdout) = match res {
+            Err(RecvTimeoutError::Timeout) => continue,
+            res => res.unwrap(),
+        };
+        if abandoned.remove(&desc) {
+            continue;
+        }
+        running_tests.remove(&desc);
+        deadlines.remove(&desc);
+
+        if concurrency != 1 {
+            callback(TeWait(desc.clone(), PadNone))?;
+        }
+        callback(TeResult(desc, result, stdout))?;
+        pending -= 1;
     }
 
     if opts.bench_benchmarks {
@@ -1137,6 +1927,12 @@ pub fn run_tests<F>(opts: &TestOpts, tests: Vec<TestDescAndFn>, mut callback: F)
     Ok(())
 }
 
+// The timeout of a test: its own `#[test_timeout]` if it has one, otherwise
+// the one given with `--test-timeout`.
+fn test_timeout(opts: &TestOpts, desc: &TestDesc) -> Option<Duration> {
+    desc.timeout.map(Duration::from_secs).or(opts.test_timeout)
+}
+
 #[allow(deprecated)]
 fn get_concurrency() -> usize {
     return match env::var("RUST_TEST_THREADS") {
@@ -1316,6 +2112,33 @@ pub fn filter_tests(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> Vec<TestDescA
     filtered
 }
 
+// Picks a seed for `--shuffle` that differs from run to run.
+fn random_seed() -> u64 {
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hasher};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let mut hasher = RandomState::new().build_hasher();
+    if let Ok(now) = SystemTime::now().duration_since(UNIX_EPOCH) {
+        hasher.write_u64(now.as_secs());
+        hasher.write_u32(now.subsec_nanos());
+    }
+    hasher.finish()
+}
+
+// Shuffles the tests in place. The order only depends on `seed` and on the
+// order of `tests`, which `filter_tests` sorts, so a seed always reproduces
+// the same run.
+pub fn shuffle_tests(seed: u64, tests: &mut [TestDescAndFn]) {
+    let mut rng = stats::SplitMix64::new(seed);
+
+    // Fisher-Yates
+    for i in (1..tests.len()).rev() {
+        let j = rng.below(i + 1);
+        tests.swap(i, j);
+    }
+}
+
 pub fn convert_benchmarks_to_tests(tests: Vec<TestDescAndFn>) -> Vec<TestDescAndFn> {
     // convert benchmarks to tests, if we're not benchmarking them
     tests.into_iter().map(|x| {
@@ -1396,9 +2219,10 @@ pub fn run_test(opts: &TestOpts,
                 io::set_panic(panicio);
             };
 
-            let test_result = calc_result(&desc, result);
+            let test_result = calc_result(&desc, result.as_ref().map(|_| ()).map_err(|e| &**e));
             let stdout = data.lock().unwrap().to_vec();
-            monitor_ch.send((desc.clone(), test_result, stdout)).unwrap();
+            // A test which timed out may only finish after the run is over.
+            let _ = monitor_ch.send((desc.clone(), test_result, stdout));
         };
 
 
@@ -1421,15 +2245,19 @@ pub fn run_test(opts: &TestOpts,
 
     match testfn {
         DynBenchFn(bencher) => {
-            let bs = ::bench::benchmark(|harness| bencher.run(harness));
+            let bs = ::bench::benchmark(&opts.bench_opts, |harness| bencher.run(harness));
             monitor_ch.send((desc, TrBench(bs), Vec::new())).unwrap();
             return;
         }
         StaticBenchFn(benchfn) => {
-            let bs = ::bench::benchmark(|harness| (benchfn.clone())(harness));
+            let bs = ::bench::benchmark(&opts.bench_opts, |harness| (benchfn.clone())(harness));
             monitor_ch.send((desc, TrBench(bs), Vec::new())).unwrap();
             return;
         }
+        DynTestFn(_) | StaticTestFn(_) if opts.isolate && supports_processes() => {
+            let timeout = test_timeout(opts, &desc);
+            run_test_in_process(desc, timeout, opts.nocapture, monitor_ch)
+        }
         DynTestFn(f) => {
             let cb = move || {
                 __rust_begin_short_backtrace(f)
@@ -1442,13 +2270,179 @@ pub fn run_test(opts: &TestOpts,
     }
 }
 
+fn supports_processes() -> bool {
+    !cfg!(target_os = "emscripten") && !cfg!(target_arch = "wasm32")
+}
+
+// Runs a test by re-executing the current binary with
+// `SECONDARY_TEST_INVOKER_VAR` naming the test, killing the child if it does
+// not finish within `timeout`. With `nocapture` the child writes to the
+// stdout and stderr of this process instead of having its output captured.
+fn run_test_in_process(desc: TestDesc,
+                       timeout: Option<Duration>,
+                       nocapture: bool,
+                       monitor_ch: Sender<MonitorMsg>) {
+    let name = desc.name.clone();
+    let runtest = move || {
+        let (result, output) = match spawn_test_process(&desc, timeout, nocapture) {
+            Ok(res) => res,
+            Err(e) => {
+                let msg = format!("failed to spawn a process for the test: {}", e);
+                (TrFailedMsg(msg), Vec::new())
+            }
+        };
+        let result = match result {
+            TrOk => TrOk,
+            _ if desc.allow_fail => TrAllowedFail,
+            result => result,
+        };
+        monitor_ch.send((desc.clone(), result, output)).unwrap();
+    };
+
+    let cfg = thread::Builder::new().name(match name {
+        DynTestName(ref name) => name.clone(),
+        StaticTestName(name) => name.to_owned(),
+    });
+    cfg.spawn(runtest).unwrap();
+}
+
+fn spawn_test_process(desc: &TestDesc, timeout: Option<Duration>, nocapture: bool)
+                      -> io::Result<(TestResult, Vec<u8>)> {
+    fn read_into<R: Read + Send + 'static>(mut r: R, out: Arc<Mutex<Vec<u8>>>)
+                                           -> thread::JoinHandle<()> {
+        thread::spawn(move || {
+            let mut buf = [0; 4096];
+            loop {
+                match r.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => out.lock().unwrap().extend_from_slice(&buf[..n]),
+                }
+            }
+        })
+    }
+
+    let stdio = || if nocapture { Stdio::inherit() } else { Stdio::piped() };
+    let mut child = Command::new(env::current_exe()?)
+        .args(env::args_os().skip(1))
+        .env(SECONDARY_TEST_INVOKER_VAR, desc.name.as_slice())
+        .stdin(Stdio::null())
+        .stdout(stdio())
+        .stderr(stdio())
+        .spawn()?;
+
+    let output = Arc::new(Mutex::new(Vec::new()));
+    let readers = match (child.stdout.take(), child.stderr.take()) {
+        (Some(stdout), Some(stderr)) => {
+            vec![read_into(stdout, output.clone()), read_into(stderr, output.clone())]
+        }
+        _ => Vec::new(),
+    };
+
+    let status = match timeout {
+        Some(timeout) => {
+            let deadline = Instant::now() + timeout;
+            loop {
+                if let Some(status) = child.try_wait()? {
+                    break Some(status);
+                }
+                if Instant::now() >= deadline {
+                    child.kill()?;
+                    child.wait()?;
+                    break None;
+                }
+                thread::sleep(Duration::from_millis(10));
+            }
+        }
+        None => Some(child.wait()?),
+    };
+
+    for reader in readers {
+        let _ = reader.join();
+    }
+    let output = output.lock().unwrap().to_vec();
+
+    let result = match status {
+        None => TrTimedOut,
+        Some(status) => match status.code() {
+            Some(TR_OK) => TrOk,
+            Some(TR_FAILED) => TrFailed,
+            _ => TrCrashed(crash_kind(status)),
+        },
+    };
+    Ok((result, output))
+}
+
+#[cfg(unix)]
+fn crash_kind(status: process::ExitStatus) -> Crash {
+    use std::os::unix::process::ExitStatusExt;
+
+    match (status.code(), status.signal()) {
+        (_, Some(libc::SIGABRT)) => Crash::Abort,
+        (_, Some(signal)) => Crash::Signal(signal),
+        (Some(code), None) => Crash::Exit(code),
+        (None, None) => unreachable!(),
+    }
+}
+
+#[cfg(not(unix))]
+fn crash_kind(status: process::ExitStatus) -> Crash {
+    // Aborting raises one of these exceptions on Windows.
+    const STATUS_ILLEGAL_INSTRUCTION: i32 = 0xC000001Du32 as i32;
+    const STATUS_STACK_BUFFER_OVERRUN: i32 = 0xC0000409u32 as i32;
+
+    match status.code() {
+        Some(STATUS_ILLEGAL_INSTRUCTION) |
+        Some(STATUS_STACK_BUFFER_OVERRUN) => Crash::Abort,
+        Some(code) => Crash::Exit(code),
+        None => Crash::Abort,
+    }
+}
+
+// Runs the test named by `SECONDARY_TEST_INVOKER_VAR` in the current process
+// and reports its result to the parent through the exit code.
+fn run_test_in_spawned_subprocess(name: &str,
+                                  tests: Vec<TestDescAndFn>,
+                                  options: Options) -> ! {
+    let test = convert_benchmarks_to_tests(tests).into_iter()
+        .find(|test| test.desc.name.as_slice() == name)
+        .unwrap_or_else(|| panic!("couldn't find a test named `{}`", name));
+    let TestDescAndFn { desc, testfn } = test;
+    let testfn: Box<FnBox() + Send> = match testfn {
+        StaticTestFn(f) => Box::new(move || __rust_begin_short_backtrace(f)),
+        DynTestFn(f) => Box::new(move || __rust_begin_short_backtrace(f)),
+        StaticBenchFn(_) | DynBenchFn(_) => unreachable!(),
+    };
+
+    // With `panic=abort` the panic can't be caught, so its result has to be
+    // reported from the panic hook, before the process aborts.
+    if options.panic_abort {
+        let builtin_panic_hook = panic::take_hook();
+        let desc = desc.clone();
+        panic::set_hook(Box::new(move |info| {
+            builtin_panic_hook(info);
+            exit_with_result(calc_result(&desc, Err(info.payload())));
+        }));
+    }
+
+    let result = catch_unwind(AssertUnwindSafe(testfn));
+    exit_with_result(calc_result(&desc, result.as_ref().map(|_| ()).map_err(|e| &**e)))
+}
+
+fn exit_with_result(result: TestResult) -> ! {
+    if let TrFailedMsg(ref msg) = result {
+        eprintln!("note: {}", msg);
+    }
+    let _ = io::stdout().flush();
+    process::exit(if result == TrOk { TR_OK } else { TR_FAILED })
+}
+
 /// Fixed frame used to clean the backtrace with `RUST_BACKTRACE=1`.
 #[inline(never)]
 fn __rust_begin_short_backtrace<F: FnOnce()>(f: F) {
     f()
 }
 
-fn calc_result(desc: &TestDesc, task_result: Result<(), Box<Any + Send>>) -> TestResult {
+fn calc_result(desc: &TestDesc, task_result: Result<(), &(Any + Send)>) -> TestResult {
     match (&desc.should_panic, task_result) {
         (&ShouldPanic::No, Ok(())) |
         (&ShouldPanic::Yes, Err(_)) => TrOk,
@@ -1541,17 +2535,24 @@ impl Bencher {
             return;
         }
 
-        self.summary = Some(iter(&mut inner));
+        self.samples = Some(iter(&self.opts, &mut inner));
     }
 
     pub fn bench<F>(&mut self, mut f: F) -> Option<stats::Summary>
         where F: FnMut(&mut Bencher)
     {
         f(self);
-        return self.summary;
+        return self.samples.as_ref().map(|samples| summarize(samples));
     }
 }
 
+// Summarizes the samples of a benchmark, minimizing the effect of outliers.
+fn summarize(samples: &[f64]) -> stats::Summary {
+    let mut samples = samples.to_vec();
+    stats::winsorize(&mut samples, 5.0);
+    stats::Summary::new(&samples)
+}
+
 fn ns_from_dur(dur: Duration) -> u64 {
     dur.as_secs() * 1_000_000_000 + (dur.subsec_nanos() as u64)
 }
@@ -1567,9 +2568,18 @@ fn ns_iter_inner<T, F>(inner: &mut F, k: u64) -> u64
 }
 
 
-pub fn iter<T, F>(inner: &mut F) -> stats::Summary
+/// Measures `inner`, returning the number of nanoseconds per iteration of
+/// every sample of the last measurement round.
+pub fn iter<T, F>(opts: &BenchOpts, inner: &mut F) -> Vec<f64>
     where F: FnMut() -> T
 {
+    // Let caches, branch predictors and CPU frequency settle before
+    // measuring anything.
+    let warm_up_start = Instant::now();
+    while warm_up_start.elapsed() < opts.warm_up {
+        black_box(inner());
+    }
+
     // Initial bench run to get ballpark figure.
     let ns_single = ns_iter_inner(inner, 1);
 
@@ -1586,24 +2596,23 @@ pub fn iter<T, F>(inner: &mut F) -> stats::Summary
     n = cmp::max(1, n);
 
     let mut total_run = Duration::new(0, 0);
-    let samples: &mut [f64] = &mut [0.0_f64; 50];
+    let mut samples = vec![0.0_f64; opts.samples];
+    let mut samples5 = vec![0.0_f64; opts.samples];
     loop {
         let loop_start = Instant::now();
 
-        for p in &mut *samples {
+        for p in &mut samples {
             *p = ns_iter_inner(inner, n) as f64 / n as f64;
         }
 
-        stats::winsorize(samples, 5.0);
-        let summ = stats::Summary::new(samples);
+        let summ = summarize(&samples);
 
-        for p in &mut *samples {
+        for p in &mut samples5 {
             let ns = ns_iter_inner(inner, 5 * n);
             *p = ns as f64 / (5 * n) as f64;
         }
 
-        stats::winsorize(samples, 5.0);
-        let summ5 = stats::Summary::new(samples);
+        let summ5 = summarize(&samples5);
 
         let loop_run = loop_start.elapsed();
 
@@ -1611,13 +2620,13 @@ pub fn iter<T, F>(inner: &mut F) -> stats::Summary
         // stable median.
         if loop_run > Duration::from_millis(100) && summ.median_abs_dev_pct < 1.0 &&
            summ.median - summ5.median < summ5.median_abs_dev {
-            return summ5;
+            return samples5;
         }
 
         total_run = total_run + loop_run;
         // Longest we ever run for is 3s.
         if total_run > Duration::from_secs(3) {
-            return summ5;
+            return samples5;
         }
 
         // If we overflow here just return the results so far. We check a
@@ -1627,7 +2636,7 @@ pub fn iter<T, F>(inner: &mut F) -> stats::Summary
         n = match n.checked_mul(10) {
             Some(_) => n * 2,
             None => {
-                return summ5;
+                return samples5;
             }
         };
     }
@@ -1636,14 +2645,16 @@ pub fn iter<T, F>(inner: &mut F) -> stats::Summary
 pub mod bench {
     use std::cmp;
     use stats;
-    use super::{Bencher, BenchSamples, BenchMode};
+    use super::{Bencher, BenchSamples, BenchMode, BenchOpts, BENCH_RESAMPLES,
+                BENCH_RESAMPLE_SEED};
 
-    pub fn benchmark<F>(f: F) -> BenchSamples
+    pub fn benchmark<F>(opts: &BenchOpts, f: F) -> BenchSamples
         where F: FnMut(&mut Bencher)
     {
         let mut bs = Bencher {
             mode: BenchMode::Auto,
-            summary: None,
+            opts: opts.clone(),
+            samples: None,
             bytes: 0,
         };
 
@@ -1651,9 +2662,16 @@ pub mod bench {
             Some(ns_iter_summ) => {
                 let ns_iter = cmp::max(ns_iter_summ.median as u64, 1);
                 let mb_s = bs.bytes * 1000 / ns_iter;
+                let samples = bs.samples.unwrap();
 
                 BenchSamples {
                     ns_iter_summ,
+                    ns_iter_median_ci: stats::bootstrap_median_ci(&samples,
+                                                                  BENCH_RESAMPLES,
+                                                                  95.0,
+                                                                  BENCH_RESAMPLE_SEED),
+                    outliers: stats::Outliers::new(&samples),
+                    samples,
                     mb_s: mb_s as usize,
                 }
             }
@@ -1663,6 +2681,9 @@ pub mod bench {
                 let samples: &mut [f64] = &mut [0.0_f64; 1];
                 BenchSamples {
                     ns_iter_summ: stats::Summary::new(samples),
+                    ns_iter_median_ci: (0.0, 0.0),
+                    outliers: stats::Outliers::default(),
+                    samples: Vec::new(),
                     mb_s: 0,
                 }
             }
@@ -1674,7 +2695,8 @@ pub mod bench {
     {
         let mut bs = Bencher {
             mode: BenchMode::Single,
-            summary: None,
+            opts: BenchOpts::new(),
+            samples: None,
             bytes: 0,
         };
         bs.bench(f);
@@ -1683,12 +2705,15 @@ pub mod bench {
 
 #[cfg(test)]
 mod tests {
-    use test::{TrFailed, TrFailedMsg, TrIgnored, TrOk, filter_tests, parse_opts, TestDesc,
-               TestDescAndFn, TestOpts, run_test, MetricMap, StaticTestName, DynTestName,
-               DynTestFn, ShouldPanic};
+    use test::{TrFailed, TrFailedMsg, TrIgnored, TrOk, TrTimedOut, filter_tests, parse_opts,
+               TestDesc, TestDescAndFn, TestOpts, run_test, MetricMap, StaticTestName,
+               DynTestName, DynTestFn, ShouldPanic, OutputFormat};
     use std::sync::mpsc::channel;
+    use std::thread;
+    use std::time::Duration;
+    use {run_tests, shuffle_tests, TestEvent};
     use bench;
-    use Bencher;
+    use {Bencher, BenchOpts, BenchComparison, BenchVerdict};
 
     #[test]
     pub fn do_not_run_ignored_tests() {
@@ -1701,6 +2726,7 @@ mod tests {
                 ignore: true,
                 should_panic: ShouldPanic::No,
                 allow_fail: false,
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(f)),
         };
@@ -1719,6 +2745,7 @@ mod tests {
                 ignore: true,
                 should_panic: ShouldPanic::No,
                 allow_fail: false,
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(f)),
         };
@@ -1739,6 +2766,7 @@ mod tests {
                 ignore: false,
                 should_panic: ShouldPanic::Yes,
                 allow_fail: false,
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(f)),
         };
@@ -1759,6 +2787,7 @@ mod tests {
                 ignore: false,
                 should_panic: ShouldPanic::YesWithMessage("error message"),
                 allow_fail: false,
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(f)),
         };
@@ -1781,6 +2810,7 @@ mod tests {
                 ignore: false,
                 should_panic: ShouldPanic::YesWithMessage(expected),
                 allow_fail: false,
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(f)),
         };
@@ -1799,6 +2829,7 @@ mod tests {
                 ignore: false,
                 should_panic: ShouldPanic::Yes,
                 allow_fail: false,
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(f)),
         };
@@ -1818,6 +2849,117 @@ mod tests {
         assert!((opts.run_ignored));
     }
 
+    #[test]
+    fn test_timeout_fails_slow_tests() {
+        // The slow test blocks until it is released after the assertions, so
+        // that its thread doesn't outlive this test.
+        let (release_tx, release_rx) = channel::<()>();
+        let (done_tx, done_rx) = channel::<()>();
+        let slow = move || {
+            release_rx.recv().unwrap();
+            done_tx.send(()).unwrap();
+        };
+        fn fast() {}
+        let tests = vec![TestDescAndFn {
+                             desc: TestDesc {
+                                 name: StaticTestName("fast"),
+                                 ignore: false,
+                                 should_panic: ShouldPanic::No,
+                                 allow_fail: false,
+                                 timeout: None,
+                             },
+                             testfn: DynTestFn(Box::new(fast)),
+                         },
+                         TestDescAndFn {
+                             desc: TestDesc {
+                                 name: StaticTestName("slow"),
+                                 ignore: false,
+                                 should_panic: ShouldPanic::No,
+                                 allow_fail: false,
+                                 timeout: None,
+                             },
+                             testfn: DynTestFn(Box::new(slow)),
+                         }];
+        let opts = TestOpts {
+            run_tests: true,
+            test_timeout: Some(Duration::from_millis(100)),
+            ..TestOpts::new()
+        };
+
+        let mut results = Vec::new();
+        run_tests(&opts, tests, |event| {
+            if let TestEvent::TeResult(desc, result, _) = event {
+                results.push((desc.name.to_string(), result));
+            }
+            Ok(())
+        }).unwrap();
+        results.sort_by(|a, b| a.0.cmp(&b.0));
+
+        assert_eq!(results.len(), 2);
+        assert!(results[0] == ("fast".to_string(), TrOk));
+        assert!(results[1] == ("slow".to_string(), TrTimedOut));
+
+        release_tx.send(()).unwrap();
+        done_rx.recv().unwrap();
+    }
+
+    #[test]
+    fn parse_test_timeout_flag() {
+        let args = vec!["progname".to_string(), "--test-timeout".to_string(), "5".to_string()];
+        let opts = match parse_opts(&args) {
+            Some(Ok(o)) => o,
+            _ => panic!("Malformed arg in parse_test_timeout_flag"),
+        };
+        assert_eq!(opts.test_timeout, Some(Duration::from_secs(5)));
+        assert!(!opts.isolate);
+
+        let args = vec!["progname".to_string(), "--test-timeout".to_string(), "0".to_string()];
+        assert!(parse_opts(&args).unwrap().is_err());
+
+        let args = vec!["progname".to_string(), "--test-timeout-kill".to_string()];
+        let opts = match parse_opts(&args) {
+            Some(Ok(o)) => o,
+            _ => panic!("Malformed arg in parse_test_timeout_flag"),
+        };
+        assert!(opts.isolate);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn crash_kind_of_exit_status() {
+        use std::process::Command;
+        use {crash_kind, Crash};
+
+        let status = Command::new("sh").args(&["-c", "exit 3"]).status().unwrap();
+        assert_eq!(crash_kind(status), Crash::Exit(3));
+        let status = Command::new("sh").args(&["-c", "kill -ABRT $$"]).status().unwrap();
+        assert_eq!(crash_kind(status), Crash::Abort);
+        let status = Command::new("sh").args(&["-c", "kill -KILL $$"]).status().unwrap();
+        assert_eq!(crash_kind(status), Crash::Signal(9));
+    }
+
+    #[test]
+    fn parse_format_flag() {
+        let parse = |args: &[&str]| {
+            let args = args.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+            match parse_opts(&args) {
+                Some(Ok(o)) => o.format,
+                _ => panic!("Malformed arg in parse_format_flag"),
+            }
+        };
+        assert_eq!(parse(&["progname"]), OutputFormat::Pretty);
+        assert_eq!(parse(&["progname", "-q"]), OutputFormat::Terse);
+        assert_eq!(parse(&["progname", "--format", "json"]), OutputFormat::Json);
+        assert_eq!(parse(&["progname", "-q", "--format", "pretty"]), OutputFormat::Pretty);
+        assert!(parse_opts(&["progname".to_string(),
+                             "--format".to_string(),
+                             "xml".to_string()]).unwrap().is_err());
+        assert!(parse_opts(&["progname".to_string(),
+                             "--format".to_string(),
+                             "json".to_string(),
+                             "--nocapture".to_string()]).unwrap().is_err());
+    }
+
     #[test]
     pub fn filter_for_ignored_option() {
         // When we run ignored tests the test filter should filter out all the
@@ -1833,6 +2975,7 @@ mod tests {
                                  ignore: true,
                                  should_panic: ShouldPanic::No,
                                  allow_fail: false,
+                                 timeout: None,
                              },
                              testfn: DynTestFn(Box::new(move || {})),
                          },
@@ -1842,6 +2985,7 @@ mod tests {
                                  ignore: false,
                                  should_panic: ShouldPanic::No,
                                  allow_fail: false,
+                                 timeout: None,
                              },
                              testfn: DynTestFn(Box::new(move || {})),
                          }];
@@ -1866,6 +3010,7 @@ mod tests {
                     ignore: false,
                     should_panic: ShouldPanic::No,
                     allow_fail: false,
+                    timeout: None,
                 },
                 testfn: DynTestFn(Box::new(move || {}))
             })
@@ -1948,6 +3093,7 @@ mod tests {
                         ignore: false,
                         should_panic: ShouldPanic::No,
                         allow_fail: false,
+                        timeout: None,
                     },
                     testfn: DynTestFn(Box::new(testfn)),
                 };
@@ -1972,6 +3118,63 @@ mod tests {
         }
     }
 
+    #[test]
+    pub fn shuffle_is_reproducible() {
+        fn tests() -> Vec<TestDescAndFn> {
+            (0..20).map(|i| TestDescAndFn {
+                desc: TestDesc {
+                    name: DynTestName(format!("test{:02}", i)),
+                    ignore: false,
+                    should_panic: ShouldPanic::No,
+                    allow_fail: false,
+                    timeout: None,
+                },
+                testfn: DynTestFn(Box::new(move || {})),
+            })
+            .collect()
+        }
+        fn names(tests: &[TestDescAndFn]) -> Vec<String> {
+            tests.iter().map(|t| t.desc.name.to_string()).collect()
+        }
+
+        let mut a = tests();
+        let mut b = tests();
+        let mut c = tests();
+        shuffle_tests(42, &mut a);
+        shuffle_tests(42, &mut b);
+        shuffle_tests(43, &mut c);
+
+        assert_eq!(names(&a), names(&b));
+        assert!(names(&a) != names(&c));
+
+        let mut sorted = names(&a);
+        sorted.sort();
+        assert_eq!(sorted, names(&tests()));
+    }
+
+    #[test]
+    fn parse_shuffle_flags() {
+        let args = vec!["progname".to_string(), "--shuffle-seed".to_string(), "7".to_string()];
+        let opts = match parse_opts(&args) {
+            Some(Ok(o)) => o,
+            _ => panic!("Malformed arg in parse_shuffle_flags"),
+        };
+        assert_eq!(opts.shuffle_seed, Some(7));
+
+        let args = vec!["progname".to_string(), "--shuffle".to_string()];
+        let opts = match parse_opts(&args) {
+            Some(Ok(o)) => o,
+            _ => panic!("Malformed arg in parse_shuffle_flags"),
+        };
+        assert!(opts.shuffle_seed.is_some());
+
+        let opts = match parse_opts(&["progname".to_string()]) {
+            Some(Ok(o)) => o,
+            _ => panic!("Malformed arg in parse_shuffle_flags"),
+        };
+        assert_eq!(opts.shuffle_seed, None);
+    }
+
     #[test]
     pub fn test_metricmap_compare() {
         let mut m1 = MetricMap::new();
@@ -2013,7 +3216,7 @@ mod tests {
     #[test]
     pub fn test_bench_no_iter() {
         fn f(_: &mut Bencher) {}
-        bench::benchmark(f);
+        bench::benchmark(&BenchOpts::new(), f);
     }
 
     #[test]
@@ -2022,6 +3225,67 @@ mod tests {
             b.iter(|| {
             })
         }
-        bench::benchmark(f);
+        bench::benchmark(&BenchOpts::new(), f);
+    }
+
+    #[test]
+    pub fn test_bench_samples_and_warm_up() {
+        fn f(b: &mut Bencher) {
+            b.iter(|| {
+            })
+        }
+        let opts = BenchOpts {
+            warm_up: Duration::from_millis(10),
+            samples: 10,
+            ..BenchOpts::new()
+        };
+        let bs = bench::benchmark(&opts, f);
+        assert_eq!(bs.samples.len(), 10);
+        assert!(bs.ns_iter_median_ci.0 <= bs.ns_iter_summ.median);
+    }
+
+    #[test]
+    pub fn baseline_round_trip() {
+        use std::env;
+        use {load_baseline, save_baseline};
+
+        let path = env::temp_dir().join("libtest-baseline-round-trip");
+        let benches = vec![("a::b".to_string(), vec![1.0, 2.5]),
+                           ("c".to_string(), vec![3.0]),
+                           ("with  spaces 1.5 ".to_string(), vec![4.0, 5.0]),
+                           ("no samples".to_string(), vec![])];
+        save_baseline(&path, &benches).unwrap();
+        let baseline = load_baseline(&path).unwrap();
+        assert_eq!(baseline.len(), 4);
+        assert_eq!(baseline["a::b"], vec![1.0, 2.5]);
+        assert_eq!(baseline["c"], vec![3.0]);
+        assert_eq!(baseline["with  spaces 1.5 "], vec![4.0, 5.0]);
+        assert_eq!(baseline["no samples"], Vec::<f64>::new());
+    }
+
+    #[test]
+    pub fn bench_comparison_verdicts() {
+        let baseline = (0..50).map(|i| 1000.0 + (i % 10) as f64).collect::<Vec<_>>();
+        let slower = baseline.iter().map(|s| s * 1.2).collect::<Vec<_>>();
+        let faster = baseline.iter().map(|s| s * 0.8).collect::<Vec<_>>();
+
+        assert_eq!(BenchComparison::new(&baseline, &slower).verdict, BenchVerdict::Regressed);
+        assert_eq!(BenchComparison::new(&baseline, &faster).verdict, BenchVerdict::Improved);
+        assert_eq!(BenchComparison::new(&baseline, &baseline).verdict, BenchVerdict::NoChange);
+    }
+
+    #[test]
+    pub fn bench_comparison_zero_baseline() {
+        let zeros = vec![0.0; 50];
+        let samples = (0..50).map(|i| 1000.0 + (i % 10) as f64).collect::<Vec<_>>();
+
+        let cmp = BenchComparison::new(&zeros, &samples);
+        assert_eq!(cmp.change, None);
+        assert_eq!(cmp.change_ci, None);
+        assert_eq!(cmp.verdict, BenchVerdict::Inconclusive);
+
+        let cmp = BenchComparison::new(&zeros, &zeros);
+        assert_eq!(cmp.change, None);
+        assert_eq!(cmp.verdict, BenchVerdict::Inconclusive);
     }
 }
@@ -327,12 +327,139 @@ pub fn winsorize(samples: &mut [f64], pct: f64) {
     }
 }
 
+/// Number of samples falling outside of Tukey's fences. Mild outliers lie
+/// more than 1.5 inter-quartile ranges beyond the first or third quartile,
+/// severe outliers more than 3 inter-quartile ranges.
+///
+/// See: <https://en.wikipedia.org/wiki/Outlier#Tukey's_fences>
+#[derive(Clone, PartialEq, Eq, Copy, Debug, Default)]
+#[allow(missing_docs)]
+pub struct Outliers {
+    pub low_severe: usize,
+    pub low_mild: usize,
+    pub high_mild: usize,
+    pub high_severe: usize,
+}
+
+impl Outliers {
+    /// Classify the samples using Tukey's fences.
+    pub fn new(samples: &[f64]) -> Outliers {
+        let (q1, _, q3) = samples.quartiles();
+        let iqr = q3 - q1;
+        let (low_severe, low_mild) = (q1 - 3.0 * iqr, q1 - 1.5 * iqr);
+        let (high_mild, high_severe) = (q3 + 1.5 * iqr, q3 + 3.0 * iqr);
+
+        let mut outliers = Outliers::default();
+        for &s in samples {
+            if s < low_severe {
+                outliers.low_severe += 1;
+            } else if s < low_mild {
+                outliers.low_mild += 1;
+            } else if s > high_severe {
+                outliers.high_severe += 1;
+            } else if s > high_mild {
+                outliers.high_mild += 1;
+            }
+        }
+        outliers
+    }
+
+    /// Total number of outliers.
+    pub fn total(&self) -> usize {
+        self.low_severe + self.low_mild + self.high_mild + self.high_severe
+    }
+}
+
+/// A small seedable pseudo-random number generator (SplitMix64). Unlike the
+/// generators in `std` its output for a given seed is guaranteed never to
+/// change, which makes it suitable for reproducible resampling and shuffling.
+///
+/// See: <http://xoshiro.di.unimi.it/splitmix64.c>
+#[derive(Clone, Debug)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> SplitMix64 {
+        SplitMix64 { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`.
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0);
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+// Fill `out` with samples drawn from `samples` with replacement.
+fn resample(rng: &mut SplitMix64, samples: &[f64], out: &mut Vec<f64>) {
+    out.clear();
+    for _ in 0..samples.len() {
+        out.push(samples[rng.below(samples.len())]);
+    }
+}
+
+/// Bootstrapped confidence interval of the median of the samples: the median
+/// is computed for `resamples` sets of samples drawn with replacement, and the
+/// interval containing the middle `confidence` percent of them is returned.
+///
+/// See: <https://en.wikipedia.org/wiki/Bootstrapping_(statistics)>
+pub fn bootstrap_median_ci(samples: &[f64],
+                           resamples: usize,
+                           confidence: f64,
+                           seed: u64) -> (f64, f64) {
+    assert!(!samples.is_empty());
+    let mut rng = SplitMix64::new(seed);
+    let mut tmp = Vec::with_capacity(samples.len());
+    let medians = (0..resamples).map(|_| {
+        resample(&mut rng, samples, &mut tmp);
+        tmp.median()
+    }).collect::<Vec<_>>();
+    confidence_interval(medians, confidence)
+}
+
+/// Bootstrapped confidence interval of the relative change of the median from
+/// `old` to `new`, e.g. `(0.05, 0.10)` means the median got between 5% and
+/// 10% larger. See `bootstrap_median_ci`.
+pub fn bootstrap_median_change_ci(old: &[f64],
+                                  new: &[f64],
+                                  resamples: usize,
+                                  confidence: f64,
+                                  seed: u64) -> (f64, f64) {
+    assert!(!old.is_empty() && !new.is_empty());
+    let mut rng = SplitMix64::new(seed);
+    let (mut old_tmp, mut new_tmp) = (Vec::new(), Vec::new());
+    let changes = (0..resamples).map(|_| {
+        resample(&mut rng, old, &mut old_tmp);
+        resample(&mut rng, new, &mut new_tmp);
+        new_tmp.median() / old_tmp.median() - 1.0
+    }).collect::<Vec<_>>();
+    confidence_interval(changes, confidence)
+}
+
+fn confidence_interval(mut estimates: Vec<f64>, confidence: f64) -> (f64, f64) {
+    assert!(0.0 < confidence && confidence < 100.0);
+    local_sort(&mut estimates);
+    let tail = (100.0 - confidence) / 2.0;
+    (percentile_of_sorted(&estimates, tail), percentile_of_sorted(&estimates, 100.0 - tail))
+}
+
 // Test vectors generated from R, using the script src/etc/stat-test-vectors.r.
 
 #[cfg(test)]
 mod tests {
     use stats::Stats;
     use stats::Summary;
+    use stats::{Outliers, bootstrap_median_ci, bootstrap_median_change_ci};
     use std::f64;
     use std::io::prelude::*;
     use std::io;
@@ -868,6 +995,40 @@ mod tests {
         check(val, summ);
     }
 
+    #[test]
+    fn test_outliers() {
+        let mut samples = (0..20).map(|i| 10.0 + (i % 5) as f64).collect::<Vec<_>>();
+        samples.extend_from_slice(&[1.0, 19.0, 30.0, 100.0]);
+        let outliers = Outliers::new(&samples);
+        assert_eq!(outliers, Outliers {
+            low_severe: 1,
+            low_mild: 0,
+            high_mild: 1,
+            high_severe: 2,
+        });
+        assert_eq!(outliers.total(), 4);
+    }
+
+    #[test]
+    fn test_bootstrap_median_ci() {
+        let samples = (0..50).map(|i| 100.0 + (i % 7) as f64).collect::<Vec<_>>();
+        let (lo, hi) = bootstrap_median_ci(&samples, 1000, 95.0, 1);
+        assert!(lo <= samples.median() && samples.median() <= hi);
+        assert!(100.0 <= lo && hi <= 106.0);
+        assert_eq!((lo, hi), bootstrap_median_ci(&samples, 1000, 95.0, 1));
+    }
+
+    #[test]
+    fn test_bootstrap_median_change_ci() {
+        let old = (0..50).map(|i| 100.0 + (i % 5) as f64).collect::<Vec<_>>();
+        let new = old.iter().map(|s| s * 1.5).collect::<Vec<_>>();
+        let (lo, hi) = bootstrap_median_change_ci(&old, &new, 1000, 95.0, 1);
+        assert!(0.4 < lo && hi < 0.6);
+
+        let (lo, hi) = bootstrap_median_change_ci(&old, &old, 1000, 95.0, 1);
+        assert!(lo <= 0.0 && 0.0 <= hi);
+    }
+
     #[test]
     fn test_sum_f64s() {
         assert_eq!([0.5f64, 3.2321f64, 1.5678f64].sum(), 5.2999);
@@ -0,0 +1,23 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(const_fn, const_control_flow, const_eval_limit)]
+#![const_eval_limit = "1000"]
+
+const fn spin(n: u32) -> u32 { let mut i = 0; while i < n { i += 1; } i }
+//~^ ERROR constant evaluation error
+//~| NOTE reached the configured maximum execution time
+
+// Each call stays below the limit, but the steps of all of them count.
+const TOTAL: u32 = spin(100) + spin(100) + spin(100) + spin(100) + spin(100) + spin(100);
+
+fn main() {
+    println!("{}", TOTAL);
+}
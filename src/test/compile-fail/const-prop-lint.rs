@@ -0,0 +1,32 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Zforce-overflow-checks=on -Zmir-opt-level=0
+
+// The values flow through local variables, so only MIR constant
+// propagation can see that these expressions always panic.
+
+#![deny(const_err)]
+
+fn black_box<T>(_: T) {
+    unimplemented!()
+}
+
+fn main() {
+    let a = 200u8;
+    let b = a + 100;
+    //~^ ERROR this expression will panic at runtime: attempt to add with overflow
+    let arr = [1, 2, 3];
+    let idx = 3;
+    let c = arr[idx];
+    //~^ ERROR index out of bounds: the len is 3 but the index is 3
+    black_box(b);
+    black_box(c);
+}
@@ -0,0 +1,19 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z interpret-main -Z mir-emit-validate=1
+
+fn safe(_x: &mut i32, _y: &mut i32) {} //~ ERROR in conflict with lock WriteLock
+
+fn main() {
+    let mut x = 0;
+    let p = &mut x as *mut i32;
+    safe(unsafe { &mut *p }, unsafe { &mut *p });
+}
@@ -0,0 +1,37 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z interpret-main -Z mir-emit-validate=1
+
+extern "C" {
+    fn pthread_create(thread: *mut usize, attr: *const u8,
+                      start: extern "C" fn(*mut u8) -> *mut u8, arg: *mut u8) -> i32;
+    fn pthread_join(thread: usize, retval: *mut *mut u8) -> i32;
+}
+
+fn bump(x: &mut u32) { *x += 1; } //~ ERROR races with a Write access by thread 1
+
+extern "C" fn bump_arg(arg: *mut u8) -> *mut u8 {
+    bump(unsafe { &mut *(arg as *mut u32) });
+    arg
+}
+
+fn main() {
+    let mut x = 0u32;
+    let mut thread = 0;
+    unsafe {
+        pthread_create(&mut thread, 0 as *const u8, bump_arg, &mut x as *mut u32 as *mut u8);
+    }
+    // Not ordered after the access of the thread, which hasn't been joined yet.
+    bump(&mut x);
+    unsafe {
+        pthread_join(thread, 0 as *mut *mut u8);
+    }
+}
@@ -0,0 +1,23 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z interpret-main
+
+#[repr(u8)]
+#[derive(Copy, Clone)]
+enum Bool { False, True }
+
+fn main() {
+    let b: Bool = unsafe { std::mem::transmute(3u8) };
+    match b { //~ ERROR invalid enum discriminant value read
+        Bool::False => {}
+        Bool::True => {}
+    }
+}
@@ -0,0 +1,70 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -C overflow-checks=on
+
+fn main() {
+    let x = 2u32 + 2;
+    let _y = x * 3;
+    let _z = choose();
+}
+
+fn choose() -> u32 {
+    let x = 2u32;
+    if x == 2 { 10 } else { 20 }
+}
+
+// END RUST SOURCE
+// START rustc.main.ConstProp.before.mir
+//  bb0: {
+//      ...
+//      _2 = CheckedAdd(const 2u32, const 2u32);
+//      assert(!move (_2.1: bool), "attempt to add with overflow") -> bb1;
+//  }
+//  bb1: {
+//      _1 = move (_2.0: u32);
+//      ...
+//      _4 = _1;
+//      _5 = CheckedMul(move _4, const 3u32);
+//      assert(!move (_5.1: bool), "attempt to multiply with overflow") -> bb2;
+//  }
+// END rustc.main.ConstProp.before.mir
+// START rustc.main.ConstProp.after.mir
+//  bb0: {
+//      ...
+//      _2 = CheckedAdd(const 2u32, const 2u32);
+//      goto -> bb1;
+//  }
+//  bb1: {
+//      _1 = const 4u32;
+//      ...
+//      _4 = const 4u32;
+//      _5 = CheckedMul(const 4u32, const 3u32);
+//      goto -> bb2;
+//  }
+// END rustc.main.ConstProp.after.mir
+// START rustc.choose.ConstProp.before.mir
+//  bb0: {
+//      ...
+//      _3 = _1;
+//      _2 = Eq(move _3, const 2u32);
+//      ...
+//      switchInt(move _2) -> [false: bb2, otherwise: bb1];
+//  }
+// END rustc.choose.ConstProp.before.mir
+// START rustc.choose.ConstProp.after.mir
+//  bb0: {
+//      ...
+//      _3 = const 2u32;
+//      _2 = Eq(const 2u32, const 2u32);
+//      ...
+//      goto -> bb1;
+//  }
+// END rustc.choose.ConstProp.after.mir
@@ -0,0 +1,43 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[allow(unused_assignments)]
+fn foo(a: u32, b: u32) -> u32 {
+    let mut x = a;
+    x = b;
+    x
+}
+
+fn main() {
+    // Make sure the function actually gets instantiated.
+    foo(0, 1);
+}
+
+// END RUST SOURCE
+// START rustc.foo.DeadStoreElimination.before.mir
+//  bb0: {
+//      ...
+//      _3 = _1;
+//      ...
+//      _3 = _2;
+//      ...
+//      return;
+//  }
+// END rustc.foo.DeadStoreElimination.before.mir
+// START rustc.foo.DeadStoreElimination.after.mir
+//  bb0: {
+//      ...
+//      nop;
+//      ...
+//      _3 = _2;
+//      ...
+//      return;
+//  }
+// END rustc.foo.DeadStoreElimination.after.mir
@@ -0,0 +1,50 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+struct Point {
+    x: u32,
+    y: u32,
+}
+
+fn foo(a: u32, b: u32) -> u32 {
+    let p = Point { x: a, y: b };
+    p.y
+}
+
+fn main() {
+    // Make sure the function actually gets instantiated.
+    foo(0, 1);
+}
+
+// END RUST SOURCE
+// START rustc.foo.ScalarReplacementOfAggregates.before.mir
+//  bb0: {
+//      ...
+//      StorageLive(_3);
+//      ...
+//      _0 = (_3.1: u32);
+//      StorageDead(_3);
+//      ...
+//      return;
+//  }
+// END rustc.foo.ScalarReplacementOfAggregates.before.mir
+// START rustc.foo.ScalarReplacementOfAggregates.after.mir
+//  bb0: {
+//      ...
+//      StorageLive(_6);
+//      StorageLive(_7);
+//      ...
+//      _0 = _7;
+//      StorageDead(_6);
+//      StorageDead(_7);
+//      ...
+//      return;
+//  }
+// END rustc.foo.ScalarReplacementOfAggregates.after.mir
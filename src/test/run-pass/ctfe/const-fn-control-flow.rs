@@ -0,0 +1,119 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(const_fn, const_control_flow)]
+
+const fn fib(n: u64) -> u64 {
+    let mut a = 0;
+    let mut b = 1;
+    let mut i = 0;
+    while i < n {
+        let next = a + b;
+        a = b;
+        b = next;
+        i += 1;
+    }
+    a
+}
+
+const fn clamp(x: i32, lo: i32, hi: i32) -> i32 {
+    if x < lo {
+        lo
+    } else if x > hi {
+        hi
+    } else {
+        x
+    }
+}
+
+const fn name_len(digit: u8) -> usize {
+    match digit {
+        0 | 4 | 5 | 9 => 4,
+        1 | 2 | 6 => 3,
+        3 | 7 | 8 => 5,
+        _ => 0,
+    }
+}
+
+#[derive(Copy, Clone)]
+enum Shape {
+    Circle(u32),
+    Rect(u32, u32),
+    Empty,
+}
+
+const fn area(shape: Shape) -> u32 {
+    match shape {
+        Shape::Circle(r) => 3 * r * r,
+        Shape::Rect(w, h) => w * h,
+        Shape::Empty => 0,
+    }
+}
+
+#[derive(Copy, Clone)]
+enum Suit {
+    Hearts = -10,
+    Spades = 20,
+}
+
+const fn points(suit: Suit) -> u32 {
+    match suit {
+        Suit::Hearts => 1,
+        Suit::Spades => 2,
+    }
+}
+
+// `None` is stored in the niche of the `bool`.
+const fn is_set(flag: Option<bool>) -> bool {
+    match flag {
+        Some(flag) => flag,
+        None => false,
+    }
+}
+
+const FIB_20: u64 = fib(20);
+const CLAMPED: i32 = clamp(-5, 0, 10);
+const CIRCLE: u32 = area(Shape::Circle(2));
+const RECT: u32 = area(Shape::Rect(3, 4));
+const EMPTY: u32 = area(Shape::Empty);
+const HEARTS: u32 = points(Suit::Hearts);
+const SPADES: u32 = points(Suit::Spades);
+const SET: bool = is_set(Some(true));
+const UNSET: bool = is_set(Some(false));
+const MISSING: bool = is_set(None);
+
+fn main() {
+    // evaluated by miri
+    let fibs = [0u8; fib(10) as usize];
+    assert_eq!(fibs.len(), 55);
+    let seven = [0u8; name_len(7)];
+    assert_eq!(seven.len(), 5);
+    let rect = [0u8; area(Shape::Rect(2, 3)) as usize];
+    assert_eq!(rect.len(), 6);
+    let spades = [0u8; points(Suit::Spades) as usize];
+    assert_eq!(spades.len(), 2);
+    let set = [0u8; is_set(Some(true)) as usize];
+    assert_eq!(set.len(), 1);
+
+    // evaluated by trans
+    assert_eq!(FIB_20, 6765);
+    assert_eq!(CLAMPED, 0);
+    assert_eq!((CIRCLE, RECT, EMPTY), (12, 12, 0));
+    assert_eq!((HEARTS, SPADES), (1, 2));
+    assert_eq!((SET, UNSET, MISSING), (true, false, false));
+
+    // and at runtime
+    assert_eq!(fib(20), 6765);
+    assert_eq!(clamp(20, 0, 10), 10);
+    assert_eq!(name_len(0), 4);
+    assert_eq!(area(Shape::Circle(1)), 3);
+    assert_eq!(points(Suit::Hearts), 1);
+    assert_eq!(is_set(None), false);
+}
@@ -0,0 +1,56 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(const_fn, const_control_flow, const_heap, box_syntax)]
+
+const fn boxed_sum(a: usize, b: usize) -> usize {
+    let a = box a;
+    let b = box b;
+    *a + *b
+}
+
+const fn boxed_pair(a: u32) -> (u32, bool) {
+    let a = box a;
+    (*a * 2, *a > 2)
+}
+
+struct Point {
+    x: i8,
+    y: i64,
+}
+
+const fn boxed_point(x: i8, y: i64) -> Point {
+    let p = box Point { x, y };
+    Point { x: p.x, y: p.y }
+}
+
+const SUM: usize = boxed_sum(2, 3);
+const PAIR: (u32, bool) = boxed_pair(3);
+const POINT: Point = boxed_point(-1, 1 << 40);
+const POINTS: [Point; 2] = [boxed_point(1, 2), boxed_point(3, 4)];
+
+// The heap allocations reachable from the value are interned along with it.
+const BOXED: &Box<u32> = &(box 5);
+const FIVE: &u32 = &**BOXED;
+const BOXED_POINT: &Box<Point> = &(box Point { x: 7, y: -7 });
+
+fn main() {
+    let arr = [0u8; boxed_sum(2, 3)];
+    assert_eq!(arr.len(), 5);
+
+    assert_eq!(SUM, 5);
+    assert_eq!(PAIR, (6, true));
+    assert_eq!((POINT.x, POINT.y), (-1, 1 << 40));
+    assert_eq!((POINTS[0].x, POINTS[0].y, POINTS[1].x, POINTS[1].y), (1, 2, 3, 4));
+
+    assert_eq!(**BOXED, 5);
+    assert_eq!(*FIVE, 5);
+    assert_eq!((BOXED_POINT.x, BOXED_POINT.y), (7, -7));
+}
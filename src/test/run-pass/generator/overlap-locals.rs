@@ -0,0 +1,44 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(generators, generator_trait)]
+
+use std::mem::size_of_val;
+use std::ops::{Generator, GeneratorState};
+
+fn main() {
+    let mut gen = || {
+        {
+            let a = [1u8; 1024];
+            yield;
+            assert!(a.iter().all(|&x| x == 1));
+        }
+        {
+            let b = [2u8; 1024];
+            yield;
+            assert!(b.iter().all(|&x| x == 2));
+        }
+    };
+
+    // `a` and `b` are never live at the same time, so they share storage
+    // after the 4 byte state.
+    assert_eq!(size_of_val(&gen), 1028);
+
+    for _ in 0..2 {
+        match gen.resume() {
+            GeneratorState::Yielded(()) => {}
+            s => panic!("bad state: {:?}", s),
+        }
+    }
+    match gen.resume() {
+        GeneratorState::Complete(()) => {}
+        s => panic!("bad state: {:?}", s),
+    }
+}
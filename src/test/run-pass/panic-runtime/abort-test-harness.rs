@@ -0,0 +1,29 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: --test -C panic=abort
+// no-prefer-dynamic
+// ignore-cloudabi no processes
+// ignore-emscripten no processes
+
+#[test]
+fn passes() {}
+
+#[test]
+#[should_panic]
+fn panics() {
+    panic!();
+}
+
+#[test]
+#[should_panic(expected = "expected")]
+fn panics_with_message() {
+    panic!("an expected message");
+}
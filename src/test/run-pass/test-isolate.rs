@@ -0,0 +1,40 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-cloudabi no processes
+// ignore-emscripten no processes
+// compile-flags: --test
+// run-flags: --isolate
+#![feature(allow_fail)]
+
+use std::process;
+
+#[test]
+fn passes() {
+    assert_eq!(1 + 1, 2);
+}
+
+#[test]
+#[should_panic(expected = "boom")]
+fn panics() {
+    panic!("boom");
+}
+
+#[test]
+#[allow_fail]
+fn aborts() {
+    process::abort();
+}
+
+#[test]
+#[allow_fail]
+fn exits() {
+    process::exit(0);
+}
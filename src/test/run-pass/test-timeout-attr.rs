@@ -0,0 +1,26 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-emscripten no threads support
+// compile-flags: --test
+#![feature(test_timeout)]
+
+#[test]
+#[test_timeout = "60"]
+fn finishes_in_time() {
+    assert!(true);
+}
+
+#[test]
+#[test_timeout = "60"]
+#[should_panic]
+fn panics_in_time() {
+    panic!();
+}
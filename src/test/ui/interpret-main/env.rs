@@ -0,0 +1,44 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z interpret-main
+// must-compile-successfully
+
+// Tests the emulation of `getenv`, `setenv` and `unsetenv`.
+
+extern "C" {
+    fn getenv(name: *const u8) -> *const u8;
+    fn setenv(name: *const u8, value: *const u8, overwrite: i32) -> i32;
+    fn unsetenv(name: *const u8) -> i32;
+    fn strlen(s: *const u8) -> usize;
+}
+
+fn main() {
+    unsafe {
+        let name = b"INTERPRET_MAIN_VAR\0".as_ptr();
+        assert!(getenv(name).is_null());
+
+        assert_eq!(setenv(name, b"first\0".as_ptr(), 1), 0);
+        assert_eq!(strlen(getenv(name)), 5);
+        // Without `overwrite` the old value is kept.
+        assert_eq!(setenv(name, b"second\0".as_ptr(), 0), 0);
+        assert_eq!(strlen(getenv(name)), 5);
+        assert_eq!(setenv(name, b"second\0".as_ptr(), 1), 0);
+        assert_eq!(strlen(getenv(name)), 6);
+        assert_eq!(*getenv(name), b's');
+
+        // Names can't be empty or contain `=`.
+        assert_eq!(setenv(b"\0".as_ptr(), b"x\0".as_ptr(), 1), -1);
+        assert_eq!(setenv(b"A=B\0".as_ptr(), b"x\0".as_ptr(), 1), -1);
+
+        assert_eq!(unsetenv(name), 0);
+        assert!(getenv(name).is_null());
+    }
+}
@@ -0,0 +1,34 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z interpret-main
+// must-compile-successfully
+
+// Tests the emulation of `malloc` and `free`.
+
+extern "C" {
+    fn malloc(size: usize) -> *mut u8;
+    fn free(ptr: *mut u8);
+}
+
+fn main() {
+    unsafe {
+        let p = malloc(16) as *mut u64;
+        assert!(!p.is_null());
+        *p = 1;
+        *p.offset(1) = 2;
+        assert_eq!(*p + *p.offset(1), 3);
+        free(p as *mut u8);
+
+        assert!(malloc(0).is_null());
+        // Freeing NULL does nothing.
+        free(0 as *mut u8);
+    }
+}
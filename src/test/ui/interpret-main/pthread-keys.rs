@@ -0,0 +1,49 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z interpret-main
+// must-compile-successfully
+
+// Tests the emulation of the pthread thread local keys, and that the destructors
+// of the keys which are still set run after `main` returns.
+
+extern "C" {
+    fn pthread_key_create(key: *mut u32, dtor: Option<unsafe extern "C" fn(*mut u8)>) -> i32;
+    fn pthread_key_delete(key: u32) -> i32;
+    fn pthread_getspecific(key: u32) -> *mut u8;
+    fn pthread_setspecific(key: u32, value: *const u8) -> i32;
+    fn write(fd: i32, buf: *const u8, count: usize) -> isize;
+}
+
+static mut VALUE: u8 = 42;
+
+unsafe extern "C" fn dtor(value: *mut u8) {
+    assert_eq!(*value, 42);
+    let msg = b"dtor ran\n";
+    write(1, msg.as_ptr(), msg.len());
+}
+
+fn main() {
+    unsafe {
+        let mut plain = 0;
+        assert_eq!(pthread_key_create(&mut plain, None), 0);
+        assert!(pthread_getspecific(plain).is_null());
+        let x = 7u8;
+        assert_eq!(pthread_setspecific(plain, &x), 0);
+        assert_eq!(*pthread_getspecific(plain), 7);
+        assert_eq!(pthread_key_delete(plain), 0);
+
+        let mut key = 0;
+        let dtor = dtor as unsafe extern "C" fn(*mut u8);
+        assert_eq!(pthread_key_create(&mut key, Some(dtor)), 0);
+        assert!(key != plain);
+        assert_eq!(pthread_setspecific(key, &VALUE), 0);
+    }
+}
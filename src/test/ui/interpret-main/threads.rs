@@ -0,0 +1,78 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z interpret-main -Z mir-emit-validate=1
+// must-compile-successfully
+
+// Tests the emulation of threads, and that accesses ordered by joining the threads or
+// by a mutex aren't reported as data races.
+
+extern "C" {
+    fn pthread_create(thread: *mut usize, attr: *const u8,
+                      start: extern "C" fn(*mut u8) -> *mut u8, arg: *mut u8) -> i32;
+    fn pthread_join(thread: usize, retval: *mut *mut u8) -> i32;
+    fn pthread_mutex_lock(mutex: *mut u8) -> i32;
+    fn pthread_mutex_unlock(mutex: *mut u8) -> i32;
+    fn write(fd: i32, buf: *const u8, count: usize) -> isize;
+}
+
+// Large enough for a `pthread_mutex_t`, which is all zeros when unlocked.
+static mut LOCK: [u64; 8] = [0; 8];
+
+fn bump(x: &mut u32) { *x += 1; }
+
+extern "C" fn bump_arg(arg: *mut u8) -> *mut u8 {
+    bump(unsafe { &mut *(arg as *mut u32) });
+    arg
+}
+
+extern "C" fn bump_locked(arg: *mut u8) -> *mut u8 {
+    unsafe {
+        pthread_mutex_lock(LOCK.as_mut_ptr() as *mut u8);
+        bump(&mut *(arg as *mut u32));
+        pthread_mutex_unlock(LOCK.as_mut_ptr() as *mut u8);
+    }
+    0 as *mut u8
+}
+
+fn spawn(start: extern "C" fn(*mut u8) -> *mut u8, x: &mut u32) -> usize {
+    let mut thread = 0;
+    unsafe {
+        assert_eq!(pthread_create(&mut thread, 0 as *const u8, start, x as *mut u32 as *mut u8), 0);
+    }
+    thread
+}
+
+fn main() {
+    let mut x = 0u32;
+
+    let thread = spawn(bump_arg, &mut x);
+    let mut ret = 0 as *mut u8;
+    unsafe {
+        assert_eq!(pthread_join(thread, &mut ret), 0);
+    }
+    assert_eq!(ret, &mut x as *mut u32 as *mut u8);
+    bump(&mut x);
+    assert_eq!(x, 2);
+
+    let thread = spawn(bump_locked, &mut x);
+    unsafe {
+        pthread_mutex_lock(LOCK.as_mut_ptr() as *mut u8);
+        bump(&mut x);
+        pthread_mutex_unlock(LOCK.as_mut_ptr() as *mut u8);
+        assert_eq!(pthread_join(thread, 0 as *mut *mut u8), 0);
+    }
+    assert_eq!(x, 4);
+
+    let msg = b"threads ran\n";
+    unsafe {
+        write(1, msg.as_ptr(), msg.len());
+    }
+}
@@ -0,0 +1,26 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z interpret-main
+// must-compile-successfully
+
+// Tests that `write` to stdout reaches the real stdout, and fails for other descriptors.
+
+extern "C" {
+    fn write(fd: i32, buf: *const u8, count: usize) -> isize;
+}
+
+fn main() {
+    let msg = b"hello from the interpreter\n";
+    let written = unsafe { write(1, msg.as_ptr(), msg.len()) };
+    assert_eq!(written, msg.len() as isize);
+    let written = unsafe { write(3, msg.as_ptr(), msg.len()) };
+    assert_eq!(written, -1);
+}
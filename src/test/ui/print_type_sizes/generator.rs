@@ -0,0 +1,37 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// compile-flags: -Z print-type-sizes
+// must-compile-successfully
+
+// This file illustrates how generators are reported: one variant per
+// suspension point, made of the upvars, the state and the locals which
+// are live across it. `a` and `b` are never live at the same time, so
+// they share the same offset and the generator only takes 1028 bytes.
+
+#![feature(start, generators)]
+
+#[start]
+fn start(_: isize, _: *const *const u8) -> isize {
+    let _gen = || {
+        {
+            let a = [1u8; 1024];
+            yield;
+            let _x = a[0];
+        }
+        {
+            let b = [2u8; 1024];
+            yield;
+            let _y = b[0];
+        }
+    };
+    0
+}
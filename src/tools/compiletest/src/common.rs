@@ -137,6 +137,10 @@ pub struct Config {
     /// The test mode, compile-fail, run-fail, run-pass
     pub mode: Mode,
 
+    /// Overwrite the expected output files with the actual output instead of
+    /// failing the tests that don't match them
+    pub bless: bool,
+
     /// Run ignored tests
     pub run_ignored: bool,
 
@@ -239,6 +243,7 @@ pub fn expected_output_path(testpaths: &TestPaths, revision: Option<&str>, kind:
     testpaths.file.with_extension(extension)
 }
 
-pub const UI_EXTENSIONS: &[&str] = &[UI_STDERR, UI_STDOUT];
+pub const UI_EXTENSIONS: &[&str] = &[UI_STDERR, UI_STDOUT, UI_FIXED];
 pub const UI_STDERR: &str = "stderr";
 pub const UI_STDOUT: &str = "stdout";
+pub const UI_FIXED: &str = "fixed";
@@ -229,6 +229,10 @@ pub struct TestProps {
     // The test must be compiled and run successfully. Only used in UI tests for
     // now.
     pub run_pass: bool,
+    // The suggestions rustc makes for the test are applied to its source, which
+    // must then match the `.fixed` file. That file must also compile. Only used
+    // in UI tests.
+    pub run_rustfix: bool,
     // customized normalization rules
     pub normalize_stdout: Vec<(String, String)>,
     pub normalize_stderr: Vec<(String, String)>,
@@ -258,6 +262,7 @@ impl TestProps {
             must_compile_successfully: false,
             check_test_line_numbers_match: false,
             run_pass: false,
+            run_rustfix: false,
             normalize_stdout: vec![],
             normalize_stderr: vec![],
         }
@@ -371,6 +376,10 @@ impl TestProps {
                 self.run_pass = config.parse_run_pass(ln);
             }
 
+            if !self.run_rustfix {
+                self.run_rustfix = config.parse_run_rustfix(ln);
+            }
+
             if !self.must_compile_successfully {
                 // run-pass implies must_compile_sucessfully
                 self.must_compile_successfully =
@@ -500,6 +509,10 @@ impl Config {
         self.parse_name_directive(line, "run-pass")
     }
 
+    fn parse_run_rustfix(&self, line: &str) -> bool {
+        self.parse_name_directive(line, "run-rustfix")
+    }
+
     fn parse_env(&self, line: &str, name: &str) -> Option<(String, String)> {
         self.parse_name_value_directive(line, name).map(|nv| {
             // nv is either FOO or FOO=BAR
@@ -8,6 +8,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use edits::{Edit, FileEdits};
 use errors::{Error, ErrorKind};
 use rustc_serialize::json;
 use std::str::FromStr;
@@ -30,6 +31,8 @@ struct Diagnostic {
 #[derive(RustcEncodable, RustcDecodable, Clone)]
 struct DiagnosticSpan {
     file_name: String,
+    byte_start: usize,
+    byte_end: usize,
     line_start: usize,
     line_end: usize,
     column_start: usize,
@@ -37,6 +40,7 @@ struct DiagnosticSpan {
     is_primary: bool,
     label: Option<String>,
     suggested_replacement: Option<String>,
+    suggestion_applicability: Option<String>,
     expansion: Option<Box<DiagnosticSpanMacroExpansion>>,
 }
 
@@ -76,6 +80,67 @@ pub fn extract_rendered(output: &str, proc_res: &ProcRes) -> String {
         .collect()
 }
 
+/// Applies the suggestions in the compiler `output` to `source`, the contents
+/// of `file_name`, and returns the fixed source.
+///
+/// Only machine-applicable suggestions with a single alternative are applied,
+/// and those overlapping with one that comes earlier in the file are left out,
+/// the same way as with `-Z apply-suggestions`.
+pub fn apply_suggestions(file_name: &str,
+                         source: &str,
+                         output: &str,
+                         proc_res: &ProcRes)
+                         -> String {
+    let mut suggestions = vec![];
+    for line in output.lines().filter(|line| line.starts_with('{')) {
+        match json::decode::<Diagnostic>(line) {
+            Ok(diagnostic) => push_suggestions(&mut suggestions, &diagnostic, file_name),
+            Err(error) => {
+                proc_res.fatal(Some(&format!("failed to decode compiler output as json: \
+                                              `{}`\noutput: {}\nline: {}",
+                                             error,
+                                             line,
+                                             output)));
+            }
+        }
+    }
+    suggestions.sort();
+
+    let mut edits = FileEdits::new();
+    for suggestion in suggestions {
+        edits.add(suggestion);
+    }
+    edits.apply(source)
+}
+
+fn push_suggestions(suggestions: &mut Vec<Vec<Edit>>,
+                    diagnostic: &Diagnostic,
+                    file_name: &str) {
+    let mut edits: Vec<_> = diagnostic.spans
+        .iter()
+        .filter(|span| Path::new(&span.file_name) == Path::new(&file_name))
+        .filter(|span| span.suggestion_applicability.as_ref().map(|a| &a[..]) ==
+                       Some("MachineApplicable"))
+        .filter_map(|span| {
+            span.suggested_replacement.as_ref().map(|replacement| {
+                (span.byte_start, span.byte_end, replacement.clone())
+            })
+        })
+        .collect();
+    edits.sort();
+
+    // The alternatives of a suggestion all replace the same code, so
+    // overlapping edits mean there is more than one way to apply it.
+    let alternatives = edits.windows(2).any(|w| w[1].0 < w[0].1 || w[1].0 == w[0].0);
+    if !edits.is_empty() && !alternatives {
+        suggestions.push(edits);
+    }
+
+    for child in &diagnostic.children {
+        push_suggestions(suggestions, child, file_name);
+    }
+}
+
 pub fn parse_output(file_name: &str, output: &str, proc_res: &ProcRes) -> Vec<Error> {
     output.lines()
         .flat_map(|line| parse_line(file_name, line, output, proc_res))
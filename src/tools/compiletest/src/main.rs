@@ -42,6 +42,9 @@ use util::logv;
 use self::header::EarlyProps;
 
 pub mod util;
+#[path = "../../../librustc_errors/edits.rs"]
+#[allow(dead_code)]
+mod edits;
 mod json;
 pub mod header;
 pub mod runtest;
@@ -139,6 +142,12 @@ pub fn parse_config(args: Vec<String>) -> Config {
         )
         .optflag("", "ignored", "run tests marked as ignored")
         .optflag("", "exact", "filters match exactly")
+        .optflag(
+            "",
+            "bless",
+            "overwrite stderr/stdout/fixed files and mir-opt and pretty \
+             expectations instead of complaining",
+        )
         .optopt(
             "",
             "runtool",
@@ -292,6 +301,7 @@ pub fn parse_config(args: Vec<String>) -> Config {
             .unwrap()
             .parse()
             .expect("invalid mode"),
+        bless: matches.opt_present("bless"),
         run_ignored: matches.opt_present("ignored"),
         filter: matches.free.first().cloned(),
         filter_exact: matches.opt_present("exact"),
@@ -353,6 +363,7 @@ pub fn log_config(config: &Config) {
         ),
     );
     logv(c, format!("filter_exact: {}", config.filter_exact));
+    logv(c, format!("bless: {}", config.bless));
     logv(c, format!("runtool: {}", opt_str(&config.runtool)));
     logv(
         c,
@@ -485,7 +496,7 @@ pub fn test_opts(config: &Config) -> test::TestOpts {
         filter: config.filter.clone(),
         filter_exact: config.filter_exact,
         run_ignored: config.run_ignored,
-        quiet: config.quiet,
+        format: if config.quiet { test::OutputFormat::Terse } else { test::OutputFormat::Pretty },
         logfile: config.logfile.clone(),
         run_tests: true,
         bench_benchmarks: true,
@@ -495,6 +506,10 @@ pub fn test_opts(config: &Config) -> test::TestOpts {
         },
         color: config.color,
         test_threads: None,
+        test_timeout: None,
+        isolate: false,
+        shuffle_seed: None,
+        bench_opts: test::BenchOpts::new(),
         skip: vec![],
         list: false,
         options: test::Options::new(),
@@ -623,6 +638,7 @@ pub fn make_test(config: &Config, testpaths: &TestPaths) -> test::TestDescAndFn
             ignore,
             should_panic,
             allow_fail: false,
+            timeout: None,
         },
         testfn: make_test_closure(config, testpaths),
     }
@@ -12,7 +12,7 @@ use common::{Config, TestPaths};
 use common::{CompileFail, ParseFail, Pretty, RunFail, RunPass, RunPassValgrind};
 use common::{Codegen, CodegenUnits, DebugInfoGdb, DebugInfoLldb, Rustdoc};
 use common::{Incremental, MirOpt, RunMake, Ui};
-use common::{expected_output_path, UI_STDERR, UI_STDOUT};
+use common::{expected_output_path, UI_FIXED, UI_STDERR, UI_STDOUT};
 use diff;
 use errors::{self, Error, ErrorKind};
 use filetime::FileTime;
@@ -21,6 +21,7 @@ use header::TestProps;
 use util::logv;
 use regex::Regex;
 
+use std::cmp;
 use std::collections::VecDeque;
 use std::collections::HashMap;
 use std::collections::HashSet;
@@ -30,6 +31,7 @@ use std::fs::{self, create_dir_all, File};
 use std::fmt;
 use std::io::prelude::*;
 use std::io::{self, BufReader};
+use std::iter;
 use std::path::{Path, PathBuf};
 use std::process::{Child, Command, ExitStatus, Output, Stdio};
 use std::str;
@@ -424,6 +426,22 @@ impl<'test> TestCx<'test> {
             expected = expected.replace(&cr, "").to_owned();
         }
 
+        if self.config.bless && expected != actual {
+            // Only an exact expectation lives in a file; converging
+            // pretty-printing has nothing to bless.
+            if let Some(ref file) = self.props.pp_exact {
+                // A bare `pp-exact` compares against the test itself, which
+                // must not be overwritten with the pretty-printer's output.
+                if Some(file.as_os_str()) == self.testpaths.file.file_name() {
+                    self.fatal("cannot bless a `pp-exact` test against its own \
+                                source, fix the test by hand");
+                }
+                let filepath = self.testpaths.file.parent().unwrap().join(file);
+                self.bless_file(&filepath, &actual);
+                expected = actual.clone();
+            }
+        }
+
         self.compare_source(&expected, &actual);
 
         // If we're only making sure that the output matches then just stop here
@@ -2509,8 +2527,25 @@ impl<'test> TestCx<'test> {
         errors += self.compare_output("stdout", &normalized_stdout, &expected_stdout);
         errors += self.compare_output("stderr", &normalized_stderr, &expected_stderr);
 
+        if self.props.run_rustfix {
+            // Apply the suggestions rustc made to the test source, and check
+            // that the result is what we expect.
+            let mut source = String::new();
+            File::open(&self.testpaths.file)
+                .and_then(|mut f| f.read_to_string(&mut source))
+                .unwrap();
+            let file_name = format!("{}", self.testpaths.file.display()).replace(r"\", "/");
+            let fixed_code = json::apply_suggestions(&file_name,
+                                                     &source,
+                                                     &proc_res.stderr,
+                                                     &proc_res);
+            let expected_fixed = self.load_expected_output(&self.expected_output_path(UI_FIXED));
+            errors += self.compare_output(UI_FIXED, &fixed_code, &expected_fixed);
+        }
+
         if errors > 0 {
-            println!("To update references, run this command from build directory:");
+            println!("To update references, rerun the tests and pass the `--bless` flag,");
+            println!("or run this command from build directory:");
             let relative_path_to_file = self.testpaths
                 .relative_dir
                 .join(self.testpaths.file.file_name().unwrap());
@@ -2535,6 +2570,21 @@ impl<'test> TestCx<'test> {
                 self.fatal_proc_rec("test run failed!", &proc_res);
             }
         }
+
+        if self.props.run_rustfix {
+            // The fixed code must compile too. This happens after the test was
+            // run, as it overwrites the test executable.
+            let mut rustc = self.make_compile_args(
+                &self.expected_output_path(UI_FIXED),
+                TargetLocation::ThisFile(self.make_exe_name()),
+            );
+            rustc.arg("-L").arg(&self.aux_output_dir_name());
+            let res = self.compose_and_run_compiler(rustc, None);
+            if !res.status.success() {
+                self.fatal_proc_rec("failed to compile fixed code", &res);
+            }
+        }
+
         if !explicit {
             if !expected_errors.is_empty() || !proc_res.status.success() {
                 // "// error-pattern" comments
@@ -2571,18 +2621,31 @@ impl<'test> TestCx<'test> {
             let (_, tests_text) = test_file_contents.split_at(idx + "// END_RUST SOURCE".len());
             let tests_text_str = String::from(tests_text);
             let mut curr_test: Option<&str> = None;
+            let mut curr_test_start = 0;
             let mut curr_test_contents = vec![ExpectedLine::Elision];
-            for l in tests_text_str.lines() {
+            // When blessing, the line ranges of the mismatching tests along
+            // with their actual output.
+            let mut blessed = vec![];
+            for (line_num, l) in tests_text_str.lines().enumerate() {
                 debug!("line: {:?}", l);
                 if l.starts_with("// START ") {
                     let (_, t) = l.split_at("// START ".len());
                     curr_test = Some(t);
+                    curr_test_start = line_num;
                 } else if l.starts_with("// END") {
                     let (_, t) = l.split_at("// END ".len());
                     if Some(t) != curr_test {
                         panic!("mismatched START END test name");
                     }
-                    self.compare_mir_test_output(curr_test.unwrap(), &curr_test_contents);
+                    let test_name = curr_test.unwrap();
+                    if let Err(msg) = self.compare_mir_test_output(test_name, &curr_test_contents) {
+                        if !self.config.bless {
+                            panic!("{}", msg);
+                        }
+                        let actual = self.mir_dump_lines(test_name);
+                        let new_contents = bless_mir_expected(&curr_test_contents, &actual);
+                        blessed.push((curr_test_start, line_num, new_contents));
+                    }
                     curr_test = None;
                     curr_test_contents.clear();
                     curr_test_contents.push(ExpectedLine::Elision);
@@ -2595,7 +2658,49 @@ impl<'test> TestCx<'test> {
                     curr_test_contents.push(ExpectedLine::Text(test_content));
                 }
             }
+
+            if !blessed.is_empty() {
+                let (head, _) = test_file_contents.split_at(idx + "// END_RUST SOURCE".len());
+                self.bless_mir_tests(head, &tests_text_str, blessed);
+            }
+        }
+    }
+
+    /// Replaces the expected lines of the given mir-opt tests, i.e. the lines
+    /// between their `// START` and `// END` markers, with the blessed ones.
+    fn bless_mir_tests(&self,
+                       head: &str,
+                       tests_text: &str,
+                       blessed: Vec<(usize, usize, Vec<String>)>) {
+        let lines: Vec<&str> = tests_text.lines().collect();
+        let mut new_lines = vec![];
+        let mut pos = 0;
+        for (start, end, actual) in blessed {
+            new_lines.extend(lines[pos..start + 1].iter().map(|l| l.to_string()));
+            new_lines.extend(actual.iter().map(|l| format!("// {}", l)));
+            pos = end;
         }
+        new_lines.extend(lines[pos..].iter().map(|l| l.to_string()));
+
+        let mut contents = head.to_owned();
+        contents.push_str(&new_lines.join("\n"));
+        if tests_text.ends_with('\n') {
+            contents.push('\n');
+        }
+        self.bless_file(&self.testpaths.file, &contents);
+    }
+
+    fn mir_dump_lines(&self, test_name: &str) -> Vec<String> {
+        let mut dumped_string = String::new();
+        fs::File::open(self.get_mir_dump_dir().join(test_name))
+            .and_then(|mut f| f.read_to_string(&mut dumped_string))
+            .unwrap();
+        dumped_string
+            .lines()
+            .map(nocomment_mir_line)
+            .filter(|l| !l.is_empty())
+            .map(str::to_owned)
+            .collect()
     }
 
     fn check_mir_test_timestamp(&self, test_name: &str, output_file: &Path) {
@@ -2617,7 +2722,8 @@ impl<'test> TestCx<'test> {
         }
     }
 
-    fn compare_mir_test_output(&self, test_name: &str, expected_content: &[ExpectedLine<&str>]) {
+    fn compare_mir_test_output(&self, test_name: &str, expected_content: &[ExpectedLine<&str>])
+                               -> Result<(), String> {
         let mut output_file = PathBuf::new();
         output_file.push(self.get_mir_dump_dir());
         output_file.push(test_name);
@@ -2673,7 +2779,7 @@ impl<'test> TestCx<'test> {
                 .map(|l| f(l))
                 .collect::<Vec<_>>()
                 .join("\n");
-            panic!(
+            format!(
                 "Did not find expected line, error: {}\n\
                  Expected Line: {:?}\n\
                  Expected:\n{}\n\
@@ -2682,7 +2788,7 @@ impl<'test> TestCx<'test> {
                 expected_line,
                 expected_content,
                 normalize_all
-            );
+            )
         };
 
         // We expect each non-empty line to appear consecutively, non-consecutive lines
@@ -2698,7 +2804,7 @@ impl<'test> TestCx<'test> {
 
                     if !compare(expected_line, dumped_line) {
                         error!("{:?}", start_block_line);
-                        error(
+                        return Err(error(
                             expected_line,
                             format!(
                                 "Mismatch in lines\n\
@@ -2707,7 +2813,7 @@ impl<'test> TestCx<'test> {
                                 start_block_line.unwrap_or("None"),
                                 dumped_line
                             ),
-                        );
+                        ));
                     }
                 }
                 Some(&ExpectedLine::Elision) => {
@@ -2727,13 +2833,15 @@ impl<'test> TestCx<'test> {
                             }
                         }
                         if !found {
-                            error(expected_line, "ran out of mir dump to match against".into());
+                            return Err(error(expected_line,
+                                             "ran out of mir dump to match against".into()));
                         }
                     }
                 }
                 None => {}
             }
         }
+        Ok(())
     }
 
     fn get_mir_dump_dir(&self) -> PathBuf {
@@ -2803,6 +2911,11 @@ impl<'test> TestCx<'test> {
             return 0;
         }
 
+        if self.config.bless {
+            self.bless_file(&self.expected_output_path(kind), actual);
+            return 0;
+        }
+
         if expected.is_empty() {
             println!("normalized {}:\n{}\n", kind, actual);
         } else {
@@ -2844,6 +2957,20 @@ impl<'test> TestCx<'test> {
         println!("Actual {} saved to {}", kind, output_file.display());
         1
     }
+
+    /// Overwrites the expectation file at `path` with `actual`, or deletes it
+    /// if `actual` is empty, as a missing file means no output is expected.
+    fn bless_file(&self, path: &Path, actual: &str) {
+        let result = if actual.is_empty() {
+            if path.exists() { fs::remove_file(path) } else { Ok(()) }
+        } else {
+            File::create(path).and_then(|mut f| f.write_all(actual.as_bytes()))
+        };
+        match result {
+            Ok(()) => println!("blessed `{}`", path.display()),
+            Err(e) => self.fatal(&format!("failed to bless `{}`: {}", path.display(), e)),
+        }
+    }
 }
 
 struct ProcArgs {
@@ -2909,6 +3036,93 @@ where
     }
 }
 
+/// Updates the expected lines of a mir-opt test to match the `actual` dump,
+/// keeping the elisions of the old expectation. The expected lines which are
+/// still found in the dump, in order, are kept; the dumped lines between two
+/// of them are elided again if the old expectation elided them, and written
+/// out otherwise. The first of `expected` is the implicit leading elision.
+fn bless_mir_expected(expected: &[ExpectedLine<&str>], actual: &[String]) -> Vec<String> {
+    let expected: Vec<_> = expected[1..]
+        .iter()
+        .filter(|l| match **l {
+            ExpectedLine::Text(t) => !t.is_empty(),
+            ExpectedLine::Elision => true,
+        })
+        .collect();
+    let expected_norm: Vec<_> = expected
+        .iter()
+        .map(|l| match **l {
+            ExpectedLine::Text(t) => Some(normalize_mir_line(t)),
+            ExpectedLine::Elision => None,
+        })
+        .collect();
+    let actual_norm: Vec<_> = actual.iter().map(|l| normalize_mir_line(l)).collect();
+    let matches = |i: usize, j: usize| expected_norm[i].as_ref() == Some(&actual_norm[j]);
+
+    // `lcs[i][j]` is the length of the longest common subsequence of
+    // `expected[i..]` and `actual[j..]`.
+    let (n, m) = (expected.len(), actual.len());
+    let mut lcs = vec![vec![0; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if matches(i, j) {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                cmp::max(lcs[i + 1][j], lcs[i][j + 1])
+            };
+        }
+    }
+    let mut anchors = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if matches(i, j) {
+            anchors.push((i, j));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    let mut new_lines = vec![];
+    let (mut e_pos, mut a_pos) = (0, 0);
+    let gaps = anchors.iter().cloned().map(Some).chain(iter::once(None));
+    for (gap_idx, anchor) in gaps.enumerate() {
+        let (e_end, a_end) = anchor.unwrap_or((n, m));
+        let old = &expected[e_pos..e_end];
+        let has_text = old.iter().any(|l| match **l {
+            ExpectedLine::Text(_) => true,
+            ExpectedLine::Elision => false,
+        });
+        let has_elision = old.iter().any(|l| match **l {
+            ExpectedLine::Text(_) => false,
+            ExpectedLine::Elision => true,
+        });
+        // Elisions skip lazily, so they can only stand for dumped lines which
+        // don't contain the next expected line.
+        let skips_to_anchor = anchor.map_or(true, |(_, j)| {
+            !actual_norm[a_pos..j].contains(&actual_norm[j])
+        });
+        // The dump may start and end with lines which aren't expected.
+        let elided = has_elision || gap_idx == 0 || anchor.is_none();
+        if !has_text && elided && skips_to_anchor {
+            if has_elision {
+                new_lines.push("...".to_owned());
+            }
+        } else {
+            new_lines.extend(actual[a_pos..a_end].iter().cloned());
+        }
+        if let Some((i, j)) = anchor {
+            new_lines.push(actual[j].clone());
+            e_pos = i + 1;
+            a_pos = j + 1;
+        }
+    }
+    new_lines
+}
+
 fn normalize_mir_line(line: &str) -> String {
     nocomment_mir_line(line).replace(char::is_whitespace, "")
 }